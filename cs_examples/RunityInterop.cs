@@ -0,0 +1,1018 @@
+// <auto-generated>
+// Generated by runity-bindgen from the rust definitions in runity. Do not edit this
+// file by hand - regenerate it with `cargo run --bin runity-bindgen -- csharp`.
+// </auto-generated>
+
+using System;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+
+namespace runity_test
+{
+    /* Structs shared with rust. These must match the rust layout exactly. */
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct String
+    {
+        public IntPtr ptr; // offset 0
+        public uint len; // offset 8
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Str
+    {
+        public IntPtr ptr; // offset 0
+        public uint len; // offset 8
+    }
+
+    // 12 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Vector3
+    {
+        public float x; // offset 0
+        public float y; // offset 4
+        public float z; // offset 8
+    }
+
+    // 16 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Quaternion
+    {
+        public float x; // offset 0
+        public float y; // offset 4
+        public float z; // offset 8
+        public float w; // offset 12
+    }
+
+    // 88 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Transform
+    {
+        public Vector3 position; // offset 0
+        public Quaternion rotation; // offset 12
+        public Vector3 localScale; // offset 28
+        public ulong parent; // offset 40
+        public Vector3 parentPosition; // offset 48
+        public Quaternion parentRotation; // offset 60
+        public Vector3 parentScale; // offset 76
+    }
+
+    // 112 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct FoundGameObject
+    {
+        public ulong hash; // offset 0
+        public Str tag; // offset 8
+        public Transform transform; // offset 24
+    }
+
+    // 48 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct GameObjectLookup
+    {
+        public FindGameObjectDelegate find; // offset 0
+        public FindGameObjectWithTagDelegate findWithTag; // offset 8
+        public FindGameObjectsWithTagDelegate findAllWithTag; // offset 16
+        public FindGameObjectByIdDelegate findById; // offset 24
+        public GetChildCountDelegate childCount; // offset 32
+        public GetChildDelegate child; // offset 40
+    }
+
+    // 24 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct PropertyValue
+    {
+        public uint kind; // offset 0
+        public int integer; // offset 4
+        public float x; // offset 8
+        public float y; // offset 12
+        public float z; // offset 16
+        public float w; // offset 20
+    }
+
+    // 48 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Property
+    {
+        public Str name; // offset 0
+        public PropertyValue @value; // offset 16
+        [MarshalAs(UnmanagedType.U1)]
+        public bool found; // offset 40
+    }
+
+    // 32 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct ComponentAccess
+    {
+        public HasComponentDelegate hasComponent; // offset 0
+        public GetPropertiesDelegate getProperties; // offset 8
+        public SetPropertiesDelegate setProperties; // offset 16
+        public InvokeComponentDelegate invoke; // offset 24
+    }
+
+    // 176 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct GameObject
+    {
+        public ulong hash; // offset 0
+        public Transform transform; // offset 8
+        public GameObjectLookup lookup; // offset 96
+        public ComponentAccess components; // offset 144
+    }
+
+    // 112 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct GameObjectChanges
+    {
+        public ulong hash; // offset 0
+        public String tag; // offset 8
+        public Transform transform; // offset 24
+    }
+
+    // 152 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Command
+    {
+        public uint kind; // offset 0
+        public ulong target; // offset 8
+        public String prefab; // offset 16
+        public ulong prefabId; // offset 32
+        public Transform transform; // offset 40
+        public ulong parent; // offset 128
+        [MarshalAs(UnmanagedType.U1)]
+        public bool active; // offset 136
+        [MarshalAs(UnmanagedType.U1)]
+        public bool worldPositionStays; // offset 137
+        public uint status; // offset 140
+        public ulong @object; // offset 144
+    }
+
+    // 64 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Message
+    {
+        public uint kind; // offset 0
+        public String name; // offset 8
+        public IntPtr payload; // offset 24
+        public uint payloadLen; // offset 32
+        public uint recipients; // offset 36
+        public ulong @object; // offset 40
+        public String tag; // offset 48
+    }
+
+    // 16 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Color
+    {
+        public float r; // offset 0
+        public float g; // offset 4
+        public float b; // offset 8
+        public float a; // offset 12
+    }
+
+    // 88 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Drawing
+    {
+        public uint kind; // offset 0
+        public Vector3 from; // offset 4
+        public Vector3 to; // offset 16
+        public Quaternion rotation; // offset 28
+        public float radius; // offset 44
+        public Color color; // offset 48
+        public float duration; // offset 64
+        public String text; // offset 72
+    }
+
+    // 64 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct ChangeBuffer
+    {
+        public IntPtr changes; // offset 0
+        public uint len; // offset 8
+        public IntPtr commands; // offset 16
+        public uint commandCount; // offset 24
+        public IntPtr messages; // offset 32
+        public uint messageCount; // offset 40
+        public IntPtr drawings; // offset 48
+        public uint drawingCount; // offset 56
+    }
+
+    // 60 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Time
+    {
+        public float deltaTime; // offset 0
+        public float fixedDeltaTime; // offset 4
+        public float fixedTime; // offset 8
+        public float fixedUnscaledDeltaTime; // offset 12
+        public float fixedUnscaledTime; // offset 16
+        public float frameCount; // offset 20
+        public float maximumDeltaTime; // offset 24
+        public float maximumParticleDeltaTime; // offset 28
+        public float realTimeSinceStartup; // offset 32
+        public float smoothDeltaTime; // offset 36
+        public float time; // offset 40
+        public float timeScale; // offset 44
+        public float timeSinceLevelLoad; // offset 48
+        public float unscaledDeltaTime; // offset 52
+        public float unscaledTime; // offset 56
+    }
+
+    // 24 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Axis
+    {
+        public Str name; // offset 0
+        public float @value; // offset 16
+    }
+
+    // 120 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct InputSnapshot
+    {
+        [MarshalAs(UnmanagedType.ByValArray, SizeConst = 8)]
+        public ulong[] keys; // offset 0
+        public uint mouseButtons; // offset 64
+        public Vector3 mousePosition; // offset 68
+        public Vector3 mouseDelta; // offset 80
+        public Vector3 mouseScroll; // offset 92
+        public IntPtr axes; // offset 104
+        public uint axisCount; // offset 112
+    }
+
+    // 240 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Input
+    {
+        public InputSnapshot current; // offset 0
+        public InputSnapshot previous; // offset 120
+    }
+
+    // 56 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct LogRecord
+    {
+        public Str message; // offset 0
+        public Str target; // offset 16
+        public Str file; // offset 32
+        public uint line; // offset 48
+        public uint level; // offset 52
+    }
+
+    // 32 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Debug
+    {
+        public LogDelegate log; // offset 0
+        public LogWarningDelegate logWarning; // offset 8
+        public LogErrorDelegate logError; // offset 16
+        public LogRecordDelegate logRecord; // offset 24
+    }
+
+    // 72 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct PhysicsQuery
+    {
+        public uint shape; // offset 0
+        public Vector3 origin; // offset 4
+        public Vector3 direction; // offset 16
+        public float maxDistance; // offset 28
+        public float radius; // offset 32
+        public Vector3 halfExtents; // offset 36
+        public Quaternion rotation; // offset 48
+        public int layerMask; // offset 64
+        [MarshalAs(UnmanagedType.U1)]
+        public bool all; // offset 68
+    }
+
+    // 56 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct FoundHit
+    {
+        public Vector3 point; // offset 0
+        public Vector3 normal; // offset 12
+        public float distance; // offset 24
+        public ulong @object; // offset 32
+        public Str tag; // offset 40
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Physics
+    {
+        public PhysicsCastDelegate cast; // offset 0
+        public PhysicsOverlapDelegate overlap; // offset 8
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Profiler
+    {
+        public ProfilerBeginSampleDelegate beginSample; // offset 0
+        public ProfilerEndSampleDelegate endSample; // offset 8
+    }
+
+    // 624 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct DataStruct
+    {
+        public Transform transform; // offset 0
+        public GameObject gameObject; // offset 88
+        public Time time; // offset 264
+        public Input input; // offset 328
+        public Debug debug; // offset 568
+        public Physics physics; // offset 600
+        public ulong instance; // offset 616
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct State
+    {
+        public IntPtr data; // offset 0
+        public uint len; // offset 8
+        public uint version; // offset 12
+    }
+
+    // 28 bytes, aligned to 4
+    [StructLayout(LayoutKind.Sequential)]
+    public struct ContactPoint
+    {
+        public Vector3 point; // offset 0
+        public Vector3 normal; // offset 12
+        public float separation; // offset 24
+    }
+
+    // 64 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Collision
+    {
+        public ulong other; // offset 0
+        public Str tag; // offset 8
+        public IntPtr contacts; // offset 24
+        public uint contactCount; // offset 32
+        public Vector3 relativeVelocity; // offset 36
+        public Vector3 impulse; // offset 48
+    }
+
+    // 24 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct Trigger
+    {
+        public ulong other; // offset 0
+        public Str tag; // offset 8
+    }
+
+    // 40 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct ReceivedMessage
+    {
+        public Str name; // offset 0
+        public IntPtr payload; // offset 16
+        public uint payloadLen; // offset 24
+        public ulong sender; // offset 32
+    }
+
+    // 56 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct SpanSample
+    {
+        public ulong frame; // offset 0
+        public Str name; // offset 8
+        public uint calls; // offset 24
+        public ulong totalNs; // offset 32
+        public ulong minNs; // offset 40
+        public ulong maxNs; // offset 48
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct LayoutField
+    {
+        public IntPtr name; // offset 0
+        public uint offset; // offset 8
+        public uint size; // offset 12
+    }
+
+    // 32 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct LayoutEntry
+    {
+        public IntPtr name; // offset 0
+        public uint size; // offset 8
+        public uint align; // offset 12
+        public IntPtr fields; // offset 16
+        public uint fieldCount; // offset 24
+    }
+
+    // 16 bytes, aligned to 8
+    [StructLayout(LayoutKind.Sequential)]
+    public struct LayoutTable
+    {
+        public IntPtr entries; // offset 0
+        public uint len; // offset 8
+    }
+
+    /* Callbacks rust can call. */
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool FindGameObjectDelegate(Str name, IntPtr found);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool FindGameObjectWithTagDelegate(Str tag, IntPtr found);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint FindGameObjectsWithTagDelegate(Str tag, IntPtr found, uint capacity);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool FindGameObjectByIdDelegate(ulong hash, IntPtr found);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint GetChildCountDelegate(ulong hash);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool GetChildDelegate(ulong hash, uint index, IntPtr found);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool HasComponentDelegate(ulong @object, Str component);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void GetPropertiesDelegate(ulong @object, Str component, IntPtr properties, uint count);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void SetPropertiesDelegate(ulong @object, Str component, IntPtr properties, uint count);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool InvokeComponentDelegate(ulong @object, Str component, Str method, IntPtr arguments, uint count);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void LogDelegate(Str message);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void LogWarningDelegate(Str message);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void LogErrorDelegate(Str message);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void LogRecordDelegate(ref LogRecord record);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint PhysicsCastDelegate(ref PhysicsQuery query, IntPtr hits, uint capacity);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint PhysicsOverlapDelegate(ref PhysicsQuery query, IntPtr hits, uint capacity);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void ProfilerBeginSampleDelegate(Str name);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void ProfilerEndSampleDelegate();
+
+    /* Functions exported by a runity library. */
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct AwakeDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct StartDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct UpdateDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct FixedUpdateDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct LateUpdateDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnEnableDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnDisableDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnApplicationFocusDelegate(DataStruct data, [MarshalAs(UnmanagedType.U1)] bool focused);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnApplicationPauseDelegate(DataStruct data, [MarshalAs(UnmanagedType.U1)] bool paused);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnApplicationQuitDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnCollisionEnterDelegate(DataStruct data, ref Collision collision);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnCollisionStayDelegate(DataStruct data, ref Collision collision);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnCollisionExitDelegate(DataStruct data, ref Collision collision);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnTriggerEnterDelegate(DataStruct data, ref Trigger other);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnTriggerStayDelegate(DataStruct data, ref Trigger other);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate DataStruct OnTriggerExitDelegate(DataStruct data, ref Trigger other);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate int DestroyDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate State RunitySaveStateDelegate(ulong instance);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate int RunityLoadStateDelegate(ref DataStruct data, State state);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void SetDataDelegate(DataStruct data);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool RunityIsFaultedDelegate(ulong instance);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate ChangeBuffer RunityChangesAcquireDelegate(ulong instance);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunityChangesReleaseDelegate(ulong instance);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunityPostMessagesDelegate(ulong instance, IntPtr messages, uint count);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint RunitySpanSamplesDelegate(IntPtr samples, uint capacity);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunitySetProfilerDelegate(Profiler profiler);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunityClearProfilerDelegate();
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public delegate bool RunityWriteChromeTraceDelegate(Str path);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate uint RunityAbiVersionDelegate();
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate LayoutTable RunityLayoutTableDelegate();
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunityStringFreeDelegate(String @string);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    public delegate void RunityStateFreeDelegate(State state);
+
+    /// <summary>
+    /// Every function exported by a runity library. Optional exports are null if the library
+    /// doesn't export them.
+    /// </summary>
+    public class RunityExports
+    {
+        // The ABI version this file describes. Libraries reporting a different version from
+        // `runityAbiVersion` must not be run.
+        public const uint AbiVersion = 19;
+
+        public AwakeDelegate awake; // Optional
+        public StartDelegate start; // Optional
+        public UpdateDelegate update; // Optional
+        public FixedUpdateDelegate fixedUpdate; // Optional
+        public LateUpdateDelegate lateUpdate; // Optional
+        public OnEnableDelegate onEnable; // Optional
+        public OnDisableDelegate onDisable; // Optional
+        public OnApplicationFocusDelegate onApplicationFocus; // Optional
+        public OnApplicationPauseDelegate onApplicationPause; // Optional
+        public OnApplicationQuitDelegate onApplicationQuit; // Optional
+        public OnCollisionEnterDelegate onCollisionEnter; // Optional
+        public OnCollisionStayDelegate onCollisionStay; // Optional
+        public OnCollisionExitDelegate onCollisionExit; // Optional
+        public OnTriggerEnterDelegate onTriggerEnter; // Optional
+        public OnTriggerStayDelegate onTriggerStay; // Optional
+        public OnTriggerExitDelegate onTriggerExit; // Optional
+        public DestroyDelegate destroy;
+        public RunitySaveStateDelegate runitySaveState; // Optional
+        public RunityLoadStateDelegate runityLoadState; // Optional
+        public SetDataDelegate setData;
+        public RunityIsFaultedDelegate runityIsFaulted;
+        public RunityChangesAcquireDelegate runityChangesAcquire;
+        public RunityChangesReleaseDelegate runityChangesRelease;
+        public RunityPostMessagesDelegate runityPostMessages;
+        public RunitySpanSamplesDelegate runitySpanSamples;
+        public RunitySetProfilerDelegate runitySetProfiler;
+        public RunityClearProfilerDelegate runityClearProfiler;
+        public RunityWriteChromeTraceDelegate runityWriteChromeTrace;
+        public RunityAbiVersionDelegate runityAbiVersion;
+        public RunityLayoutTableDelegate runityLayoutTable;
+        public RunityStringFreeDelegate runityStringFree;
+        public RunityStateFreeDelegate runityStateFree;
+
+        // The name of every required export the library is missing
+        public List<string> missing = new List<string>();
+
+        public static RunityExports Load(string dllName)
+        {
+            RunityExports exports = new RunityExports();
+            Delegate function;
+            IntPtr pointer;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "awake", typeof(AwakeDelegate));
+            if (pointer != IntPtr.Zero) exports.awake = (AwakeDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "start", typeof(StartDelegate));
+            if (pointer != IntPtr.Zero) exports.start = (StartDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "update", typeof(UpdateDelegate));
+            if (pointer != IntPtr.Zero) exports.update = (UpdateDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "fixed_update", typeof(FixedUpdateDelegate));
+            if (pointer != IntPtr.Zero) exports.fixedUpdate = (FixedUpdateDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "late_update", typeof(LateUpdateDelegate));
+            if (pointer != IntPtr.Zero) exports.lateUpdate = (LateUpdateDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_enable", typeof(OnEnableDelegate));
+            if (pointer != IntPtr.Zero) exports.onEnable = (OnEnableDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_disable", typeof(OnDisableDelegate));
+            if (pointer != IntPtr.Zero) exports.onDisable = (OnDisableDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_application_focus", typeof(OnApplicationFocusDelegate));
+            if (pointer != IntPtr.Zero) exports.onApplicationFocus = (OnApplicationFocusDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_application_pause", typeof(OnApplicationPauseDelegate));
+            if (pointer != IntPtr.Zero) exports.onApplicationPause = (OnApplicationPauseDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_application_quit", typeof(OnApplicationQuitDelegate));
+            if (pointer != IntPtr.Zero) exports.onApplicationQuit = (OnApplicationQuitDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_collision_enter", typeof(OnCollisionEnterDelegate));
+            if (pointer != IntPtr.Zero) exports.onCollisionEnter = (OnCollisionEnterDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_collision_stay", typeof(OnCollisionStayDelegate));
+            if (pointer != IntPtr.Zero) exports.onCollisionStay = (OnCollisionStayDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_collision_exit", typeof(OnCollisionExitDelegate));
+            if (pointer != IntPtr.Zero) exports.onCollisionExit = (OnCollisionExitDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_trigger_enter", typeof(OnTriggerEnterDelegate));
+            if (pointer != IntPtr.Zero) exports.onTriggerEnter = (OnTriggerEnterDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_trigger_stay", typeof(OnTriggerStayDelegate));
+            if (pointer != IntPtr.Zero) exports.onTriggerStay = (OnTriggerStayDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "on_trigger_exit", typeof(OnTriggerExitDelegate));
+            if (pointer != IntPtr.Zero) exports.onTriggerExit = (OnTriggerExitDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "destroy", typeof(DestroyDelegate));
+            if (pointer != IntPtr.Zero) exports.destroy = (DestroyDelegate)function;
+            else exports.missing.Add("destroy");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_save_state", typeof(RunitySaveStateDelegate));
+            if (pointer != IntPtr.Zero) exports.runitySaveState = (RunitySaveStateDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_load_state", typeof(RunityLoadStateDelegate));
+            if (pointer != IntPtr.Zero) exports.runityLoadState = (RunityLoadStateDelegate)function;
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "set_data", typeof(SetDataDelegate));
+            if (pointer != IntPtr.Zero) exports.setData = (SetDataDelegate)function;
+            else exports.missing.Add("set_data");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_is_faulted", typeof(RunityIsFaultedDelegate));
+            if (pointer != IntPtr.Zero) exports.runityIsFaulted = (RunityIsFaultedDelegate)function;
+            else exports.missing.Add("runity_is_faulted");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_changes_acquire", typeof(RunityChangesAcquireDelegate));
+            if (pointer != IntPtr.Zero) exports.runityChangesAcquire = (RunityChangesAcquireDelegate)function;
+            else exports.missing.Add("runity_changes_acquire");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_changes_release", typeof(RunityChangesReleaseDelegate));
+            if (pointer != IntPtr.Zero) exports.runityChangesRelease = (RunityChangesReleaseDelegate)function;
+            else exports.missing.Add("runity_changes_release");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_post_messages", typeof(RunityPostMessagesDelegate));
+            if (pointer != IntPtr.Zero) exports.runityPostMessages = (RunityPostMessagesDelegate)function;
+            else exports.missing.Add("runity_post_messages");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_span_samples", typeof(RunitySpanSamplesDelegate));
+            if (pointer != IntPtr.Zero) exports.runitySpanSamples = (RunitySpanSamplesDelegate)function;
+            else exports.missing.Add("runity_span_samples");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_set_profiler", typeof(RunitySetProfilerDelegate));
+            if (pointer != IntPtr.Zero) exports.runitySetProfiler = (RunitySetProfilerDelegate)function;
+            else exports.missing.Add("runity_set_profiler");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_clear_profiler", typeof(RunityClearProfilerDelegate));
+            if (pointer != IntPtr.Zero) exports.runityClearProfiler = (RunityClearProfilerDelegate)function;
+            else exports.missing.Add("runity_clear_profiler");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_write_chrome_trace", typeof(RunityWriteChromeTraceDelegate));
+            if (pointer != IntPtr.Zero) exports.runityWriteChromeTrace = (RunityWriteChromeTraceDelegate)function;
+            else exports.missing.Add("runity_write_chrome_trace");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_abi_version", typeof(RunityAbiVersionDelegate));
+            if (pointer != IntPtr.Zero) exports.runityAbiVersion = (RunityAbiVersionDelegate)function;
+            else exports.missing.Add("runity_abi_version");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_layout_table", typeof(RunityLayoutTableDelegate));
+            if (pointer != IntPtr.Zero) exports.runityLayoutTable = (RunityLayoutTableDelegate)function;
+            else exports.missing.Add("runity_layout_table");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_string_free", typeof(RunityStringFreeDelegate));
+            if (pointer != IntPtr.Zero) exports.runityStringFree = (RunityStringFreeDelegate)function;
+            else exports.missing.Add("runity_string_free");
+
+            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, "runity_state_free", typeof(RunityStateFreeDelegate));
+            if (pointer != IntPtr.Zero) exports.runityStateFree = (RunityStateFreeDelegate)function;
+            else exports.missing.Add("runity_state_free");
+
+            return exports;
+        }
+
+        // The C# type and field names of every struct shared with rust, by their rust names
+        static readonly Dictionary<string, Type> sharedStructs = new Dictionary<string, Type>
+        {
+            { "String", typeof(String) },
+            { "Str", typeof(Str) },
+            { "Vector3", typeof(Vector3) },
+            { "Quaternion", typeof(Quaternion) },
+            { "Transform", typeof(Transform) },
+            { "FoundGameObject", typeof(FoundGameObject) },
+            { "GameObjectLookup", typeof(GameObjectLookup) },
+            { "PropertyValue", typeof(PropertyValue) },
+            { "Property", typeof(Property) },
+            { "ComponentAccess", typeof(ComponentAccess) },
+            { "GameObject", typeof(GameObject) },
+            { "GameObjectChanges", typeof(GameObjectChanges) },
+            { "Command", typeof(Command) },
+            { "Message", typeof(Message) },
+            { "Color", typeof(Color) },
+            { "Drawing", typeof(Drawing) },
+            { "ChangeBuffer", typeof(ChangeBuffer) },
+            { "Time", typeof(Time) },
+            { "Axis", typeof(Axis) },
+            { "InputSnapshot", typeof(InputSnapshot) },
+            { "Input", typeof(Input) },
+            { "LogRecord", typeof(LogRecord) },
+            { "Debug", typeof(Debug) },
+            { "PhysicsQuery", typeof(PhysicsQuery) },
+            { "FoundHit", typeof(FoundHit) },
+            { "Physics", typeof(Physics) },
+            { "Profiler", typeof(Profiler) },
+            { "DataStruct", typeof(DataStruct) },
+            { "State", typeof(State) },
+            { "ContactPoint", typeof(ContactPoint) },
+            { "Collision", typeof(Collision) },
+            { "Trigger", typeof(Trigger) },
+            { "ReceivedMessage", typeof(ReceivedMessage) },
+            { "SpanSample", typeof(SpanSample) },
+            { "LayoutField", typeof(LayoutField) },
+            { "LayoutEntry", typeof(LayoutEntry) },
+            { "LayoutTable", typeof(LayoutTable) },
+        };
+
+        static readonly Dictionary<string, string> sharedFields = new Dictionary<string, string>
+        {
+            { "String.ptr", "ptr" },
+            { "String.len", "len" },
+            { "Str.ptr", "ptr" },
+            { "Str.len", "len" },
+            { "Vector3.x", "x" },
+            { "Vector3.y", "y" },
+            { "Vector3.z", "z" },
+            { "Quaternion.x", "x" },
+            { "Quaternion.y", "y" },
+            { "Quaternion.z", "z" },
+            { "Quaternion.w", "w" },
+            { "Transform.position", "position" },
+            { "Transform.rotation", "rotation" },
+            { "Transform.local_scale", "localScale" },
+            { "Transform.parent", "parent" },
+            { "Transform.parent_position", "parentPosition" },
+            { "Transform.parent_rotation", "parentRotation" },
+            { "Transform.parent_scale", "parentScale" },
+            { "FoundGameObject.hash", "hash" },
+            { "FoundGameObject.tag", "tag" },
+            { "FoundGameObject.transform", "transform" },
+            { "GameObjectLookup.find", "find" },
+            { "GameObjectLookup.find_with_tag", "findWithTag" },
+            { "GameObjectLookup.find_all_with_tag", "findAllWithTag" },
+            { "GameObjectLookup.find_by_id", "findById" },
+            { "GameObjectLookup.child_count", "childCount" },
+            { "GameObjectLookup.child", "child" },
+            { "PropertyValue.kind", "kind" },
+            { "PropertyValue.integer", "integer" },
+            { "PropertyValue.x", "x" },
+            { "PropertyValue.y", "y" },
+            { "PropertyValue.z", "z" },
+            { "PropertyValue.w", "w" },
+            { "Property.name", "name" },
+            { "Property.value", "value" },
+            { "Property.found", "found" },
+            { "ComponentAccess.has_component", "hasComponent" },
+            { "ComponentAccess.get_properties", "getProperties" },
+            { "ComponentAccess.set_properties", "setProperties" },
+            { "ComponentAccess.invoke", "invoke" },
+            { "GameObject.hash", "hash" },
+            { "GameObject.transform", "transform" },
+            { "GameObject.lookup", "lookup" },
+            { "GameObject.components", "components" },
+            { "GameObjectChanges.hash", "hash" },
+            { "GameObjectChanges.tag", "tag" },
+            { "GameObjectChanges.transform", "transform" },
+            { "Command.kind", "kind" },
+            { "Command.target", "target" },
+            { "Command.prefab", "prefab" },
+            { "Command.prefab_id", "prefabId" },
+            { "Command.transform", "transform" },
+            { "Command.parent", "parent" },
+            { "Command.active", "active" },
+            { "Command.world_position_stays", "worldPositionStays" },
+            { "Command.status", "status" },
+            { "Command.object", "object" },
+            { "Message.kind", "kind" },
+            { "Message.name", "name" },
+            { "Message.payload", "payload" },
+            { "Message.payload_len", "payloadLen" },
+            { "Message.recipients", "recipients" },
+            { "Message.object", "object" },
+            { "Message.tag", "tag" },
+            { "Color.r", "r" },
+            { "Color.g", "g" },
+            { "Color.b", "b" },
+            { "Color.a", "a" },
+            { "Drawing.kind", "kind" },
+            { "Drawing.from", "from" },
+            { "Drawing.to", "to" },
+            { "Drawing.rotation", "rotation" },
+            { "Drawing.radius", "radius" },
+            { "Drawing.color", "color" },
+            { "Drawing.duration", "duration" },
+            { "Drawing.text", "text" },
+            { "ChangeBuffer.changes", "changes" },
+            { "ChangeBuffer.len", "len" },
+            { "ChangeBuffer.commands", "commands" },
+            { "ChangeBuffer.command_count", "commandCount" },
+            { "ChangeBuffer.messages", "messages" },
+            { "ChangeBuffer.message_count", "messageCount" },
+            { "ChangeBuffer.drawings", "drawings" },
+            { "ChangeBuffer.drawing_count", "drawingCount" },
+            { "Time.delta_time", "deltaTime" },
+            { "Time.fixed_delta_time", "fixedDeltaTime" },
+            { "Time.fixed_time", "fixedTime" },
+            { "Time.fixed_unscaled_delta_time", "fixedUnscaledDeltaTime" },
+            { "Time.fixed_unscaled_time", "fixedUnscaledTime" },
+            { "Time.frame_count", "frameCount" },
+            { "Time.maximum_delta_time", "maximumDeltaTime" },
+            { "Time.maximum_particle_delta_time", "maximumParticleDeltaTime" },
+            { "Time.real_time_since_startup", "realTimeSinceStartup" },
+            { "Time.smooth_delta_time", "smoothDeltaTime" },
+            { "Time.time", "time" },
+            { "Time.time_scale", "timeScale" },
+            { "Time.time_since_level_load", "timeSinceLevelLoad" },
+            { "Time.unscaled_delta_time", "unscaledDeltaTime" },
+            { "Time.unscaled_time", "unscaledTime" },
+            { "Axis.name", "name" },
+            { "Axis.value", "value" },
+            { "InputSnapshot.keys", "keys" },
+            { "InputSnapshot.mouse_buttons", "mouseButtons" },
+            { "InputSnapshot.mouse_position", "mousePosition" },
+            { "InputSnapshot.mouse_delta", "mouseDelta" },
+            { "InputSnapshot.mouse_scroll", "mouseScroll" },
+            { "InputSnapshot.axes", "axes" },
+            { "InputSnapshot.axis_count", "axisCount" },
+            { "Input.current", "current" },
+            { "Input.previous", "previous" },
+            { "LogRecord.message", "message" },
+            { "LogRecord.target", "target" },
+            { "LogRecord.file", "file" },
+            { "LogRecord.line", "line" },
+            { "LogRecord.level", "level" },
+            { "Debug.log", "log" },
+            { "Debug.log_warning", "logWarning" },
+            { "Debug.log_error", "logError" },
+            { "Debug.log_record", "logRecord" },
+            { "PhysicsQuery.shape", "shape" },
+            { "PhysicsQuery.origin", "origin" },
+            { "PhysicsQuery.direction", "direction" },
+            { "PhysicsQuery.max_distance", "maxDistance" },
+            { "PhysicsQuery.radius", "radius" },
+            { "PhysicsQuery.half_extents", "halfExtents" },
+            { "PhysicsQuery.rotation", "rotation" },
+            { "PhysicsQuery.layer_mask", "layerMask" },
+            { "PhysicsQuery.all", "all" },
+            { "FoundHit.point", "point" },
+            { "FoundHit.normal", "normal" },
+            { "FoundHit.distance", "distance" },
+            { "FoundHit.object", "object" },
+            { "FoundHit.tag", "tag" },
+            { "Physics.cast", "cast" },
+            { "Physics.overlap", "overlap" },
+            { "Profiler.begin_sample", "beginSample" },
+            { "Profiler.end_sample", "endSample" },
+            { "DataStruct.transform", "transform" },
+            { "DataStruct.game_object", "gameObject" },
+            { "DataStruct.time", "time" },
+            { "DataStruct.input", "input" },
+            { "DataStruct.debug", "debug" },
+            { "DataStruct.physics", "physics" },
+            { "DataStruct.instance", "instance" },
+            { "State.data", "data" },
+            { "State.len", "len" },
+            { "State.version", "version" },
+            { "ContactPoint.point", "point" },
+            { "ContactPoint.normal", "normal" },
+            { "ContactPoint.separation", "separation" },
+            { "Collision.other", "other" },
+            { "Collision.tag", "tag" },
+            { "Collision.contacts", "contacts" },
+            { "Collision.contact_count", "contactCount" },
+            { "Collision.relative_velocity", "relativeVelocity" },
+            { "Collision.impulse", "impulse" },
+            { "Trigger.other", "other" },
+            { "Trigger.tag", "tag" },
+            { "ReceivedMessage.name", "name" },
+            { "ReceivedMessage.payload", "payload" },
+            { "ReceivedMessage.payload_len", "payloadLen" },
+            { "ReceivedMessage.sender", "sender" },
+            { "SpanSample.frame", "frame" },
+            { "SpanSample.name", "name" },
+            { "SpanSample.calls", "calls" },
+            { "SpanSample.total_ns", "totalNs" },
+            { "SpanSample.min_ns", "minNs" },
+            { "SpanSample.max_ns", "maxNs" },
+            { "LayoutField.name", "name" },
+            { "LayoutField.offset", "offset" },
+            { "LayoutField.size", "size" },
+            { "LayoutEntry.name", "name" },
+            { "LayoutEntry.size", "size" },
+            { "LayoutEntry.align", "align" },
+            { "LayoutEntry.fields", "fields" },
+            { "LayoutEntry.field_count", "fieldCount" },
+            { "LayoutTable.entries", "entries" },
+            { "LayoutTable.len", "len" },
+        };
+
+        /// <summary>
+        /// Checks the library was built against the same ABI as this file, and that every shared
+        /// struct has the same layout on both sides. Returns null if it is compatible, or why it
+        /// isn't. This must be checked before calling into the library.
+        /// </summary>
+        public string CheckAbi()
+        {
+            if (runityAbiVersion == null || runityLayoutTable == null)
+                return "library does not report its ABI version";
+
+            uint version = runityAbiVersion();
+            if (version != AbiVersion)
+                return "library was built against runity ABI version " + version + ", but this host expects version " + AbiVersion;
+
+            LayoutTable table = runityLayoutTable();
+            HashSet<string> described = new HashSet<string>();
+            for (int i = 0; i < table.len; i++)
+            {
+                LayoutEntry entry = Marshal.PtrToStructure<LayoutEntry>(table.entries + i * Marshal.SizeOf<LayoutEntry>());
+                string name = Marshal.PtrToStringAnsi(entry.name);
+                if (!sharedStructs.TryGetValue(name, out Type type))
+                    continue;
+
+                described.Add(name);
+                if (Marshal.SizeOf(type) != entry.size)
+                    return name + " is " + entry.size + " bytes in the library, but " + Marshal.SizeOf(type) + " bytes in C#";
+
+                for (int j = 0; j < entry.fieldCount; j++)
+                {
+                    LayoutField field = Marshal.PtrToStructure<LayoutField>(entry.fields + j * Marshal.SizeOf<LayoutField>());
+                    string fieldName = name + "." + Marshal.PtrToStringAnsi(field.name);
+                    if (!sharedFields.TryGetValue(fieldName, out string csharpName))
+                        return fieldName + " is in the library, but not C#";
+                    long offset = Marshal.OffsetOf(type, csharpName).ToInt64();
+                    if (offset != field.offset)
+                        return fieldName + " is at offset " + field.offset + " in the library, but " + offset + " in C#";
+                }
+            }
+
+            foreach (string name in sharedStructs.Keys)
+            {
+                if (!described.Contains(name))
+                    return "library does not describe " + name;
+            }
+
+            return null;
+        }
+    }
+}
@@ -6,6 +6,7 @@ using System.Collections.Generic;
 using UnityEngine.Scripting;
 using System.Text;
 using System.Buffers;
+using System.Reflection;
 
 [assembly: Preserve]
 
@@ -40,132 +41,75 @@ namespace runity_test
 
         // Our function pointers, so we can recycle them rather than waste
         // processing time reloading the DLL
-        StartDelegate start;
-        UpdateDelegate update;
-        DestroyDelegate destroy;
+        RunityExports exports;
 
         // An object pool to avoid calling Find on gameobjects every frame
         Dictionary<string, UnityEngine.GameObject> objectPool = new Dictionary<string, UnityEngine.GameObject>();
 
-        // We use these booleans to check if we should run the respective unity functions.
-        // This is so we can check collisions conditionally.
-        bool runStart;
-        bool runUpdate;
-
-        // We don't have an optional destroy function - this must exist.
-
-        /* Import our functions (start, update, destroy and awake) as delegates, as they will be pointers to the functions
-         * since we want to load them dynamically at runtime 
-         */
-
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        private delegate DataStruct StartDelegate(DataStruct data);
+        // Every gameobject handed to rust, by its hash, so changes rust sends back can be applied
+        Dictionary<ulong, UnityEngine.GameObject> knownObjects = new Dictionary<ulong, UnityEngine.GameObject>();
 
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        private delegate DataStruct UpdateDelegate(DataStruct data);
+        // The functions rust finds gameobjects with, and the tags they have lent to rust
+        GameObjectLookup lookup;
+        List<GCHandle> lentTags = new List<GCHandle>();
 
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        private delegate int DestroyDelegate(DataStruct data);
-
-
-        /* Define the structs to use to interface with rust with. This gives
-         * the option to implement safe, rust compatiable datatypes which
-         * we can't guarantee with unity */
-
-        [StructLayout(LayoutKind.Sequential)]
-        public struct String
-        {
-            public IntPtr ptr;
-            public Int32 len;
-        }
-
-        [StructLayout(LayoutKind.Sequential)]
-        public struct Vector3
-        {
-            public float x;
-            public float y;
-            public float z;
-        }
+        // The functions rust reaches components with, and the component types rust has asked for, by name
+        ComponentAccess components;
+        Dictionary<string, Type> componentTypes = new Dictionary<string, Type>();
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct Quaternion
-        {
-            public float x;
-            public float y;
-            public float z;
-            public float w;
-        }
+        // The functions rust queries the physics scene with
+        Physics physics;
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct Transform
-        {
-            public Vector3 position;
-            public Quaternion rotation;
-        }
+        // Every Runity script, so messages can be routed between them
+        static readonly List<Runity> scripts = new List<Runity>();
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct GameObject
+        // A message routed to this script, and the frame it was published in
+        struct PostedMessage
         {
-            public String tag;
-            public Transform transform;
-            public FindGameObjectWithTagDelegate GetGameObjectByTag;
+            public string name;
+            public byte[] payload;
+            public ulong sender;
+            public int frame;
         }
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct Time
-        {
-            public float deltaTime;
-            public float fixedDeltaTime;
-            public float fixedTime;
-            public float fixedUnscaledDeltaTime;
-            public float fixedUnscaledTime;
-            public float frameCount;
-            public float maximumDeltaTime;
-            public float maximumParticleDeltaTime;
-            public float realTimeSinceStartup;
-            public float smoothDeltaTime;
-            public float time;
-            public float timeScale;
-            public float timeSinceLevelLoad;
-            public float unscaledDeltaTime;
-            public float unscaledTime;
-        }
+        // The messages rust has subscribed to, and the messages waiting to be posted to it
+        HashSet<string> subscriptions = new HashSet<string>();
+        List<PostedMessage> inbox = new List<PostedMessage>();
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct Debug{
-            public LogDelegate log;
-            public LogWarningDelegate logWarning;
-            public LogErrorDelegate logError;
-        }
+        // We use these booleans to check if we should run the respective unity functions.
+        // This is so we can check collisions conditionally.
+        bool runStart;
+        bool runUpdate;
 
-        [StructLayout(LayoutKind.Sequential)]
-        public struct DataStruct
-        {
-            public Transform transform;
-            public GameObject gameObject;
-            public Time time;
-            public Debug debug;
-        }
+        // The prefabs rust can spawn, by name or by their index in this list
+        public UnityEngine.GameObject[] prefabs = new UnityEngine.GameObject[0];
 
-        /* Define our delegates, which are callbacks to functions we want to use 
-         * in rust */
+        // Where the rust crate's source is, relative to the project (e.g. "../game"), so records logged
+        // from rust link back to the file they were logged from in the console
+        public string rustSourceRoot = "";
 
-        /* GameObject functions */
+        // The axes sent to rust every frame, by name
+        public string[] axisNames = { "Horizontal", "Vertical", "Mouse X", "Mouse Y" };
 
-        // We ensure that the delegate is defined as an unmanaged function pointer
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        public delegate void FindGameObjectWithTagDelegate(String tag, IntPtr gameObjectPtr); // This delegate acts as FindGameObjectWithTag
+        // The axes (and their names) are lent to rust for as long as the input is, so they are pinned
+        Axis[] axes;
+        GCHandle axesHandle;
+        List<GCHandle> axisNameHandles = new List<GCHandle>();
 
-        /* Logging/Debug functions */
+        // Input is sampled once per frame, so every function in a frame sees the same input
+        int inputFrame = -1;
+        UnityEngine.Vector3 lastMousePosition;
+        static readonly KeyCode[] keyCodes = (KeyCode[])Enum.GetValues(typeof(KeyCode));
 
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        public delegate void LogDelegate(String message); // This delegate acts as Unity's log
+        // Set once the DLL has been checked against the ABI RunityInterop.cs was generated for.
+        // A DLL built against a different layout is never called into.
+        bool compatible;
 
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        public delegate void LogWarningDelegate(String message); // This delegate acts as Unity's logWarning
+        // We don't have an optional destroy function - this must exist.
 
-        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
-        public delegate void LogErrorDelegate(String message); // This delegate acts as Unity's logError
+        /* The structs and delegates shared with rust (DataStruct, GameObject, Time, Debug etc.) are
+         * generated from the rust definitions, so the two sides can't disagree about layout. See
+         * RunityInterop.cs, and regenerate it with `cargo run --bin runity-bindgen -- csharp`. */
 
 
         /* Run built-in unity functions */
@@ -174,43 +118,45 @@ namespace runity_test
                         // Load the DLL. This is important, as the DLL must be loaded before we can call any functions
             DLLPool.LoadDLL(DLLName);
 
-            (Delegate startFunction, IntPtr startPtr) = DLLPool.LoadFunctionFromDLL(DLLName, "start", typeof(StartDelegate));
+            exports = RunityExports.Load(DLLName);
 
-            if (startPtr != IntPtr.Zero)
+            foreach (string missing in exports.missing)
             {
-                runStart = true;
-                UnityEngine.Debug.Log("Start function loaded");
-                start = (StartDelegate)startFunction;
+                UnityEngine.Debug.LogError(missing + " function not loaded");
             }
-            else
+
+            string incompatible = exports.CheckAbi();
+            if (incompatible != null)
             {
-                UnityEngine.Debug.LogWarning("Start function not loaded");
+                UnityEngine.Debug.LogError(DLLName + " is not compatible with this version of runity: " + incompatible);
+                enabled = false;
+                return;
             }
+            compatible = true;
 
-
-            (Delegate updateFunction, IntPtr updatePtr) = DLLPool.LoadFunctionFromDLL(DLLName, "update", typeof(UpdateDelegate));
-
-            if (updatePtr != IntPtr.Zero)
+            // Rust's profiler scopes show up in unity's Profiler window, nested under this script's functions
+            if (exports.runitySetProfiler != null)
             {
-                runUpdate = true;
-                UnityEngine.Debug.Log("Update function loaded");
-                update = (UpdateDelegate)updateFunction;
+                samplers.Clear();
+                exports.runitySetProfiler(new Profiler { beginSample = beginSample, endSample = endSample });
             }
-            else
+            scripts.Add(this);
+
+            if (exports.awake == null)
             {
-                UnityEngine.Debug.LogWarning("Update function not loaded");
+                UnityEngine.Debug.LogError("Awake function not loaded");
             }
 
-            (Delegate destroyFunction, IntPtr destroyPtr) = DLLPool.LoadFunctionFromDLL(DLLName, "destroy", typeof(DestroyDelegate));
-
-            if (destroyPtr != IntPtr.Zero)
+            runStart = exports.start != null;
+            if (!runStart)
             {
-                UnityEngine.Debug.Log("Destroy function loaded");
-                destroy = (DestroyDelegate)destroyFunction;
+                UnityEngine.Debug.LogWarning("Start function not loaded");
             }
-            else
+
+            runUpdate = exports.update != null;
+            if (!runUpdate)
             {
-                UnityEngine.Debug.LogError("Destroy function not loaded");
+                UnityEngine.Debug.LogWarning("Update function not loaded");
             }
 
             // We now assign our delegates to point to our functions.
@@ -221,6 +167,30 @@ namespace runity_test
             dataStruct = new DataStruct { };
 
             m_time = new Time { };
+            lookup = CreateLookup();
+            components = CreateComponentAccess();
+            physics = CreatePhysics();
+
+            // Rust reaches this gameobject's components (and sends it changes) by its hash
+            m_gameObject.hash = Track(gameObject);
+
+            // Awake allocates the instance for this gameobject, which we keep in the data struct.
+            // Every object running the same DLL gets its own instance.
+            if (exports.awake != null)
+            {
+                // OnEnable runs before Start, so the callbacks rust can use are set up here
+                m_gameObject.lookup = lookup;
+                m_gameObject.components = components;
+                dataStruct.gameObject = m_gameObject;
+
+                SetTime();
+                dataStruct.time = m_time;
+                SampleInput();
+                dataStruct.debug = new Debug { log = new LogDelegate(Log), logWarning = new LogWarningDelegate(LogWarning), logError = new LogErrorDelegate(LogError), logRecord = new LogRecordDelegate(WriteLogRecord) };
+                dataStruct.physics = physics;
+
+                dataStruct = exports.awake(dataStruct);
+            }
         }
 
         // Start is called before the first frame update
@@ -230,12 +200,11 @@ namespace runity_test
 
             if (runStart)
             {
-                m_transform.position = new Vector3 { x = 0, y = 0, z = 0 };
-                m_transform.rotation = new Quaternion { x = 0, y = .25f, z = 0, w = 1.0f };
-
+                m_transform = ToRust(transform);
 
                 m_gameObject.transform = m_transform;
-                m_gameObject.GetGameObjectByTag = new FindGameObjectWithTagDelegate(GetGameObjectFromTag);
+                m_gameObject.lookup = lookup;
+                m_gameObject.components = components;
 
                 dataStruct.transform = m_transform;
                 dataStruct.gameObject = m_gameObject;
@@ -244,9 +213,12 @@ namespace runity_test
 
                 dataStruct.time = m_time;
 
-                dataStruct.debug = new Debug { log = new LogDelegate(Log), logWarning = new LogWarningDelegate(LogWarning), logError = new LogErrorDelegate(LogError) };
+                SampleInput();
+
+                dataStruct.debug = new Debug { log = new LogDelegate(Log), logWarning = new LogWarningDelegate(LogWarning), logError = new LogErrorDelegate(LogError), logRecord = new LogRecordDelegate(WriteLogRecord) };
+                dataStruct.physics = physics;
 
-                dataStruct = start(dataStruct);
+                dataStruct = exports.start(dataStruct);
 
                 UnityEngine.Time.fixedDeltaTime = dataStruct.time.fixedDeltaTime;
                 UnityEngine.Time.timeScale = dataStruct.time.timeScale;
@@ -256,8 +228,9 @@ namespace runity_test
                 position = m_transform.position;
                 rotation = m_transform.rotation;
 
-                transform.position = new UnityEngine.Vector3(position.x, position.y, position.z);
-                transform.rotation = new UnityEngine.Quaternion(rotation.x, rotation.y, rotation.z, rotation.w);
+                Apply(m_transform, transform);
+
+                ApplyChanges();
             }
         }
 
@@ -266,36 +239,759 @@ namespace runity_test
         {
             if (runUpdate)
             {
-                m_gameObject.transform = m_transform;
+                PostMessages();
+                Run(exports.update.Invoke);
+            }
+        }
 
-                dataStruct.transform = m_transform;
-                dataStruct.gameObject = m_gameObject;
+        // FixedUpdate is called once per physics step, before Update
+        void FixedUpdate()
+        {
+            if (exports.fixedUpdate != null)
+            {
+                Run(exports.fixedUpdate.Invoke);
+            }
+        }
 
-                SetTime();
+        // LateUpdate is called once per frame, after every Update
+        void LateUpdate()
+        {
+            if (exports.lateUpdate != null)
+            {
+                Run(exports.lateUpdate.Invoke);
+            }
+        }
 
-                dataStruct.time = m_time;
+        void OnEnable()
+        {
+            if (compatible && exports.onEnable != null)
+            {
+                Run(exports.onEnable.Invoke);
+            }
+        }
 
-                dataStruct = update(dataStruct);
+        void OnDisable()
+        {
+            if (compatible && exports.onDisable != null)
+            {
+                Run(exports.onDisable.Invoke);
+            }
+        }
 
-                UnityEngine.Time.fixedDeltaTime = dataStruct.time.fixedDeltaTime;
-                UnityEngine.Time.timeScale = dataStruct.time.timeScale;
+        void OnApplicationFocus(bool focused)
+        {
+            if (compatible && exports.onApplicationFocus != null)
+            {
+                Run(data => exports.onApplicationFocus(data, focused));
+            }
+        }
 
-                m_gameObject = dataStruct.gameObject;
-                m_transform = dataStruct.transform;
-                position = m_transform.position;
-                rotation = m_transform.rotation;
+        void OnApplicationPause(bool paused)
+        {
+            if (compatible && exports.onApplicationPause != null)
+            {
+                Run(data => exports.onApplicationPause(data, paused));
+            }
+        }
+
+        // Called before OnDisable and OnDestroy when the application quits
+        void OnApplicationQuit()
+        {
+            if (compatible && exports.onApplicationQuit != null)
+            {
+                Run(exports.onApplicationQuit.Invoke);
+            }
+        }
+
+        void OnCollisionEnter(UnityEngine.Collision collision)
+        {
+            if (compatible && exports.onCollisionEnter != null)
+            {
+                SendCollision(collision, (data, lent) => exports.onCollisionEnter(data, ref lent));
+            }
+        }
+
+        void OnCollisionStay(UnityEngine.Collision collision)
+        {
+            if (compatible && exports.onCollisionStay != null)
+            {
+                SendCollision(collision, (data, lent) => exports.onCollisionStay(data, ref lent));
+            }
+        }
+
+        void OnCollisionExit(UnityEngine.Collision collision)
+        {
+            if (compatible && exports.onCollisionExit != null)
+            {
+                SendCollision(collision, (data, lent) => exports.onCollisionExit(data, ref lent));
+            }
+        }
 
-                transform.position = new UnityEngine.Vector3(position.x, position.y, position.z);
-                transform.rotation = new UnityEngine.Quaternion(rotation.x, rotation.y, rotation.z, rotation.w);
+        void OnTriggerEnter(Collider other)
+        {
+            if (compatible && exports.onTriggerEnter != null)
+            {
+                SendTrigger(other.gameObject, (data, lent) => exports.onTriggerEnter(data, ref lent));
+            }
+        }
+
+        void OnTriggerStay(Collider other)
+        {
+            if (compatible && exports.onTriggerStay != null)
+            {
+                SendTrigger(other.gameObject, (data, lent) => exports.onTriggerStay(data, ref lent));
+            }
+        }
+
+        void OnTriggerExit(Collider other)
+        {
+            if (compatible && exports.onTriggerExit != null)
+            {
+                SendTrigger(other.gameObject, (data, lent) => exports.onTriggerExit(data, ref lent));
             }
         }
 
+        /// <summary>
+        /// Sends a collision to rust. The contacts and the other object's tag are only lent to rust for
+        /// the call, so they are pinned until it returns and released afterwards.
+        /// </summary>
+        void SendCollision(UnityEngine.Collision collision, Func<DataStruct, Collision, DataStruct> send)
+        {
+            var contacts = new ContactPoint[collision.contactCount];
+            for (int i = 0; i < contacts.Length; i++)
+            {
+                UnityEngine.ContactPoint contact = collision.GetContact(i);
+                contacts[i] = new ContactPoint { point = ToRust(contact.point), normal = ToRust(contact.normal), separation = contact.separation };
+            }
+
+            byte[] tag = Encoding.UTF8.GetBytes(collision.gameObject.tag);
+            GCHandle contactsHandle = GCHandle.Alloc(contacts, GCHandleType.Pinned);
+            GCHandle tagHandle = GCHandle.Alloc(tag, GCHandleType.Pinned);
+            try
+            {
+                Collision lent = new Collision
+                {
+                    other = Track(collision.gameObject),
+                    tag = new Str { ptr = tagHandle.AddrOfPinnedObject(), len = (uint)tag.Length },
+                    contacts = contactsHandle.AddrOfPinnedObject(),
+                    contactCount = (uint)contacts.Length,
+                    relativeVelocity = ToRust(collision.relativeVelocity),
+                    impulse = ToRust(collision.impulse)
+                };
+                Run(data => send(data, lent));
+            }
+            finally
+            {
+                contactsHandle.Free();
+                tagHandle.Free();
+            }
+        }
+
+        /// <summary>
+        /// Sends a trigger to rust. Like collisions, the tag is only lent for the call.
+        /// </summary>
+        void SendTrigger(UnityEngine.GameObject other, Func<DataStruct, Trigger, DataStruct> send)
+        {
+            byte[] tag = Encoding.UTF8.GetBytes(other.tag);
+            GCHandle tagHandle = GCHandle.Alloc(tag, GCHandleType.Pinned);
+            try
+            {
+                Trigger lent = new Trigger { other = Track(other), tag = new Str { ptr = tagHandle.AddrOfPinnedObject(), len = (uint)tag.Length } };
+                Run(data => send(data, lent));
+            }
+            finally
+            {
+                tagHandle.Free();
+            }
+        }
+
+        /// <summary>
+        /// Returns the hash rust knows a gameobject by, remembering it so changes rust sends back can be applied.
+        /// </summary>
+        ulong Track(UnityEngine.GameObject gameObject)
+        {
+            ulong hash = (ulong)(uint)gameObject.GetInstanceID();
+            knownObjects[hash] = gameObject;
+            return hash;
+        }
+
+        static Vector3 ToRust(UnityEngine.Vector3 vector)
+        {
+            return new Vector3 { x = vector.x, y = vector.y, z = vector.z };
+        }
+
+        static Quaternion ToRust(UnityEngine.Quaternion rotation)
+        {
+            return new Quaternion { x = rotation.x, y = rotation.y, z = rotation.z, w = rotation.w };
+        }
+
+        /// <summary>
+        /// Copies a unity transform, along with its parent's world space frame so rust can work out
+        /// local space values. Rust only ever sees world space positions and rotations.
+        /// </summary>
+        Transform ToRust(UnityEngine.Transform unityTransform)
+        {
+            Transform rustTransform = new Transform
+            {
+                position = ToRust(unityTransform.position),
+                rotation = ToRust(unityTransform.rotation),
+                localScale = ToRust(unityTransform.localScale),
+                parentPosition = new Vector3 { x = 0, y = 0, z = 0 },
+                parentRotation = new Quaternion { x = 0, y = 0, z = 0, w = 1 },
+                parentScale = new Vector3 { x = 1, y = 1, z = 1 }
+            };
+
+            UnityEngine.Transform parent = unityTransform.parent;
+            if (parent != null)
+            {
+                rustTransform.parent = Track(parent.gameObject);
+                rustTransform.parentPosition = ToRust(parent.position);
+                rustTransform.parentRotation = ToRust(parent.rotation);
+                rustTransform.parentScale = ToRust(parent.lossyScale);
+            }
+
+            return rustTransform;
+        }
+
+        /// <summary>
+        /// Applies a transform sent back by rust. Setting the world position and rotation lets unity
+        /// keep the local values (and any children) consistent.
+        /// </summary>
+        static void Apply(Transform rustTransform, UnityEngine.Transform unityTransform)
+        {
+            unityTransform.position = new UnityEngine.Vector3(rustTransform.position.x, rustTransform.position.y, rustTransform.position.z);
+            unityTransform.rotation = new UnityEngine.Quaternion(rustTransform.rotation.x, rustTransform.rotation.y, rustTransform.rotation.z, rustTransform.rotation.w);
+            unityTransform.localScale = new UnityEngine.Vector3(rustTransform.localScale.x, rustTransform.localScale.y, rustTransform.localScale.z);
+        }
+
+        /// <summary>
+        /// Runs a lifecycle function with this frame's data, then applies everything rust changed.
+        /// </summary>
+        void Run(Func<DataStruct, DataStruct> function)
+        {
+            // Read fresh every call, as the parent may have moved since
+            m_transform = ToRust(transform);
+            m_gameObject.transform = m_transform;
+
+            dataStruct.transform = m_transform;
+            dataStruct.gameObject = m_gameObject;
+
+            SetTime();
+
+            dataStruct.time = m_time;
+
+            SampleInput();
+
+            dataStruct = function(dataStruct);
+
+            UnityEngine.Time.fixedDeltaTime = dataStruct.time.fixedDeltaTime;
+            UnityEngine.Time.timeScale = dataStruct.time.timeScale;
+
+            m_gameObject = dataStruct.gameObject;
+            m_transform = dataStruct.transform;
+            position = m_transform.position;
+            rotation = m_transform.rotation;
+
+            Apply(m_transform, transform);
+
+            ApplyChanges();
+        }
+
         // This function releases all our pointers to remain safe
         private void OnDestroy()
         {
             // This is VERY important, we must free and release the link before we exit!
-            int value = destroy(dataStruct);
+            if (!compatible)
+            {
+                DLLPool.UnloadDLL(DLLName);
+                return;
+            }
+
+            scripts.Remove(this);
+            int value = exports.destroy(dataStruct);
             DLLPool.UnloadDLL(DLLName);
+
+            // Rust is done with the axes now
+            if (axesHandle.IsAllocated)
+                axesHandle.Free();
+            foreach (GCHandle handle in axisNameHandles)
+                handle.Free();
+            axisNameHandles.Clear();
+            ReleaseLentTags();
+        }
+
+        /// <summary>
+        /// Applies the changes rust queued for other gameobjects. The changes are only lent to us
+        /// until they are released, so they must not be kept.
+        /// </summary>
+        void ApplyChanges()
+        {
+            if (exports.runityChangesAcquire == null || exports.runityChangesRelease == null)
+                return;
+
+            ChangeBuffer buffer = exports.runityChangesAcquire(dataStruct.instance);
+            int size = Marshal.SizeOf<GameObjectChanges>();
+            for (int i = 0; i < buffer.len; i++)
+            {
+                GameObjectChanges change = Marshal.PtrToStructure<GameObjectChanges>(buffer.changes + i * size);
+                if (knownObjects.TryGetValue(change.hash, out UnityEngine.GameObject target) && target != null)
+                {
+                    Apply(change.transform, target.transform);
+                }
+            }
+
+            // Commands are carried out in order, writing back whether each worked so rust can
+            // resolve its handles once the buffer is released
+            Dictionary<ulong, ulong> spawned = new Dictionary<ulong, ulong>();
+            int commandSize = Marshal.SizeOf<Command>();
+            for (int i = 0; i < buffer.commandCount; i++)
+            {
+                IntPtr pointer = buffer.commands + i * commandSize;
+                Command command = Marshal.PtrToStructure<Command>(pointer);
+                RunCommand(ref command, spawned);
+                Marshal.StructureToPtr(command, pointer, false);
+            }
+
+            // Messages come after the commands, so they can be published to what was just spawned
+            int messageSize = Marshal.SizeOf<Message>();
+            for (int i = 0; i < buffer.messageCount; i++)
+            {
+                Message message = Marshal.PtrToStructure<Message>(buffer.messages + i * messageSize);
+                RouteMessage(message, spawned);
+            }
+
+            // Drawings are only lent, so they are copied to be drawn until they expire
+            int drawingSize = Marshal.SizeOf<Drawing>();
+            for (int i = 0; i < buffer.drawingCount; i++)
+            {
+                Drawing drawing = Marshal.PtrToStructure<Drawing>(buffer.drawings + i * drawingSize);
+                drawings.Add(new ShownDrawing
+                {
+                    drawing = drawing,
+                    text = NativeToString(drawing.text.ptr, (int)drawing.text.len),
+                    frame = UnityEngine.Time.frameCount,
+                    until = UnityEngine.Time.time + drawing.duration,
+                });
+            }
+
+            exports.runityChangesRelease(dataStruct.instance);
+        }
+
+        // Mirrors `runity::DrawingKind`
+        enum DrawingKind : uint { Line = 0, Arrow = 1, WireSphere = 2, WireBox = 3, WireCapsule = 4, Label = 5 }
+
+        // A debug drawing sent by rust, with its text copied out. The text pointer in `drawing` isn't valid once the buffer is released.
+        struct ShownDrawing
+        {
+            public Drawing drawing;
+            public string text;
+            public int frame;
+            public float until;
+        }
+
+        List<ShownDrawing> drawings = new List<ShownDrawing>();
+
+        static UnityEngine.Vector3 ToUnity(Vector3 vector)
+        {
+            return new UnityEngine.Vector3(vector.x, vector.y, vector.z);
+        }
+
+        /// <summary>
+        /// Draws the debug drawings rust sent, as gizmos. Like Debug.DrawLine, they only show in the scene view (or the game view with gizmos on).
+        /// </summary>
+        void OnDrawGizmos()
+        {
+            // Drawings last at least the frame they were sent in, then until their time is up
+            drawings.RemoveAll(shown => shown.frame != UnityEngine.Time.frameCount && shown.until <= UnityEngine.Time.time);
+
+            foreach (ShownDrawing shown in drawings)
+            {
+                Drawing drawing = shown.drawing;
+                UnityEngine.Vector3 from = ToUnity(drawing.from);
+                UnityEngine.Vector3 to = ToUnity(drawing.to);
+                Gizmos.color = new UnityEngine.Color(drawing.color.r, drawing.color.g, drawing.color.b, drawing.color.a);
+                Gizmos.matrix = Matrix4x4.identity;
+
+                switch ((DrawingKind)drawing.kind)
+                {
+                    case DrawingKind.Line:
+                        Gizmos.DrawLine(from, to);
+                        break;
+                    case DrawingKind.Arrow:
+                        Gizmos.DrawLine(from, to);
+                        if (to != from)
+                        {
+                            UnityEngine.Vector3 forward = (to - from).normalized;
+                            float head = (to - from).magnitude * 0.2f;
+                            UnityEngine.Quaternion look = UnityEngine.Quaternion.LookRotation(forward);
+                            foreach (UnityEngine.Vector3 side in new[] { UnityEngine.Vector3.right, UnityEngine.Vector3.up, UnityEngine.Vector3.left, UnityEngine.Vector3.down })
+                                Gizmos.DrawLine(to, to - forward * head + look * side * head * 0.5f);
+                        }
+                        break;
+                    case DrawingKind.WireSphere:
+                        Gizmos.DrawWireSphere(from, drawing.radius);
+                        break;
+                    case DrawingKind.WireBox:
+                        Gizmos.matrix = Matrix4x4.TRS(from, new UnityEngine.Quaternion(drawing.rotation.x, drawing.rotation.y, drawing.rotation.z, drawing.rotation.w), UnityEngine.Vector3.one);
+                        Gizmos.DrawWireCube(UnityEngine.Vector3.zero, to);
+                        break;
+                    case DrawingKind.WireCapsule:
+                        Gizmos.DrawWireSphere(from, drawing.radius);
+                        Gizmos.DrawWireSphere(to, drawing.radius);
+                        if (to != from)
+                        {
+                            UnityEngine.Quaternion along = UnityEngine.Quaternion.LookRotation(to - from);
+                            foreach (UnityEngine.Vector3 side in new[] { UnityEngine.Vector3.right, UnityEngine.Vector3.up, UnityEngine.Vector3.left, UnityEngine.Vector3.down })
+                                Gizmos.DrawLine(from + along * side * drawing.radius, to + along * side * drawing.radius);
+                        }
+                        break;
+                    case DrawingKind.Label:
+#if UNITY_EDITOR
+                        GUIStyle style = new GUIStyle();
+                        style.normal.textColor = new UnityEngine.Color(drawing.color.r, drawing.color.g, drawing.color.b, drawing.color.a);
+                        UnityEditor.Handles.Label(from, shown.text, style);
+#endif
+                        break;
+                }
+            }
+
+            Gizmos.matrix = Matrix4x4.identity;
+        }
+
+        // Mirrors `runity::MessageKind`, and the recipients of a published message
+        enum MessageKind : uint { Publish = 0, Subscribe = 1, Unsubscribe = 2 }
+        enum MessageRecipients : uint { Object = 0, Tag = 1, All = 2 }
+
+        /// <summary>
+        /// Updates this script's subscriptions, or routes a message it published. The message is only lent, so its name and payload are copied.
+        /// </summary>
+        void RouteMessage(Message message, Dictionary<ulong, ulong> spawned)
+        {
+            string name = NativeToString(message.name.ptr, (int)message.name.len);
+            switch ((MessageKind)message.kind)
+            {
+                case MessageKind.Subscribe:
+                    subscriptions.Add(name);
+                    break;
+                case MessageKind.Unsubscribe:
+                    subscriptions.Remove(name);
+                    break;
+                default:
+                    byte[] payload = new byte[message.payloadLen];
+                    if (payload.Length > 0)
+                        Marshal.Copy(message.payload, payload, 0, payload.Length);
+
+                    ulong target = spawned.TryGetValue(message.@object, out ulong spawnedHash) ? spawnedHash : message.@object;
+                    string tag = message.tag.len > 0 ? NativeToString(message.tag.ptr, (int)message.tag.len) : null;
+                    Route(name, payload, (MessageRecipients)message.recipients, target, tag, m_gameObject.hash);
+                    break;
+            }
+        }
+
+        // Queues a message for every enabled script subscribed to it, to be posted before its next Update
+        static void Route(string name, byte[] payload, MessageRecipients recipients, ulong target, string tag, ulong sender)
+        {
+            foreach (Runity script in scripts)
+            {
+                if (script == null || !script.isActiveAndEnabled || !script.subscriptions.Contains(name))
+                    continue;
+
+                bool reaches = recipients == MessageRecipients.All
+                    || (recipients == MessageRecipients.Object && (ulong)(uint)script.gameObject.GetInstanceID() == target)
+                    || (recipients == MessageRecipients.Tag && script.gameObject.CompareTag(tag));
+                if (reaches)
+                    script.inbox.Add(new PostedMessage { name = name, payload = payload, sender = sender, frame = UnityEngine.Time.frameCount });
+            }
+        }
+
+        /// <summary>
+        /// Publishes a message from C# (UI code, for example) to every script subscribed to it. Like messages
+        /// published by rust, it is passed to `Script::on_message` in the next frame's update. It has no sender.
+        /// </summary>
+        public static void Publish(string name, byte[] payload)
+        {
+            Route(name, payload, MessageRecipients.All, 0, null, 0);
+        }
+
+        /// <summary>
+        /// Publishes a message from C# to the scripts on a single gameobject.
+        /// </summary>
+        public static void Publish(UnityEngine.GameObject target, string name, byte[] payload)
+        {
+            Route(name, payload, MessageRecipients.Object, (ulong)(uint)target.GetInstanceID(), null, 0);
+        }
+
+        /// <summary>
+        /// Publishes a message from C# to the scripts on every gameobject with a tag.
+        /// </summary>
+        public static void PublishToTag(string tag, string name, byte[] payload)
+        {
+            Route(name, payload, MessageRecipients.Tag, 0, tag, 0);
+        }
+
+        // Rust's profiler scopes, as samplers cached by the name's pointer (names live as long as the DLL). Only used on the main thread.
+        static Dictionary<IntPtr, UnityEngine.Profiling.CustomSampler> samplers = new Dictionary<IntPtr, UnityEngine.Profiling.CustomSampler>();
+        static Stack<UnityEngine.Profiling.CustomSampler> openSamplers = new Stack<UnityEngine.Profiling.CustomSampler>();
+        // Kept in static fields so they aren't collected while rust holds them
+        static readonly ProfilerBeginSampleDelegate beginSample = BeginSample;
+        static readonly ProfilerEndSampleDelegate endSample = EndSample;
+
+        static void BeginSample(Str name)
+        {
+            if (!samplers.TryGetValue(name.ptr, out UnityEngine.Profiling.CustomSampler sampler))
+            {
+                sampler = UnityEngine.Profiling.CustomSampler.Create(NativeToString(name.ptr, (int)name.len));
+                samplers[name.ptr] = sampler;
+            }
+            sampler.Begin();
+            openSamplers.Push(sampler);
+        }
+
+        static void EndSample()
+        {
+            if (openSamplers.Count > 0)
+                openSamplers.Pop().End();
+        }
+
+        /// <summary>
+        /// Asks the DLL to write the profiler scopes it recorded (those run off the main thread, which unity's profiler doesn't get) as a
+        /// Chrome trace, for chrome://tracing or Perfetto.
+        /// </summary>
+        [ContextMenu("Write Rust Chrome Trace")]
+        public void WriteChromeTrace()
+        {
+            if (exports.runityWriteChromeTrace == null)
+                return;
+
+            string path = System.IO.Path.Combine(Application.persistentDataPath, "runity-trace.json");
+            byte[] pathBytes = Encoding.UTF8.GetBytes(path);
+            GCHandle pathHandle = GCHandle.Alloc(pathBytes, GCHandleType.Pinned);
+            try
+            {
+                if (exports.runityWriteChromeTrace(new Str { ptr = pathHandle.AddrOfPinnedObject(), len = (uint)pathBytes.Length }))
+                    UnityEngine.Debug.Log("Rust profiler trace written to " + path);
+                else
+                    UnityEngine.Debug.LogWarning("Couldn't write the rust profiler trace to " + path);
+            }
+            finally
+            {
+                pathHandle.Free();
+            }
+        }
+
+        /// <summary>
+        /// Returns the span timings rust's `SpanTimer` has kept, oldest first, for an overlay to chart. Empty unless
+        /// the DLL installed it. Each name is copied, as it is only lent while the DLL stays loaded.
+        /// </summary>
+        public List<(ulong frame, string name, uint calls, double totalMs, double minMs, double maxMs)> GetSpanSamples()
+        {
+            var result = new List<(ulong, string, uint, double, double, double)>();
+            if (exports.runitySpanSamples == null)
+                return result;
+
+            // Like FindAllWithTag, rust is asked again if there wasn't enough room
+            uint count = exports.runitySpanSamples(IntPtr.Zero, 0);
+            int size = Marshal.SizeOf<SpanSample>();
+            IntPtr buffer = Marshal.AllocHGlobal(size * (int)Math.Max(count, 1));
+            try
+            {
+                uint written = exports.runitySpanSamples(buffer, count);
+                for (int i = 0; i < Math.Min(written, count); i++)
+                {
+                    SpanSample sample = Marshal.PtrToStructure<SpanSample>(buffer + i * size);
+                    result.Add((sample.frame, NativeToString(sample.name.ptr, (int)sample.name.len), sample.calls,
+                        sample.totalNs / 1e6, sample.minNs / 1e6, sample.maxNs / 1e6));
+                }
+            }
+            finally
+            {
+                Marshal.FreeHGlobal(buffer);
+            }
+            return result;
+        }
+
+        /// <summary>
+        /// Posts rust the messages published before this frame. They are only lent for the call, so they are pinned until it returns.
+        /// </summary>
+        void PostMessages()
+        {
+            if (exports.runityPostMessages == null || inbox.Count == 0)
+                return;
+
+            int frame = UnityEngine.Time.frameCount;
+            List<PostedMessage> posting = inbox.FindAll(message => message.frame < frame);
+            if (posting.Count == 0)
+                return;
+            inbox.RemoveAll(message => message.frame < frame);
+
+            var lent = new ReceivedMessage[posting.Count];
+            var handles = new List<GCHandle>();
+            try
+            {
+                for (int i = 0; i < posting.Count; i++)
+                {
+                    byte[] name = Encoding.UTF8.GetBytes(posting[i].name);
+                    GCHandle nameHandle = GCHandle.Alloc(name, GCHandleType.Pinned);
+                    GCHandle payloadHandle = GCHandle.Alloc(posting[i].payload, GCHandleType.Pinned);
+                    handles.Add(nameHandle);
+                    handles.Add(payloadHandle);
+
+                    lent[i] = new ReceivedMessage
+                    {
+                        name = new Str { ptr = nameHandle.AddrOfPinnedObject(), len = (uint)name.Length },
+                        payload = payloadHandle.AddrOfPinnedObject(),
+                        payloadLen = (uint)posting[i].payload.Length,
+                        sender = posting[i].sender
+                    };
+                }
+
+                GCHandle lentHandle = GCHandle.Alloc(lent, GCHandleType.Pinned);
+                handles.Add(lentHandle);
+                exports.runityPostMessages(dataStruct.instance, lentHandle.AddrOfPinnedObject(), (uint)lent.Length);
+            }
+            finally
+            {
+                foreach (GCHandle handle in handles)
+                    handle.Free();
+            }
+        }
+
+        // Mirrors `runity::CommandKind` and `runity::CommandStatus`
+        enum CommandKind : uint { Spawn = 0, Destroy = 1, SetActive = 2, SetParent = 3 }
+        enum CommandStatus : uint { Pending = 0, Done = 1, UnknownPrefab = 2, UnknownObject = 3, InvalidParent = 4 }
+
+        /// <summary>
+        /// Carries out a command sent by rust. Handles spawned earlier in the same buffer are mapped to the gameobject they spawned.
+        /// </summary>
+        void RunCommand(ref Command command, Dictionary<ulong, ulong> spawned)
+        {
+            ulong targetHash = spawned.TryGetValue(command.target, out ulong spawnedHash) ? spawnedHash : command.target;
+            knownObjects.TryGetValue(targetHash, out UnityEngine.GameObject target);
+
+            CommandStatus status;
+            switch ((CommandKind)command.kind)
+            {
+                case CommandKind.Spawn:
+                    UnityEngine.GameObject prefab = FindPrefab(command);
+                    if (prefab == null)
+                    {
+                        status = CommandStatus.UnknownPrefab;
+                        break;
+                    }
+
+                    UnityEngine.GameObject instance = Instantiate(prefab,
+                        new UnityEngine.Vector3(command.transform.position.x, command.transform.position.y, command.transform.position.z),
+                        new UnityEngine.Quaternion(command.transform.rotation.x, command.transform.rotation.y, command.transform.rotation.z, command.transform.rotation.w));
+                    command.@object = Track(instance);
+                    spawned[command.target] = command.@object;
+                    status = CommandStatus.Done;
+                    break;
+                case CommandKind.Destroy:
+                    status = target != null ? CommandStatus.Done : CommandStatus.UnknownObject;
+                    if (target != null)
+                        Destroy(target);
+                    break;
+                case CommandKind.SetActive:
+                    status = target != null ? CommandStatus.Done : CommandStatus.UnknownObject;
+                    if (target != null)
+                        target.SetActive(command.active);
+                    break;
+                default:
+                    UnityEngine.Transform parent = null;
+                    if (command.parent != 0)
+                    {
+                        ulong parentHash = spawned.TryGetValue(command.parent, out ulong spawnedParent) ? spawnedParent : command.parent;
+                        if (knownObjects.TryGetValue(parentHash, out UnityEngine.GameObject parentObject) && parentObject != null)
+                            parent = parentObject.transform;
+                    }
+
+                    if (target == null)
+                        status = CommandStatus.UnknownObject;
+                    else if ((command.parent != 0 && parent == null) || (parent != null && parent.IsChildOf(target.transform)))
+                        status = CommandStatus.InvalidParent;
+                    else
+                    {
+                        target.transform.SetParent(parent, command.worldPositionStays);
+                        status = CommandStatus.Done;
+                    }
+                    break;
+            }
+
+            command.status = (uint)status;
+        }
+
+        // Prefabs are found by name, or by their index in `prefabs`
+        UnityEngine.GameObject FindPrefab(Command command)
+        {
+            if (command.prefab.len == 0)
+                return command.prefabId < (ulong)prefabs.Length ? prefabs[command.prefabId] : null;
+
+            string name = NativeToString(command.prefab.ptr, (int)command.prefab.len);
+            foreach (UnityEngine.GameObject prefab in prefabs)
+            {
+                if (prefab != null && prefab.name == name)
+                    return prefab;
+            }
+            return null;
+        }
+
+        /// <summary>
+        /// Samples the keyboard, mouse and axes into the data struct, once per frame. Last frame's
+        /// input is kept alongside it, so rust can tell which keys went down or up this frame.
+        /// </summary>
+        void SampleInput()
+        {
+            if (inputFrame == UnityEngine.Time.frameCount)
+                return;
+            inputFrame = UnityEngine.Time.frameCount;
+
+            if (axes == null)
+            {
+                axes = new Axis[axisNames.Length];
+                for (int i = 0; i < axisNames.Length; i++)
+                {
+                    byte[] name = Encoding.UTF8.GetBytes(axisNames[i]);
+                    GCHandle nameHandle = GCHandle.Alloc(name, GCHandleType.Pinned);
+                    axisNameHandles.Add(nameHandle);
+                    axes[i].name = new Str { ptr = nameHandle.AddrOfPinnedObject(), len = (uint)name.Length };
+                }
+                axesHandle = GCHandle.Alloc(axes, GCHandleType.Pinned);
+                lastMousePosition = UnityEngine.Input.mousePosition;
+            }
+
+            InputSnapshot snapshot = new InputSnapshot { keys = new ulong[8] };
+            foreach (KeyCode key in keyCodes)
+            {
+                int code = (int)key;
+                if (code < 512 && UnityEngine.Input.GetKey(key))
+                    snapshot.keys[code / 64] |= 1UL << (code % 64);
+            }
+            for (int button = 0; button < 3; button++)
+            {
+                if (UnityEngine.Input.GetMouseButton(button))
+                    snapshot.mouseButtons |= 1u << button;
+            }
+
+            UnityEngine.Vector3 mousePosition = UnityEngine.Input.mousePosition;
+            snapshot.mousePosition = ToRust(mousePosition);
+            snapshot.mouseDelta = ToRust(mousePosition - lastMousePosition);
+            snapshot.mouseScroll = ToRust(UnityEngine.Input.mouseScrollDelta);
+            lastMousePosition = mousePosition;
+
+            // The axes are pinned, so they can be updated in place
+            for (int i = 0; i < axes.Length; i++)
+                axes[i].value = UnityEngine.Input.GetAxis(axisNames[i]);
+            snapshot.axes = axesHandle.AddrOfPinnedObject();
+            snapshot.axisCount = (uint)axes.Length;
+
+            // Only the current snapshot's axes are read
+            InputSnapshot previous = dataStruct.input.current;
+            if (previous.keys == null)
+                previous.keys = new ulong[8];
+            previous.axes = IntPtr.Zero;
+            previous.axisCount = 0;
+
+            dataStruct.input = new Input { current = snapshot, previous = previous };
         }
 
         /// <summary>
@@ -332,7 +1028,7 @@ namespace runity_test
         /// </summary>
         /// <param name="ptr">The pointer to convert</param>
         /// <returns>The string</returns>
-        private string NativeToString(IntPtr ptr, int length)
+        private static string NativeToString(IntPtr ptr, int length)
         {
             // Allocate a buffer
             var buffer = ArrayPool<byte>.Shared.Rent(length);
@@ -350,93 +1046,484 @@ namespace runity_test
         }
 
 
-        public void GetGameObjectFromTag(String tag, IntPtr gameObjectPtr)
+        /// <summary>
+        /// The lookups handed to rust in every gameobject. Kept in a field, so the delegates aren't collected while rust holds them.
+        /// </summary>
+        GameObjectLookup CreateLookup()
+        {
+            return new GameObjectLookup
+            {
+                find = new FindGameObjectDelegate(Find),
+                findWithTag = new FindGameObjectWithTagDelegate(FindWithTag),
+                findAllWithTag = new FindGameObjectsWithTagDelegate(FindAllWithTag),
+                findById = new FindGameObjectByIdDelegate(FindById),
+                childCount = new GetChildCountDelegate(ChildCount),
+                child = new GetChildDelegate(Child)
+            };
+        }
+
+        // The tags lent to rust by the last lookup. Rust copies them straight away, so they are freed on the next one.
+        void ReleaseLentTags()
+        {
+            foreach (GCHandle handle in lentTags)
+                handle.Free();
+            lentTags.Clear();
+        }
+
+        FoundGameObject ToFound(UnityEngine.GameObject foundObj)
+        {
+            byte[] tag = Encoding.UTF8.GetBytes(foundObj.tag);
+            GCHandle tagHandle = GCHandle.Alloc(tag, GCHandleType.Pinned);
+            lentTags.Add(tagHandle);
+
+            return new FoundGameObject
+            {
+                hash = Track(foundObj),
+                tag = new Str { ptr = tagHandle.AddrOfPinnedObject(), len = (uint)tag.Length },
+                transform = ToRust(foundObj.transform)
+            };
+        }
+
+        // Writes a found gameobject for rust, or returns false if there wasn't one
+        bool WriteFound(UnityEngine.GameObject foundObj, IntPtr foundPtr)
+        {
+            ReleaseLentTags();
+
+            if (foundObj == null)
+                return false;
+
+            Marshal.StructureToPtr(ToFound(foundObj), foundPtr, false);
+            return true;
+        }
+
+        public bool Find(Str name, IntPtr foundPtr)
+        {
+            return WriteFound(UnityEngine.GameObject.Find(NativeToString(name.ptr, (int)name.len)), foundPtr);
+        }
+
+        public bool FindWithTag(Str tag, IntPtr foundPtr)
+        {
+            // The tag we are given is only lent to us for this call, so it is copied rather than kept
+            var tagString = NativeToString(tag.ptr, (int)tag.len);
+
+            // Pooled objects are checked first, to avoid calling Find on gameobjects every frame. A pooled
+            // object may have been destroyed since, in which case it is found again.
+            UnityEngine.GameObject foundObj;
+            if (!objectPool.TryGetValue(tagString, out foundObj) || foundObj == null)
+            {
+                foundObj = UnityEngine.GameObject.FindGameObjectWithTag(tagString);
+                if (foundObj != null)
+                    objectPool[tagString] = foundObj;
+            }
+
+            return WriteFound(foundObj, foundPtr);
+        }
+
+        public uint FindAllWithTag(Str tag, IntPtr foundPtr, uint capacity)
         {
-            // The gameObjectPtr is a pointer to a GameObject. This will be modified by this code and then used by rust.
+            ReleaseLentTags();
 
-            // Convert the IntPtr to a GameObject
-            GameObject gameObject = Marshal.PtrToStructure<GameObject>(gameObjectPtr);
+            UnityEngine.GameObject[] objects = UnityEngine.GameObject.FindGameObjectsWithTag(NativeToString(tag.ptr, (int)tag.len));
 
-            // We check if the object with its tag is not already pooled. If it is, we make sure it hasn't been destroyed and then take it from the pool.
-            // otherwise, we load it and add it to the pool
+            // Rust asks again with enough room if there are more than `capacity`
+            int size = Marshal.SizeOf<FoundGameObject>();
+            for (int i = 0; i < objects.Length && i < capacity; i++)
+                Marshal.StructureToPtr(ToFound(objects[i]), foundPtr + i * size, false);
 
-            // Convert the tag into a string
-            var tagString = NativeToString(tag.ptr, tag.len);
+            return (uint)objects.Length;
+        }
 
-            // This long, complex code basically checks that the object exists in the pool. If it doesn't, we add it. 
-            //
-            // It will find the gameobject, find its transforms, then add them to the gameobject that it returns. This should be callable from rust.
-            //
-            // If it doesn't find the gameobject, it defaults to zero for pos and rot. This is simply a fallback.
+        // The gameobject with the given hash, or null if there isn't one
+        UnityEngine.GameObject Resolve(ulong hash)
+        {
             UnityEngine.GameObject foundObj;
+            if (knownObjects.TryGetValue(hash, out foundObj) && foundObj != null)
+                return foundObj;
 
-            if (objectPool.TryGetValue(tagString, out foundObj))
+            // Rust hasn't been handed this gameobject yet, so every gameobject is searched
+            foreach (UnityEngine.GameObject candidate in UnityEngine.Object.FindObjectsOfType<UnityEngine.GameObject>())
             {
-                // Make sure it isn't a false positive, and return the value.
-                // If it is, find it and store it.
-                if (foundObj == null)
+                if ((ulong)(uint)candidate.GetInstanceID() == hash)
+                    return candidate;
+            }
+
+            return null;
+        }
+
+        public bool FindById(ulong hash, IntPtr foundPtr)
+        {
+            return WriteFound(Resolve(hash), foundPtr);
+        }
+
+        public uint ChildCount(ulong hash)
+        {
+            UnityEngine.GameObject parent = Resolve(hash);
+            return parent == null ? 0 : (uint)parent.transform.childCount;
+        }
+
+        public bool Child(ulong hash, uint index, IntPtr foundPtr)
+        {
+            UnityEngine.GameObject parent = Resolve(hash);
+            if (parent == null || index >= parent.transform.childCount)
+                return WriteFound(null, foundPtr);
+
+            return WriteFound(parent.transform.GetChild((int)index).gameObject, foundPtr);
+        }
+
+        /// <summary>
+        /// The component access handed to rust in every gameobject. Kept in a field, like the lookups.
+        /// </summary>
+        ComponentAccess CreateComponentAccess()
+        {
+            return new ComponentAccess
+            {
+                hasComponent = new HasComponentDelegate(HasComponent),
+                getProperties = new GetPropertiesDelegate(GetProperties),
+                setProperties = new SetPropertiesDelegate(SetProperties),
+                invoke = new InvokeComponentDelegate(InvokeComponent)
+            };
+        }
+
+        // Finds a component type by the name rust uses for it, such as "Rigidbody". Unity's own
+        // components are looked for first, then components written in C#.
+        Type FindComponentType(string name)
+        {
+            Type type;
+            if (componentTypes.TryGetValue(name, out type))
+                return type;
+
+            foreach (Assembly assembly in AppDomain.CurrentDomain.GetAssemblies())
+            {
+                type = assembly.GetType("UnityEngine." + name) ?? assembly.GetType(name);
+                if (type != null && typeof(UnityEngine.Component).IsAssignableFrom(type))
+                    break;
+                type = null;
+            }
+
+            componentTypes[name] = type;
+            return type;
+        }
+
+        // Returns the component rust is asking for, or null if there isn't one
+        UnityEngine.Component FindComponent(ulong hash, Str component)
+        {
+            UnityEngine.GameObject obj;
+            if (!knownObjects.TryGetValue(hash, out obj) || obj == null)
+                return null;
+
+            Type type = FindComponentType(NativeToString(component.ptr, (int)component.len));
+            return type == null ? null : obj.GetComponent(type);
+        }
+
+        // Follows a property path (such as "material.color") to the object holding its last member
+        static bool FindMember(object owner, string path, out object holder, out MemberInfo member)
+        {
+            holder = owner;
+            member = null;
+
+            string[] names = path.Split('.');
+            for (int i = 0; i < names.Length; i++)
+            {
+                if (holder == null)
+                    return false;
+
+                Type type = holder.GetType();
+                member = (MemberInfo)type.GetProperty(names[i]) ?? type.GetField(names[i]);
+                if (member == null)
+                    return false;
+
+                if (i < names.Length - 1)
+                    holder = member is PropertyInfo property ? property.GetValue(holder) : ((FieldInfo)member).GetValue(holder);
+            }
+
+            return true;
+        }
+
+        // Converts a value sent by rust to `type`, returning false if it can't be
+        static bool FromPropertyValue(PropertyValue value, Type type, out object result)
+        {
+            switch (value.kind)
+            {
+                case 1: result = value.integer != 0; break;
+                // Enums (such as ForceMode) are sent as ints
+                case 2: result = type.IsEnum ? Enum.ToObject(type, value.integer) : type == typeof(float) ? (object)(float)value.integer : value.integer; break;
+                case 3: result = value.x; break;
+                case 4: result = new UnityEngine.Vector3(value.x, value.y, value.z); break;
+                case 5: result = new UnityEngine.Color(value.x, value.y, value.z, value.w); break;
+                default: result = null; return false;
+            }
+
+            return type.IsInstanceOfType(result);
+        }
+
+        // Converts a value to send to rust, returning false if rust has no kind of value for it
+        static bool ToPropertyValue(object value, out PropertyValue result)
+        {
+            result = new PropertyValue();
+            switch (value)
+            {
+                case bool b: result.kind = 1; result.integer = b ? 1 : 0; return true;
+                case int i: result.kind = 2; result.integer = i; return true;
+                case Enum e: result.kind = 2; result.integer = Convert.ToInt32(e); return true;
+                case float f: result.kind = 3; result.x = f; return true;
+                case UnityEngine.Vector3 v: result.kind = 4; result.x = v.x; result.y = v.y; result.z = v.z; return true;
+                case UnityEngine.Color c: result.kind = 5; result.x = c.r; result.y = c.g; result.z = c.b; result.w = c.a; return true;
+                default: return false;
+            }
+        }
+
+        public bool HasComponent(ulong hash, Str component)
+        {
+            return FindComponent(hash, component) != null;
+        }
+
+        public void GetProperties(ulong hash, Str component, IntPtr propertiesPtr, uint count)
+        {
+            UnityEngine.Component target = FindComponent(hash, component);
+            if (target == null)
+                return;
+
+            // Every property in the batch is read in this one call
+            int size = Marshal.SizeOf<Property>();
+            for (int i = 0; i < count; i++)
+            {
+                IntPtr propertyPtr = propertiesPtr + i * size;
+                Property property = Marshal.PtrToStructure<Property>(propertyPtr);
+
+                try
                 {
-                    foundObj = UnityEngine.GameObject.FindGameObjectWithTag(tagString);
-                    objectPool.Add(tagString, foundObj);
-                }
+                    object holder;
+                    MemberInfo member;
+                    if (!FindMember(target, NativeToString(property.name.ptr, (int)property.name.len), out holder, out member))
+                        continue;
 
-                Transform transform = new Transform
+                    object value = member is PropertyInfo info ? info.GetValue(holder) : ((FieldInfo)member).GetValue(holder);
+                    property.found = ToPropertyValue(value, out property.@value);
+                }
+                catch (Exception e)
                 {
-                    position = new Vector3 { x = foundObj.transform.position.x, y = foundObj.transform.position.y, z = foundObj.transform.position.z },
-                    rotation = new Quaternion { x = foundObj.transform.rotation.x, y = foundObj.transform.rotation.y, z = foundObj.transform.rotation.z, w = foundObj.transform.rotation.w }
-                };
-                gameObject.transform = transform;
-                gameObject.tag = tag; // This assigns a borrowed string to the gameObject's tag. This is fairly unsafe behavior in rust. Therefore, the GameObject returned 
-                                      // from this function is read-only.
+                    // Exceptions can't cross into rust, so the property is reported as missing
+                    UnityEngine.Debug.LogException(e);
+                    continue;
+                }
+
+                Marshal.StructureToPtr(property, propertyPtr, false);
             }
-            else
+        }
+
+        public void SetProperties(ulong hash, Str component, IntPtr propertiesPtr, uint count)
+        {
+            UnityEngine.Component target = FindComponent(hash, component);
+            if (target == null)
+                return;
+
+            int size = Marshal.SizeOf<Property>();
+            for (int i = 0; i < count; i++)
             {
-                // Our requested object hasn't been found, so now we search it. If it doesn't exist, return
-                // a default value. If it does, store it and return the value.
-                foundObj = UnityEngine.GameObject.FindGameObjectWithTag(tagString);
-                if (foundObj == null)
+                IntPtr propertyPtr = propertiesPtr + i * size;
+                Property property = Marshal.PtrToStructure<Property>(propertyPtr);
+
+                try
                 {
-                    UnityEngine.Debug.LogWarning("Warning: Tag -> " + tagString  + " was not found. Falling back to default transform. (all values zeroed) ");
-                    Transform transform = new Transform
+                    object holder;
+                    MemberInfo member;
+                    if (!FindMember(target, NativeToString(property.name.ptr, (int)property.name.len), out holder, out member))
+                        continue;
+
+                    object value;
+                    if (member is PropertyInfo info)
                     {
-                        position = new Vector3 { x = 0, y = 0, z = 0 },
-                        rotation = new Quaternion { x = 0, y = 0, z = 0, w = 0 }
-                    };
-                    gameObject.transform = transform;
-                    gameObject.tag = tag;
+                        if (!info.CanWrite || !FromPropertyValue(property.@value, info.PropertyType, out value))
+                            continue;
+                        info.SetValue(holder, value);
+                    }
+                    else
+                    {
+                        FieldInfo field = (FieldInfo)member;
+                        if (!FromPropertyValue(property.@value, field.FieldType, out value))
+                            continue;
+                        field.SetValue(holder, value);
+                    }
+                    property.found = true;
                 }
-                else
+                catch (Exception e)
                 {
-                    Transform transform = new Transform
-                    {
-                        position = new Vector3 { x = foundObj.transform.position.x, y = foundObj.transform.position.y, z = foundObj.transform.position.z },
-                        rotation = new Quaternion { x = foundObj.transform.rotation.x, y = foundObj.transform.rotation.y, z = foundObj.transform.rotation.z, w = foundObj.transform.rotation.w }
-                    };
-                    gameObject.transform = transform;
-                    gameObject.tag = tag; //  This is pretty unsafe as the tag is read-only, but the returned GameObject can be modified.
-                    objectPool.Add(tagString, foundObj);
+                    UnityEngine.Debug.LogException(e);
+                    continue;
+                }
+
+                Marshal.StructureToPtr(property, propertyPtr, false);
+            }
+        }
+
+        public bool InvokeComponent(ulong hash, Str component, Str method, IntPtr argumentsPtr, uint count)
+        {
+            UnityEngine.Component target = FindComponent(hash, component);
+            if (target == null)
+                return false;
+
+            string methodName = NativeToString(method.ptr, (int)method.len);
+
+            PropertyValue[] arguments = new PropertyValue[count];
+            int size = Marshal.SizeOf<PropertyValue>();
+            for (int i = 0; i < count; i++)
+                arguments[i] = Marshal.PtrToStructure<PropertyValue>(argumentsPtr + i * size);
+
+            // The first overload the arguments can be converted to is called
+            foreach (MethodInfo candidate in target.GetType().GetMethods())
+            {
+                ParameterInfo[] parameters = candidate.GetParameters();
+                if (candidate.Name != methodName || parameters.Length != count)
+                    continue;
+
+                object[] values = new object[count];
+                bool matches = true;
+                for (int i = 0; i < count && matches; i++)
+                    matches = FromPropertyValue(arguments[i], parameters[i].ParameterType, out values[i]);
+
+                if (!matches)
+                    continue;
+
+                try
+                {
+                    candidate.Invoke(target, values);
                 }
-            } 
-            // Now copy the newly created GameObject into the gameObjectPtr
-            Marshal.StructureToPtr(gameObject, gameObjectPtr, false);
+                catch (Exception e)
+                {
+                    UnityEngine.Debug.LogException(e);
+                }
+                return true;
+            }
+
+            return false;
+        }
+
+        /// <summary>
+        /// The physics queries handed to rust. Kept in a field, like the lookups.
+        /// </summary>
+        Physics CreatePhysics()
+        {
+            return new Physics
+            {
+                cast = new PhysicsCastDelegate(PhysicsCast),
+                overlap = new PhysicsOverlapDelegate(PhysicsOverlap)
+            };
+        }
+
+        // Writes up to `capacity` hits for rust, lending each the tag of the gameobject hit (like
+        // the lookups), and returns how many there are
+        uint WriteHits(List<FoundHit> hits, IntPtr hitsPtr, uint capacity)
+        {
+            int size = Marshal.SizeOf<FoundHit>();
+            for (int i = 0; i < hits.Count && i < capacity; i++)
+                Marshal.StructureToPtr(hits[i], hitsPtr + i * size, false);
+
+            return (uint)hits.Count;
         }
 
-        public void Log(String message)
+        FoundHit ToFoundHit(UnityEngine.Vector3 point, UnityEngine.Vector3 normal, float distance, UnityEngine.Collider collider)
         {
-            string messageString = NativeToString(message.ptr, message.len);
+            byte[] tag = Encoding.UTF8.GetBytes(collider.gameObject.tag);
+            GCHandle tagHandle = GCHandle.Alloc(tag, GCHandleType.Pinned);
+            lentTags.Add(tagHandle);
+
+            return new FoundHit
+            {
+                point = ToRust(point),
+                normal = ToRust(normal),
+                distance = distance,
+                @object = Track(collider.gameObject),
+                tag = new Str { ptr = tagHandle.AddrOfPinnedObject(), len = (uint)tag.Length }
+            };
+        }
+
+        public uint PhysicsCast(ref PhysicsQuery query, IntPtr hitsPtr, uint capacity)
+        {
+            ReleaseLentTags();
+
+            UnityEngine.Vector3 origin = new UnityEngine.Vector3(query.origin.x, query.origin.y, query.origin.z);
+            UnityEngine.Vector3 direction = new UnityEngine.Vector3(query.direction.x, query.direction.y, query.direction.z);
+            bool sphere = query.shape == 1;
+
+            UnityEngine.RaycastHit[] found;
+            if (query.all)
+            {
+                found = sphere
+                    ? UnityEngine.Physics.SphereCastAll(origin, query.radius, direction, query.maxDistance, query.layerMask)
+                    : UnityEngine.Physics.RaycastAll(origin, direction, query.maxDistance, query.layerMask);
+            }
+            else
+            {
+                // Only the closest hit is wanted
+                UnityEngine.RaycastHit hit;
+                bool didHit = sphere
+                    ? UnityEngine.Physics.SphereCast(origin, query.radius, direction, out hit, query.maxDistance, query.layerMask)
+                    : UnityEngine.Physics.Raycast(origin, direction, out hit, query.maxDistance, query.layerMask);
+                found = didHit ? new UnityEngine.RaycastHit[] { hit } : new UnityEngine.RaycastHit[0];
+            }
+
+            List<FoundHit> hits = new List<FoundHit>();
+            foreach (UnityEngine.RaycastHit hit in found)
+                hits.Add(ToFoundHit(hit.point, hit.normal, hit.distance, hit.collider));
+
+            return WriteHits(hits, hitsPtr, capacity);
+        }
+
+        public uint PhysicsOverlap(ref PhysicsQuery query, IntPtr hitsPtr, uint capacity)
+        {
+            ReleaseLentTags();
+
+            UnityEngine.Vector3 center = new UnityEngine.Vector3(query.origin.x, query.origin.y, query.origin.z);
+            UnityEngine.Collider[] colliders = query.shape == 2
+                ? UnityEngine.Physics.OverlapBox(center, new UnityEngine.Vector3(query.halfExtents.x, query.halfExtents.y, query.halfExtents.z), new UnityEngine.Quaternion(query.rotation.x, query.rotation.y, query.rotation.z, query.rotation.w), query.layerMask)
+                : UnityEngine.Physics.OverlapSphere(center, query.radius, query.layerMask);
+
+            // Overlaps don't give a point, so the closest point on each collider to the center is sent
+            List<FoundHit> hits = new List<FoundHit>();
+            foreach (UnityEngine.Collider collider in colliders)
+            {
+                UnityEngine.Vector3 point = collider.ClosestPoint(center);
+                hits.Add(ToFoundHit(point, (center - point).normalized, UnityEngine.Vector3.Distance(center, point), collider));
+            }
+
+            return WriteHits(hits, hitsPtr, capacity);
+        }
+
+        public void Log(Str message)
+        {
+            string messageString = NativeToString(message.ptr, (int)message.len);
             UnityEngine.Debug.Log(messageString);
         }
 
-        public void LogWarning(String message)
+        public void LogWarning(Str message)
         {
-            string messageString = NativeToString(message.ptr, message.len);
+            string messageString = NativeToString(message.ptr, (int)message.len);
             UnityEngine.Debug.LogWarning(messageString);
         }
 
-        public void LogError(String message)
+        public void LogError(Str message)
         {
-            string messageString = NativeToString(message.ptr, message.len);
+            string messageString = NativeToString(message.ptr, (int)message.len);
             UnityEngine.Debug.LogError(messageString);
         }
+
+        public void WriteLogRecord(ref LogRecord record)
+        {
+            string message = NativeToString(record.message.ptr, (int)record.message.len);
+            string target = NativeToString(record.target.ptr, (int)record.target.len);
+            string file = NativeToString(record.file.ptr, (int)record.file.len);
+
+            LogType type = record.level == 1 ? LogType.Error : record.level == 2 ? LogType.Warning : LogType.Log;
+            string text = target.Length > 0 ? "[" + target + "] " + message : message;
+
+            // The C# stack trace is of no use here, so it is replaced with the rust location, which the console links to
+            if (file.Length > 0)
+            {
+                string path = rustSourceRoot.Length > 0 ? rustSourceRoot.TrimEnd('/', '\\') + "/" + file : file;
+                text += "\n(at " + path.Replace('\\', '/') + ":" + record.line + ")";
+            }
+
+            UnityEngine.Debug.LogFormat(type, LogOption.NoStacktrace, null, "{0}", text);
+        }
     }
 }
\ No newline at end of file
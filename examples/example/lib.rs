@@ -1,52 +1,42 @@
-use runity::{DataStruct, Quaternion, String, Vector3};
-
-// This function is called when the script is first run
-#[no_mangle]
-pub extern "C" fn awake(mut _data: DataStruct) -> DataStruct{
-    _data
-}
-
-// This function is called after awake, use it to initialize values and setup the rest of the script
-#[no_mangle]
-pub extern "C" fn start(mut data: DataStruct) -> DataStruct{
-    data.transform.position = Vector3::translate(data.transform.position, Vector3::new(0.0, 5.0, 0.0));
-    data
+use runity::{DataStruct, Quaternion, Script, Vector3};
+
+// The script's state lives in the struct, rather than in statics.
+// `#[runity::script]` generates the awake, start, update and destroy functions unity expects.
+#[runity::script]
+#[derive(Default)]
+pub struct Follower{
+    speed: f32,
 }
 
-// This function is run every frame
-#[no_mangle]
-pub extern "C" fn update(mut data: DataStruct) -> DataStruct{
-    let time = data.time; // Extract time related things.
-
-    let tag = String::from("Player"); // Get the tag for "Player". Make sure to free this string once you're done with it (before you return).
-
-
-    let player_obj = data.game_object.get_gameobject_from_tag(&tag); // Get the game object associated with the tag
+impl Script for Follower{
+    // This function is called when the script is first run
+    fn awake(&mut self, _data: &mut DataStruct){
+        self.speed = 0.0015;
+    }
 
-    let pos_to_go_towards = player_obj.transform.position;
+    // This function is called after awake, use it to initialize values and setup the rest of the script
+    fn start(&mut self, data: &mut DataStruct){
+        data.transform.position = Vector3::translate(data.transform.position, Vector3::new(0.0, 5.0, 0.0));
+    }
 
-    data.transform.position = Vector3::lerp(data.transform.position, pos_to_go_towards, 0.0015 * time.delta_time); // move towards the player using lerp, scaling to the timestep
+    // This function is run every frame
+    fn update(&mut self, data: &mut DataStruct){
+        let time = data.time; // Extract time related things.
 
-    data.transform.rotation *= Quaternion::from_euler(5.0 * time.delta_time, 5.0 * time.delta_time, 5.0 * time.delta_time);
+        // Get the game object associated with the "Player" tag, if there is one
+        if let Some(player_obj) = data.game_object.find_with_tag("Player"){
+            let pos_to_go_towards = player_obj.transform.position;
 
-    data.debug.log("Hello from rust!".to_string());
+            data.transform.position = Vector3::lerp(data.transform.position, pos_to_go_towards, self.speed * time.delta_time); // move towards the player using lerp, scaling to the timestep
+        }
 
+        data.transform.rotation *= Quaternion::from_euler(5.0 * time.delta_time, 5.0 * time.delta_time, 5.0 * time.delta_time);
 
+        data.debug.log("Hello from rust!");
+    }
 
-    tag.free(); // free the tag
-
-    data
+    // This function is called when the script is destroyed. Strings (such as the gameobject's
+    // tag) free themselves when dropped, so there is nothing to clean up here.
+    fn destroy(&mut self, _data: &mut DataStruct){
+    }
 }
-
-// This function is called when the script is destroyed
-#[no_mangle]
-pub extern "C" fn destroy(data: DataStruct) -> i32{
-    // This function is called when the script is destroyed. This MUST be called, to ensure that the memory is freed.
-
-    // This is a good place to free any memory that was allocated in the awake function. For example, if you allocated a string, you should free it here.
-
-    // For example, the tag of the game object is a string, so we need to free it.
-    data.game_object.tag.free();
-
-    0
-}
\ No newline at end of file
@@ -0,0 +1,706 @@
+/*
+ * runity.h
+ *
+ * Generated by runity-bindgen from the rust definitions in runity. Do not edit this
+ * file by hand - regenerate it with `cargo run --bin runity-bindgen -- c`.
+ */
+
+#ifndef RUNITY_H
+#define RUNITY_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The ABI version this header describes. Check it against `runity_abi_version()` before calling into a library. */
+#define RUNITY_ABI_VERSION 19
+
+/* Structs shared with rust */
+
+typedef struct RunityString RunityString;
+typedef struct RunityStr RunityStr;
+typedef struct RunityVector3 RunityVector3;
+typedef struct RunityQuaternion RunityQuaternion;
+typedef struct RunityTransform RunityTransform;
+typedef struct RunityFoundGameObject RunityFoundGameObject;
+typedef struct RunityGameObjectLookup RunityGameObjectLookup;
+typedef struct RunityPropertyValue RunityPropertyValue;
+typedef struct RunityProperty RunityProperty;
+typedef struct RunityComponentAccess RunityComponentAccess;
+typedef struct RunityGameObject RunityGameObject;
+typedef struct RunityGameObjectChanges RunityGameObjectChanges;
+typedef struct RunityCommand RunityCommand;
+typedef struct RunityMessage RunityMessage;
+typedef struct RunityColor RunityColor;
+typedef struct RunityDrawing RunityDrawing;
+typedef struct RunityChangeBuffer RunityChangeBuffer;
+typedef struct RunityTime RunityTime;
+typedef struct RunityAxis RunityAxis;
+typedef struct RunityInputSnapshot RunityInputSnapshot;
+typedef struct RunityInput RunityInput;
+typedef struct RunityLogRecord RunityLogRecord;
+typedef struct RunityDebug RunityDebug;
+typedef struct RunityPhysicsQuery RunityPhysicsQuery;
+typedef struct RunityFoundHit RunityFoundHit;
+typedef struct RunityPhysics RunityPhysics;
+typedef struct RunityProfiler RunityProfiler;
+typedef struct RunityDataStruct RunityDataStruct;
+typedef struct RunityState RunityState;
+typedef struct RunityContactPoint RunityContactPoint;
+typedef struct RunityCollision RunityCollision;
+typedef struct RunityTrigger RunityTrigger;
+typedef struct RunityReceivedMessage RunityReceivedMessage;
+typedef struct RunitySpanSample RunitySpanSample;
+typedef struct RunityLayoutField RunityLayoutField;
+typedef struct RunityLayoutEntry RunityLayoutEntry;
+typedef struct RunityLayoutTable RunityLayoutTable;
+
+/* Callbacks rust can call */
+
+typedef bool (*RunityFindGameObjectFn)(RunityStr name, RunityFoundGameObject *found);
+typedef bool (*RunityFindGameObjectWithTagFn)(RunityStr tag, RunityFoundGameObject *found);
+typedef uint32_t (*RunityFindGameObjectsWithTagFn)(RunityStr tag, RunityFoundGameObject *found, uint32_t capacity);
+typedef bool (*RunityFindGameObjectByIdFn)(uint64_t hash, RunityFoundGameObject *found);
+typedef uint32_t (*RunityGetChildCountFn)(uint64_t hash);
+typedef bool (*RunityGetChildFn)(uint64_t hash, uint32_t index, RunityFoundGameObject *found);
+typedef bool (*RunityHasComponentFn)(uint64_t object, RunityStr component);
+typedef void (*RunityGetPropertiesFn)(uint64_t object, RunityStr component, RunityProperty *properties, uint32_t count);
+typedef void (*RunitySetPropertiesFn)(uint64_t object, RunityStr component, RunityProperty *properties, uint32_t count);
+typedef bool (*RunityInvokeComponentFn)(uint64_t object, RunityStr component, RunityStr method, RunityPropertyValue *arguments, uint32_t count);
+typedef void (*RunityLogFn)(RunityStr message);
+typedef void (*RunityLogWarningFn)(RunityStr message);
+typedef void (*RunityLogErrorFn)(RunityStr message);
+typedef void (*RunityLogRecordFn)(const RunityLogRecord *record);
+typedef uint32_t (*RunityPhysicsCastFn)(const RunityPhysicsQuery *query, RunityFoundHit *hits, uint32_t capacity);
+typedef uint32_t (*RunityPhysicsOverlapFn)(const RunityPhysicsQuery *query, RunityFoundHit *hits, uint32_t capacity);
+typedef void (*RunityProfilerBeginSampleFn)(RunityStr name);
+typedef void (*RunityProfilerEndSampleFn)(void);
+
+/* 16 bytes, aligned to 8 */
+struct RunityString{
+    char *ptr;
+    uint32_t len;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityStr{
+    char *ptr;
+    uint32_t len;
+};
+
+/* 12 bytes, aligned to 4 */
+struct RunityVector3{
+    float x;
+    float y;
+    float z;
+};
+
+/* 16 bytes, aligned to 4 */
+struct RunityQuaternion{
+    float x;
+    float y;
+    float z;
+    float w;
+};
+
+/* 88 bytes, aligned to 8 */
+struct RunityTransform{
+    RunityVector3 position;
+    RunityQuaternion rotation;
+    RunityVector3 local_scale;
+    uint64_t parent;
+    RunityVector3 parent_position;
+    RunityQuaternion parent_rotation;
+    RunityVector3 parent_scale;
+};
+
+/* 112 bytes, aligned to 8 */
+struct RunityFoundGameObject{
+    uint64_t hash;
+    RunityStr tag;
+    RunityTransform transform;
+};
+
+/* 48 bytes, aligned to 8 */
+struct RunityGameObjectLookup{
+    RunityFindGameObjectFn find;
+    RunityFindGameObjectWithTagFn find_with_tag;
+    RunityFindGameObjectsWithTagFn find_all_with_tag;
+    RunityFindGameObjectByIdFn find_by_id;
+    RunityGetChildCountFn child_count;
+    RunityGetChildFn child;
+};
+
+/* 24 bytes, aligned to 4 */
+struct RunityPropertyValue{
+    uint32_t kind;
+    int32_t integer;
+    float x;
+    float y;
+    float z;
+    float w;
+};
+
+/* 48 bytes, aligned to 8 */
+struct RunityProperty{
+    RunityStr name;
+    RunityPropertyValue value;
+    bool found;
+};
+
+/* 32 bytes, aligned to 8 */
+struct RunityComponentAccess{
+    RunityHasComponentFn has_component;
+    RunityGetPropertiesFn get_properties;
+    RunitySetPropertiesFn set_properties;
+    RunityInvokeComponentFn invoke;
+};
+
+/* 176 bytes, aligned to 8 */
+struct RunityGameObject{
+    uint64_t hash;
+    RunityTransform transform;
+    RunityGameObjectLookup lookup;
+    RunityComponentAccess components;
+};
+
+/* 112 bytes, aligned to 8 */
+struct RunityGameObjectChanges{
+    uint64_t hash;
+    RunityString tag;
+    RunityTransform transform;
+};
+
+/* 152 bytes, aligned to 8 */
+struct RunityCommand{
+    uint32_t kind;
+    uint64_t target;
+    RunityString prefab;
+    uint64_t prefab_id;
+    RunityTransform transform;
+    uint64_t parent;
+    bool active;
+    bool world_position_stays;
+    uint32_t status;
+    uint64_t object;
+};
+
+/* 64 bytes, aligned to 8 */
+struct RunityMessage{
+    uint32_t kind;
+    RunityString name;
+    uint8_t *payload;
+    uint32_t payload_len;
+    uint32_t recipients;
+    uint64_t object;
+    RunityString tag;
+};
+
+/* 16 bytes, aligned to 4 */
+struct RunityColor{
+    float r;
+    float g;
+    float b;
+    float a;
+};
+
+/* 88 bytes, aligned to 8 */
+struct RunityDrawing{
+    uint32_t kind;
+    RunityVector3 from;
+    RunityVector3 to;
+    RunityQuaternion rotation;
+    float radius;
+    RunityColor color;
+    float duration;
+    RunityString text;
+};
+
+/* 64 bytes, aligned to 8 */
+struct RunityChangeBuffer{
+    RunityGameObjectChanges *changes;
+    uint32_t len;
+    RunityCommand *commands;
+    uint32_t command_count;
+    RunityMessage *messages;
+    uint32_t message_count;
+    RunityDrawing *drawings;
+    uint32_t drawing_count;
+};
+
+/* 60 bytes, aligned to 4 */
+struct RunityTime{
+    float delta_time;
+    float fixed_delta_time;
+    float fixed_time;
+    float fixed_unscaled_delta_time;
+    float fixed_unscaled_time;
+    float frame_count;
+    float maximum_delta_time;
+    float maximum_particle_delta_time;
+    float real_time_since_startup;
+    float smooth_delta_time;
+    float time;
+    float time_scale;
+    float time_since_level_load;
+    float unscaled_delta_time;
+    float unscaled_time;
+};
+
+/* 24 bytes, aligned to 8 */
+struct RunityAxis{
+    RunityStr name;
+    float value;
+};
+
+/* 120 bytes, aligned to 8 */
+struct RunityInputSnapshot{
+    uint64_t keys[8];
+    uint32_t mouse_buttons;
+    RunityVector3 mouse_position;
+    RunityVector3 mouse_delta;
+    RunityVector3 mouse_scroll;
+    RunityAxis *axes;
+    uint32_t axis_count;
+};
+
+/* 240 bytes, aligned to 8 */
+struct RunityInput{
+    RunityInputSnapshot current;
+    RunityInputSnapshot previous;
+};
+
+/* 56 bytes, aligned to 8 */
+struct RunityLogRecord{
+    RunityStr message;
+    RunityStr target;
+    RunityStr file;
+    uint32_t line;
+    uint32_t level;
+};
+
+/* 32 bytes, aligned to 8 */
+struct RunityDebug{
+    RunityLogFn log;
+    RunityLogWarningFn log_warning;
+    RunityLogErrorFn log_error;
+    RunityLogRecordFn log_record;
+};
+
+/* 72 bytes, aligned to 4 */
+struct RunityPhysicsQuery{
+    uint32_t shape;
+    RunityVector3 origin;
+    RunityVector3 direction;
+    float max_distance;
+    float radius;
+    RunityVector3 half_extents;
+    RunityQuaternion rotation;
+    int32_t layer_mask;
+    bool all;
+};
+
+/* 56 bytes, aligned to 8 */
+struct RunityFoundHit{
+    RunityVector3 point;
+    RunityVector3 normal;
+    float distance;
+    uint64_t object;
+    RunityStr tag;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityPhysics{
+    RunityPhysicsCastFn cast;
+    RunityPhysicsOverlapFn overlap;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityProfiler{
+    RunityProfilerBeginSampleFn begin_sample;
+    RunityProfilerEndSampleFn end_sample;
+};
+
+/* 624 bytes, aligned to 8 */
+struct RunityDataStruct{
+    RunityTransform transform;
+    RunityGameObject game_object;
+    RunityTime time;
+    RunityInput input;
+    RunityDebug debug;
+    RunityPhysics physics;
+    uint64_t instance;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityState{
+    uint8_t *data;
+    uint32_t len;
+    uint32_t version;
+};
+
+/* 28 bytes, aligned to 4 */
+struct RunityContactPoint{
+    RunityVector3 point;
+    RunityVector3 normal;
+    float separation;
+};
+
+/* 64 bytes, aligned to 8 */
+struct RunityCollision{
+    uint64_t other;
+    RunityStr tag;
+    RunityContactPoint *contacts;
+    uint32_t contact_count;
+    RunityVector3 relative_velocity;
+    RunityVector3 impulse;
+};
+
+/* 24 bytes, aligned to 8 */
+struct RunityTrigger{
+    uint64_t other;
+    RunityStr tag;
+};
+
+/* 40 bytes, aligned to 8 */
+struct RunityReceivedMessage{
+    RunityStr name;
+    uint8_t *payload;
+    uint32_t payload_len;
+    uint64_t sender;
+};
+
+/* 56 bytes, aligned to 8 */
+struct RunitySpanSample{
+    uint64_t frame;
+    RunityStr name;
+    uint32_t calls;
+    uint64_t total_ns;
+    uint64_t min_ns;
+    uint64_t max_ns;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityLayoutField{
+    char *name;
+    uint32_t offset;
+    uint32_t size;
+};
+
+/* 32 bytes, aligned to 8 */
+struct RunityLayoutEntry{
+    char *name;
+    uint32_t size;
+    uint32_t align;
+    RunityLayoutField *fields;
+    uint32_t field_count;
+};
+
+/* 16 bytes, aligned to 8 */
+struct RunityLayoutTable{
+    RunityLayoutEntry *entries;
+    uint32_t len;
+};
+
+/* Layout checks - these only hold for 64-bit hosts */
+#if UINTPTR_MAX == 0xffffffffffffffffu
+#if defined(__cplusplus)
+#define RUNITY_STATIC_ASSERT(condition, message) static_assert(condition, message)
+#else
+#define RUNITY_STATIC_ASSERT(condition, message) _Static_assert(condition, message)
+#endif
+RUNITY_STATIC_ASSERT(sizeof(RunityString) == 16, "RunityString must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityString, ptr) == 0, "RunityString.ptr must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityString, len) == 8, "RunityString.len must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityStr) == 16, "RunityStr must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityStr, ptr) == 0, "RunityStr.ptr must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityStr, len) == 8, "RunityStr.len must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityVector3) == 12, "RunityVector3 must be 12 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityVector3, x) == 0, "RunityVector3.x must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityVector3, y) == 4, "RunityVector3.y must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityVector3, z) == 8, "RunityVector3.z must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityQuaternion) == 16, "RunityQuaternion must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityQuaternion, x) == 0, "RunityQuaternion.x must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityQuaternion, y) == 4, "RunityQuaternion.y must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityQuaternion, z) == 8, "RunityQuaternion.z must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityQuaternion, w) == 12, "RunityQuaternion.w must be at offset 12");
+RUNITY_STATIC_ASSERT(sizeof(RunityTransform) == 88, "RunityTransform must be 88 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, position) == 0, "RunityTransform.position must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, rotation) == 12, "RunityTransform.rotation must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, local_scale) == 28, "RunityTransform.local_scale must be at offset 28");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, parent) == 40, "RunityTransform.parent must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, parent_position) == 48, "RunityTransform.parent_position must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, parent_rotation) == 60, "RunityTransform.parent_rotation must be at offset 60");
+RUNITY_STATIC_ASSERT(offsetof(RunityTransform, parent_scale) == 76, "RunityTransform.parent_scale must be at offset 76");
+RUNITY_STATIC_ASSERT(sizeof(RunityFoundGameObject) == 112, "RunityFoundGameObject must be 112 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundGameObject, hash) == 0, "RunityFoundGameObject.hash must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundGameObject, tag) == 8, "RunityFoundGameObject.tag must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundGameObject, transform) == 24, "RunityFoundGameObject.transform must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityGameObjectLookup) == 48, "RunityGameObjectLookup must be 48 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, find) == 0, "RunityGameObjectLookup.find must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, find_with_tag) == 8, "RunityGameObjectLookup.find_with_tag must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, find_all_with_tag) == 16, "RunityGameObjectLookup.find_all_with_tag must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, find_by_id) == 24, "RunityGameObjectLookup.find_by_id must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, child_count) == 32, "RunityGameObjectLookup.child_count must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectLookup, child) == 40, "RunityGameObjectLookup.child must be at offset 40");
+RUNITY_STATIC_ASSERT(sizeof(RunityPropertyValue) == 24, "RunityPropertyValue must be 24 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, kind) == 0, "RunityPropertyValue.kind must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, integer) == 4, "RunityPropertyValue.integer must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, x) == 8, "RunityPropertyValue.x must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, y) == 12, "RunityPropertyValue.y must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, z) == 16, "RunityPropertyValue.z must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityPropertyValue, w) == 20, "RunityPropertyValue.w must be at offset 20");
+RUNITY_STATIC_ASSERT(sizeof(RunityProperty) == 48, "RunityProperty must be 48 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityProperty, name) == 0, "RunityProperty.name must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityProperty, value) == 16, "RunityProperty.value must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityProperty, found) == 40, "RunityProperty.found must be at offset 40");
+RUNITY_STATIC_ASSERT(sizeof(RunityComponentAccess) == 32, "RunityComponentAccess must be 32 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityComponentAccess, has_component) == 0, "RunityComponentAccess.has_component must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityComponentAccess, get_properties) == 8, "RunityComponentAccess.get_properties must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityComponentAccess, set_properties) == 16, "RunityComponentAccess.set_properties must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityComponentAccess, invoke) == 24, "RunityComponentAccess.invoke must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityGameObject) == 176, "RunityGameObject must be 176 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObject, hash) == 0, "RunityGameObject.hash must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObject, transform) == 8, "RunityGameObject.transform must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObject, lookup) == 96, "RunityGameObject.lookup must be at offset 96");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObject, components) == 144, "RunityGameObject.components must be at offset 144");
+RUNITY_STATIC_ASSERT(sizeof(RunityGameObjectChanges) == 112, "RunityGameObjectChanges must be 112 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectChanges, hash) == 0, "RunityGameObjectChanges.hash must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectChanges, tag) == 8, "RunityGameObjectChanges.tag must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityGameObjectChanges, transform) == 24, "RunityGameObjectChanges.transform must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityCommand) == 152, "RunityCommand must be 152 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, kind) == 0, "RunityCommand.kind must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, target) == 8, "RunityCommand.target must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, prefab) == 16, "RunityCommand.prefab must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, prefab_id) == 32, "RunityCommand.prefab_id must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, transform) == 40, "RunityCommand.transform must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, parent) == 128, "RunityCommand.parent must be at offset 128");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, active) == 136, "RunityCommand.active must be at offset 136");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, world_position_stays) == 137, "RunityCommand.world_position_stays must be at offset 137");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, status) == 140, "RunityCommand.status must be at offset 140");
+RUNITY_STATIC_ASSERT(offsetof(RunityCommand, object) == 144, "RunityCommand.object must be at offset 144");
+RUNITY_STATIC_ASSERT(sizeof(RunityMessage) == 64, "RunityMessage must be 64 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, kind) == 0, "RunityMessage.kind must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, name) == 8, "RunityMessage.name must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, payload) == 24, "RunityMessage.payload must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, payload_len) == 32, "RunityMessage.payload_len must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, recipients) == 36, "RunityMessage.recipients must be at offset 36");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, object) == 40, "RunityMessage.object must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunityMessage, tag) == 48, "RunityMessage.tag must be at offset 48");
+RUNITY_STATIC_ASSERT(sizeof(RunityColor) == 16, "RunityColor must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityColor, r) == 0, "RunityColor.r must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityColor, g) == 4, "RunityColor.g must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityColor, b) == 8, "RunityColor.b must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityColor, a) == 12, "RunityColor.a must be at offset 12");
+RUNITY_STATIC_ASSERT(sizeof(RunityDrawing) == 88, "RunityDrawing must be 88 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, kind) == 0, "RunityDrawing.kind must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, from) == 4, "RunityDrawing.from must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, to) == 16, "RunityDrawing.to must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, rotation) == 28, "RunityDrawing.rotation must be at offset 28");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, radius) == 44, "RunityDrawing.radius must be at offset 44");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, color) == 48, "RunityDrawing.color must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, duration) == 64, "RunityDrawing.duration must be at offset 64");
+RUNITY_STATIC_ASSERT(offsetof(RunityDrawing, text) == 72, "RunityDrawing.text must be at offset 72");
+RUNITY_STATIC_ASSERT(sizeof(RunityChangeBuffer) == 64, "RunityChangeBuffer must be 64 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, changes) == 0, "RunityChangeBuffer.changes must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, len) == 8, "RunityChangeBuffer.len must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, commands) == 16, "RunityChangeBuffer.commands must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, command_count) == 24, "RunityChangeBuffer.command_count must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, messages) == 32, "RunityChangeBuffer.messages must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, message_count) == 40, "RunityChangeBuffer.message_count must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, drawings) == 48, "RunityChangeBuffer.drawings must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityChangeBuffer, drawing_count) == 56, "RunityChangeBuffer.drawing_count must be at offset 56");
+RUNITY_STATIC_ASSERT(sizeof(RunityTime) == 60, "RunityTime must be 60 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, delta_time) == 0, "RunityTime.delta_time must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, fixed_delta_time) == 4, "RunityTime.fixed_delta_time must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, fixed_time) == 8, "RunityTime.fixed_time must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, fixed_unscaled_delta_time) == 12, "RunityTime.fixed_unscaled_delta_time must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, fixed_unscaled_time) == 16, "RunityTime.fixed_unscaled_time must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, frame_count) == 20, "RunityTime.frame_count must be at offset 20");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, maximum_delta_time) == 24, "RunityTime.maximum_delta_time must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, maximum_particle_delta_time) == 28, "RunityTime.maximum_particle_delta_time must be at offset 28");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, real_time_since_startup) == 32, "RunityTime.real_time_since_startup must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, smooth_delta_time) == 36, "RunityTime.smooth_delta_time must be at offset 36");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, time) == 40, "RunityTime.time must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, time_scale) == 44, "RunityTime.time_scale must be at offset 44");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, time_since_level_load) == 48, "RunityTime.time_since_level_load must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, unscaled_delta_time) == 52, "RunityTime.unscaled_delta_time must be at offset 52");
+RUNITY_STATIC_ASSERT(offsetof(RunityTime, unscaled_time) == 56, "RunityTime.unscaled_time must be at offset 56");
+RUNITY_STATIC_ASSERT(sizeof(RunityAxis) == 24, "RunityAxis must be 24 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityAxis, name) == 0, "RunityAxis.name must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityAxis, value) == 16, "RunityAxis.value must be at offset 16");
+RUNITY_STATIC_ASSERT(sizeof(RunityInputSnapshot) == 120, "RunityInputSnapshot must be 120 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, keys) == 0, "RunityInputSnapshot.keys must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, mouse_buttons) == 64, "RunityInputSnapshot.mouse_buttons must be at offset 64");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, mouse_position) == 68, "RunityInputSnapshot.mouse_position must be at offset 68");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, mouse_delta) == 80, "RunityInputSnapshot.mouse_delta must be at offset 80");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, mouse_scroll) == 92, "RunityInputSnapshot.mouse_scroll must be at offset 92");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, axes) == 104, "RunityInputSnapshot.axes must be at offset 104");
+RUNITY_STATIC_ASSERT(offsetof(RunityInputSnapshot, axis_count) == 112, "RunityInputSnapshot.axis_count must be at offset 112");
+RUNITY_STATIC_ASSERT(sizeof(RunityInput) == 240, "RunityInput must be 240 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityInput, current) == 0, "RunityInput.current must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityInput, previous) == 120, "RunityInput.previous must be at offset 120");
+RUNITY_STATIC_ASSERT(sizeof(RunityLogRecord) == 56, "RunityLogRecord must be 56 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityLogRecord, message) == 0, "RunityLogRecord.message must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityLogRecord, target) == 16, "RunityLogRecord.target must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityLogRecord, file) == 32, "RunityLogRecord.file must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityLogRecord, line) == 48, "RunityLogRecord.line must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityLogRecord, level) == 52, "RunityLogRecord.level must be at offset 52");
+RUNITY_STATIC_ASSERT(sizeof(RunityDebug) == 32, "RunityDebug must be 32 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityDebug, log) == 0, "RunityDebug.log must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityDebug, log_warning) == 8, "RunityDebug.log_warning must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityDebug, log_error) == 16, "RunityDebug.log_error must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityDebug, log_record) == 24, "RunityDebug.log_record must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityPhysicsQuery) == 72, "RunityPhysicsQuery must be 72 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, shape) == 0, "RunityPhysicsQuery.shape must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, origin) == 4, "RunityPhysicsQuery.origin must be at offset 4");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, direction) == 16, "RunityPhysicsQuery.direction must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, max_distance) == 28, "RunityPhysicsQuery.max_distance must be at offset 28");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, radius) == 32, "RunityPhysicsQuery.radius must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, half_extents) == 36, "RunityPhysicsQuery.half_extents must be at offset 36");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, rotation) == 48, "RunityPhysicsQuery.rotation must be at offset 48");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, layer_mask) == 64, "RunityPhysicsQuery.layer_mask must be at offset 64");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysicsQuery, all) == 68, "RunityPhysicsQuery.all must be at offset 68");
+RUNITY_STATIC_ASSERT(sizeof(RunityFoundHit) == 56, "RunityFoundHit must be 56 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundHit, point) == 0, "RunityFoundHit.point must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundHit, normal) == 12, "RunityFoundHit.normal must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundHit, distance) == 24, "RunityFoundHit.distance must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundHit, object) == 32, "RunityFoundHit.object must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityFoundHit, tag) == 40, "RunityFoundHit.tag must be at offset 40");
+RUNITY_STATIC_ASSERT(sizeof(RunityPhysics) == 16, "RunityPhysics must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysics, cast) == 0, "RunityPhysics.cast must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityPhysics, overlap) == 8, "RunityPhysics.overlap must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityProfiler) == 16, "RunityProfiler must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityProfiler, begin_sample) == 0, "RunityProfiler.begin_sample must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityProfiler, end_sample) == 8, "RunityProfiler.end_sample must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityDataStruct) == 624, "RunityDataStruct must be 624 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, transform) == 0, "RunityDataStruct.transform must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, game_object) == 88, "RunityDataStruct.game_object must be at offset 88");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, time) == 264, "RunityDataStruct.time must be at offset 264");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, input) == 328, "RunityDataStruct.input must be at offset 328");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, debug) == 568, "RunityDataStruct.debug must be at offset 568");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, physics) == 600, "RunityDataStruct.physics must be at offset 600");
+RUNITY_STATIC_ASSERT(offsetof(RunityDataStruct, instance) == 616, "RunityDataStruct.instance must be at offset 616");
+RUNITY_STATIC_ASSERT(sizeof(RunityState) == 16, "RunityState must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityState, data) == 0, "RunityState.data must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityState, len) == 8, "RunityState.len must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityState, version) == 12, "RunityState.version must be at offset 12");
+RUNITY_STATIC_ASSERT(sizeof(RunityContactPoint) == 28, "RunityContactPoint must be 28 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityContactPoint, point) == 0, "RunityContactPoint.point must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityContactPoint, normal) == 12, "RunityContactPoint.normal must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityContactPoint, separation) == 24, "RunityContactPoint.separation must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityCollision) == 64, "RunityCollision must be 64 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, other) == 0, "RunityCollision.other must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, tag) == 8, "RunityCollision.tag must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, contacts) == 24, "RunityCollision.contacts must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, contact_count) == 32, "RunityCollision.contact_count must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, relative_velocity) == 36, "RunityCollision.relative_velocity must be at offset 36");
+RUNITY_STATIC_ASSERT(offsetof(RunityCollision, impulse) == 48, "RunityCollision.impulse must be at offset 48");
+RUNITY_STATIC_ASSERT(sizeof(RunityTrigger) == 24, "RunityTrigger must be 24 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityTrigger, other) == 0, "RunityTrigger.other must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityTrigger, tag) == 8, "RunityTrigger.tag must be at offset 8");
+RUNITY_STATIC_ASSERT(sizeof(RunityReceivedMessage) == 40, "RunityReceivedMessage must be 40 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityReceivedMessage, name) == 0, "RunityReceivedMessage.name must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityReceivedMessage, payload) == 16, "RunityReceivedMessage.payload must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityReceivedMessage, payload_len) == 24, "RunityReceivedMessage.payload_len must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunityReceivedMessage, sender) == 32, "RunityReceivedMessage.sender must be at offset 32");
+RUNITY_STATIC_ASSERT(sizeof(RunitySpanSample) == 56, "RunitySpanSample must be 56 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, frame) == 0, "RunitySpanSample.frame must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, name) == 8, "RunitySpanSample.name must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, calls) == 24, "RunitySpanSample.calls must be at offset 24");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, total_ns) == 32, "RunitySpanSample.total_ns must be at offset 32");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, min_ns) == 40, "RunitySpanSample.min_ns must be at offset 40");
+RUNITY_STATIC_ASSERT(offsetof(RunitySpanSample, max_ns) == 48, "RunitySpanSample.max_ns must be at offset 48");
+RUNITY_STATIC_ASSERT(sizeof(RunityLayoutField) == 16, "RunityLayoutField must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutField, name) == 0, "RunityLayoutField.name must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutField, offset) == 8, "RunityLayoutField.offset must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutField, size) == 12, "RunityLayoutField.size must be at offset 12");
+RUNITY_STATIC_ASSERT(sizeof(RunityLayoutEntry) == 32, "RunityLayoutEntry must be 32 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutEntry, name) == 0, "RunityLayoutEntry.name must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutEntry, size) == 8, "RunityLayoutEntry.size must be at offset 8");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutEntry, align) == 12, "RunityLayoutEntry.align must be at offset 12");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutEntry, fields) == 16, "RunityLayoutEntry.fields must be at offset 16");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutEntry, field_count) == 24, "RunityLayoutEntry.field_count must be at offset 24");
+RUNITY_STATIC_ASSERT(sizeof(RunityLayoutTable) == 16, "RunityLayoutTable must be 16 bytes");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutTable, entries) == 0, "RunityLayoutTable.entries must be at offset 0");
+RUNITY_STATIC_ASSERT(offsetof(RunityLayoutTable, len) == 8, "RunityLayoutTable.len must be at offset 8");
+#undef RUNITY_STATIC_ASSERT
+#endif
+
+/* Functions exported by a runity library */
+
+typedef RunityDataStruct (*RunityAwakeFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityStartFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityUpdateFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityFixedUpdateFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityLateUpdateFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityOnEnableFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityOnDisableFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityOnApplicationFocusFn)(RunityDataStruct data, bool focused); /* optional */
+typedef RunityDataStruct (*RunityOnApplicationPauseFn)(RunityDataStruct data, bool paused); /* optional */
+typedef RunityDataStruct (*RunityOnApplicationQuitFn)(RunityDataStruct data); /* optional */
+typedef RunityDataStruct (*RunityOnCollisionEnterFn)(RunityDataStruct data, const RunityCollision *collision); /* optional */
+typedef RunityDataStruct (*RunityOnCollisionStayFn)(RunityDataStruct data, const RunityCollision *collision); /* optional */
+typedef RunityDataStruct (*RunityOnCollisionExitFn)(RunityDataStruct data, const RunityCollision *collision); /* optional */
+typedef RunityDataStruct (*RunityOnTriggerEnterFn)(RunityDataStruct data, const RunityTrigger *other); /* optional */
+typedef RunityDataStruct (*RunityOnTriggerStayFn)(RunityDataStruct data, const RunityTrigger *other); /* optional */
+typedef RunityDataStruct (*RunityOnTriggerExitFn)(RunityDataStruct data, const RunityTrigger *other); /* optional */
+typedef int32_t (*RunityDestroyFn)(RunityDataStruct data);
+typedef RunityState (*RunitySaveStateFn)(uint64_t instance); /* optional */
+typedef int32_t (*RunityLoadStateFn)(RunityDataStruct *data, RunityState state); /* optional */
+typedef void (*RunitySetDataFn)(RunityDataStruct data);
+typedef bool (*RunityIsFaultedFn)(uint64_t instance);
+typedef RunityChangeBuffer (*RunityChangesAcquireFn)(uint64_t instance);
+typedef void (*RunityChangesReleaseFn)(uint64_t instance);
+typedef void (*RunityPostMessagesFn)(uint64_t instance, RunityReceivedMessage *messages, uint32_t count);
+typedef uint32_t (*RunitySpanSamplesFn)(RunitySpanSample *samples, uint32_t capacity);
+typedef void (*RunitySetProfilerFn)(RunityProfiler profiler);
+typedef void (*RunityClearProfilerFn)(void);
+typedef bool (*RunityWriteChromeTraceFn)(RunityStr path);
+typedef uint32_t (*RunityAbiVersionFn)(void);
+typedef RunityLayoutTable (*RunityLayoutTableFn)(void);
+typedef void (*RunityStringFreeFn)(RunityString string);
+typedef void (*RunityStateFreeFn)(RunityState state);
+
+RunityDataStruct awake(RunityDataStruct data);
+RunityDataStruct start(RunityDataStruct data);
+RunityDataStruct update(RunityDataStruct data);
+RunityDataStruct fixed_update(RunityDataStruct data);
+RunityDataStruct late_update(RunityDataStruct data);
+RunityDataStruct on_enable(RunityDataStruct data);
+RunityDataStruct on_disable(RunityDataStruct data);
+RunityDataStruct on_application_focus(RunityDataStruct data, bool focused);
+RunityDataStruct on_application_pause(RunityDataStruct data, bool paused);
+RunityDataStruct on_application_quit(RunityDataStruct data);
+RunityDataStruct on_collision_enter(RunityDataStruct data, const RunityCollision *collision);
+RunityDataStruct on_collision_stay(RunityDataStruct data, const RunityCollision *collision);
+RunityDataStruct on_collision_exit(RunityDataStruct data, const RunityCollision *collision);
+RunityDataStruct on_trigger_enter(RunityDataStruct data, const RunityTrigger *other);
+RunityDataStruct on_trigger_stay(RunityDataStruct data, const RunityTrigger *other);
+RunityDataStruct on_trigger_exit(RunityDataStruct data, const RunityTrigger *other);
+int32_t destroy(RunityDataStruct data);
+RunityState runity_save_state(uint64_t instance);
+int32_t runity_load_state(RunityDataStruct *data, RunityState state);
+void set_data(RunityDataStruct data);
+bool runity_is_faulted(uint64_t instance);
+RunityChangeBuffer runity_changes_acquire(uint64_t instance);
+void runity_changes_release(uint64_t instance);
+void runity_post_messages(uint64_t instance, RunityReceivedMessage *messages, uint32_t count);
+uint32_t runity_span_samples(RunitySpanSample *samples, uint32_t capacity);
+void runity_set_profiler(RunityProfiler profiler);
+void runity_clear_profiler(void);
+bool runity_write_chrome_trace(RunityStr path);
+uint32_t runity_abi_version(void);
+RunityLayoutTable runity_layout_table(void);
+void runity_string_free(RunityString string);
+void runity_state_free(RunityState state);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUNITY_H */
@@ -0,0 +1,75 @@
+//! Runs a runity library headlessly, printing everything it logs.
+//!
+//! Usage: `runity-host <library> [frames] [tag...]`
+//!
+//! The script is attached to a single gameobject at the origin. Every extra tag adds
+//! an empty gameobject with that tag, so scripts looking up tags have something to find.
+
+use std::process;
+
+use runity::{Quaternion, Transform, Vector3};
+use runity_host::{Host, LogLevel};
+
+fn main(){
+    let mut args = std::env::args().skip(1);
+
+    let path = match args.next(){
+        Some(path) => path,
+        None => {
+            eprintln!("usage: runity-host <library> [frames] [tag...]");
+            process::exit(2);
+        }
+    };
+
+    let frames = match args.next().map(|frames| frames.parse::<u32>()){
+        Some(Ok(frames)) => frames,
+        Some(Err(error)) => {
+            eprintln!("invalid frame count: {}", error);
+            process::exit(2);
+        }
+        None => 1,
+    };
+
+    let mut host = match Host::load(&path){
+        Ok(host) => host,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
+
+    let identity = || Transform::new(Vector3::zero(), Quaternion::new(0.0, 0.0, 0.0, 1.0));
+
+    for tag in args{
+        host.scene_mut().add(&tag, &tag, identity());
+    }
+
+    let object = host.scene_mut().add("Script", "Untagged", identity());
+    let instance = match host.attach(object){
+        Ok(instance) => instance,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
+
+    host.step(frames);
+
+    // Taken before quitting, which detaches the script
+    let transform = host.transform(instance);
+    host.quit();
+
+    for entry in host.take_logs(){
+        let level = match entry.level{
+            LogLevel::Log => "log",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+        };
+        println!("[{}] {}", level, entry.message);
+    }
+
+    if let Some(transform) = transform{
+        println!("position: {:?}", transform.position);
+        println!("rotation: {:?}", transform.rotation);
+    }
+}
@@ -0,0 +1,295 @@
+//! The functions handed to rust through `Debug`, `GameObject` and `Physics`.
+//!
+//! These are plain `extern "C"` functions, so they can't capture the host. Instead, the
+//! host makes its scene and log active on the current thread for the length of each call
+//! into the library (see `with_active`), and the callbacks work on whatever is active.
+
+use std::cell::Cell;
+use std::ptr;
+use std::slice;
+
+use runity::{ComponentAccess, FoundGameObject, FoundHit, GameObjectLookup, LogRecord, Physics, PhysicsQuery, Property, PropertyValue, Str};
+
+use crate::physics::{self, Hit};
+use crate::{Scene, SceneComponent, SceneObject};
+
+/// # Log Level
+///
+/// The level a message was logged at, matching the `Debug` function used to log it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel{
+    Log,
+    Warning,
+    Error,
+}
+
+/// # Log Entry
+///
+/// A single message logged by a script. Records (from `runity::log!`, or the `log` and
+/// `tracing` crates) also have a `source`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry{
+    pub level: LogLevel,
+    pub message: String,
+    pub source: Option<LogSource>,
+}
+
+/// # Log Source
+///
+/// Where a record was logged from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSource{
+    /// The level the record was logged at, which `LogEntry::level` only keeps the unity part of.
+    pub level: runity::LogLevel,
+    /// The module the record was logged from.
+    pub target: String,
+    /// The file the record was logged from, relative to its crate. Empty if it isn't known.
+    pub file: String,
+    /// The line the record was logged on, or `0` if it isn't known.
+    pub line: u32,
+}
+
+// The scene and log of the host currently calling into a library
+#[derive(Clone, Copy)]
+struct Active{
+    scene: *mut Scene,
+    logs: *mut Vec<LogEntry>,
+}
+
+thread_local!{
+    static ACTIVE: Cell<Option<Active>> = const { Cell::new(None) };
+}
+
+// Restores the previously active host, even if the call unwinds
+struct Restore(Option<Active>);
+
+impl Drop for Restore{
+    fn drop(&mut self){
+        ACTIVE.with(|active| active.set(self.0));
+    }
+}
+
+/// Makes `scene` and `logs` active for the callbacks while `f` runs.
+pub(crate) fn with_active<R>(scene: &mut Scene, logs: &mut Vec<LogEntry>, f: impl FnOnce() -> R) -> R{
+    let previous = ACTIVE.with(|active| active.replace(Some(Active{
+        scene: scene as *mut Scene,
+        logs: logs as *mut Vec<LogEntry>,
+    })));
+    let _restore = Restore(previous);
+
+    f()
+}
+
+fn push_log(level: LogLevel, message: String, source: Option<LogSource>){
+    ACTIVE.with(|active| {
+        if let Some(active) = active.get(){
+            unsafe{ (*active.logs).push(LogEntry{ level, message, source }) };
+        }
+    });
+}
+
+pub(crate) extern "C" fn log(message: Str){
+    push_log(LogLevel::Log, message.to_string_lossy().into_owned(), None);
+}
+
+pub(crate) extern "C" fn log_warning(message: Str){
+    push_log(LogLevel::Warning, message.to_string_lossy().into_owned(), None);
+}
+
+pub(crate) extern "C" fn log_error(message: Str){
+    push_log(LogLevel::Error, message.to_string_lossy().into_owned(), None);
+}
+
+pub(crate) extern "C" fn log_record(record: &LogRecord){
+    let level = match record.level(){
+        runity::LogLevel::Error => LogLevel::Error,
+        runity::LogLevel::Warning => LogLevel::Warning,
+        _ => LogLevel::Log,
+    };
+
+    push_log(level, record.message().to_string_lossy().into_owned(), Some(LogSource{
+        level: record.level(),
+        target: record.target().to_string_lossy().into_owned(),
+        file: record.file().to_string_lossy().into_owned(),
+        line: record.line(),
+    }));
+}
+
+/// The lookups handed to rust in every `GameObject`, answered from the active scene.
+pub(crate) fn lookup() -> GameObjectLookup{
+    GameObjectLookup{
+        find,
+        find_with_tag,
+        find_all_with_tag,
+        find_by_id,
+        child_count,
+        child,
+    }
+}
+
+// Runs `f` with the active scene, if a host is calling into a library
+fn with_scene<R>(f: impl FnOnce(&Scene) -> R) -> Option<R>{
+    ACTIVE.with(|active| active.get().map(|active| f(unsafe{ &*active.scene })))
+}
+
+// Writes `object` to `found`, lending it the tag, and returns whether it could be written. The
+// scene can't change while a script is running, so the tag outlives the lookup (the cast only
+// ties the lent tag to the scene).
+fn write_found(scene: &Scene, object: &SceneObject, found: *mut FoundGameObject) -> bool{
+    match scene.transform(object.id){
+        Some(transform) => {
+            unsafe{ ptr::write(found.cast(), FoundGameObject::new(object.id, Str::from(object.tag.as_str()), transform)) };
+            true
+        }
+        None => false,
+    }
+}
+
+// Answers a lookup for a single gameobject. Rust only reads `found` if this returns true, so it
+// is only true once `found` has been written.
+fn find_one(found: *mut FoundGameObject, f: impl FnOnce(&Scene) -> Option<&SceneObject>) -> bool{
+    with_scene(|scene| f(scene).is_some_and(|object| write_found(scene, object, found))).unwrap_or(false)
+}
+
+/// Answers `GameObject::find` from the active scene.
+pub(crate) extern "C" fn find(name: Str, found: *mut FoundGameObject) -> bool{
+    find_one(found, |scene| scene.find(&name.to_string_lossy()))
+}
+
+/// Answers `GameObject::find_with_tag` from the active scene.
+pub(crate) extern "C" fn find_with_tag(tag: Str, found: *mut FoundGameObject) -> bool{
+    find_one(found, |scene| scene.find_with_tag(&tag.to_string_lossy()))
+}
+
+/// Answers `GameObject::find_by_id` from the active scene.
+pub(crate) extern "C" fn find_by_id(hash: u64, found: *mut FoundGameObject) -> bool{
+    find_one(found, |scene| scene.get(hash))
+}
+
+/// Answers `GameObject::child_count` from the active scene.
+pub(crate) extern "C" fn child_count(hash: u64) -> u32{
+    with_scene(|scene| scene.children(hash).len() as u32).unwrap_or(0)
+}
+
+/// Answers `GameObject::child` from the active scene.
+pub(crate) extern "C" fn child(hash: u64, index: u32, found: *mut FoundGameObject) -> bool{
+    find_one(found, |scene| scene.children(hash).get(index as usize).and_then(|&child| scene.get(child)))
+}
+
+/// Answers `GameObject::find_all_with_tag` from the active scene, writing up to `capacity`
+/// gameobjects and returning how many there are.
+pub(crate) extern "C" fn find_all_with_tag(tag: Str, found: *mut FoundGameObject, capacity: u32) -> u32{
+    with_scene(|scene| {
+        // Rust reads back as many slots as this counts, so a gameobject that can't be written
+        // isn't counted, and the rest are written after the last one that was
+        let mut count = 0;
+        for object in scene.find_all_with_tag(&tag.to_string_lossy()){
+            let written = if count < capacity{
+                write_found(scene, object, unsafe{ found.add(count as usize) })
+            }else{
+                scene.transform(object.id).is_some()
+            };
+
+            if written{
+                count += 1;
+            }
+        }
+
+        count
+    }).unwrap_or(0)
+}
+
+/// The component access handed to rust in every `GameObject`, answered from the active scene.
+pub(crate) fn components() -> ComponentAccess{
+    ComponentAccess{
+        has_component,
+        get_properties,
+        set_properties,
+        invoke: invoke_component,
+    }
+}
+
+// Runs `f` with a component in the active scene, if there is one. Components are only ever
+// reached one call at a time, so nothing else borrows the scene while it is changed.
+fn with_component<R>(object: u64, component: Str, f: impl FnOnce(&mut SceneComponent) -> R) -> Option<R>{
+    ACTIVE.with(|active| {
+        let scene = unsafe{ &mut *active.get()?.scene };
+        scene.get_mut(object)?.component_mut(&component.to_string_lossy()).map(f)
+    })
+}
+
+// Lends the properties passed by rust to `f`
+fn with_properties(properties: *mut Property, count: u32, f: impl FnOnce(&mut [Property])){
+    if count > 0{
+        f(unsafe{ slice::from_raw_parts_mut(properties, count as usize) });
+    }
+}
+
+/// Answers `GameObject::get_component` from the active scene.
+pub(crate) extern "C" fn has_component(object: u64, component: Str) -> bool{
+    with_component(object, component, |_| ()).is_some()
+}
+
+/// Answers `ComponentRef::get_many`, marking each property the component has as found.
+pub(crate) extern "C" fn get_properties(object: u64, component: Str, properties: *mut Property, count: u32){
+    with_component(object, component, |component| with_properties(properties, count, |properties| {
+        for property in properties.iter_mut(){
+            if let Some(value) = component.get(&property.name.to_string_lossy()){
+                property.value = PropertyValue::from(value);
+                property.found = true;
+            }
+        }
+    }));
+}
+
+/// Answers `ComponentRef::set_many`, marking each property that was set as found.
+pub(crate) extern "C" fn set_properties(object: u64, component: Str, properties: *mut Property, count: u32){
+    with_component(object, component, |component| with_properties(properties, count, |properties| {
+        for property in properties.iter_mut(){
+            property.found = match property.value.value(){
+                Some(value) => component.set(&property.name.to_string_lossy(), value),
+                None => false,
+            };
+        }
+    }));
+}
+
+/// Answers `ComponentRef::invoke`, recording the call on the component.
+pub(crate) extern "C" fn invoke_component(object: u64, component: Str, method: Str, arguments: *const PropertyValue, count: u32) -> bool{
+    let arguments = if count > 0{
+        unsafe{ slice::from_raw_parts(arguments, count as usize) }.iter().filter_map(PropertyValue::value).collect()
+    }else{
+        Vec::new()
+    };
+
+    with_component(object, component, |component| component.call(&method.to_string_lossy(), arguments)).unwrap_or(false)
+}
+
+/// The physics queries handed to rust in every `DataStruct`, answered from the active scene.
+pub(crate) fn physics() -> Physics{
+    Physics::new(physics_cast, physics_overlap)
+}
+
+// Writes up to `capacity` hits, lending each the tag of the gameobject hit (like `write_found`),
+// and returns how many there are
+fn write_hits(scene: &Scene, query: &PhysicsQuery, hits: Vec<Hit>, found: *mut FoundHit, capacity: u32) -> u32{
+    // Only the closest hit is wanted, so the rest aren't counted (or written)
+    let count = if query.all(){ hits.len() }else{ hits.len().min(1) };
+
+    for (index, hit) in hits.iter().take(count.min(capacity as usize)).enumerate(){
+        let tag = scene.get(hit.object).map(|object| object.tag.as_str()).unwrap_or_default();
+        unsafe{ ptr::write(found.add(index).cast(), FoundHit::new(hit.point, hit.normal, hit.distance, hit.object, Str::from(tag))) };
+    }
+
+    count as u32
+}
+
+/// Answers `Physics::raycast` and `Physics::sphere_cast` from the active scene.
+pub(crate) extern "C" fn physics_cast(query: &PhysicsQuery, found: *mut FoundHit, capacity: u32) -> u32{
+    with_scene(|scene| write_hits(scene, query, physics::cast(scene, query), found, capacity)).unwrap_or(0)
+}
+
+/// Answers `Physics::overlap_sphere` and `Physics::overlap_box` from the active scene.
+pub(crate) extern "C" fn physics_overlap(query: &PhysicsQuery, found: *mut FoundHit, capacity: u32) -> u32{
+    with_scene(|scene| write_hits(scene, query, physics::overlap(scene, query), found, capacity)).unwrap_or(0)
+}
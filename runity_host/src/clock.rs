@@ -0,0 +1,122 @@
+use runity::Time;
+
+/// # Clock
+///
+/// A simulated clock, used to fill in `Time` for each frame. Every frame is exactly
+/// `delta_time` seconds long, so runs are deterministic.
+///
+/// Scripts can change `time_scale` and `fixed_delta_time` (as they can in unity), and
+/// the host will write them back into the clock after each call.
+#[derive(Debug, Clone)]
+pub struct Clock{
+    /// The length of a frame in seconds, before `time_scale` is applied.
+    pub delta_time: f32,
+    /// The length of a fixed (physics) step in seconds.
+    pub fixed_delta_time: f32,
+    /// The scale at which time passes.
+    pub time_scale: f32,
+
+    frame_count: u64,
+    time: f32,
+    unscaled_time: f32,
+    fixed_time: f32,
+    fixed_unscaled_time: f32,
+}
+
+impl Clock{
+    /// # New
+    ///
+    /// Creates a new clock, where each frame is `delta_time` seconds long.
+    pub fn new(delta_time: f32) -> Self{
+        Self{
+            delta_time,
+            fixed_delta_time: 0.02,
+            time_scale: 1.0,
+
+            frame_count: 0,
+            time: 0.0,
+            unscaled_time: 0.0,
+            fixed_time: 0.0,
+            fixed_unscaled_time: 0.0,
+        }
+    }
+
+    /// # Advance
+    ///
+    /// Moves the clock on by one frame. Fixed time is caught up separately, one fixed step
+    /// at a time, with `advance_fixed`.
+    pub fn advance(&mut self){
+        self.frame_count += 1;
+        self.time += self.delta_time * self.time_scale;
+        self.unscaled_time += self.delta_time;
+    }
+
+    /// # Advance Fixed
+    ///
+    /// Moves fixed time on by one fixed step, if a whole step fits before `time`. Returns
+    /// false once fixed time has caught up, so it can be called in a loop (like unity runs
+    /// `FixedUpdate`) after each `advance`.
+    pub fn advance_fixed(&mut self) -> bool{
+        // Fixed time only moves in whole fixed steps, and never past `time`
+        if self.fixed_delta_time <= 0.0 || self.fixed_time + self.fixed_delta_time > self.time{
+            return false;
+        }
+
+        self.fixed_time += self.fixed_delta_time;
+        self.fixed_unscaled_time += self.fixed_delta_time;
+        true
+    }
+
+    /// # Frame Count
+    ///
+    /// Returns the number of frames that have passed.
+    pub fn frame_count(&self) -> u64{
+        self.frame_count
+    }
+
+    /// # Time
+    ///
+    /// Returns the `Time` for the current frame.
+    pub fn time(&self) -> Time{
+        let delta_time = self.delta_time * self.time_scale;
+
+        Time{
+            delta_time,
+            fixed_delta_time: self.fixed_delta_time,
+            fixed_time: self.fixed_time,
+            fixed_unscaled_delta_time: self.fixed_delta_time,
+            fixed_unscaled_time: self.fixed_unscaled_time,
+            frame_count: self.frame_count as f32,
+            maximum_delta_time: 1.0 / 3.0,
+            maximum_particle_delta_time: 0.03,
+            real_time_since_startup: self.unscaled_time,
+            smooth_delta_time: delta_time,
+            time: self.time,
+            time_scale: self.time_scale,
+            time_since_level_load: self.time,
+            unscaled_delta_time: self.delta_time,
+            unscaled_time: self.unscaled_time,
+        }
+    }
+
+    /// # Fixed Time
+    ///
+    /// Returns the `Time` for the current fixed step. Like unity, `delta_time` and `time`
+    /// are the fixed step's, rather than the frame's.
+    pub fn fixed_time(&self) -> Time{
+        Time{
+            delta_time: self.fixed_delta_time,
+            unscaled_delta_time: self.fixed_delta_time,
+            time: self.fixed_time,
+            unscaled_time: self.fixed_unscaled_time,
+            ..self.time()
+        }
+    }
+}
+
+impl Default for Clock{
+    /// A clock running at 60 frames per second
+    fn default() -> Self{
+        Self::new(1.0 / 60.0)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use runity::{Color, Value, Vector3};
+
+/// # Scene Component
+///
+/// A component on a gameobject in the host's scene, as a bag of named properties - the same
+/// names as unity's C# API, such as `velocity` or `material.color`.
+///
+/// Scripts can read any property, and write it with a value of the same kind. The host doesn't
+/// simulate anything, so methods called by scripts (such as `AddForce`) are only recorded in
+/// `calls`, to be checked after running a number of frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneComponent{
+    /// The name of the component's type, such as `Rigidbody`.
+    pub name: String,
+    pub properties: BTreeMap<String, Value>,
+    /// The methods scripts may call.
+    pub methods: Vec<String>,
+    /// Every method scripts have called, in order.
+    pub calls: Vec<ComponentCall>,
+}
+
+/// # Component Call
+///
+/// A method called on a `SceneComponent` by a script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentCall{
+    pub method: String,
+    pub arguments: Vec<Value>,
+}
+
+impl SceneComponent{
+    /// # New
+    ///
+    /// Creates a component without any properties or methods.
+    pub fn new(name: &str) -> Self{
+        Self{
+            name: name.to_string(),
+            properties: BTreeMap::new(),
+            methods: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// # With
+    ///
+    /// Adds a property, with its starting value.
+    pub fn with(mut self, property: &str, value: Value) -> Self{
+        self.properties.insert(property.to_string(), value);
+        self
+    }
+
+    /// # With Method
+    ///
+    /// Adds a method scripts may call.
+    pub fn with_method(mut self, method: &str) -> Self{
+        self.methods.push(method.to_string());
+        self
+    }
+
+    /// # Rigidbody
+    ///
+    /// Creates a `Rigidbody` at rest, with unity's defaults.
+    pub fn rigidbody() -> Self{
+        Self::new("Rigidbody")
+            .with("velocity", Value::Vector3(Vector3::zero()))
+            .with("angularVelocity", Value::Vector3(Vector3::zero()))
+            .with("mass", Value::Float(1.0))
+            .with("isKinematic", Value::Bool(false))
+            .with_method("AddForce")
+    }
+
+    /// # Renderer
+    ///
+    /// Creates an enabled `Renderer` with a white material.
+    pub fn renderer() -> Self{
+        Self::new("Renderer")
+            .with("enabled", Value::Bool(true))
+            .with("material.color", Value::Color(Color::white()))
+    }
+
+    /// # Light
+    ///
+    /// Creates a `Light`, with unity's defaults.
+    pub fn light() -> Self{
+        Self::new("Light")
+            .with("color", Value::Color(Color::white()))
+            .with("intensity", Value::Float(1.0))
+            .with("range", Value::Float(10.0))
+    }
+
+    /// # Collider
+    ///
+    /// Creates an enabled `Collider`, that isn't a trigger.
+    pub fn collider() -> Self{
+        Self::new("Collider")
+            .with("enabled", Value::Bool(true))
+            .with("isTrigger", Value::Bool(false))
+    }
+
+    /// # Get
+    ///
+    /// Returns the value of a property, if the component has it.
+    pub fn get(&self, property: &str) -> Option<Value>{
+        self.properties.get(property).copied()
+    }
+
+    /// # Set
+    ///
+    /// Sets a property the component has, returning false if it doesn't have it or `value`
+    /// is a different kind of value. Like unity, ints can be written to floats.
+    pub fn set(&mut self, property: &str, value: Value) -> bool{
+        let current = match self.properties.get_mut(property){
+            Some(current) => current,
+            None => return false,
+        };
+
+        let value = match (*current, value){
+            (Value::Float(_), Value::Int(value)) => Value::Float(value as f32),
+            (current, value) if std::mem::discriminant(&current) == std::mem::discriminant(&value) => value,
+            _ => return false,
+        };
+
+        *current = value;
+        true
+    }
+
+    /// # Call
+    ///
+    /// Records a call to one of the component's methods, returning false if it doesn't have it.
+    pub fn call(&mut self, method: &str, arguments: Vec<Value>) -> bool{
+        if !self.methods.iter().any(|name| name == method){
+            return false;
+        }
+
+        self.calls.push(ComponentCall{ method: method.to_string(), arguments });
+        true
+    }
+}
@@ -0,0 +1,53 @@
+use runity::{ContactPoint, Vector3};
+
+/// # Phase
+///
+/// Which part of a collision (or trigger) an event is for - like unity's `Enter`, `Stay`
+/// and `Exit` messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase{
+    Enter,
+    Stay,
+    Exit,
+}
+
+/// # Collision Event
+///
+/// A collision with another gameobject, sent to scripts with `Host::collide`.
+///
+/// The host owns the contact points, and only lends them to the script for the call.
+#[derive(Debug, Clone)]
+pub struct CollisionEvent{
+    /// The id of the other gameobject.
+    pub other: u64,
+    pub contacts: Vec<ContactPoint>,
+    pub relative_velocity: Vector3,
+    pub impulse: Vector3,
+}
+
+impl CollisionEvent{
+    /// # New
+    ///
+    /// Creates a collision with `other`, without any contacts or velocity.
+    pub fn new(other: u64) -> Self{
+        Self{
+            other,
+            contacts: Vec::new(),
+            relative_velocity: Vector3::zero(),
+            impulse: Vector3::zero(),
+        }
+    }
+}
+
+// An event waiting for the next fixed step, for every script attached to `object`
+pub(crate) struct PendingContact{
+    pub(crate) object: u64,
+    pub(crate) phase: Phase,
+    pub(crate) kind: ContactKind,
+}
+
+pub(crate) enum ContactKind{
+    Collision(CollisionEvent),
+    // The id of the other gameobject
+    Trigger(u64),
+}
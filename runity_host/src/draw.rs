@@ -0,0 +1,451 @@
+//! Debug drawings sent by scripts, and a top-down view of them.
+//!
+//! The host keeps every drawing a script sends for as long as it is shown (see
+//! `Scene::drawings`), and `TopDown` renders them, looking down onto the x/z plane, to an SVG
+//! or a PPM image. The output only depends on the drawings, so it can be compared against a
+//! saved snapshot.
+
+use std::fmt::Write;
+
+use runity::{Color, Drawing, DrawingKind, Vector3};
+
+/// # Scene Drawing
+///
+/// A debug drawing sent by a script, copied out of its `ChangeBuffer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneDrawing{
+    pub kind: DrawingKind,
+    /// The position of a label, or where anything else starts (or is centered).
+    pub position: Vector3,
+    /// The lines the drawing is made of (see `Drawing::segments`).
+    pub segments: Vec<(Vector3, Vector3)>,
+    /// The text of a label.
+    pub text: String,
+    pub color: Color,
+    /// The frame the drawing was sent in.
+    pub frame: u64,
+    /// The time (`Time.time`) the drawing is shown until.
+    pub until: f32,
+}
+
+impl SceneDrawing{
+    pub(crate) fn copy(drawing: &Drawing, frame: u64, time: f32) -> Self{
+        Self{
+            kind: drawing.kind(),
+            position: drawing.from(),
+            segments: drawing.segments(),
+            text: drawing.text().to_string(),
+            color: drawing.color(),
+            frame,
+            until: time + drawing.duration(),
+        }
+    }
+
+    // Drawings are shown for at least the frame they were sent in, then until their time is up
+    pub(crate) fn shown(&self, frame: u64, time: f32) -> bool{
+        self.frame == frame || self.until > time
+    }
+}
+
+/// # Top Down
+///
+/// Renders drawings as seen from above, with x to the right and z up the image. `min` and
+/// `max` are the corners of the area shown, in world space (as x and z), which is stretched
+/// over an image `width` by `height` pixels.
+///
+/// Labels are written as text in an SVG. A PPM has no text, so they are marked with a small cross.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopDown{
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+    pub width: u32,
+    pub height: u32,
+    pub background: Color,
+}
+
+impl TopDown{
+    /// # New
+    ///
+    /// Creates a view of the area from `min` to `max` (as x and z), on a black background.
+    pub fn new(min: (f32, f32), max: (f32, f32), width: u32, height: u32) -> Self{
+        Self{
+            min,
+            max,
+            width,
+            height,
+            background: Color::black(),
+        }
+    }
+
+    /// # Fit
+    ///
+    /// Creates a view that fits every drawing, with a small margin, keeping the drawings'
+    /// proportions.
+    pub fn fit(drawings: &[SceneDrawing], width: u32, height: u32) -> Self{
+        let points = drawings.iter().flat_map(|drawing| {
+            drawing.segments.iter()
+                .flat_map(|(a, b)| [*a, *b])
+                .chain(std::iter::once(drawing.position))
+        });
+
+        let (mut min, mut max) = ((f32::INFINITY, f32::INFINITY), (f32::NEG_INFINITY, f32::NEG_INFINITY));
+        for point in points{
+            min = (min.0.min(point.x), min.1.min(point.z));
+            max = (max.0.max(point.x), max.1.max(point.z));
+        }
+        if min.0 > max.0{
+            // Nothing was drawn
+            return Self::new((-1.0, -1.0), (1.0, 1.0), width, height);
+        }
+
+        // Grown to the image's proportions around the middle, so nothing is stretched
+        let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+        let size = ((max.0 - min.0) * 1.1).max(1.0e-3);
+        let depth = ((max.1 - min.1) * 1.1).max(1.0e-3);
+        let aspect = width.max(1) as f32 / height.max(1) as f32;
+        let (size, depth) = if size / depth > aspect{ (size, size / aspect) }else{ (depth * aspect, depth) };
+
+        Self::new((center.0 - size / 2.0, center.1 - depth / 2.0), (center.0 + size / 2.0, center.1 + depth / 2.0), width, height)
+    }
+
+    // Where a point lands in the image, in pixels. Points that don't land anywhere (NaN or
+    // infinite ones, or any point in a view with no area) are `None`, and aren't drawn.
+    fn project(&self, point: Vector3) -> Option<(f32, f32)>{
+        let x = (point.x - self.min.0) / (self.max.0 - self.min.0) * self.width as f32;
+        let y = (self.max.1 - point.z) / (self.max.1 - self.min.1) * self.height as f32;
+        if x.is_finite() && y.is_finite(){ Some((x, y)) }else{ None }
+    }
+
+    /// # SVG
+    ///
+    /// Renders the drawings to an SVG, in the order they were drawn.
+    pub fn svg(&self, drawings: &[SceneDrawing]) -> String{
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#, self.width, self.height);
+        let _ = writeln!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, self.width, self.height, hex(self.background));
+
+        for drawing in drawings{
+            let color = hex(drawing.color);
+            let opacity = drawing.color.a.clamp(0.0, 1.0);
+
+            for (a, b) in drawing.segments.iter(){
+                let (a, b) = match (self.project(*a), self.project(*b)){
+                    (Some(a), Some(b)) => (a, b),
+                    _ => continue,
+                };
+                let _ = writeln!(svg, r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-opacity="{:.2}"/>"#, a.0, a.1, b.0, b.1, color, opacity);
+            }
+
+            if let (DrawingKind::Label, Some((x, y))) = (drawing.kind, self.project(drawing.position)){
+                let _ = writeln!(svg, r#"<text x="{:.2}" y="{:.2}" fill="{}" fill-opacity="{:.2}" font-family="monospace" font-size="12">{}</text>"#, x, y, color, opacity, escape(&drawing.text));
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// # PPM
+    ///
+    /// Renders the drawings to a binary (`P6`) PPM image, in the order they were drawn. Colours
+    /// are blended over what is underneath by their alpha.
+    pub fn ppm(&self, drawings: &[SceneDrawing]) -> Vec<u8>{
+        let (width, height) = (self.width as usize, self.height as usize);
+        let background = rgb(self.background);
+        let mut pixels = vec![background; width * height];
+
+        let mut plot = |x: i64, y: i64, color: Color|{
+            if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height{
+                let pixel = &mut pixels[y as usize * width + x as usize];
+                let alpha = color.a.clamp(0.0, 1.0);
+                let over = rgb(color);
+                for channel in 0..3{
+                    pixel[channel] = (pixel[channel] as f32 * (1.0 - alpha) + over[channel] as f32 * alpha).round() as u8;
+                }
+            }
+        };
+
+        for drawing in drawings{
+            for (a, b) in drawing.segments.iter(){
+                let clipped = match (self.project(*a), self.project(*b)){
+                    (Some(a), Some(b)) => self.clip(a, b),
+                    _ => None,
+                };
+                if let Some((a, b)) = clipped{
+                    line(a, b, |x, y| plot(x, y, drawing.color));
+                }
+            }
+
+            if let (DrawingKind::Label, Some((x, y))) = (drawing.kind, self.project(drawing.position)){
+                let (x, y) = (x.floor() as i64, y.floor() as i64);
+                for offset in -2..=2{
+                    plot(x + offset, y, drawing.color);
+                    if offset != 0{
+                        plot(x, y + offset, drawing.color);
+                    }
+                }
+            }
+        }
+
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        ppm.reserve(width * height * 3);
+        for pixel in pixels{
+            ppm.extend_from_slice(&pixel);
+        }
+        ppm
+    }
+
+    // Clips a line to the image (Liang-Barsky), so lines far outside it aren't walked pixel by pixel
+    fn clip(&self, a: (f32, f32), b: (f32, f32)) -> Option<((f32, f32), (f32, f32))>{
+        // NaN slips through the comparisons below, so it is turned away first
+        if ![a.0, a.1, b.0, b.1].iter().all(|value| value.is_finite()){
+            return None;
+        }
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let (mut start, mut end) = (0.0f32, 1.0f32);
+
+        for (p, q) in [(-dx, a.0), (dx, self.width as f32 - a.0), (-dy, a.1), (dy, self.height as f32 - a.1)]{
+            if p == 0.0{
+                if q < 0.0{
+                    return None;
+                }
+            }else{
+                let t = q / p;
+                if p < 0.0{
+                    start = start.max(t);
+                }else{
+                    end = end.min(t);
+                }
+            }
+        }
+
+        if start > end || !start.is_finite() || !end.is_finite(){
+            return None;
+        }
+        Some(((a.0 + dx * start, a.1 + dy * start), (a.0 + dx * end, a.1 + dy * end)))
+    }
+}
+
+// Walks the pixels of a line (Bresenham)
+fn line(a: (f32, f32), b: (f32, f32), mut plot: impl FnMut(i64, i64)){
+    let (mut x, mut y) = (a.0.floor() as i64, a.1.floor() as i64);
+    let (x1, y1) = (b.0.floor() as i64, b.1.floor() as i64);
+    let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+    let (sx, sy) = (if x < x1{ 1 }else{ -1 }, if y < y1{ 1 }else{ -1 });
+    let mut error = dx + dy;
+
+    loop{
+        plot(x, y);
+        if x == x1 && y == y1{
+            break;
+        }
+
+        let doubled = error * 2;
+        if doubled >= dy{
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx{
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+fn rgb(color: Color) -> [u8; 3]{
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [channel(color.r), channel(color.g), channel(color.b)]
+}
+
+fn hex(color: Color) -> String{
+    let [r, g, b] = rgb(color);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String{
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use std::path::Path;
+
+    // A line's ends, as x and z
+    type Segment = ((f32, f32), (f32, f32));
+
+    fn drawing(kind: DrawingKind, segments: &[Segment], color: Color) -> SceneDrawing{
+        let point = |(x, z): (f32, f32)| Vector3::new(x, 0.0, z);
+        SceneDrawing{
+            kind,
+            position: segments.first().map(|(a, _)| point(*a)).unwrap_or_else(Vector3::zero),
+            segments: segments.iter().map(|(a, b)| (point(*a), point(*b))).collect(),
+            text: String::new(),
+            color,
+            frame: 1,
+            until: 0.0,
+        }
+    }
+
+    fn label(text: &str, (x, z): (f32, f32), color: Color) -> SceneDrawing{
+        SceneDrawing{
+            position: Vector3::new(x, 0.0, z),
+            text: text.to_string(),
+            ..drawing(DrawingKind::Label, &[], color)
+        }
+    }
+
+    // Compares against the snapshot in `tests/snapshots`, writing it instead with UPDATE_SNAPSHOTS set
+    fn assert_snapshot(name: &str, rendered: &[u8]){
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some(){
+            std::fs::write(&path, rendered).unwrap();
+        }
+
+        let snapshot = std::fs::read(&path).unwrap_or_else(|_| panic!("{} is missing - run with UPDATE_SNAPSHOTS=1 to write it", path.display()));
+        assert!(snapshot == rendered, "{} doesn't match its snapshot - run with UPDATE_SNAPSHOTS=1 to update it", name);
+    }
+
+    fn scene() -> Vec<SceneDrawing>{
+        vec![
+            drawing(DrawingKind::WireBox, &[((-3.0, -1.5), (3.0, -1.5)), ((3.0, -1.5), (3.0, 1.5)), ((3.0, 1.5), (-3.0, 1.5)), ((-3.0, 1.5), (-3.0, -1.5))], Color::green()),
+            drawing(DrawingKind::Line, &[((-4.0, -2.0), (4.0, 2.0))], Color::red()),
+            drawing(DrawingKind::Line, &[((-4.0, 2.0), (4.0, -2.0))], Color::new(0.0, 0.0, 1.0, 0.5)),
+            label("a < b & \"c\"", (1.0, 1.0), Color::white()),
+        ]
+    }
+
+    // The pixels of a PPM, without its header
+    fn pixels(ppm: &[u8], width: usize, height: usize) -> &[u8]{
+        &ppm[ppm.len() - width * height * 3..]
+    }
+
+    #[test]
+    fn svg_snapshot(){
+        let view = TopDown::new((-4.0, -2.0), (4.0, 2.0), 32, 16);
+        assert_snapshot("top_down.svg", view.svg(&scene()).as_bytes());
+    }
+
+    #[test]
+    fn ppm_snapshot(){
+        let view = TopDown::new((-4.0, -2.0), (4.0, 2.0), 32, 16);
+        let ppm = view.ppm(&scene());
+
+        assert!(ppm.starts_with(b"P6\n32 16\n255\n"));
+        assert_eq!(ppm.len(), b"P6\n32 16\n255\n".len() + 32 * 16 * 3);
+        assert_snapshot("top_down.ppm", &ppm);
+    }
+
+    #[test]
+    fn fit_keeps_the_image_proportions(){
+        let drawings = [drawing(DrawingKind::Line, &[((-2.0, -1.0), (2.0, 1.0))], Color::red())];
+
+        // Already 2:1, so only the margin is added
+        let wide = TopDown::fit(&drawings, 200, 100);
+        assert!((wide.min.0 + 2.2).abs() < 1e-5 && (wide.min.1 + 1.1).abs() < 1e-5, "{:?}", wide);
+        assert!((wide.max.0 - 2.2).abs() < 1e-5 && (wide.max.1 - 1.1).abs() < 1e-5, "{:?}", wide);
+
+        // A square image grows the depth to match
+        let square = TopDown::fit(&drawings, 100, 100);
+        assert!((square.min.1 + 2.2).abs() < 1e-5 && (square.max.1 - 2.2).abs() < 1e-5, "{:?}", square);
+        assert_eq!((square.min.0, square.max.0), (wide.min.0, wide.max.0));
+    }
+
+    #[test]
+    fn fit_without_any_area(){
+        assert_eq!(TopDown::fit(&[], 10, 10), TopDown::new((-1.0, -1.0), (1.0, 1.0), 10, 10));
+
+        // A single point still gets a view with some area, centered on it
+        let point = TopDown::fit(&[label("here", (3.0, -2.0), Color::white())], 10, 10);
+        assert!(point.max.0 > point.min.0 && point.max.1 > point.min.1);
+        assert!(((point.min.0 + point.max.0) / 2.0 - 3.0).abs() < 1e-5);
+
+        // NaN points are left out
+        let drawings = [drawing(DrawingKind::Line, &[((f32::NAN, 0.0), (1.0, f32::NAN)), ((-1.0, -1.0), (1.0, 1.0))], Color::red())];
+        let fitted = TopDown::fit(&drawings, 10, 10);
+        assert!(fitted.min.0.is_finite() && fitted.max.1.is_finite(), "{:?}", fitted);
+    }
+
+    #[test]
+    fn clip_to_the_image(){
+        let view = TopDown::new((0.0, 0.0), (10.0, 10.0), 10, 10);
+
+        // Inside, untouched
+        assert_eq!(view.clip((1.0, 2.0), (8.0, 9.0)), Some(((1.0, 2.0), (8.0, 9.0))));
+        // Crossing an edge, cut at it
+        assert_eq!(view.clip((-5.0, 5.0), (5.0, 5.0)), Some(((0.0, 5.0), (5.0, 5.0))));
+        assert_eq!(view.clip((5.0, -10.0), (5.0, 20.0)), Some(((5.0, 0.0), (5.0, 10.0))));
+        // Crossing the whole image
+        assert_eq!(view.clip((-10.0, -10.0), (20.0, 20.0)), Some(((0.0, 0.0), (10.0, 10.0))));
+    }
+
+    #[test]
+    fn clip_segments_outside_the_image(){
+        let view = TopDown::new((0.0, 0.0), (10.0, 10.0), 10, 10);
+
+        // Beside the image, parallel to an edge
+        assert_eq!(view.clip((-5.0, 1.0), (-5.0, 9.0)), None);
+        assert_eq!(view.clip((1.0, 12.0), (9.0, 12.0)), None);
+        // Pointing at it, but stopping short
+        assert_eq!(view.clip((-10.0, 5.0), (-1.0, 5.0)), None);
+        // Passing a corner
+        assert_eq!(view.clip((-5.0, 4.0), (4.0, -5.0)), None);
+        // NaN
+        assert_eq!(view.clip((f32::NAN, 5.0), (5.0, 5.0)), None);
+    }
+
+    #[test]
+    fn line_walks_every_pixel(){
+        let walk = |a: (f32, f32), b: (f32, f32)| {
+            let mut pixels = Vec::new();
+            line(a, b, |x, y| pixels.push((x, y)));
+            pixels
+        };
+
+        assert_eq!(walk((0.5, 0.5), (3.5, 0.5)), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(walk((0.0, 0.0), (3.0, 3.0)), [(0, 0), (1, 1), (2, 2), (3, 3)]);
+        assert_eq!(walk((0.0, 0.0), (1.0, 3.0)), [(0, 0), (0, 1), (1, 2), (1, 3)]);
+        assert_eq!(walk((3.0, 1.0), (0.0, 0.0)), [(3, 1), (2, 1), (1, 0), (0, 0)]);
+        assert_eq!(walk((2.7, 4.2), (2.1, 4.9)), [(2, 4)]);
+    }
+
+    #[test]
+    fn fully_clipped_segments_draw_nothing(){
+        let view = TopDown::new((0.0, 0.0), (10.0, 10.0), 10, 10);
+        let outside = [drawing(DrawingKind::Line, &[((-5.0, -5.0), (-1.0, 20.0)), ((11.0, 0.0), (30.0, 10.0))], Color::white())];
+
+        assert_eq!(view.ppm(&outside), view.ppm(&[]));
+    }
+
+    #[test]
+    fn degenerate_views_draw_nothing(){
+        let drawings = scene();
+
+        for view in [TopDown::new((1.0, 1.0), (1.0, 1.0), 8, 8), TopDown::new((-1.0, 2.0), (1.0, 2.0), 8, 8)].iter(){
+            assert_eq!(view.ppm(&drawings), view.ppm(&[]));
+            assert_eq!(view.svg(&drawings), view.svg(&[]));
+        }
+    }
+
+    #[test]
+    fn nan_points_are_left_out(){
+        let view = TopDown::new((0.0, 0.0), (4.0, 4.0), 4, 4);
+        let drawings = [
+            drawing(DrawingKind::Line, &[((f32::NAN, 1.0), (3.0, 1.0)), ((0.5, 3.5), (3.5, 3.5))], Color::white()),
+            label("lost", (f32::NAN, f32::NAN), Color::white()),
+        ];
+
+        let svg = view.svg(&drawings);
+        assert_eq!(svg.matches("<line").count(), 1, "{}", svg);
+        assert!(!svg.contains("NaN") && !svg.contains("<text"), "{}", svg);
+
+        // Only the bottom row is drawn
+        let ppm = view.ppm(&drawings);
+        let lit: Vec<usize> = pixels(&ppm, 4, 4).chunks(3).enumerate()
+            .filter(|(_, pixel)| *pixel != [0, 0, 0])
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(lit, [0, 1, 2, 3]);
+    }
+}
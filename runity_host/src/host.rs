@@ -0,0 +1,917 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::{c_char, OsStr};
+use std::fmt;
+use std::path::Path;
+
+use runity::{AbiMismatch, Axis, Collision, Command, CommandKind, CommandStatus, DataStruct, Debug, GameObject, Handle, Input, InputSnapshot, Instance, Message, MessageKind, Profiler, ReceivedMessage, Recipients, SpanSample, State, Status, Str, Transform, Trigger};
+
+use crate::callbacks::{self, LogEntry, LogLevel};
+use crate::contact::{ContactKind, PendingContact};
+use crate::{Clock, CollisionEvent, Library, Phase, Scene, SceneDrawing};
+
+/// # Host Error
+///
+/// Everything that can go wrong while loading or driving a library.
+#[derive(Debug)]
+pub enum HostError{
+    /// The library couldn't be loaded.
+    Load(libloading::Error),
+    /// The library couldn't be copied, to be loaded again after it was rebuilt.
+    Copy(std::io::Error),
+    /// The library doesn't export a required function.
+    MissingExport(&'static str),
+    /// The library was built against a different runity ABI than the host.
+    Incompatible(AbiMismatch),
+    /// There is no gameobject with this id in the scene.
+    UnknownObject(u64),
+    /// The rebuilt library couldn't restore the saved state of the script attached to
+    /// this gameobject, so the hot reload was refused.
+    StateRejected{ object: u64, reason: String },
+}
+
+impl fmt::Display for HostError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            HostError::Load(error) => write!(f, "failed to load library: {}", error),
+            HostError::Copy(error) => write!(f, "failed to copy library: {}", error),
+            HostError::MissingExport(name) => write!(f, "library does not export `{}`", name),
+            HostError::Incompatible(mismatch) => write!(f, "library is not compatible with this host: {}", mismatch),
+            HostError::UnknownObject(id) => write!(f, "no gameobject with id {} in the scene", id),
+            HostError::StateRejected{ object, reason } => write!(f, "the state of the script attached to gameobject {} couldn't be restored: {}", object, reason),
+        }
+    }
+}
+
+impl std::error::Error for HostError{}
+
+/// # Sent Change
+///
+/// A change to a gameobject sent by a script, copied out of the library's change buffer.
+#[derive(Clone)]
+pub struct SentChange{
+    /// The id of the gameobject the change is for.
+    pub object: u64,
+    pub tag: String,
+    pub transform: Transform,
+}
+
+// A script attached to a gameobject in the scene
+struct Attached{
+    object: u64,
+    instance: Instance,
+    // Taken while the library is being called, as it is passed by value
+    data: Option<DataStruct>,
+    enabled: bool,
+    // Whether the gameobject was active in the hierarchy when last checked
+    active: bool,
+    started: bool,
+    changes: Vec<SentChange>,
+    // The messages the script is subscribed to. Kept by the host, so they last through a hot reload.
+    subscriptions: BTreeSet<String>,
+}
+
+// Who a published message is for, once any spawned handle has been resolved
+enum Route{
+    Object(u64),
+    Tag(String),
+    All,
+}
+
+// A message published during a frame, posted to its recipients in the next one
+struct Published{
+    name: String,
+    payload: Vec<u8>,
+    route: Route,
+    // The gameobject that published it, or 0 for the host
+    sender: u64,
+}
+
+impl Published{
+    fn reaches(&self, scene: &Scene, object: u64) -> bool{
+        match &self.route{
+            Route::Object(id) => *id == object,
+            Route::Tag(tag) => scene.get(object).map(|object| &object.tag == tag).unwrap_or(false),
+            Route::All => true,
+        }
+    }
+}
+
+impl Attached{
+    // Like unity, a script only runs while it is enabled and its gameobject is active
+    fn running(&self) -> bool{
+        self.enabled && self.active
+    }
+}
+
+/// # Host
+///
+/// Loads a runity library, and runs it against an in-memory `Scene`.
+///
+/// Scripts are attached to gameobjects in the scene with `attach` (which calls `awake`
+/// and `on_enable`), then driven frame by frame with `step`. Like unity, each frame calls
+/// `start` on any script that hasn't started yet, `fixed_update` once for every fixed step
+/// that fits in the frame, then `update` and finally `late_update`. Each function is called
+/// on every enabled script, in the order they were attached, before moving on to the next.
+///
+/// Collisions and triggers (see `collide` and `trigger`) are sent after `fixed_update`, as
+/// unity sends them after each physics step.
+///
+/// Messages published during a frame (by scripts, or with `publish`) are posted to every
+/// running script subscribed to them right before the next frame's `update`, which passes them
+/// to `Script::on_message`. Messages for scripts that aren't running are dropped.
+///
+/// Commands sent by scripts are carried out as soon as the script returns. At the end of each
+/// frame, scripts on destroyed gameobjects are destroyed, scripts on gameobjects that were
+/// activated or deactivated are enabled or disabled, and the script is attached to anything
+/// spawned from a scripted prefab.
+pub struct Host{
+    scene: Scene,
+    clock: Clock,
+    logs: Vec<LogEntry>,
+    attached: Vec<Attached>,
+    contacts: Vec<PendingContact>,
+    messages: Vec<Published>,
+
+    // The input sampled at the start of each frame, and the input for the current frame
+    next_input: InputSnapshot,
+    input: Input,
+    // Axis names are never removed, so they stay valid while the axes are lent
+    axes: Vec<(Box<str>, f32)>,
+    // The axes lent to the current frame's input. Only rebuilt at the start of a frame,
+    // right before every script's input is replaced.
+    lent_axes: Vec<Axis<'static>>,
+    // The profiler handed to the library, which is handed to the new library after a reload
+    profiler: Option<Profiler>,
+
+    // Kept last, so it is dropped after everything that came from it
+    library: Library,
+}
+
+impl Host{
+    /// # Load
+    ///
+    /// Loads the library at `path`, with an empty scene and a 60 frames per second clock.
+    pub fn load<P: AsRef<OsStr>>(path: P) -> Result<Self, HostError>{
+        Ok(Self::new(Library::load(path)?))
+    }
+
+    /// # New
+    ///
+    /// Creates a host for an already loaded library.
+    pub fn new(library: Library) -> Self{
+        Self{
+            scene: Scene::new(),
+            clock: Clock::default(),
+            logs: Vec::new(),
+            attached: Vec::new(),
+            contacts: Vec::new(),
+            messages: Vec::new(),
+            next_input: InputSnapshot::EMPTY,
+            input: Input::default(),
+            axes: Vec::new(),
+            lent_axes: Vec::new(),
+            profiler: None,
+            library,
+        }
+    }
+
+    /// # Attach
+    ///
+    /// Attaches the library's script to the gameobject `object`, and runs `awake` then `on_enable`
+    /// (if the gameobject is active).
+    ///
+    /// Returns the instance the script allocated. If the library doesn't export `awake`,
+    /// this is `Instance::NONE`.
+    pub fn attach(&mut self, object: u64) -> Result<Instance, HostError>{
+        let data = self.new_data(object)?;
+
+        let mut attached = Attached{
+            object,
+            instance: Instance::NONE,
+            data: Some(data),
+            enabled: true,
+            active: self.scene.active_in_hierarchy(object),
+            started: false,
+            changes: Vec::new(),
+            subscriptions: BTreeSet::new(),
+        };
+
+        if let Some(awake) = self.library.awake{
+            Self::call(&mut self.scene, &mut self.logs, &mut attached, awake);
+        }
+
+        attached.instance = attached.data.as_ref().map(|data| data.instance).unwrap_or_default();
+
+        if let (true, Some(on_enable)) = (attached.running(), self.library.on_enable){
+            Self::call(&mut self.scene, &mut self.logs, &mut attached, on_enable);
+            Self::sync(&mut self.scene, &mut self.clock, &self.library, &mut self.messages, &mut attached);
+        }
+
+        let instance = attached.instance;
+        self.attached.push(attached);
+        Ok(instance)
+    }
+
+    // Builds the data for a script attached to `object`, before it has an instance
+    fn new_data(&self, object: u64) -> Result<DataStruct, HostError>{
+        let transform = self.scene.transform(object).ok_or(HostError::UnknownObject(object))?;
+
+        Ok(DataStruct{
+            transform: transform.clone(),
+            game_object: GameObject::new(object, transform, callbacks::lookup(), callbacks::components()),
+            time: self.clock.time(),
+            input: self.input,
+            debug: Debug::new(callbacks::log, callbacks::log_warning, callbacks::log_error, callbacks::log_record),
+            physics: callbacks::physics(),
+            instance: Instance::NONE,
+        })
+    }
+
+    /// # Reload
+    ///
+    /// Hot reloads the library, replacing it with the (rebuilt) library at `path`.
+    ///
+    /// The state of every attached script is saved from the old library and restored into the
+    /// new one (see `Script::save_state`), so scripts carry on where they left off - neither
+    /// `awake` nor `start` are run again. Faulted scripts, and scripts without state, start
+    /// again from `Default`. Once every script has been restored, the old library's scripts are
+    /// destroyed (running their `destroy`) before it is unloaded.
+    ///
+    /// If the new library can't be loaded, or any script's state can't be restored (such as when
+    /// its `STATE_VERSION` changed), the reload is refused and the old library keeps running. The
+    /// scripts restored before then are destroyed in the new library, running their `destroy`.
+    pub fn reload<P: AsRef<Path>>(&mut self, path: P) -> Result<(), HostError>{
+        let library = Library::load_copy(path)?;
+
+        let save_state = self.library.save_state.ok_or(HostError::MissingExport("runity_save_state"))?;
+        let state_free = self.library.state_free.ok_or(HostError::MissingExport("runity_state_free"))?;
+        let load_state = library.load_state.ok_or(HostError::MissingExport("runity_load_state"))?;
+
+        // Copy every script's state out of the old library, which owns it
+        let mut saved = Vec::new();
+        for script in self.attached.iter(){
+            let state = callbacks::with_active(&mut self.scene, &mut self.logs, || save_state(script.instance));
+            let bytes = if state.is_empty(){ None } else { Some(unsafe{ state.as_bytes() }.to_vec()) };
+            saved.push((bytes, state.version));
+            state_free(state);
+        }
+
+        // Restore it into the new library. Nothing is changed until every script has been restored.
+        let mut restored = Vec::new();
+        let mut rejected = None;
+        for (script, (bytes, version)) in self.attached.iter().zip(saved.iter()){
+            let mut data = match self.new_data(script.object){
+                Ok(data) => data,
+                Err(error) => {
+                    rejected = Some(error);
+                    break;
+                }
+            };
+            let state = match bytes{
+                Some(bytes) => State{ data: bytes.as_ptr(), len: bytes.len() as u32, version: *version },
+                None => State::EMPTY,
+            };
+
+            let logged = self.logs.len();
+            let status = callbacks::with_active(&mut self.scene, &mut self.logs, || load_state(&mut data, state));
+            if status != Status::Ok as i32{
+                // The library reports why through `Debug::log_error`
+                let reason = self.logs[logged..].iter().rev()
+                    .find(|entry| entry.level == LogLevel::Error)
+                    .map(|entry| entry.message.clone())
+                    .unwrap_or_else(|| format!("`runity_load_state` returned {}", status));
+                rejected = Some(HostError::StateRejected{ object: script.object, reason });
+                break;
+            }
+
+            restored.push(data);
+        }
+
+        // The scripts already restored are destroyed before the new library is unloaded, so
+        // nothing of theirs is left behind
+        if let Some(error) = rejected{
+            let destroy = library.destroy;
+            for data in restored{
+                callbacks::with_active(&mut self.scene, &mut self.logs, || destroy(data));
+            }
+            return Err(error);
+        }
+
+        // The old library's instances are destroyed before it is unloaded, so nothing of theirs is
+        // left behind. Anything their `destroy` changes is dropped with them.
+        let destroy = self.library.destroy;
+        for (script, data) in self.attached.iter_mut().zip(restored){
+            script.instance = data.instance;
+            if let Some(old) = script.data.replace(data){
+                callbacks::with_active(&mut self.scene, &mut self.logs, || destroy(old));
+            }
+        }
+
+        self.library = library;
+        if let (Some(profiler), Some(set_profiler)) = (self.profiler, self.library.set_profiler){
+            set_profiler(profiler);
+        }
+        Ok(())
+    }
+
+    /// # Step
+    ///
+    /// Runs `frames` frames, advancing the clock before each one.
+    pub fn step(&mut self, frames: u32){
+        for _ in 0..frames{
+            self.clock.advance();
+            self.sample_input();
+            self.scene.expire_drawings(self.clock.frame_count(), self.clock.time().time);
+
+            // Only messages published before this frame are posted in it
+            let posting = std::mem::take(&mut self.messages);
+
+            let Host{ scene, clock, logs, attached, contacts, messages, input, library, .. } = self;
+
+            // Prepare every script's data for this frame
+            for script in attached.iter_mut(){
+                if let Some(data) = script.data.as_mut(){
+                    data.time = clock.time();
+                    data.input = *input;
+                }
+                script.changes.clear();
+            }
+
+            // Like unity, every new script starts before any script updates
+            for script in attached.iter_mut().filter(|script| script.running() && !script.started){
+                script.started = true;
+                if let Some(start) = library.start{
+                    Self::call(scene, logs, script, start);
+                }
+                Self::sync(scene, clock, library, messages, script);
+            }
+
+            // Fixed steps run before `update`, with the fixed step's time
+            while clock.advance_fixed(){
+                if let Some(fixed_update) = library.fixed_update{
+                    for script in attached.iter_mut().filter(|script| script.running()){
+                        if let Some(data) = script.data.as_mut(){
+                            data.time = clock.fixed_time();
+                        }
+                        Self::call(scene, logs, script, fixed_update);
+                        Self::sync(scene, clock, library, messages, script);
+                    }
+                }
+
+                for contact in std::mem::take(contacts){
+                    Self::send_contact(scene, clock, logs, attached, library, messages, contact);
+                }
+            }
+
+            for script in attached.iter_mut(){
+                if let Some(data) = script.data.as_mut(){
+                    data.time = clock.time();
+                }
+            }
+
+            Self::post_messages(scene, logs, attached, library, &posting);
+
+            for function in [library.update, library.late_update].iter().flatten(){
+                for script in attached.iter_mut().filter(|script| script.running()){
+                    Self::call(scene, logs, script, *function);
+                    Self::sync(scene, clock, library, messages, script);
+                }
+            }
+
+            self.settle();
+        }
+    }
+
+    // Catches the scripts up with the commands carried out this frame
+    fn settle(&mut self){
+        // Scripts on destroyed gameobjects are destroyed with them
+        let (destroyed, attached): (Vec<Attached>, Vec<Attached>) = std::mem::take(&mut self.attached)
+            .into_iter()
+            .partition(|script| self.scene.get(script.object).is_none());
+        self.attached = attached;
+
+        for script in destroyed{
+            self.destroy_attached(script);
+        }
+
+        let Host{ scene, clock, logs, attached, messages, library, .. } = self;
+
+        for script in attached.iter_mut(){
+            let active = scene.active_in_hierarchy(script.object);
+            if script.active == active{
+                continue;
+            }
+            script.active = active;
+
+            let function = if active { library.on_enable } else { library.on_disable };
+            if let (true, Some(function)) = (script.enabled, function){
+                Self::call(scene, logs, script, function);
+                Self::sync(scene, clock, library, messages, script);
+            }
+        }
+
+        for object in self.scene.take_awaiting_scripts(){
+            // Spawning can't have picked an unknown gameobject
+            let _ = self.attach(object);
+        }
+    }
+
+    // Moves the input on to the next frame, lending it the current axes
+    fn sample_input(&mut self){
+        self.lent_axes = self.axes.iter()
+            .map(|(name, value)| Axis{
+                // The names live as long as the host, and are never changed
+                name: unsafe{ Str::from_raw_parts(name.as_ptr() as *const c_char, name.len()) },
+                value: *value,
+            })
+            .collect();
+
+        let mut next = self.next_input;
+        unsafe{ next.set_axes(&self.lent_axes) };
+        self.input.advance(next);
+    }
+
+    /// # Input Mut
+    ///
+    /// Returns the input sampled at the start of every following frame, mutably. Use this to
+    /// press and release keys and buttons, and move the mouse.
+    ///
+    /// Like unity, a key pressed here is only "down" (`Input::get_key_down`) during the first
+    /// frame it is held. Axes are set with `set_axis`.
+    pub fn input_mut(&mut self) -> &mut InputSnapshot{
+        &mut self.next_input
+    }
+
+    /// # Set Axis
+    ///
+    /// Sets the value of a named axis, from the start of the next frame.
+    pub fn set_axis(&mut self, name: &str, value: f32){
+        match self.axes.iter_mut().find(|(axis, _)| &**axis == name){
+            Some((_, axis_value)) => *axis_value = value,
+            None => self.axes.push((name.into(), value)),
+        }
+    }
+
+    /// # Set Enabled
+    ///
+    /// Enables or disables the script with this instance, like setting `enabled` on a
+    /// `MonoBehaviour`, running `on_enable` or `on_disable` if it changed. Disabled scripts
+    /// aren't started or updated until they are enabled again.
+    ///
+    /// Returns false if no script has this instance.
+    pub fn set_enabled(&mut self, instance: Instance, enabled: bool) -> bool{
+        let Host{ scene, clock, logs, attached, messages, library, .. } = self;
+
+        let script = match attached.iter_mut().find(|script| script.instance == instance){
+            Some(script) => script,
+            None => return false,
+        };
+
+        if script.enabled != enabled{
+            script.enabled = enabled;
+
+            let function = if enabled { library.on_enable } else { library.on_disable };
+            if let (true, Some(function)) = (script.active, function){
+                Self::call(scene, logs, script, function);
+                Self::sync(scene, clock, library, messages, script);
+            }
+        }
+
+        true
+    }
+
+    /// # Collide
+    ///
+    /// Sends a collision between `object` and `collision.other` to every script attached to
+    /// `object`, through `on_collision_enter`, `on_collision_stay` or `on_collision_exit`.
+    ///
+    /// Like unity, the collision is sent during the next fixed step, after `fixed_update`. The
+    /// host doesn't simulate physics, so `Phase::Stay` must be sent every fixed step the objects
+    /// keep touching.
+    pub fn collide(&mut self, object: u64, phase: Phase, collision: CollisionEvent) -> Result<(), HostError>{
+        self.queue_contact(object, collision.other, phase, ContactKind::Collision(collision))
+    }
+
+    /// # Trigger
+    ///
+    /// Sends `other` entering, staying in or leaving a trigger to every script attached to
+    /// `object`, through `on_trigger_enter`, `on_trigger_stay` or `on_trigger_exit`. Like
+    /// `collide`, this is sent during the next fixed step.
+    pub fn trigger(&mut self, object: u64, phase: Phase, other: u64) -> Result<(), HostError>{
+        self.queue_contact(object, other, phase, ContactKind::Trigger(other))
+    }
+
+    fn queue_contact(&mut self, object: u64, other: u64, phase: Phase, kind: ContactKind) -> Result<(), HostError>{
+        for id in [object, other].iter(){
+            self.scene.get(*id).ok_or(HostError::UnknownObject(*id))?;
+        }
+
+        self.contacts.push(PendingContact{ object, phase, kind });
+        Ok(())
+    }
+
+    // Sends a collision or trigger to every script attached to its object, lending it the
+    // contacts and the other object's tag for the call. Like unity, disabled scripts get it too.
+    fn send_contact(scene: &mut Scene, clock: &mut Clock, logs: &mut Vec<LogEntry>, attached: &mut [Attached], library: &Library, messages: &mut Vec<Published>, contact: PendingContact){
+        let other = match &contact.kind{
+            ContactKind::Collision(collision) => collision.other,
+            ContactKind::Trigger(other) => *other,
+        };
+        // The other object may have been removed since the contact was queued
+        let tag = scene.get(other).map(|object| object.tag.clone()).unwrap_or_default();
+
+        for script in attached.iter_mut().filter(|script| script.object == contact.object){
+            match &contact.kind{
+                ContactKind::Collision(collision) => if let Some(function) = library.on_collision(contact.phase){
+                    let collision = Collision::new(other, Str::from(tag.as_str()), &collision.contacts, collision.relative_velocity, collision.impulse);
+                    Self::call_with(scene, logs, script, |data| function(data, &collision));
+                },
+                ContactKind::Trigger(_) => if let Some(function) = library.on_trigger(contact.phase){
+                    let trigger = Trigger::new(other, Str::from(tag.as_str()));
+                    Self::call_with(scene, logs, script, |data| function(data, &trigger));
+                },
+            }
+            Self::sync(scene, clock, library, messages, script);
+        }
+    }
+
+    // Posts each script the messages it is subscribed to, lending them for the call
+    fn post_messages(scene: &mut Scene, logs: &mut Vec<LogEntry>, attached: &[Attached], library: &Library, messages: &[Published]){
+        let post_messages = match library.post_messages{
+            Some(post_messages) if !messages.is_empty() => post_messages,
+            _ => return,
+        };
+
+        for script in attached.iter().filter(|script| script.running()){
+            let received: Vec<ReceivedMessage> = messages.iter()
+                .filter(|message| script.subscriptions.contains(&message.name) && message.reaches(scene, script.object))
+                .map(|message| ReceivedMessage::new(Str::from(message.name.as_str()), &message.payload, message.sender))
+                .collect();
+
+            if !received.is_empty(){
+                callbacks::with_active(scene, logs, || post_messages(script.instance, received.as_ptr(), received.len() as u32));
+            }
+        }
+    }
+
+    /// # Publish
+    ///
+    /// Publishes a message from the host (like unity UI code would), posted to every script in
+    /// `to` subscribed to `name` before the next frame's `update`. It has no sender.
+    pub fn publish(&mut self, to: Recipients, name: &str, payload: &[u8]){
+        let route = match to{
+            Recipients::Object(handle) => Route::Object(handle.id()),
+            Recipients::Tag(tag) => Route::Tag(tag.to_string()),
+            Recipients::All => Route::All,
+        };
+
+        self.messages.push(Published{ name: name.to_string(), payload: payload.to_vec(), route, sender: 0 });
+    }
+
+    /// # Subscriptions
+    ///
+    /// Returns the messages the script with this instance is subscribed to.
+    pub fn subscriptions(&self, instance: Instance) -> Vec<String>{
+        self.attached.iter()
+            .find(|script| script.instance == instance)
+            .map(|script| script.subscriptions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// # Set Focus
+    ///
+    /// Tells every enabled script the application gained (`focused` is true) or lost focus,
+    /// through `on_application_focus`.
+    pub fn set_focus(&mut self, focused: bool){
+        if let Some(on_application_focus) = self.library.on_application_focus{
+            self.call_enabled(|data| on_application_focus(data, focused));
+        }
+    }
+
+    /// # Set Paused
+    ///
+    /// Tells every enabled script the application was paused (`paused` is true) or resumed,
+    /// through `on_application_pause`.
+    pub fn set_paused(&mut self, paused: bool){
+        if let Some(on_application_pause) = self.library.on_application_pause{
+            self.call_enabled(|data| on_application_pause(data, paused));
+        }
+    }
+
+    /// # Quit
+    ///
+    /// Quits the application like unity does, running `on_application_quit` on every enabled
+    /// script, then destroying every script (which runs `on_disable` first, if it is enabled).
+    ///
+    /// This is also done when the host is dropped.
+    pub fn quit(&mut self){
+        if let Some(on_application_quit) = self.library.on_application_quit{
+            self.call_enabled(|data| on_application_quit(data));
+        }
+
+        for script in std::mem::take(&mut self.attached){
+            self.destroy_attached(script);
+        }
+    }
+
+    // Calls `function` on every enabled script, in the order they were attached
+    fn call_enabled(&mut self, function: impl Fn(DataStruct) -> DataStruct){
+        let Host{ scene, clock, logs, attached, messages, library, .. } = self;
+
+        for script in attached.iter_mut().filter(|script| script.running()){
+            Self::call_with(scene, logs, script, &function);
+            Self::sync(scene, clock, library, messages, script);
+        }
+    }
+
+    /// # Destroy
+    ///
+    /// Runs `destroy` on the script with the given instance (after `on_disable`, if it is
+    /// enabled), and detaches it.
+    ///
+    /// Returns the value returned by `destroy`, or `None` if no script has this instance.
+    pub fn destroy(&mut self, instance: Instance) -> Option<i32>{
+        let index = self.attached.iter().position(|script| script.instance == instance)?;
+        let script = self.attached.remove(index);
+        self.destroy_attached(script)
+    }
+
+    fn destroy_attached(&mut self, mut script: Attached) -> Option<i32>{
+        if let (true, Some(on_disable)) = (script.running(), self.library.on_disable){
+            Self::call(&mut self.scene, &mut self.logs, &mut script, on_disable);
+            Self::sync(&mut self.scene, &mut self.clock, &self.library, &mut self.messages, &mut script);
+        }
+
+        let destroy = self.library.destroy;
+        let data = script.data.take()?;
+        Some(callbacks::with_active(&mut self.scene, &mut self.logs, || destroy(data)))
+    }
+
+    // Calls a lifecycle function with the script's data, keeping what it returns
+    fn call(scene: &mut Scene, logs: &mut Vec<LogEntry>, script: &mut Attached, function: extern "C" fn(DataStruct) -> DataStruct){
+        Self::call_with(scene, logs, script, |data| function(data));
+    }
+
+    // Like `call`, for functions taking more than the script's data
+    fn call_with(scene: &mut Scene, logs: &mut Vec<LogEntry>, script: &mut Attached, function: impl FnOnce(DataStruct) -> DataStruct){
+        if let Some(mut data) = script.data.take(){
+            // Another script may have moved this gameobject (or its parent) since the last call,
+            // so like the unity host, the transform is read fresh every call
+            if let Some(transform) = scene.transform(script.object){
+                data.game_object.transform = transform.clone();
+                data.transform = transform;
+            }
+            script.data = Some(callbacks::with_active(scene, logs, || function(data)));
+        }
+    }
+
+    // Applies what the script returned back to the scene and clock, like the unity host does
+    fn sync(scene: &mut Scene, clock: &mut Clock, library: &Library, messages: &mut Vec<Published>, script: &mut Attached){
+        if let Some(data) = script.data.as_ref(){
+            clock.time_scale = data.time.time_scale;
+            clock.fixed_delta_time = data.time.fixed_delta_time;
+
+            scene.set_transform(script.object, &data.transform);
+        }
+
+        // The changes are only lent until they are released, so they are applied and copied first
+        if let (Some(acquire_changes), Some(release_changes)) = (library.acquire_changes, library.release_changes){
+            let buffer = acquire_changes(script.instance);
+            for change in unsafe{ buffer.as_slice() }.iter(){
+                scene.set_transform(change.hash(), &change.transform);
+                script.changes.push(SentChange{
+                    object: change.hash(),
+                    tag: change.tag.to_string(),
+                    transform: change.transform.clone(),
+                });
+            }
+
+            // Gameobjects spawned by this batch, by the pending handle they were spawned with
+            let mut spawned = HashMap::new();
+            for command in unsafe{ buffer.commands() }.iter_mut(){
+                Self::run_command(scene, &mut spawned, command);
+            }
+
+            // Messages come after the commands, so they can be published to what was just spawned
+            for message in unsafe{ buffer.messages() }.iter(){
+                Self::route_message(&spawned, script, messages, message);
+            }
+
+            let (frame, time) = (clock.frame_count(), clock.time().time);
+            for drawing in unsafe{ buffer.drawings() }.iter(){
+                scene.add_drawing(SceneDrawing::copy(drawing, frame, time));
+            }
+            release_changes(script.instance);
+        }
+    }
+
+    // Updates the script's subscriptions, or queues a message it published
+    fn route_message(spawned: &HashMap<u64, u64>, script: &mut Attached, messages: &mut Vec<Published>, message: &Message){
+        let name = message.name().to_string();
+
+        match message.kind(){
+            MessageKind::Subscribe => {
+                script.subscriptions.insert(name);
+            }
+            MessageKind::Unsubscribe => {
+                script.subscriptions.remove(&name);
+            }
+            MessageKind::Publish => {
+                let route = match message.recipients(){
+                    Recipients::Object(handle) => Route::Object(spawned.get(&handle.id()).copied().unwrap_or_else(|| handle.id())),
+                    Recipients::Tag(tag) => Route::Tag(tag.to_string()),
+                    Recipients::All => Route::All,
+                };
+
+                messages.push(Published{ name, payload: message.payload().to_vec(), route, sender: script.object });
+            }
+        }
+    }
+
+    // Carries out a command sent by a script, writing back whether it worked
+    fn run_command(scene: &mut Scene, spawned: &mut HashMap<u64, u64>, command: &mut Command){
+        let resolve = |handle: Handle| spawned.get(&handle.id()).copied().unwrap_or_else(|| handle.id());
+        let target = resolve(command.target());
+
+        let status = match command.kind(){
+            CommandKind::Spawn => match scene.find_prefab(command.prefab()){
+                Some(prefab) => {
+                    let object = scene.instantiate(prefab, command.transform());
+                    spawned.insert(command.target().id(), object);
+                    command.complete(CommandStatus::Done, object);
+                    return;
+                }
+                None => CommandStatus::UnknownPrefab,
+            },
+            CommandKind::Destroy => match scene.remove(target){
+                Some(_) => CommandStatus::Done,
+                None => CommandStatus::UnknownObject,
+            },
+            CommandKind::SetActive => match scene.get_mut(target){
+                Some(object) => {
+                    object.active = command.active();
+                    CommandStatus::Done
+                }
+                None => CommandStatus::UnknownObject,
+            },
+            CommandKind::SetParent => {
+                let parent = command.parent().map(resolve);
+                if scene.get(target).is_none(){
+                    CommandStatus::UnknownObject
+                }else if scene.set_parent(target, parent, command.world_position_stays()){
+                    CommandStatus::Done
+                }else{
+                    CommandStatus::InvalidParent
+                }
+            }
+        };
+
+        command.complete(status, 0);
+    }
+
+    /// # Scene
+    ///
+    /// Returns the scene scripts are running in.
+    pub fn scene(&self) -> &Scene{
+        &self.scene
+    }
+
+    /// # Scene Mut
+    ///
+    /// Returns the scene scripts are running in, mutably. Use this to set up gameobjects
+    /// before attaching scripts.
+    pub fn scene_mut(&mut self) -> &mut Scene{
+        &mut self.scene
+    }
+
+    /// # Clock
+    ///
+    /// Returns the clock used to fill in `Time`.
+    pub fn clock(&self) -> &Clock{
+        &self.clock
+    }
+
+    /// # Clock Mut
+    ///
+    /// Returns the clock used to fill in `Time`, mutably.
+    pub fn clock_mut(&mut self) -> &mut Clock{
+        &mut self.clock
+    }
+
+    /// # Logs
+    ///
+    /// Returns everything logged so far.
+    pub fn logs(&self) -> &[LogEntry]{
+        &self.logs
+    }
+
+    /// # Take Logs
+    ///
+    /// Returns everything logged so far, clearing the log.
+    pub fn take_logs(&mut self) -> Vec<LogEntry>{
+        std::mem::take(&mut self.logs)
+    }
+
+    /// # Transform
+    ///
+    /// Returns the transform (in world space) of the gameobject the script with this instance
+    /// is attached to.
+    pub fn transform(&self, instance: Instance) -> Option<Transform>{
+        let script = self.attached.iter().find(|script| script.instance == instance)?;
+        self.scene.transform(script.object)
+    }
+
+    /// # Changes
+    ///
+    /// Returns the changes sent by the script with this instance during the last frame.
+    pub fn changes(&self, instance: Instance) -> &[SentChange]{
+        self.attached.iter()
+            .find(|script| script.instance == instance)
+            .map(|script| script.changes.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// # Is Faulted
+    ///
+    /// Returns true if the script with this instance has panicked, and stopped running.
+    pub fn is_faulted(&self, instance: Instance) -> bool{
+        self.library.is_faulted.map(|is_faulted| is_faulted(instance)).unwrap_or(false)
+    }
+
+    /// # Span Samples
+    ///
+    /// Returns the span timings the library's `SpanTimer` has kept, oldest first. Empty unless
+    /// the library installed it. The names are lent by the library, so they are only valid
+    /// while it stays loaded.
+    pub fn span_samples(&self) -> Vec<SpanSample<'_>>{
+        let span_samples = match self.library.span_samples{
+            Some(span_samples) => span_samples,
+            None => return Vec::new(),
+        };
+
+        // Like the physics queries, the library is asked again if there wasn't enough room
+        let mut samples: Vec<SpanSample<'static>> = Vec::new();
+        loop{
+            let capacity = samples.capacity();
+            let count = span_samples(samples.as_mut_ptr(), capacity as u32) as usize;
+            if count <= capacity{
+                unsafe{ samples.set_len(count) };
+                return samples;
+            }
+            samples.reserve_exact(count);
+        }
+    }
+
+    /// # Set Profiler
+    ///
+    /// Hands the library a profiler, like the unity host does with unity's own. Every
+    /// `ProfileScope` started on this thread (including the one around each entry point) is
+    /// passed to its `begin_sample` and `end_sample`, rather than recorded by the library's
+    /// `FrameProfiler`. `None` takes it back, so scopes are recorded again.
+    ///
+    /// Returns false if the library doesn't export `runity_set_profiler` and `runity_clear_profiler`.
+    pub fn set_profiler(&mut self, profiler: Option<Profiler>) -> bool{
+        let handed = match (profiler, self.library.set_profiler, self.library.clear_profiler){
+            (Some(profiler), Some(set_profiler), Some(_)) => {
+                set_profiler(profiler);
+                true
+            }
+            (None, Some(_), Some(clear_profiler)) => {
+                clear_profiler();
+                true
+            }
+            _ => false,
+        };
+
+        if handed{
+            self.profiler = profiler;
+        }
+        handed
+    }
+
+    /// # Write Chrome Trace
+    ///
+    /// Asks the library to write the scopes its `FrameProfiler` recorded to `path`, as a Chrome
+    /// trace. Unless a profiler was handed over with `set_profiler`, every `ProfileScope` is
+    /// recorded. Returns whether the trace was written.
+    pub fn write_chrome_trace<P: AsRef<Path>>(&self, path: P) -> bool{
+        match self.library.write_chrome_trace{
+            Some(write_chrome_trace) => write_chrome_trace(Str::from(&*path.as_ref().to_string_lossy())),
+            None => false,
+        }
+    }
+
+    /// # Instances
+    ///
+    /// Returns the instance of every attached script, in the order they were attached.
+    pub fn instances(&self) -> Vec<Instance>{
+        self.attached.iter().map(|script| script.instance).collect()
+    }
+}
+
+impl Drop for Host{
+    /// Quits, destroying every script still attached before the library is unloaded
+    fn drop(&mut self){
+        self.quit();
+    }
+}
@@ -0,0 +1,56 @@
+//! # Runity Host
+//!
+//! A headless, native stand-in for unity. It loads a runity library (a `cdylib`) and
+//! drives it the same way `cs_examples/runity.cs` does - calling `awake`, `start`,
+//! `update` and `destroy` with a real `DataStruct`.
+//!
+//! Instead of a unity scene, the host keeps a small in-memory `Scene` which answers
+//! gameobject lookups, component reads and writes and physics queries (against simple
+//! shapes), and a simulated `Clock` which fills in `Time`. Anything logged through `Debug`
+//! is captured, so it can be inspected after running a number of frames.
+//!
+//! ```no_run
+//! use runity::{Quaternion, Transform, Vector3};
+//! use runity_host::Host;
+//!
+//! # fn main() -> Result<(), runity_host::HostError>{
+//!
+//! let mut host = Host::load("target/debug/libmy_script.so")?;
+//! host.scene_mut().add("Player", "Player", Transform::new(Vector3::new(0.0, 0.0, 10.0), Quaternion::new(0.0, 0.0, 0.0, 1.0)));
+//!
+//! let enemy = host.scene_mut().add("Enemy", "Untagged", Transform::new(Vector3::zero(), Quaternion::new(0.0, 0.0, 0.0, 1.0)));
+//! let instance = host.attach(enemy)?;
+//!
+//! host.step(60);
+//!
+//! assert!(host.logs().iter().any(|entry| entry.message == "Hello from rust!"));
+//! let transform = host.transform(instance);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Debug drawings sent by scripts are kept on the scene while they are shown, and `TopDown`
+//! renders them from above to an SVG or PPM image, to check what a script drew.
+//!
+//! A rebuilt library can be swapped in with `Host::reload`, which carries every script's state
+//! over through `Script::save_state` and `Script::load_state`.
+
+mod callbacks;
+mod clock;
+mod component;
+mod contact;
+mod draw;
+mod host;
+mod library;
+mod physics;
+mod scene;
+
+pub use callbacks::{LogEntry, LogLevel, LogSource};
+pub use clock::Clock;
+pub use component::{ComponentCall, SceneComponent};
+pub use contact::{CollisionEvent, Phase};
+pub use draw::{SceneDrawing, TopDown};
+pub use host::{Host, HostError, SentChange};
+pub use library::Library;
+pub use physics::SceneShape;
+pub use scene::{Scene, SceneObject, ScenePrefab};
@@ -0,0 +1,204 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use runity::{ChangeBuffer, Collision, DataStruct, Instance, LayoutTable, Profiler, ReceivedMessage, SpanSample, State, Str, Trigger};
+
+use crate::{HostError, Phase};
+
+type LifecycleFn = extern "C" fn(DataStruct) -> DataStruct;
+type ApplicationStateFn = extern "C" fn(DataStruct, bool) -> DataStruct;
+pub(crate) type CollisionFn = extern "C" fn(DataStruct, &Collision) -> DataStruct;
+pub(crate) type TriggerFn = extern "C" fn(DataStruct, &Trigger) -> DataStruct;
+type DestroyFn = extern "C" fn(DataStruct) -> i32;
+type AcquireChangesFn = extern "C" fn(Instance) -> ChangeBuffer;
+type ReleaseChangesFn = extern "C" fn(Instance);
+type PostMessagesFn = extern "C" fn(Instance, *const ReceivedMessage, u32);
+type SpanSamplesFn = extern "C" fn(*mut SpanSample<'static>, u32) -> u32;
+type WriteChromeTraceFn = extern "C" fn(Str) -> bool;
+type SetProfilerFn = extern "C" fn(Profiler);
+type ClearProfilerFn = extern "C" fn();
+type IsFaultedFn = extern "C" fn(Instance) -> bool;
+type AbiVersionFn = extern "C" fn() -> u32;
+type LayoutTableFn = extern "C" fn() -> LayoutTable;
+type SaveStateFn = extern "C" fn(Instance) -> State;
+type LoadStateFn = extern "C" fn(&mut DataStruct, State) -> i32;
+type StateFreeFn = extern "C" fn(State);
+
+/// # Library
+///
+/// A loaded runity library, and the lifecycle functions it exports.
+///
+/// Like the unity host, every lifecycle function is optional except `destroy`, which must exist.
+/// Libraries built against a different runity ABI than the host are refused.
+pub struct Library{
+    pub(crate) awake: Option<LifecycleFn>,
+    pub(crate) start: Option<LifecycleFn>,
+    pub(crate) update: Option<LifecycleFn>,
+    pub(crate) fixed_update: Option<LifecycleFn>,
+    pub(crate) late_update: Option<LifecycleFn>,
+    pub(crate) on_enable: Option<LifecycleFn>,
+    pub(crate) on_disable: Option<LifecycleFn>,
+    pub(crate) on_application_focus: Option<ApplicationStateFn>,
+    pub(crate) on_application_pause: Option<ApplicationStateFn>,
+    pub(crate) on_application_quit: Option<LifecycleFn>,
+    pub(crate) on_collision_enter: Option<CollisionFn>,
+    pub(crate) on_collision_stay: Option<CollisionFn>,
+    pub(crate) on_collision_exit: Option<CollisionFn>,
+    pub(crate) on_trigger_enter: Option<TriggerFn>,
+    pub(crate) on_trigger_stay: Option<TriggerFn>,
+    pub(crate) on_trigger_exit: Option<TriggerFn>,
+    pub(crate) destroy: DestroyFn,
+    pub(crate) acquire_changes: Option<AcquireChangesFn>,
+    pub(crate) release_changes: Option<ReleaseChangesFn>,
+    pub(crate) post_messages: Option<PostMessagesFn>,
+    pub(crate) span_samples: Option<SpanSamplesFn>,
+    pub(crate) write_chrome_trace: Option<WriteChromeTraceFn>,
+    pub(crate) set_profiler: Option<SetProfilerFn>,
+    pub(crate) clear_profiler: Option<ClearProfilerFn>,
+    pub(crate) is_faulted: Option<IsFaultedFn>,
+    pub(crate) save_state: Option<SaveStateFn>,
+    pub(crate) load_state: Option<LoadStateFn>,
+    pub(crate) state_free: Option<StateFreeFn>,
+
+    // Must outlive every function pointer above, so it is kept after them (and dropped after them)
+    _library: libloading::Library,
+    // Deleted once the library has been unloaded
+    _copy: Option<LibraryCopy>,
+}
+
+// A copy of a library, deleted when dropped
+struct LibraryCopy(PathBuf);
+
+impl Drop for LibraryCopy{
+    fn drop(&mut self){
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+impl Library{
+    /// # Load
+    ///
+    /// Loads the library at `path`, looks up its exports, and checks it was built against
+    /// the same ABI as the host.
+    pub fn load<P: AsRef<OsStr>>(path: P) -> Result<Self, HostError>{
+        let library = unsafe{ libloading::Library::new(path) }.map_err(HostError::Load)?;
+        Self::from_library(library, None)
+    }
+
+    /// # Load Copy
+    ///
+    /// Like `load`, but loads a copy of the library at `path` rather than the file itself. The
+    /// copy is deleted once the library is dropped.
+    ///
+    /// A library already loaded from the same path would otherwise be handed back again, so
+    /// this is how `Host::reload` loads a library rebuilt in place.
+    pub fn load_copy<P: AsRef<Path>>(path: P) -> Result<Self, HostError>{
+        static COPIES: AtomicU64 = AtomicU64::new(0);
+
+        let path = path.as_ref();
+        let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let copy = std::env::temp_dir().join(format!("runity_host-{}-{}-{}", std::process::id(), COPIES.fetch_add(1, Ordering::Relaxed), name));
+
+        std::fs::copy(path, &copy).map_err(HostError::Copy)?;
+        let copy = LibraryCopy(copy);
+
+        let library = unsafe{ libloading::Library::new(&copy.0) }.map_err(HostError::Load)?;
+        Self::from_library(library, Some(copy))
+    }
+
+    fn from_library(library: libloading::Library, copy: Option<LibraryCopy>) -> Result<Self, HostError>{
+        unsafe{
+            let awake = optional::<LifecycleFn>(&library, b"awake\0");
+            let start = optional::<LifecycleFn>(&library, b"start\0");
+            let update = optional::<LifecycleFn>(&library, b"update\0");
+            let fixed_update = optional::<LifecycleFn>(&library, b"fixed_update\0");
+            let late_update = optional::<LifecycleFn>(&library, b"late_update\0");
+            let on_enable = optional::<LifecycleFn>(&library, b"on_enable\0");
+            let on_disable = optional::<LifecycleFn>(&library, b"on_disable\0");
+            let on_application_focus = optional::<ApplicationStateFn>(&library, b"on_application_focus\0");
+            let on_application_pause = optional::<ApplicationStateFn>(&library, b"on_application_pause\0");
+            let on_application_quit = optional::<LifecycleFn>(&library, b"on_application_quit\0");
+            let on_collision_enter = optional::<CollisionFn>(&library, b"on_collision_enter\0");
+            let on_collision_stay = optional::<CollisionFn>(&library, b"on_collision_stay\0");
+            let on_collision_exit = optional::<CollisionFn>(&library, b"on_collision_exit\0");
+            let on_trigger_enter = optional::<TriggerFn>(&library, b"on_trigger_enter\0");
+            let on_trigger_stay = optional::<TriggerFn>(&library, b"on_trigger_stay\0");
+            let on_trigger_exit = optional::<TriggerFn>(&library, b"on_trigger_exit\0");
+            let acquire_changes = optional::<AcquireChangesFn>(&library, b"runity_changes_acquire\0");
+            let release_changes = optional::<ReleaseChangesFn>(&library, b"runity_changes_release\0");
+            let post_messages = optional::<PostMessagesFn>(&library, b"runity_post_messages\0");
+            let span_samples = optional::<SpanSamplesFn>(&library, b"runity_span_samples\0");
+            let write_chrome_trace = optional::<WriteChromeTraceFn>(&library, b"runity_write_chrome_trace\0");
+            let set_profiler = optional::<SetProfilerFn>(&library, b"runity_set_profiler\0");
+            let clear_profiler = optional::<ClearProfilerFn>(&library, b"runity_clear_profiler\0");
+            let is_faulted = optional::<IsFaultedFn>(&library, b"runity_is_faulted\0");
+            let save_state = optional::<SaveStateFn>(&library, b"runity_save_state\0");
+            let load_state = optional::<LoadStateFn>(&library, b"runity_load_state\0");
+            let state_free = optional::<StateFreeFn>(&library, b"runity_state_free\0");
+            let destroy = optional::<DestroyFn>(&library, b"destroy\0").ok_or(HostError::MissingExport("destroy"))?;
+
+            // Nothing else is called before the layout has been checked
+            let abi_version = optional::<AbiVersionFn>(&library, b"runity_abi_version\0").ok_or(HostError::MissingExport("runity_abi_version"))?;
+            let layout_table = optional::<LayoutTableFn>(&library, b"runity_layout_table\0").ok_or(HostError::MissingExport("runity_layout_table"))?;
+            runity::check_abi(abi_version(), layout_table()).map_err(HostError::Incompatible)?;
+
+            Ok(Self{
+                awake,
+                start,
+                update,
+                fixed_update,
+                late_update,
+                on_enable,
+                on_disable,
+                on_application_focus,
+                on_application_pause,
+                on_application_quit,
+                on_collision_enter,
+                on_collision_stay,
+                on_collision_exit,
+                on_trigger_enter,
+                on_trigger_stay,
+                on_trigger_exit,
+                destroy,
+                acquire_changes,
+                release_changes,
+                post_messages,
+                span_samples,
+                write_chrome_trace,
+                set_profiler,
+                clear_profiler,
+                is_faulted,
+                save_state,
+                load_state,
+                state_free,
+                _library: library,
+                _copy: copy,
+            })
+        }
+    }
+
+    // The collision function for `phase`, if the library exports it
+    pub(crate) fn on_collision(&self, phase: Phase) -> Option<CollisionFn>{
+        match phase{
+            Phase::Enter => self.on_collision_enter,
+            Phase::Stay => self.on_collision_stay,
+            Phase::Exit => self.on_collision_exit,
+        }
+    }
+
+    // The trigger function for `phase`, if the library exports it
+    pub(crate) fn on_trigger(&self, phase: Phase) -> Option<TriggerFn>{
+        match phase{
+            Phase::Enter => self.on_trigger_enter,
+            Phase::Stay => self.on_trigger_stay,
+            Phase::Exit => self.on_trigger_exit,
+        }
+    }
+}
+
+// Looks up an export, returning `None` if it doesn't exist. The returned function
+// pointer must not outlive `library`.
+unsafe fn optional<T: Copy>(library: &libloading::Library, name: &[u8]) -> Option<T>{
+    library.get::<T>(name).ok().map(|symbol| *symbol)
+}
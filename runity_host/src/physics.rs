@@ -0,0 +1,240 @@
+use runity::{PhysicsQuery, Quaternion, QueryShape, Vector3};
+
+use crate::Scene;
+
+/// # Scene Shape
+///
+/// The shape of a gameobject's collider, used to answer physics queries. Shapes are centered
+/// on the gameobject, and rotated and scaled with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SceneShape{
+    /// A sphere, scaled by the largest axis of the gameobject's scale (like a `SphereCollider`).
+    Sphere{ radius: f32 },
+    /// A box, with half its size on each axis.
+    Box{ half_extents: Vector3 },
+}
+
+// A query hit, before it is written for rust
+pub(crate) struct Hit{
+    pub point: Vector3,
+    pub normal: Vector3,
+    pub distance: f32,
+    pub object: u64,
+}
+
+// A collider in world space
+enum Solid{
+    Sphere{ center: Vector3, radius: f32 },
+    Box{ center: Vector3, rotation: Quaternion, half_extents: Vector3 },
+}
+
+impl Solid{
+    fn center(&self) -> Vector3{
+        match *self{
+            Solid::Sphere{ center, .. } | Solid::Box{ center, .. } => center,
+        }
+    }
+
+    // The closest point on (or in) the solid to `point`
+    fn closest_point(&self, point: Vector3) -> Vector3{
+        match *self{
+            Solid::Sphere{ center, radius } => {
+                let offset = point - center;
+                if Vector3::magnitude(offset) <= radius{
+                    point
+                }else{
+                    center + times(Vector3::normalize(offset), radius)
+                }
+            }
+            Solid::Box{ center, rotation, half_extents } => {
+                let local = Quaternion::inverse(rotation) * (point - center);
+                let clamped = Vector3::min(Vector3::max(local, times(half_extents, -1.0)), half_extents);
+                center + rotation * clamped
+            }
+        }
+    }
+}
+
+// Every collider in the scene on a layer the query hits, in world space
+fn solids<'a>(scene: &'a Scene, query: &'a PhysicsQuery) -> impl Iterator<Item = (u64, Solid)> + 'a{
+    scene.objects().iter().filter_map(move |object| {
+        let shape = object.shape?;
+        if !query.hits_layer(object.layer) || !scene.active_in_hierarchy(object.id){
+            return None;
+        }
+        // A disabled collider component turns the shape off
+        if let Some(enabled) = object.component("Collider").and_then(|collider| collider.get("enabled")){
+            if enabled.as_bool() == Some(false){
+                return None;
+            }
+        }
+
+        let transform = scene.transform(object.id)?;
+        let scale = transform.lossy_scale();
+        let solid = match shape{
+            SceneShape::Sphere{ radius } => Solid::Sphere{
+                center: transform.position,
+                radius: radius * scale.x.abs().max(scale.y.abs()).max(scale.z.abs()),
+            },
+            SceneShape::Box{ half_extents } => Solid::Box{
+                center: transform.position,
+                rotation: transform.rotation,
+                half_extents: Vector3::scale(half_extents, Vector3::new(scale.x.abs(), scale.y.abs(), scale.z.abs())),
+            },
+        };
+
+        Some((object.id, solid))
+    })
+}
+
+/// Answers a ray or sphere cast, closest hit first.
+pub(crate) fn cast(scene: &Scene, query: &PhysicsQuery) -> Vec<Hit>{
+    // A ray is a sphere with no radius
+    let radius = match query.shape(){
+        QueryShape::Sphere => query.radius(),
+        _ => 0.0,
+    };
+
+    let mut hits: Vec<Hit> = solids(scene, query)
+        .filter_map(|(object, solid)| {
+            let (distance, normal) = sweep(&solid, query.origin(), query.direction(), radius)?;
+            if distance > query.max_distance(){
+                return None;
+            }
+
+            let center = query.origin() + times(query.direction(), distance);
+            let point = match solid{
+                // The sphere touches the target where the normal leaves it
+                Solid::Sphere{ center, radius } => center + times(normal, radius),
+                Solid::Box{ .. } => center - times(normal, radius),
+            };
+
+            Some(Hit{ point, normal, distance, object })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    hits
+}
+
+/// Answers a sphere or box overlap.
+pub(crate) fn overlap(scene: &Scene, query: &PhysicsQuery) -> Vec<Hit>{
+    let center = query.origin();
+
+    solids(scene, query)
+        .filter(|(_, solid)| match query.shape(){
+            QueryShape::Box => box_overlaps(center, query.rotation(), query.half_extents(), solid),
+            _ => Vector3::distance(solid.closest_point(center), center) <= query.radius(),
+        })
+        .map(|(object, solid)| {
+            let point = solid.closest_point(center);
+            Hit{ point, normal: Vector3::normalize(center - point), distance: Vector3::distance(center, point), object }
+        })
+        .collect()
+}
+
+// Sweeps a sphere of `radius` (or a ray, with no radius) into `solid`, returning how far it
+// travels before touching it and the normal there. Like unity, solids it starts inside are missed.
+//
+// Boxes are grown by the radius on every side, so sweeps past their corners hit a little early.
+fn sweep(solid: &Solid, origin: Vector3, direction: Vector3, radius: f32) -> Option<(f32, Vector3)>{
+    match *solid{
+        Solid::Sphere{ center, radius: target } => {
+            let reach = target + radius;
+            let offset = origin - center;
+            let b = Vector3::dot(offset, direction);
+            let c = Vector3::dot(offset, offset) - reach * reach;
+            let discriminant = b * b - c;
+            if c <= 0.0 || discriminant < 0.0{
+                return None;
+            }
+
+            let distance = -b - discriminant.sqrt();
+            if distance < 0.0{
+                return None;
+            }
+
+            Some((distance, Vector3::normalize(origin + times(direction, distance) - center)))
+        }
+        Solid::Box{ center, rotation, half_extents } => {
+            // Slab test in the box's own space
+            let inverse = Quaternion::inverse(rotation);
+            let origin = axes(inverse * (origin - center));
+            let direction = axes(inverse * direction);
+            let half_extents = axes(half_extents);
+
+            let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+            let mut normal = [0.0; 3];
+            for axis in 0..3{
+                let extent = half_extents[axis] + radius;
+                if direction[axis].abs() < Vector3::K_EPSILON{
+                    if origin[axis].abs() > extent{
+                        return None;
+                    }
+                    continue;
+                }
+
+                let (mut enter, mut exit) = ((-extent - origin[axis]) / direction[axis], (extent - origin[axis]) / direction[axis]);
+                if enter > exit{
+                    std::mem::swap(&mut enter, &mut exit);
+                }
+                if enter > near{
+                    near = enter;
+                    normal = [0.0; 3];
+                    normal[axis] = -direction[axis].signum();
+                }
+                far = far.min(exit);
+                if near > far{
+                    return None;
+                }
+            }
+
+            if near < 0.0{
+                return None;
+            }
+
+            Some((near, rotation * Vector3::new(normal[0], normal[1], normal[2])))
+        }
+    }
+}
+
+// Whether a box at `center` touches `solid`, using separating axes for boxes
+fn box_overlaps(center: Vector3, rotation: Quaternion, half_extents: Vector3, solid: &Solid) -> bool{
+    let query = Solid::Box{ center, rotation, half_extents };
+
+    let (other_rotation, other_half_extents) = match *solid{
+        Solid::Sphere{ center, radius } => return Vector3::distance(query.closest_point(center), center) <= radius,
+        Solid::Box{ rotation, half_extents, .. } => (rotation, half_extents),
+    };
+
+    let a = [rotation * Vector3::right(), rotation * Vector3::up(), rotation * Vector3::forward()];
+    let b = [other_rotation * Vector3::right(), other_rotation * Vector3::up(), other_rotation * Vector3::forward()];
+    let (a_extents, b_extents) = (axes(half_extents), axes(other_half_extents));
+    let offset = solid.center() - center;
+
+    let mut candidates = a.to_vec();
+    candidates.extend_from_slice(&b);
+    for a_axis in a.iter(){
+        for b_axis in b.iter(){
+            candidates.push(Vector3::cross(*a_axis, *b_axis));
+        }
+    }
+
+    candidates.into_iter()
+        // Parallel edges give no axis
+        .filter(|axis| Vector3::sqr_magnitude(*axis) > Vector3::K_EPSILON)
+        .all(|axis| {
+            let project = |axes: &[Vector3; 3], extents: [f32; 3]| -> f32{
+                (0..3).map(|i| Vector3::dot(axes[i], axis).abs() * extents[i]).sum()
+            };
+            Vector3::dot(offset, axis).abs() <= project(&a, a_extents) + project(&b, b_extents)
+        })
+}
+
+fn times(vector: Vector3, scale: f32) -> Vector3{
+    Vector3::new(vector.x * scale, vector.y * scale, vector.z * scale)
+}
+
+fn axes(vector: Vector3) -> [f32; 3]{
+    [vector.x, vector.y, vector.z]
+}
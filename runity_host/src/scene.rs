@@ -0,0 +1,347 @@
+use runity::{Prefab, Quaternion, Transform, Vector3};
+
+use crate::{SceneComponent, SceneDrawing, SceneShape};
+
+/// # Scene Object
+///
+/// A gameobject in the host's scene. This only stores what runity can see of a
+/// gameobject - its id (the gameobject's hash), name, tag, transform, components and the
+/// shape of its collider.
+///
+/// Like unity, the transform is stored relative to the parent, so children move with it.
+/// Use `Scene::transform` for the transform in world space.
+#[derive(Clone)]
+pub struct SceneObject{
+    pub id: u64,
+    pub name: String,
+    pub tag: String,
+    pub local_position: Vector3,
+    pub local_rotation: Quaternion,
+    pub local_scale: Vector3,
+    /// Whether the gameobject itself is active, like `GameObject.activeSelf`.
+    pub active: bool,
+    pub components: Vec<SceneComponent>,
+    /// The layer the gameobject is on, from 0 to 31, checked against the layer mask of physics queries.
+    pub layer: u32,
+    /// The shape physics queries hit, if the gameobject has a collider.
+    pub shape: Option<SceneShape>,
+    parent: Option<u64>,
+    // Spawned from a scripted prefab, and waiting for the host to attach the script
+    awaiting_script: bool,
+}
+
+impl SceneObject{
+    /// # Parent
+    ///
+    /// Returns the id of the parent gameobject, if there is one. Use `Scene::set_parent` to change it.
+    pub fn parent(&self) -> Option<u64>{
+        self.parent
+    }
+
+    /// # Component
+    ///
+    /// Returns the component with the given type name, if the gameobject has one.
+    pub fn component(&self, name: &str) -> Option<&SceneComponent>{
+        self.components.iter().find(|component| component.name == name)
+    }
+
+    /// # Component Mut
+    ///
+    /// Returns the component with the given type name mutably, if the gameobject has one.
+    pub fn component_mut(&mut self, name: &str) -> Option<&mut SceneComponent>{
+        self.components.iter_mut().find(|component| component.name == name)
+    }
+}
+
+/// # Scene Prefab
+///
+/// A prefab scripts can spawn. Spawned gameobjects take the prefab's name, tag, scale, layer,
+/// collider shape and (a copy of) its components.
+#[derive(Clone)]
+pub struct ScenePrefab{
+    pub name: String,
+    pub tag: String,
+    pub local_scale: Vector3,
+    pub components: Vec<SceneComponent>,
+    pub layer: u32,
+    pub shape: Option<SceneShape>,
+    /// Whether the library's script is attached to gameobjects spawned from this prefab,
+    /// like a prefab with `runity.cs` on it.
+    pub scripted: bool,
+}
+
+/// # Scene
+///
+/// An in-memory scene, used in place of a unity scene. Scripts look gameobjects up
+/// in here, and any changes they send are applied to it.
+#[derive(Default)]
+pub struct Scene{
+    objects: Vec<SceneObject>,
+    prefabs: Vec<ScenePrefab>,
+    drawings: Vec<SceneDrawing>,
+    next_id: u64,
+}
+
+impl Scene{
+    /// # New
+    ///
+    /// Creates a new, empty scene.
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    /// # Add
+    ///
+    /// Adds a gameobject to the root of the scene, returning its id.
+    pub fn add(&mut self, name: &str, tag: &str, transform: Transform) -> u64{
+        // Ids start at 1, so a zeroed hash is never a valid gameobject
+        self.next_id += 1;
+
+        self.objects.push(SceneObject{
+            id: self.next_id,
+            name: name.to_string(),
+            tag: tag.to_string(),
+            local_position: transform.position,
+            local_rotation: transform.rotation,
+            local_scale: transform.local_scale,
+            active: true,
+            components: Vec::new(),
+            layer: 0,
+            shape: None,
+            parent: None,
+            awaiting_script: false,
+        });
+
+        self.next_id
+    }
+
+    /// # Add Prefab
+    ///
+    /// Adds a prefab scripts can spawn, returning its id. Like the prefabs listed on
+    /// `runity.cs`, ids count up from 0 in the order they were added.
+    pub fn add_prefab(&mut self, prefab: ScenePrefab) -> u64{
+        self.prefabs.push(prefab);
+        self.prefabs.len() as u64 - 1
+    }
+
+    /// # Find Prefab
+    ///
+    /// Returns the id of a prefab, by its name or id.
+    pub fn find_prefab(&self, prefab: Prefab) -> Option<u64>{
+        match prefab{
+            Prefab::Name(name) => self.prefabs.iter().position(|prefab| prefab.name == name).map(|index| index as u64),
+            Prefab::Id(id) => self.prefabs.get(id as usize).map(|_| id),
+        }
+    }
+
+    /// # Instantiate
+    ///
+    /// Spawns the prefab with the given id at the world space position and rotation of
+    /// `transform`, returning the new gameobject's id, like `Object.Instantiate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no prefab with this id.
+    pub fn instantiate(&mut self, prefab: u64, transform: &Transform) -> u64{
+        let prefab = self.prefabs[prefab as usize].clone();
+
+        let mut spawned = Transform::new(transform.position, transform.rotation);
+        spawned.local_scale = prefab.local_scale;
+
+        let id = self.add(&prefab.name, &prefab.tag, spawned);
+        if let Some(object) = self.get_mut(id){
+            object.components = prefab.components;
+            object.layer = prefab.layer;
+            object.shape = prefab.shape;
+            object.awaiting_script = prefab.scripted;
+        }
+
+        id
+    }
+
+    // The gameobjects spawned from scripted prefabs since this was last called
+    pub(crate) fn take_awaiting_scripts(&mut self) -> Vec<u64>{
+        self.objects.iter_mut()
+            .filter_map(|object| std::mem::take(&mut object.awaiting_script).then_some(object.id))
+            .collect()
+    }
+
+    /// # Active In Hierarchy
+    ///
+    /// Returns whether the gameobject and every one of its parents is active, like
+    /// `GameObject.activeInHierarchy`. Returns false if there is no gameobject with this id.
+    pub fn active_in_hierarchy(&self, id: u64) -> bool{
+        match self.get(id){
+            Some(object) => object.active && object.parent.map(|parent| self.active_in_hierarchy(parent)).unwrap_or(true),
+            None => false,
+        }
+    }
+
+    /// # Remove
+    ///
+    /// Removes a gameobject from the scene, returning it if it existed. Like unity, its
+    /// children are removed with it.
+    pub fn remove(&mut self, id: u64) -> Option<SceneObject>{
+        let index = self.objects.iter().position(|object| object.id == id)?;
+        let object = self.objects.remove(index);
+
+        for child in self.children(id){
+            self.remove(child);
+        }
+
+        Some(object)
+    }
+
+    /// # Transform
+    ///
+    /// Returns the transform of the gameobject with the given id in world space, along with
+    /// its parent's, as it is sent to scripts.
+    pub fn transform(&self, id: u64) -> Option<Transform>{
+        let object = self.get(id)?;
+        let parent = object.parent.and_then(|parent| self.transform(parent));
+
+        let mut transform = Transform::new(Vector3::zero(), Quaternion::identity());
+        if let (Some(hash), Some(parent)) = (object.parent, parent.as_ref()){
+            transform.set_parent_transform(hash, parent);
+        }
+
+        transform.local_scale = object.local_scale;
+        transform.set_local_position(object.local_position);
+        transform.set_local_rotation(object.local_rotation);
+
+        Some(transform)
+    }
+
+    /// # Set Transform
+    ///
+    /// Moves the gameobject with the given id to the world space position and rotation of
+    /// `transform`, and sets its local scale. Its children move with it. Returns false if
+    /// there is no gameobject with this id.
+    pub fn set_transform(&mut self, id: u64, transform: &Transform) -> bool{
+        // The transform may have been sent with an old parent, so it is put in the current one
+        let mut moved = Transform::new(transform.position, transform.rotation);
+        moved.local_scale = transform.local_scale;
+
+        let parent = match self.get(id){
+            Some(object) => object.parent,
+            None => return false,
+        };
+
+        if let Some((hash, parent)) = parent.and_then(|hash| self.transform(hash).map(|parent| (hash, parent))){
+            moved.set_parent_transform(hash, &parent);
+        }
+
+        if let Some(object) = self.get_mut(id){
+            object.local_position = moved.local_position();
+            object.local_rotation = moved.local_rotation();
+            object.local_scale = moved.local_scale;
+        }
+
+        true
+    }
+
+    /// # Set Parent
+    ///
+    /// Makes `parent` the parent of the gameobject with id `child`, or moves it to the root
+    /// of the scene with `None`. Like unity's `Transform.SetParent`, `world_position_stays`
+    /// keeps it where it is in the world - otherwise its local transform is kept, so it moves
+    /// with its new parent.
+    ///
+    /// Returns false if either gameobject doesn't exist, or if `parent` is `child` or one of its children.
+    pub fn set_parent(&mut self, child: u64, parent: Option<u64>, world_position_stays: bool) -> bool{
+        let transform = match self.transform(child){
+            Some(transform) => transform,
+            None => return false,
+        };
+
+        if let Some(parent) = parent{
+            // Walk up from the new parent, to make sure this wouldn't make a loop
+            let mut ancestor = Some(parent);
+            while let Some(id) = ancestor{
+                if id == child{
+                    return false;
+                }
+                ancestor = match self.get(id){
+                    Some(object) => object.parent,
+                    None => return false,
+                };
+            }
+        }
+
+        if let Some(object) = self.get_mut(child){
+            object.parent = parent;
+        }
+
+        if world_position_stays{
+            self.set_transform(child, &transform);
+        }
+
+        true
+    }
+
+    /// # Children
+    ///
+    /// Returns the ids of the direct children of the gameobject with the given id.
+    pub fn children(&self, id: u64) -> Vec<u64>{
+        self.objects.iter().filter(|object| object.parent == Some(id)).map(|object| object.id).collect()
+    }
+
+    /// # Get
+    ///
+    /// Returns the gameobject with the given id.
+    pub fn get(&self, id: u64) -> Option<&SceneObject>{
+        self.objects.iter().find(|object| object.id == id)
+    }
+
+    /// # Get Mut
+    ///
+    /// Returns the gameobject with the given id, mutably.
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut SceneObject>{
+        self.objects.iter_mut().find(|object| object.id == id)
+    }
+
+    /// # Find With Tag
+    ///
+    /// Returns the first gameobject with the given tag, like `GameObject.FindGameObjectWithTag`.
+    pub fn find_with_tag(&self, tag: &str) -> Option<&SceneObject>{
+        self.objects.iter().find(|object| object.tag == tag)
+    }
+
+    /// # Find
+    ///
+    /// Returns the first gameobject with the given name, like `GameObject.Find`.
+    pub fn find(&self, name: &str) -> Option<&SceneObject>{
+        self.objects.iter().find(|object| object.name == name)
+    }
+
+    /// # Find All With Tag
+    ///
+    /// Returns every gameobject with the given tag, like `GameObject.FindGameObjectsWithTag`.
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<&SceneObject>{
+        self.objects.iter().filter(|object| object.tag == tag).collect()
+    }
+
+    /// # Objects
+    ///
+    /// Returns every gameobject in the scene.
+    pub fn objects(&self) -> &[SceneObject]{
+        &self.objects
+    }
+
+    /// # Drawings
+    ///
+    /// Returns the debug drawings being shown, in the order they were drawn. Render them with
+    /// `TopDown`.
+    pub fn drawings(&self) -> &[SceneDrawing]{
+        &self.drawings
+    }
+
+    pub(crate) fn add_drawing(&mut self, drawing: SceneDrawing){
+        self.drawings.push(drawing);
+    }
+
+    // Drops the drawings that are no longer shown, at the start of each frame
+    pub(crate) fn expire_drawings(&mut self, frame: u64, time: f32){
+        self.drawings.retain(|drawing| drawing.shown(frame, time));
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+use runity_host::{Host, ScenePrefab};
+
+// A host with a script on a gameobject at `(1, 0, 0)`, which can spawn `Crate`s
+fn host() -> (Host, u64){
+    let mut host = common::host();
+    host.scene_mut().add_prefab(ScenePrefab{
+        name: "Crate".to_string(),
+        tag: "Crate".to_string(),
+        local_scale: Vector3::one(),
+        components: Vec::new(),
+        layer: 0,
+        shape: None,
+        scripted: false,
+    });
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::new(1.0, 0.0, 0.0)));
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    (host, object)
+}
+
+// Steps a frame, returning what the script logged besides its lifecycle
+fn step(host: &mut Host) -> Vec<String>{
+    host.step(1);
+    common::messages(host).into_iter().filter(|message| !message.contains("update")).collect()
+}
+
+// Presses `key` for a frame, like `step`
+fn press(host: &mut Host, key: KeyCode) -> Vec<String>{
+    host.input_mut().set_key(key, true);
+    let messages = step(host);
+    host.input_mut().set_key(key, false);
+
+    messages
+}
+
+#[test]
+fn spawned_gameobjects_resolve_once_they_exist(){
+    let (mut host, object) = host();
+
+    // The handle is pending until the host has spawned the crate, after `update` returns
+    assert_eq!(press(&mut host, KeyCode::N), [format!("{} spawned(pending: true, resolve: None) 2", object)]);
+
+    let spawned = host.scene().find("Crate").expect("the crate was spawned").id;
+    assert_eq!(host.scene().get(spawned).unwrap().tag, "Crate");
+    assert_eq!(host.scene().objects().len(), 2);
+
+    // The pending handle was parented in the same frame it was spawned in, keeping its place in the world
+    assert_eq!(host.scene().get(spawned).unwrap().parent(), Some(object));
+    assert_eq!(host.scene().transform(spawned).unwrap().position, Vector3::new(0.0, 0.0, 3.0));
+
+    assert_eq!(press(&mut host, KeyCode::V), [
+        format!("{} failed(Spawn, UnknownPrefab) 3", object),
+        format!("{} resolve(Some({})) 3", object, spawned),
+    ]);
+}
+
+#[test]
+fn commands_reach_spawned_gameobjects(){
+    let (mut host, object) = host();
+    press(&mut host, KeyCode::N);
+    let spawned = host.scene().find("Crate").unwrap().id;
+
+    assert_eq!(press(&mut host, KeyCode::T), [format!("{} failed(Spawn, UnknownPrefab) 3", object)]);
+    assert!(!host.scene().get(spawned).unwrap().active);
+    assert!(host.scene().active_in_hierarchy(object));
+
+    assert!(press(&mut host, KeyCode::Z).is_empty());
+    assert!(host.scene().get(spawned).is_none());
+    assert!(host.scene().get(object).is_some());
+}
+
+#[test]
+fn commands_for_destroyed_gameobjects_fail(){
+    let (mut host, object) = host();
+    press(&mut host, KeyCode::N);
+    let spawned = host.scene().find("Crate").unwrap().id;
+    press(&mut host, KeyCode::Z);
+    step(&mut host);
+
+    // The handle still resolves, but the crate is gone
+    press(&mut host, KeyCode::Z);
+    assert_eq!(press(&mut host, KeyCode::T), [format!("{} failed(Destroy, UnknownObject) 6", object)]);
+    assert_eq!(press(&mut host, KeyCode::V), [
+        format!("{} failed(SetActive, UnknownObject) 7", object),
+        format!("{} resolve(Some({})) 7", object, spawned),
+    ]);
+}
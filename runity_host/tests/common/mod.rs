@@ -0,0 +1,61 @@
+//! Builds the fixture library (`tests/fixture`) the integration tests run, and loads it.
+
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use runity::{Quaternion, Transform, Vector3};
+use runity_host::{Host, Library};
+
+/// The path of the fixture library, built once per test binary. It has a target directory of
+/// its own, so building it doesn't wait on the build that is running the tests.
+pub fn fixture() -> &'static Path{
+    static FIXTURE: OnceLock<PathBuf> = OnceLock::new();
+
+    FIXTURE.get_or_init(|| build("fixture", &[]))
+}
+
+/// The path of the fixture library built with a higher `STATE_VERSION`, whose libraries can't
+/// restore the state `fixture`'s save.
+#[allow(dead_code)]
+pub fn bumped_fixture() -> &'static Path{
+    static FIXTURE: OnceLock<PathBuf> = OnceLock::new();
+
+    FIXTURE.get_or_init(|| build("fixture-bumped", &["--features", "runity_fixture/bumped_state"]))
+}
+
+// Builds the fixture into `target/<directory>`, returning the path of the library
+fn build(directory: &str, args: &[&str]) -> PathBuf{
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("runity_host is in the workspace");
+    let target = workspace.join("target").join(directory);
+
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--package", "runity_fixture", "--target-dir"])
+        .arg(&target)
+        .args(args)
+        .current_dir(workspace)
+        .status()
+        .expect("cargo couldn't be run to build the fixture");
+    assert!(status.success(), "the fixture library didn't build");
+
+    target.join("debug").join(format!("{}runity_fixture{}", DLL_PREFIX, DLL_SUFFIX))
+}
+
+/// A host running a copy of the fixture library of its own, so tests running at the same
+/// time don't share the library's statics.
+pub fn host() -> Host{
+    Host::new(Library::load_copy(fixture()).expect("the fixture library couldn't be loaded"))
+}
+
+/// A transform at `position`, without any rotation.
+#[allow(dead_code)]
+pub fn at(position: Vector3) -> Transform{
+    Transform::new(position, Quaternion::identity())
+}
+
+/// Every message logged since the last call, clearing the log.
+#[allow(dead_code)]
+pub fn messages(host: &mut Host) -> Vec<String>{
+    host.take_logs().into_iter().map(|entry| entry.message).collect()
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use runity::{KeyCode, Value, Vector3};
+use runity_host::{ComponentCall, Host, SceneComponent};
+
+// Presses `B` for a frame, so every script reads and writes its components, then lets go of it
+// for a frame, returning what the scripts logged
+fn touch_components(host: &mut Host) -> Vec<String>{
+    host.input_mut().set_key(KeyCode::B, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::B, false);
+    host.step(1);
+
+    common::messages(host).into_iter().filter(|message| !message.contains("update")).collect()
+}
+
+#[test]
+fn typed_components_are_read_and_written_through_the_host(){
+    let mut host = common::host();
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.scene_mut().get_mut(object).unwrap().components.push(SceneComponent::rigidbody().with("mass", Value::Float(3.0)));
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    assert_eq!(touch_components(&mut host), [
+        format!("{} light(false) 2", object),
+        format!("{} rigidbody({:?}, 3, false) 2", object, Vector3::zero()),
+        format!("{} set_rigidbody((true, true, true)) 2", object),
+    ]);
+
+    let rigidbody = host.scene().get(object).unwrap().component("Rigidbody").unwrap();
+    assert_eq!(rigidbody.get("velocity"), Some(Value::Vector3(Vector3::new(0.0, 2.0, 0.0))));
+    assert_eq!(rigidbody.get("mass"), Some(Value::Float(2.5)));
+    assert_eq!(rigidbody.get("isKinematic"), Some(Value::Bool(true)));
+    assert_eq!(rigidbody.calls, [ComponentCall{ method: "AddForce".to_string(), arguments: vec![Value::Vector3(Vector3::new(0.0, 0.0, 4.0)), Value::Int(1)] }]);
+
+    // The script reads back what it wrote
+    assert_eq!(touch_components(&mut host)[1], format!("{} rigidbody({:?}, 2.5, true) 4", object, Vector3::new(0.0, 2.0, 0.0)));
+}
+
+#[test]
+fn components_runity_doesnt_model_are_reached_by_name(){
+    let mut host = common::host();
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let health = SceneComponent::new("Health").with("points", Value::Int(10)).with("shield", Value::Float(0.5));
+    host.scene_mut().get_mut(object).unwrap().components.push(health);
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    assert_eq!(touch_components(&mut host), [
+        format!("{} light(false) 2", object),
+        format!("{} health([Some(Int(10)), Some(Float(0.5)), None]) 2", object),
+        format!("{} set_health([true, true, false, false]) 2", object),
+    ]);
+
+    let health = host.scene().get(object).unwrap().component("Health").unwrap();
+    assert_eq!(health.get("points"), Some(Value::Int(7)));
+    assert_eq!(health.get("shield"), Some(Value::Float(3.0)));
+    assert_eq!(health.get("missing"), None);
+}
+
+#[test]
+fn missing_components_are_none(){
+    let mut host = common::host();
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    assert_eq!(touch_components(&mut host), [format!("{} light(false) 2", object)]);
+}
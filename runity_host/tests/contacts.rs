@@ -0,0 +1,129 @@
+mod common;
+
+use runity::{ContactPoint, Vector3};
+use runity_host::{CollisionEvent, Host, HostError, Phase};
+
+// A host with a script on `object`, and a wall tagged `Wall` for it to touch. Each frame has a
+// single fixed step, which is when contacts are sent.
+fn host() -> (Host, u64, u64){
+    let mut host = common::host();
+    host.clock_mut().delta_time = 0.0625;
+    host.clock_mut().fixed_delta_time = 0.0625;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let wall = host.scene_mut().add("Wall", "Wall", common::at(Vector3::new(0.0, 0.0, 1.0)));
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    (host, object, wall)
+}
+
+// What the script was sent since the last call, leaving out the lifecycle
+fn contact_messages(host: &mut Host) -> Vec<String>{
+    common::messages(host).into_iter().filter(|message| message.contains("on_collision") || message.contains("on_trigger")).collect()
+}
+
+fn contact(z: f32, separation: f32) -> ContactPoint{
+    ContactPoint{ point: Vector3::new(0.0, 0.0, z), normal: Vector3::new(0.0, 0.0, -1.0), separation }
+}
+
+#[test]
+fn collisions_lend_their_contacts(){
+    let (mut host, object, wall) = host();
+
+    let enter = CollisionEvent{
+        other: wall,
+        contacts: vec![contact(0.5, -0.25), contact(0.5, -0.125)],
+        relative_velocity: Vector3::new(0.0, 0.0, 2.0),
+        impulse: Vector3::new(0.0, 0.0, -1.5),
+    };
+    host.collide(object, Phase::Enter, enter.clone()).unwrap();
+    host.step(1);
+    assert_eq!(contact_messages(&mut host), [
+        format!("{} on_collision_enter({}, Wall, {:?}, {:?}, {:?}) 2", object, wall, enter.contacts, enter.relative_velocity, enter.impulse),
+    ]);
+
+    let stay = CollisionEvent{ contacts: vec![contact(0.5, 0.0)], ..CollisionEvent::new(wall) };
+    host.collide(object, Phase::Stay, stay.clone()).unwrap();
+    host.step(1);
+    assert_eq!(contact_messages(&mut host), [
+        format!("{} on_collision_stay({}, Wall, {:?}, {:?}, {:?}) 3", object, wall, stay.contacts, Vector3::zero(), Vector3::zero()),
+    ]);
+
+    // Like unity, there are no contacts once the colliders have separated
+    host.collide(object, Phase::Exit, CollisionEvent::new(wall)).unwrap();
+    host.step(1);
+    assert_eq!(contact_messages(&mut host), [
+        format!("{} on_collision_exit({}, Wall, [], {:?}, {:?}) 4", object, wall, Vector3::zero(), Vector3::zero()),
+    ]);
+}
+
+#[test]
+fn contacts_wait_for_the_next_fixed_step(){
+    let (mut host, object, wall) = host();
+
+    host.trigger(object, Phase::Enter, wall).unwrap();
+    host.trigger(object, Phase::Stay, wall).unwrap();
+    assert!(contact_messages(&mut host).is_empty());
+
+    // Sent in the order they were queued, after `fixed_update`
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} fixed_update 2", object),
+        format!("{} on_trigger_enter({}, Wall) 2", object, wall),
+        format!("{} on_trigger_stay({}, Wall) 2", object, wall),
+        format!("{} update 2", object),
+        format!("{} late_update 2", object),
+    ]);
+
+    host.trigger(object, Phase::Exit, wall).unwrap();
+    host.step(1);
+    assert_eq!(contact_messages(&mut host), [format!("{} on_trigger_exit({}, Wall) 3", object, wall)]);
+}
+
+#[test]
+fn contacts_go_to_every_script_on_the_object(){
+    let (mut host, object, wall) = host();
+    let other = host.scene_mut().add("Other", "Untagged", common::at(Vector3::zero()));
+    let second = host.attach(object).unwrap();
+    host.attach(other).unwrap();
+
+    // Disabled scripts are sent contacts too
+    host.set_enabled(second, false);
+    host.step(1);
+    common::messages(&mut host);
+
+    host.trigger(object, Phase::Enter, wall).unwrap();
+    host.step(1);
+    assert_eq!(contact_messages(&mut host), [
+        format!("{} on_trigger_enter({}, Wall) 3", object, wall),
+        format!("{} on_trigger_enter({}, Wall) 3", object, wall),
+    ]);
+}
+
+#[test]
+fn removed_objects_are_sent_without_a_tag(){
+    let (mut host, object, wall) = host();
+
+    host.collide(object, Phase::Exit, CollisionEvent::new(wall)).unwrap();
+    host.trigger(object, Phase::Exit, wall).unwrap();
+    host.scene_mut().remove(wall);
+    host.step(1);
+
+    assert_eq!(contact_messages(&mut host), [
+        format!("{} on_collision_exit({}, , [], {:?}, {:?}) 2", object, wall, Vector3::zero(), Vector3::zero()),
+        format!("{} on_trigger_exit({}, ) 2", object, wall),
+    ]);
+}
+
+#[test]
+fn contacts_with_unknown_objects_are_refused(){
+    let (mut host, object, _) = host();
+
+    assert!(matches!(host.collide(object, Phase::Enter, CollisionEvent::new(999)), Err(HostError::UnknownObject(999))));
+    assert!(matches!(host.trigger(999, Phase::Enter, object), Err(HostError::UnknownObject(999))));
+
+    host.step(1);
+    assert!(contact_messages(&mut host).is_empty());
+}
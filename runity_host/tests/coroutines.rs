@@ -0,0 +1,99 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+use runity_host::Host;
+
+// A host with a single script, which has been started. Each frame is 1/16 of a second (exact,
+// so times add up without rounding) with one fixed step in it.
+fn started() -> (Host, u64){
+    let mut host = common::host();
+    host.clock_mut().delta_time = 0.0625;
+    host.clock_mut().fixed_delta_time = 0.0625;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    (host, object)
+}
+
+// What the coroutines logged since the last call, leaving out the lifecycle
+fn coroutine_messages(host: &mut Host) -> Vec<String>{
+    common::messages(host).into_iter().filter(|message| message.contains("waited") || message.contains("dropped")).collect()
+}
+
+#[test]
+fn coroutines_resume_on_exact_frames(){
+    let (mut host, object) = started();
+
+    // Started in frame 2, and first polled at the end of its update
+    host.input_mut().set_key(KeyCode::K, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::K, false);
+    assert_eq!(coroutine_messages(&mut host), Vec::<String>::new());
+
+    let mut resumed = Vec::new();
+    for _ in 0..6{
+        host.step(1);
+        resumed.push(coroutine_messages(&mut host));
+    }
+
+    assert_eq!(resumed, [
+        // The next fixed step
+        vec![format!("{} waited(fixed_update) 3", object)],
+        // Two frames on
+        vec![format!("{} waited(cancellable) 4", object), format!("{} coroutine dropped", object)],
+        // Three frames on
+        vec![format!("{} waited(frames) 5", object)],
+        // A quarter of a second (four frames) on
+        vec![format!("{} waited(seconds) 6", object)],
+        vec![],
+        vec![],
+    ]);
+}
+
+#[test]
+fn cancelled_coroutines_are_dropped_at_the_next_poll(){
+    let (mut host, object) = started();
+
+    host.input_mut().set_key(KeyCode::K, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::K, false);
+
+    host.input_mut().set_key(KeyCode::X, true);
+    host.step(1);
+    assert_eq!(coroutine_messages(&mut host), [
+        format!("{} waited(fixed_update) 3", object),
+        format!("{} coroutine dropped", object),
+    ]);
+
+    // The others carry on as before, and the cancelled one never resumes
+    host.step(4);
+    assert_eq!(coroutine_messages(&mut host), [
+        format!("{} waited(frames) 5", object),
+        format!("{} waited(seconds) 6", object),
+    ]);
+}
+
+#[test]
+fn destroy_stops_coroutines(){
+    let (mut host, object) = started();
+    let instance = host.instances()[0];
+
+    host.input_mut().set_key(KeyCode::K, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::K, false);
+    common::messages(&mut host);
+
+    // Coroutines are stopped before the script's `destroy` runs
+    host.destroy(instance);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} on_disable 2", object),
+        format!("{} coroutine dropped", object),
+        format!("{} destroy 2", object),
+    ]);
+
+    host.step(6);
+    assert_eq!(coroutine_messages(&mut host), Vec::<String>::new());
+}
@@ -0,0 +1,303 @@
+//! The script runity_host's integration tests run.
+//!
+//! Every lifecycle call is logged as `<gameobject id> <call> <frame>`, so a test can read the
+//! order the host called the script in back out of `Host::logs`. Pressing `D` makes every
+//! script destroy its gameobject during `late_update`, pressing `R` makes it call back into
+//! its own store during `update`, and pressing `P` makes its next `save_state` panic. Pressing
+//! `U` logs how many updates the script has run, which is the state it keeps through a reload.
+//! Pressing `F` makes `update` move the gameobject and then panic, faulting the script.
+//! Pressing `S` makes the next `save_state` write state `load_state` can't read, and building
+//! with the `bumped_state` feature raises `STATE_VERSION`, as if the state had changed shape.
+//! Every `on_disable` lifts the gameobject by one, so a test can see it was synced afterwards.
+//! Pressing `C` casts a ray forward, logging what `raycast` and `raycast_all` hit. Pressing `H`
+//! logs the gameobject's parent and each of its children, with their world position and rotation.
+//! Pressing `L` looks up the gameobject named `Target`, every gameobject tagged `Enemy`, and
+//! gameobjects by id, logging the id, tag and position of each one found.
+//!
+//! Collisions and triggers are logged with everything they were lent - the other gameobject,
+//! its tag, and for collisions the contacts, relative velocity and impulse.
+//!
+//! Pressing `N` spawns a `Crate` at `(0, 0, 3)`, parented to the gameobject in the same frame,
+//! along with a prefab that doesn't exist. Pressing `V` logs what the crate's handle resolves
+//! to, `T` deactivates the crate and `Z` destroys it. Commands the host couldn't carry out are
+//! logged at the start of the next `update`.
+//!
+//! Pressing `B` reads the gameobject's `Rigidbody` and its `Health` (a component runity doesn't
+//! model) and logs what it read, then writes new values to both, logging which writes worked.
+//!
+//! Pressing `M` subscribes to `Ping` and `Note` messages, and `Q` unsubscribes from `Note`.
+//! Pressing `E` sends a `Ping` (holding the frame it was sent in) to every gameobject tagged
+//! `Listener`, and publishes a `Note` to every script and one to the gameobject itself. Every
+//! message received is logged with its sender.
+//!
+//! Pressing `K` starts a coroutine for each awaitable, which log when they resume. Pressing `X`
+//! cancels the one waiting two frames, which logs when it is dropped.
+
+use std::convert::TryInto;
+
+use runity::{wait_fixed_update, wait_frames, wait_seconds, with_data, Collision, CoroutineHandle, DataStruct, Debug, Event, ForceMode, GameObject, Handle, KeyCode, Light, Prefab, Quaternion, ReceivedMessage, Recipients, Rigidbody, Script, Transform, Trigger, Value, Vector3};
+
+#[runity::script(fixed_update, late_update, enable, collisions, triggers)]
+#[derive(Default)]
+pub struct Fixture{
+    updates: u32,
+    panic_on_save: bool,
+    short_state: bool,
+    cancellable: Option<CoroutineHandle>,
+    spawned: Option<Handle>,
+}
+
+// A typed message, holding the frame it was sent in
+struct Ping(u64);
+
+impl Event for Ping{
+    const NAME: &'static str = "Ping";
+
+    fn encode(&self) -> Vec<u8>{
+        self.0.to_le_bytes().to_vec()
+    }
+
+    fn decode(payload: &[u8]) -> Option<Self>{
+        Some(Ping(u64::from_le_bytes(payload.try_into().ok()?)))
+    }
+}
+
+// Logs when the coroutine holding it is dropped
+struct Dropped(Debug, u64);
+
+impl Drop for Dropped{
+    fn drop(&mut self){
+        self.0.log(format!("{} coroutine dropped", self.1));
+    }
+}
+
+fn log(data: &DataStruct, call: &str){
+    data.debug.log(format!("{} {} {}", data.game_object.hash(), call, data.time.frame_count));
+}
+
+fn log_collision(data: &DataStruct, call: &str, collision: &Collision){
+    log(data, &format!("{}({}, {}, {:?}, {:?}, {:?})", call, collision.other(), collision.tag(), collision.contacts(), collision.relative_velocity(), collision.impulse()));
+}
+
+// Describes a gameobject found by a lookup
+fn found(object: &GameObject) -> String{
+    format!("({}, {}, {:?})", object.hash(), object.tag(), object.transform.position)
+}
+
+fn log_trigger(data: &DataStruct, call: &str, other: &Trigger){
+    log(data, &format!("{}({}, {})", call, other.other(), other.tag()));
+}
+
+impl Script for Fixture{
+    const STATE_VERSION: u32 = if cfg!(feature = "bumped_state"){ 2 }else{ 1 };
+
+    fn awake(&mut self, data: &mut DataStruct){
+        log(data, "awake");
+    }
+
+    fn start(&mut self, data: &mut DataStruct){
+        log(data, "start");
+    }
+
+    fn fixed_update(&mut self, data: &mut DataStruct){
+        log(data, "fixed_update");
+    }
+
+    fn update(&mut self, data: &mut DataStruct){
+        runity::profile!("fixture update");
+        log(data, "update");
+        self.updates += 1;
+
+        for failure in data.take_command_failures(){
+            log(data, &format!("failed({:?}, {:?})", failure.kind, failure.status));
+        }
+
+        if data.input.get_key_down(KeyCode::R){
+            // The script is out of the store while it runs, so there is nothing to save
+            let saved = !runity_save_state(data.instance).is_empty();
+            log(data, &format!("reentered(saved: {})", saved));
+        }
+        if data.input.get_key_down(KeyCode::P){
+            self.panic_on_save = true;
+        }
+        if data.input.get_key_down(KeyCode::S){
+            self.short_state = true;
+        }
+        if data.input.get_key_down(KeyCode::F){
+            data.transform.position.x += 5.0;
+            panic!("the fixture was asked to panic in update");
+        }
+        if data.input.get_key_down(KeyCode::C){
+            let (origin, forward) = (data.transform.position, data.transform.forward());
+            let closest = data.physics.raycast(origin, forward, f32::INFINITY, -1).map(|hit| hit.object);
+            let all: Vec<_> = data.physics.raycast_all(origin, forward, f32::INFINITY, -1).into_iter().map(|hit| hit.object).collect();
+            log(data, &format!("raycast({:?}, {:?})", closest, all));
+        }
+        if data.input.get_key_down(KeyCode::H){
+            let game_object = data.game_object.clone();
+            let parent = game_object.parent().map(|parent| parent.hash());
+            log(data, &format!("hierarchy(parent: {:?}, children: {})", parent, game_object.child_count()));
+
+            for child in (0..game_object.child_count()).filter_map(|index| game_object.child(index)){
+                log(data, &format!("child({}, {:?}, {:?})", child.hash(), child.transform.position, child.transform.rotation));
+            }
+            if game_object.child(game_object.child_count()).is_some(){
+                log(data, "child(past the end)");
+            }
+        }
+        if data.input.get_key_down(KeyCode::L){
+            let game_object = data.game_object.clone();
+            log(data, &format!("find({:?})", game_object.find("Target").as_ref().map(found)));
+            log(data, &format!("find_all_with_tag({:?})", game_object.find_all_with_tag("Enemy").iter().map(found).collect::<Vec<_>>()));
+            log(data, &format!("find_by_id({:?})", game_object.find_by_id(game_object.hash()).as_ref().map(found)));
+            log(data, &format!("find_by_id({:?})", game_object.find_by_id(u64::MAX).as_ref().map(found)));
+        }
+        if data.input.get_key_down(KeyCode::N){
+            let spawned = data.spawn(Prefab::Name("Crate"), Transform::new(Vector3::new(0.0, 0.0, 3.0), Quaternion::identity()));
+            data.set_parent(spawned, Some(data.game_object.handle()), true);
+            data.spawn(Prefab::Name("Missing"), data.transform.clone());
+
+            self.spawned = Some(spawned);
+            log(data, &format!("spawned(pending: {}, resolve: {:?})", spawned.is_pending(), data.resolve(spawned)));
+        }
+        if let Some(spawned) = self.spawned{
+            if data.input.get_key_down(KeyCode::V){
+                log(data, &format!("resolve({:?})", data.resolve(spawned)));
+            }
+            if data.input.get_key_down(KeyCode::T){
+                data.set_active(spawned, false);
+            }
+            if data.input.get_key_down(KeyCode::Z){
+                data.destroy(spawned);
+            }
+        }
+        if data.input.get_key_down(KeyCode::B){
+            let game_object = data.game_object.clone();
+            log(data, &format!("light({})", game_object.get_component::<Light>().is_some()));
+
+            if let Some(rigidbody) = game_object.get_component::<Rigidbody>(){
+                log(data, &format!("rigidbody({:?}, {}, {})", rigidbody.velocity(), rigidbody.mass(), rigidbody.is_kinematic()));
+
+                let set = (rigidbody.set_velocity(Vector3::new(0.0, 2.0, 0.0)), rigidbody.set_mass(2.5), rigidbody.set_is_kinematic(true));
+                rigidbody.add_force(Vector3::new(0.0, 0.0, 4.0), ForceMode::Impulse);
+                log(data, &format!("set_rigidbody({:?})", set));
+            }
+            if let Some(health) = game_object.get_component_by_name("Health"){
+                log(data, &format!("health({:?})", health.get_many(&["points", "shield", "missing"])));
+
+                // An int can be written to a float, but not a bool to an int
+                let set = health.set_many(&[("points", Value::Int(7)), ("shield", Value::Int(3)), ("points", Value::Bool(true)), ("missing", Value::Int(1))]);
+                log(data, &format!("set_health({:?})", set));
+            }
+        }
+        if data.input.get_key_down(KeyCode::M){
+            data.subscribe_to::<Ping>();
+            data.subscribe("Note");
+        }
+        if data.input.get_key_down(KeyCode::Q){
+            data.unsubscribe("Note");
+        }
+        if data.input.get_key_down(KeyCode::E){
+            data.send(Recipients::Tag("Listener"), &Ping(data.time.frame_count as u64));
+            data.publish(Recipients::All, "Note", b"all");
+            data.publish(Recipients::Object(data.game_object.handle()), "Note", b"self");
+        }
+        if data.input.get_key_down(KeyCode::K){
+            data.start_coroutine(async{
+                wait_seconds(0.25).await;
+                with_data(|data| log(data, "waited(seconds)"));
+            });
+            data.start_coroutine(async{
+                wait_frames(3).await;
+                with_data(|data| log(data, "waited(frames)"));
+            });
+            data.start_coroutine(async{
+                wait_fixed_update().await;
+                with_data(|data| log(data, "waited(fixed_update)"));
+            });
+
+            let dropped = Dropped(data.debug, data.game_object.hash());
+            self.cancellable = Some(data.start_coroutine(async move{
+                let _dropped = dropped;
+                wait_frames(2).await;
+                with_data(|data| log(data, "waited(cancellable)"));
+            }));
+        }
+        if let (true, Some(cancellable)) = (data.input.get_key_down(KeyCode::X), self.cancellable.as_ref()){
+            cancellable.cancel();
+        }
+        if data.input.get_key_down(KeyCode::U){
+            log(data, &format!("updates({})", self.updates));
+        }
+    }
+
+    fn late_update(&mut self, data: &mut DataStruct){
+        log(data, "late_update");
+
+        if data.input.get_key_down(KeyCode::D){
+            data.destroy(data.game_object.handle());
+        }
+    }
+
+    fn on_enable(&mut self, data: &mut DataStruct){
+        log(data, "on_enable");
+    }
+
+    fn on_disable(&mut self, data: &mut DataStruct){
+        log(data, "on_disable");
+        data.transform.position.y += 1.0;
+    }
+
+    fn on_collision_enter(&mut self, data: &mut DataStruct, collision: &Collision){
+        log_collision(data, "on_collision_enter", collision);
+    }
+
+    fn on_collision_stay(&mut self, data: &mut DataStruct, collision: &Collision){
+        log_collision(data, "on_collision_stay", collision);
+    }
+
+    fn on_collision_exit(&mut self, data: &mut DataStruct, collision: &Collision){
+        log_collision(data, "on_collision_exit", collision);
+    }
+
+    fn on_trigger_enter(&mut self, data: &mut DataStruct, other: &Trigger){
+        log_trigger(data, "on_trigger_enter", other);
+    }
+
+    fn on_trigger_stay(&mut self, data: &mut DataStruct, other: &Trigger){
+        log_trigger(data, "on_trigger_stay", other);
+    }
+
+    fn on_trigger_exit(&mut self, data: &mut DataStruct, other: &Trigger){
+        log_trigger(data, "on_trigger_exit", other);
+    }
+
+    fn on_message(&mut self, data: &mut DataStruct, message: &ReceivedMessage){
+        let sender = message.sender().map(|sender| sender.id());
+        match message.decode::<Ping>(){
+            Some(Ping(frame)) => log(data, &format!("on_message(Ping({}), {:?})", frame, sender)),
+            None => log(data, &format!("on_message({}, {}, {:?})", message.name(), String::from_utf8_lossy(message.payload()), sender)),
+        }
+    }
+
+    fn destroy(&mut self, data: &mut DataStruct){
+        log(data, "destroy");
+    }
+
+    fn save_state(&self) -> Option<Vec<u8>>{
+        if self.panic_on_save{
+            panic!("the fixture was asked to panic");
+        }
+        let state = self.updates.to_le_bytes();
+        if self.short_state{
+            return Some(state[..3].to_vec());
+        }
+        Some(state.to_vec())
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String>{
+        let bytes = state.try_into().map_err(|_| "expected 4 bytes".to_string())?;
+        self.updates = u32::from_le_bytes(bytes);
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use runity::{KeyCode, Quaternion, Transform, Vector3};
+use runity_host::Host;
+
+fn assert_close(a: Vector3, b: Vector3){
+    assert!(Vector3::distance(a, b) < 1e-4, "{:?} != {:?}", a, b);
+}
+
+// Presses `H` for a frame, so every script logs its place in the hierarchy, then lets go of it
+// for a frame, so it can be pressed again
+fn log_hierarchy(host: &mut Host) -> Vec<String>{
+    host.input_mut().set_key(KeyCode::H, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::H, false);
+    host.step(1);
+
+    common::messages(host).into_iter().filter(|message| message.contains("hierarchy(") || message.contains("child(")).collect()
+}
+
+#[test]
+fn children_follow_their_parent(){
+    let mut host = common::host();
+    let parent = host.scene_mut().add("Parent", "Untagged", common::at(Vector3::new(1.0, 0.0, 0.0)));
+    let first = host.scene_mut().add("First", "Untagged", common::at(Vector3::new(1.0, 0.0, 2.0)));
+    let second = host.scene_mut().add("Second", "Untagged", common::at(Vector3::new(0.0, 3.0, 0.0)));
+    assert!(host.scene_mut().set_parent(first, Some(parent), true));
+    assert!(host.scene_mut().set_parent(second, Some(parent), true));
+    host.attach(parent).unwrap();
+    host.attach(first).unwrap();
+
+    let world = |host: &Host, id: u64| host.scene().transform(id).unwrap();
+    let child = |host: &Host, id: u64| format!("{} child({}, {:?}, {:?})", parent, id, world(host, id).position, world(host, id).rotation);
+
+    assert_eq!(log_hierarchy(&mut host), [
+        format!("{} hierarchy(parent: None, children: 2) 1", parent),
+        format!("{} 1", child(&host, first)),
+        format!("{} 1", child(&host, second)),
+        format!("{} hierarchy(parent: Some({}), children: 0) 1", first, parent),
+    ]);
+
+    // Turning the parent a quarter turn about `y` swings its children around it
+    let rotation = Quaternion::angle_axis(90.0, Vector3::up());
+    assert!(host.scene_mut().set_transform(parent, &Transform::new(Vector3::new(0.0, 0.0, 5.0), rotation)));
+
+    assert_close(world(&host, first).position, Vector3::new(2.0, 0.0, 5.0));
+    assert_close(world(&host, second).position, Vector3::new(0.0, 3.0, 6.0));
+    assert_close(world(&host, first).rotation * Vector3::forward(), Vector3::right());
+
+    // The script is sent its children where they are now
+    assert_eq!(log_hierarchy(&mut host), [
+        format!("{} hierarchy(parent: None, children: 2) 3", parent),
+        format!("{} 3", child(&host, first)),
+        format!("{} 3", child(&host, second)),
+        format!("{} hierarchy(parent: Some({}), children: 0) 3", first, parent),
+    ]);
+}
+
+#[test]
+fn children_leave_with_their_parent(){
+    let mut host = common::host();
+    let parent = host.scene_mut().add("Parent", "Untagged", common::at(Vector3::zero()));
+    let child = host.scene_mut().add("Child", "Untagged", common::at(Vector3::zero()));
+    host.attach(parent).unwrap();
+
+    assert_eq!(log_hierarchy(&mut host), [format!("{} hierarchy(parent: None, children: 0) 1", parent)]);
+
+    assert!(host.scene_mut().set_parent(child, Some(parent), false));
+    assert_eq!(log_hierarchy(&mut host)[0], format!("{} hierarchy(parent: None, children: 1) 3", parent));
+
+    assert!(host.scene_mut().set_parent(child, None, true));
+    assert_eq!(log_hierarchy(&mut host), [format!("{} hierarchy(parent: None, children: 0) 5", parent)]);
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+use runity_host::{CollisionEvent, LogLevel, Phase};
+
+#[test]
+fn step_runs_the_lifecycle_in_order(){
+    let mut host = common::host();
+    // Exactly two fixed steps a frame
+    host.clock_mut().delta_time = 0.0625;
+    host.clock_mut().fixed_delta_time = 0.03125;
+
+    let first = host.scene_mut().add("First", "Untagged", common::at(Vector3::zero()));
+    let second = host.scene_mut().add("Second", "Untagged", common::at(Vector3::zero()));
+    let wall = host.scene_mut().add("Wall", "Wall", common::at(Vector3::zero()));
+
+    host.attach(first).unwrap();
+    host.attach(second).unwrap();
+    assert_eq!(common::messages(&mut host), [
+        format!("{} awake 0", first),
+        format!("{} on_enable 0", first),
+        format!("{} awake 0", second),
+        format!("{} on_enable 0", second),
+    ]);
+
+    // Contacts are sent after the next fixed step's `fixed_update`
+    host.collide(first, Phase::Enter, CollisionEvent::new(wall)).unwrap();
+    host.trigger(second, Phase::Enter, wall).unwrap();
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} start 1", first),
+        format!("{} start 1", second),
+        format!("{} fixed_update 1", first),
+        format!("{} fixed_update 1", second),
+        format!("{} on_collision_enter({}, Wall, [], {:?}, {:?}) 1", first, wall, Vector3::zero(), Vector3::zero()),
+        format!("{} on_trigger_enter({}, Wall) 1", second, wall),
+        format!("{} fixed_update 1", first),
+        format!("{} fixed_update 1", second),
+        format!("{} update 1", first),
+        format!("{} update 1", second),
+        format!("{} late_update 1", first),
+        format!("{} late_update 1", second),
+    ]);
+
+    // Gameobjects destroyed during the frame have their scripts destroyed once it settles
+    host.input_mut().set_key(KeyCode::D, true);
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} fixed_update 2", first),
+        format!("{} fixed_update 2", second),
+        format!("{} fixed_update 2", first),
+        format!("{} fixed_update 2", second),
+        format!("{} update 2", first),
+        format!("{} update 2", second),
+        format!("{} late_update 2", first),
+        format!("{} late_update 2", second),
+        format!("{} on_disable 2", first),
+        format!("{} destroy 2", first),
+        format!("{} on_disable 2", second),
+        format!("{} destroy 2", second),
+    ]);
+
+    assert!(host.instances().is_empty());
+    assert!(host.scene().get(first).is_none() && host.scene().get(second).is_none());
+
+    host.step(1);
+    assert!(common::messages(&mut host).is_empty());
+}
+
+#[test]
+fn disabled_scripts_wait_to_start(){
+    let mut host = common::host();
+    // A single fixed step a frame
+    host.clock_mut().delta_time = 0.0625;
+    host.clock_mut().fixed_delta_time = 0.0625;
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let instance = host.attach(object).unwrap();
+
+    host.set_enabled(instance, false);
+    host.step(2);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} awake 0", object),
+        format!("{} on_enable 0", object),
+        format!("{} on_disable 0", object),
+    ]);
+
+    // Like unity, a script enabled later starts at the beginning of its first frame
+    host.set_enabled(instance, true);
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} on_enable 2", object),
+        format!("{} start 3", object),
+        format!("{} fixed_update 3", object),
+        format!("{} update 3", object),
+        format!("{} late_update 3", object),
+    ]);
+}
+
+#[test]
+fn on_disable_is_synced_before_destroy(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let instance = host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    host.destroy(instance);
+    assert_eq!(common::messages(&mut host), [format!("{} on_disable 1", object), format!("{} destroy 1", object)]);
+    assert_eq!(host.scene().transform(object).unwrap().position, Vector3::new(0.0, 1.0, 0.0));
+
+    // Quitting disables (and destroys) every script left the same way
+    let other = host.scene_mut().add("Other", "Untagged", common::at(Vector3::zero()));
+    host.attach(other).unwrap();
+    host.step(1);
+    host.quit();
+    assert_eq!(host.scene().transform(other).unwrap().position, Vector3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn panics_in_update_fault_the_script(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let instance = host.attach(object).unwrap();
+    host.step(1);
+    host.take_logs();
+    assert!(!host.is_faulted(instance));
+
+    // The script moves its gameobject before panicking
+    host.input_mut().set_key(KeyCode::F, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::F, false);
+
+    let logs = host.take_logs();
+    let messages: Vec<_> = logs.iter().filter(|entry| entry.level == LogLevel::Log).map(|entry| entry.message.clone()).collect();
+    let errors: Vec<_> = logs.iter().filter(|entry| entry.level == LogLevel::Error).map(|entry| entry.message.clone()).collect();
+
+    // `late_update` is skipped, and the panic is reported with where it happened
+    assert_eq!(messages, [format!("{} update 2", object)]);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].starts_with("runity: `update` panicked: the fixture was asked to panic in update (at "), "{}", errors[0]);
+    assert!(errors[0].contains("fixture/src/lib.rs:"), "{}", errors[0]);
+    assert!(host.is_faulted(instance));
+
+    // The `DataStruct` came back as it was passed in, so the move was never applied
+    assert_eq!(host.scene().transform(object).unwrap().position, Vector3::zero());
+    assert_eq!(host.transform(instance).unwrap().position, Vector3::zero());
+
+    // Nothing runs on the script again
+    host.step(2);
+    assert!(host.take_logs().is_empty());
+}
+
+#[test]
+fn only_the_hooks_asked_for_are_exported(){
+    let library = unsafe{ libloading::Library::new(common::fixture()) }.unwrap();
+    let exported = |name: &str| unsafe{ library.get::<extern "C" fn()>(name.as_bytes()) }.is_ok();
+
+    // The fixture asks for every group but `application`
+    for name in ["awake", "update", "fixed_update", "late_update", "on_enable", "on_disable", "on_collision_stay", "on_trigger_exit", "destroy", "runity_save_state"]{
+        assert!(exported(name), "{} isn't exported", name);
+    }
+    for name in ["on_application_focus", "on_application_pause", "on_application_quit"]{
+        assert!(!exported(name), "{} is exported", name);
+    }
+}
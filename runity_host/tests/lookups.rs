@@ -0,0 +1,72 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+use runity_host::Host;
+
+// Presses `L` for a frame, so every script logs what its lookups found
+fn look_up(host: &mut Host) -> Vec<String>{
+    host.input_mut().set_key(KeyCode::L, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::L, false);
+
+    common::messages(host).into_iter().filter(|message| message.contains(" find")).collect()
+}
+
+// How the fixture logs a gameobject found by a lookup
+fn found(host: &Host, id: u64) -> String{
+    let object = host.scene().get(id).unwrap();
+    format!("({}, {}, {:?})", id, object.tag, host.scene().transform(id).unwrap().position)
+}
+
+#[test]
+fn lookups_find_what_the_scene_has(){
+    let mut host = common::host();
+    let script = host.scene_mut().add("Script", "Player", common::at(Vector3::zero()));
+    let target = host.scene_mut().add("Target", "Untagged", common::at(Vector3::new(0.0, 0.0, 4.0)));
+    // More enemies than rust makes room for at first, so it has to ask again
+    let enemies: Vec<u64> = (0..3).map(|index| host.scene_mut().add("Enemy", "Enemy", common::at(Vector3::new(index as f32, 0.0, 0.0)))).collect();
+    let parent = host.scene_mut().add("Parent", "Untagged", common::at(Vector3::new(0.0, 10.0, 0.0)));
+    assert!(host.scene_mut().set_parent(enemies[2], Some(parent), false));
+    host.attach(script).unwrap();
+
+    assert_eq!(look_up(&mut host), [
+        format!("{} find(Some({:?})) 1", script, found(&host, target)),
+        format!("{} find_all_with_tag({:?}) 1", script, enemies.iter().map(|&enemy| found(&host, enemy)).collect::<Vec<_>>()),
+        format!("{} find_by_id(Some({:?})) 1", script, found(&host, script)),
+        format!("{} find_by_id(None) 1", script),
+    ]);
+
+    // The parented enemy was sent where it is in the world, not relative to its parent
+    assert_eq!(host.scene().transform(enemies[2]).unwrap().position, Vector3::new(2.0, 10.0, 0.0));
+}
+
+#[test]
+fn lookups_miss_with_none(){
+    let mut host = common::host();
+    let script = host.scene_mut().add("Script", "Player", common::at(Vector3::zero()));
+    host.attach(script).unwrap();
+
+    assert_eq!(look_up(&mut host), [
+        format!("{} find(None) 1", script),
+        format!("{} find_all_with_tag([]) 1", script),
+        format!("{} find_by_id(Some({:?})) 1", script, found(&host, script)),
+        format!("{} find_by_id(None) 1", script),
+    ]);
+}
+
+#[test]
+fn removed_gameobjects_are_not_found(){
+    let mut host = common::host();
+    let script = host.scene_mut().add("Script", "Player", common::at(Vector3::zero()));
+    let target = host.scene_mut().add("Target", "Enemy", common::at(Vector3::zero()));
+    host.attach(script).unwrap();
+    host.step(1);
+    host.scene_mut().remove(target);
+
+    assert_eq!(look_up(&mut host), [
+        format!("{} find(None) 2", script),
+        format!("{} find_all_with_tag([]) 2", script),
+        format!("{} find_by_id(Some({:?})) 2", script, found(&host, script)),
+        format!("{} find_by_id(None) 2", script),
+    ]);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use runity::{Handle, Instance, KeyCode, Recipients, Vector3};
+use runity_host::Host;
+
+// Presses `key` for a frame, then lets go of it for a frame, returning the messages the scripts
+// received in either
+fn press(host: &mut Host, key: KeyCode) -> Vec<String>{
+    host.input_mut().set_key(key, true);
+    host.step(1);
+    host.input_mut().set_key(key, false);
+    host.step(1);
+
+    received(host)
+}
+
+// The messages the scripts received since the last call
+fn received(host: &mut Host) -> Vec<String>{
+    common::messages(host).into_iter().filter(|message| message.contains("on_message")).collect()
+}
+
+// A host with a script tagged `Player` and one tagged `Listener`, both subscribed
+fn host() -> (Host, u64, u64, Instance){
+    let mut host = common::host();
+    let player = host.scene_mut().add("Player", "Player", common::at(Vector3::zero()));
+    let listener = host.scene_mut().add("Listener", "Listener", common::at(Vector3::zero()));
+    host.attach(player).unwrap();
+    let instance = host.attach(listener).unwrap();
+
+    assert!(press(&mut host, KeyCode::M).is_empty());
+    (host, player, listener, instance)
+}
+
+#[test]
+fn messages_are_routed_to_subscribed_scripts(){
+    let (mut host, player, listener, instance) = host();
+    assert_eq!(host.subscriptions(instance), ["Note", "Ping"]);
+
+    // Sent in frame 3, and received at the start of frame 4's `update`, in the order they were sent
+    assert_eq!(press(&mut host, KeyCode::E), [
+        format!("{} on_message(Note, all, Some({})) 4", player, player),
+        format!("{} on_message(Note, self, Some({})) 4", player, player),
+        format!("{} on_message(Note, all, Some({})) 4", player, listener),
+        format!("{} on_message(Ping(3), Some({})) 4", listener, player),
+        format!("{} on_message(Note, all, Some({})) 4", listener, player),
+        format!("{} on_message(Ping(3), Some({})) 4", listener, listener),
+        format!("{} on_message(Note, all, Some({})) 4", listener, listener),
+        format!("{} on_message(Note, self, Some({})) 4", listener, listener),
+    ]);
+}
+
+#[test]
+fn unsubscribed_scripts_receive_nothing(){
+    let mut host = common::host();
+    let listener = host.scene_mut().add("Listener", "Listener", common::at(Vector3::zero()));
+    let instance = host.attach(listener).unwrap();
+
+    assert!(press(&mut host, KeyCode::E).is_empty());
+    assert!(host.subscriptions(instance).is_empty());
+
+    press(&mut host, KeyCode::M);
+    assert!(press(&mut host, KeyCode::Q).is_empty());
+    assert_eq!(host.subscriptions(instance), ["Ping"]);
+
+    assert_eq!(press(&mut host, KeyCode::E), [format!("{} on_message(Ping(7), Some({})) 8", listener, listener)]);
+}
+
+#[test]
+fn the_host_publishes_without_a_sender(){
+    let (mut host, player, listener, _) = host();
+
+    host.publish(Recipients::Tag("Player"), "Note", b"host");
+    host.publish(Recipients::All, "Unheard", b"");
+    host.publish(Recipients::Object(Handle::of(listener)), "Ping", &5u64.to_le_bytes());
+    host.step(1);
+
+    assert_eq!(received(&mut host), [
+        format!("{} on_message(Note, host, None) 3", player),
+        format!("{} on_message(Ping(5), None) 3", listener),
+    ]);
+}
+
+#[test]
+fn messages_for_disabled_scripts_are_dropped(){
+    let (mut host, _, listener, instance) = host();
+
+    host.set_enabled(instance, false);
+    host.publish(Recipients::Object(Handle::of(listener)), "Note", b"host");
+    host.step(1);
+    host.set_enabled(instance, true);
+    host.step(1);
+
+    assert!(received(&mut host).is_empty());
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use runity::{Handle, KeyCode, Vector3};
+use runity_host::SceneShape;
+
+#[test]
+fn raycast_hits_the_first_of_raycast_all(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+
+    // Added out of order, with one beside the ray and one behind it
+    let mut wall = |z: f32, x: f32| {
+        let wall = host.scene_mut().add("Wall", "Untagged", common::at(Vector3::new(x, 0.0, z)));
+        host.scene_mut().get_mut(wall).unwrap().shape = Some(SceneShape::Box{ half_extents: Vector3::new(0.5, 0.5, 0.5) });
+        Handle::of(wall)
+    };
+    let middle = wall(5.0, 0.0);
+    let near = wall(2.0, 0.0);
+    let far = wall(8.0, 0.0);
+    wall(4.0, 3.0);
+    wall(-4.0, 0.0);
+
+    host.step(1);
+    common::messages(&mut host);
+    host.input_mut().set_key(KeyCode::C, true);
+    host.step(1);
+
+    assert_eq!(common::messages(&mut host), [
+        format!("{} update 2", object),
+        format!("{} raycast({:?}, {:?}) 2", object, Some(near), [near, middle, far]),
+        format!("{} late_update 2", object),
+    ]);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use std::cell::RefCell;
+
+use runity::{Profiler, Str, Vector3};
+use runity_host::Host;
+
+thread_local!{
+    // The samples the library handed the profiler on this thread, as they began and ended
+    static SAMPLES: RefCell<Vec<String>> = const{ RefCell::new(Vec::new()) };
+}
+
+extern "C" fn begin_sample(name: Str){
+    SAMPLES.with(|samples| samples.borrow_mut().push(format!("begin {}", name.to_string_lossy())));
+}
+
+extern "C" fn end_sample(){
+    SAMPLES.with(|samples| samples.borrow_mut().push("end".to_string()));
+}
+
+fn samples() -> Vec<String>{
+    SAMPLES.with(|samples| samples.borrow_mut().drain(..).collect())
+}
+
+// A host with a single script, with no fixed steps so frames only run `update` and `late_update`
+fn host() -> Host{
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host
+}
+
+// The library's Chrome trace, written to a file of the test's own
+fn chrome_trace(host: &mut Host, test: &str) -> String{
+    let path = std::env::temp_dir().join(format!("runity_host_{}_{}.json", test, std::process::id()));
+    assert!(host.write_chrome_trace(&path));
+    let trace = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    trace
+}
+
+#[test]
+fn scopes_are_handed_to_the_host_profiler(){
+    let mut host = host();
+    host.step(1);
+    samples();
+
+    assert!(host.set_profiler(Some(Profiler::new(begin_sample, end_sample))));
+    host.step(1);
+    assert_eq!(samples(), [
+        "begin update",
+        "begin fixture update",
+        "end",
+        "end",
+        "begin late_update",
+        "end",
+    ]);
+
+    // Only the first frame was recorded by the library
+    let trace = chrome_trace(&mut host, "handed");
+    assert!(trace.contains(r#""args":{"frame":1}"#), "{}", trace);
+    assert!(!trace.contains(r#""args":{"frame":2}"#), "{}", trace);
+
+    // Taken back, scopes are recorded again
+    assert!(host.set_profiler(None));
+    host.step(1);
+    assert_eq!(samples(), Vec::<String>::new());
+    let trace = chrome_trace(&mut host, "taken_back");
+    assert!(trace.contains(r#""name":"fixture update","cat":"runity","ph":"X","pid":1,"tid":1,"ts":"#), "{}", trace);
+    assert!(trace.contains(r#""args":{"frame":3}"#), "{}", trace);
+}
+
+#[test]
+fn scopes_are_recorded_without_a_host_profiler(){
+    let mut host = host();
+    host.step(2);
+
+    let trace = chrome_trace(&mut host, "recorded");
+    assert_eq!(samples(), Vec::<String>::new());
+    for name in ["awake", "on_enable", "start", "update", "fixture update", "late_update"]{
+        assert!(trace.contains(&format!(r#""name":"{}","cat":"runity""#, name)), "{} is missing from {}", name, trace);
+    }
+    assert!(trace.contains(r#""name":"frame 2""#), "{}", trace);
+}
+
+#[test]
+fn the_profiler_is_handed_to_a_reloaded_library(){
+    let mut host = host();
+    assert!(host.set_profiler(Some(Profiler::new(begin_sample, end_sample))));
+    host.step(1);
+
+    host.reload(common::fixture()).unwrap();
+    samples();
+    host.step(1);
+    assert_eq!(samples(), [
+        "begin update",
+        "begin fixture update",
+        "end",
+        "end",
+        "begin late_update",
+        "end",
+    ]);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+use runity_host::{HostError, LogLevel};
+
+#[test]
+fn save_state_panics_are_reported(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host.input_mut().set_key(KeyCode::P, true);
+    host.step(1);
+    host.take_logs();
+
+    // `runity_save_state` isn't passed a `DataStruct`, so it reports through the debug of the
+    // last call the script ran in
+    host.reload(common::fixture()).unwrap();
+    let errors: Vec<_> = host.take_logs().into_iter()
+        .filter(|entry| entry.level == LogLevel::Error)
+        .map(|entry| entry.message)
+        .collect();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].starts_with("runity: `runity_save_state` panicked: the fixture was asked to panic"), "{}", errors[0]);
+}
+
+#[test]
+fn reload_keeps_state_and_destroys_the_old_scripts(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host.step(3);
+    common::messages(&mut host);
+
+    host.reload(common::fixture()).unwrap();
+    assert_eq!(common::messages(&mut host), [format!("{} destroy 3", object)]);
+
+    // The count of updates carried over, and neither `awake` nor `start` ran again
+    host.input_mut().set_key(KeyCode::U, true);
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} update 4", object),
+        format!("{} updates(4) 4", object),
+        format!("{} late_update 4", object),
+    ]);
+}
+
+#[test]
+fn reload_refuses_state_of_another_version(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    host.attach(object).unwrap();
+    host.step(2);
+    common::messages(&mut host);
+
+    match host.reload(common::bumped_fixture()){
+        Err(HostError::StateRejected{ object: rejected, reason }) => {
+            assert_eq!(rejected, object);
+            assert_eq!(reason, "runity: the saved state is version 1, but the script expects version 2");
+        }
+        result => panic!("expected the state to be rejected, got {:?}", result),
+    }
+
+    // Nothing was restored, and the old library carries on
+    assert_eq!(common::messages(&mut host), ["runity: the saved state is version 1, but the script expects version 2"]);
+    host.input_mut().set_key(KeyCode::U, true);
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} update 3", object),
+        format!("{} updates(3) 3", object),
+        format!("{} late_update 3", object),
+    ]);
+}
+
+#[test]
+fn refused_reloads_destroy_the_scripts_already_restored(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let first = host.scene_mut().add("First", "Untagged", common::at(Vector3::zero()));
+    let second = host.scene_mut().add("Second", "Untagged", common::at(Vector3::zero()));
+    let restored = host.attach(first).unwrap();
+    host.attach(second).unwrap();
+
+    // Only the second script writes state that can't be read back
+    host.set_enabled(restored, false);
+    host.input_mut().set_key(KeyCode::S, true);
+    host.step(1);
+    host.input_mut().set_key(KeyCode::S, false);
+    host.set_enabled(restored, true);
+    common::messages(&mut host);
+
+    match host.reload(common::fixture()){
+        Err(HostError::StateRejected{ object, reason }) => {
+            assert_eq!(object, second);
+            assert_eq!(reason, "runity: the saved state couldn't be restored: expected 4 bytes");
+        }
+        result => panic!("expected the state to be rejected, got {:?}", result),
+    }
+
+    // The first script was restored into the new library, and destroyed there
+    assert_eq!(common::messages(&mut host), [
+        "runity: the saved state couldn't be restored: expected 4 bytes".to_string(),
+        format!("{} destroy 1", first),
+    ]);
+    assert_eq!(host.instances().len(), 2);
+
+    // The first script was disabled through the first frame, so it only starts now
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} start 2", first),
+        format!("{} update 2", first),
+        format!("{} update 2", second),
+        format!("{} late_update 2", first),
+        format!("{} late_update 2", second),
+    ]);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use runity::{KeyCode, Vector3};
+
+#[test]
+fn scripts_can_call_back_into_their_store(){
+    let mut host = common::host();
+    host.clock_mut().fixed_delta_time = 0.0;
+
+    let object = host.scene_mut().add("Script", "Untagged", common::at(Vector3::zero()));
+    let instance = host.attach(object).unwrap();
+    host.step(1);
+    common::messages(&mut host);
+
+    // This used to deadlock, as the store was locked while the script ran
+    host.input_mut().set_key(KeyCode::R, true);
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} update 2", object),
+        format!("{} reentered(saved: false) 2", object),
+        format!("{} late_update 2", object),
+    ]);
+
+    // And the script was put back afterwards
+    host.step(1);
+    assert_eq!(common::messages(&mut host), [
+        format!("{} update 3", object),
+        format!("{} late_update 3", object),
+    ]);
+    assert!(!host.is_faulted(instance));
+}
@@ -0,0 +1,184 @@
+//! Procedural macros for runity.
+//!
+//! These are re-exported by `runity`, and should be used through it (`#[runity::script]`).
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Ident, ItemStruct, Token};
+
+// The groups of optional hooks a script can ask for. Unity calls these often (every fixed step,
+// or for every contact), so they are only exported when the script asks for them.
+const HOOKS: &[&str] = &["fixed_update", "late_update", "enable", "application", "collisions", "triggers"];
+
+/// # Script
+///
+/// Marks a struct as the script of this library. The struct must implement
+/// `runity::Script` and `Default`.
+///
+/// This generates the `awake`, `start`, `update` and `destroy` exports that unity
+/// loads, along with `runity_save_state` and `runity_load_state` for hot reloading, and
+/// stores the script between calls. Only one script can be declared per library.
+///
+/// The rest of the lifecycle is only exported for the hooks named in the attribute, as
+/// the host calls every hook it finds:
+///
+/// - `fixed_update`
+/// - `late_update`
+/// - `enable` - `on_enable` and `on_disable`
+/// - `application` - `on_application_focus`, `on_application_pause` and `on_application_quit`
+/// - `collisions` - `on_collision_enter`, `on_collision_stay` and `on_collision_exit`
+/// - `triggers` - `on_trigger_enter`, `on_trigger_stay` and `on_trigger_exit`
+///
+/// ```ignore
+/// #[runity::script(fixed_update, collisions)]
+/// #[derive(Default)]
+/// pub struct Ball;
+/// ```
+#[proc_macro_attribute]
+pub fn script(attr: TokenStream, item: TokenStream) -> TokenStream{
+    let hooks = parse_macro_input!(attr with Punctuated::<Ident, Token![,]>::parse_terminated);
+    for hook in hooks.iter(){
+        if !HOOKS.iter().any(|name| hook == name){
+            let message = format!("unknown hook `{}`, expected one of: {}", hook, HOOKS.join(", "));
+            return syn::Error::new_spanned(hook, message)
+                .to_compile_error()
+                .into();
+        }
+    }
+    let wants = |name: &str| hooks.iter().any(|hook| hook == name);
+
+    let item = parse_macro_input!(item as ItemStruct);
+
+    // The store is a plain `static`, so the script can't be generic
+    if !item.generics.params.is_empty(){
+        return syn::Error::new_spanned(&item.generics, "`#[runity::script]` can't be used on a generic struct")
+            .to_compile_error()
+            .into();
+    }
+
+    let ident = &item.ident;
+
+    let fixed_update = wants("fixed_update").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn fixed_update(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.fixed_update(data)
+        }
+    });
+
+    let late_update = wants("late_update").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn late_update(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.late_update(data)
+        }
+    });
+
+    let enable = wants("enable").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn on_enable(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_enable(data)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_disable(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_disable(data)
+        }
+    });
+
+    let application = wants("application").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn on_application_focus(data: ::runity::DataStruct, focused: bool) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_application_focus(data, focused)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_application_pause(data: ::runity::DataStruct, paused: bool) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_application_pause(data, paused)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_application_quit(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_application_quit(data)
+        }
+    });
+
+    let collisions = wants("collisions").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn on_collision_enter(data: ::runity::DataStruct, collision: &::runity::Collision) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_collision_enter(data, collision)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_collision_stay(data: ::runity::DataStruct, collision: &::runity::Collision) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_collision_stay(data, collision)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_collision_exit(data: ::runity::DataStruct, collision: &::runity::Collision) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_collision_exit(data, collision)
+        }
+    });
+
+    let triggers = wants("triggers").then(|| quote!{
+        #[no_mangle]
+        pub extern "C" fn on_trigger_enter(data: ::runity::DataStruct, other: &::runity::Trigger) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_trigger_enter(data, other)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_trigger_stay(data: ::runity::DataStruct, other: &::runity::Trigger) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_trigger_stay(data, other)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn on_trigger_exit(data: ::runity::DataStruct, other: &::runity::Trigger) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.on_trigger_exit(data, other)
+        }
+    });
+
+    let expanded = quote!{
+        #item
+
+        #[doc(hidden)]
+        static __RUNITY_SCRIPT: ::runity::ScriptStore<#ident> = ::runity::ScriptStore::new();
+
+        #[no_mangle]
+        pub extern "C" fn awake(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.awake(data)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn start(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.start(data)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn update(data: ::runity::DataStruct) -> ::runity::DataStruct{
+            __RUNITY_SCRIPT.update(data)
+        }
+
+        #fixed_update
+        #late_update
+        #enable
+        #application
+        #collisions
+        #triggers
+
+        #[no_mangle]
+        pub extern "C" fn destroy(data: ::runity::DataStruct) -> i32{
+            __RUNITY_SCRIPT.destroy(data)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn runity_save_state(instance: ::runity::Instance) -> ::runity::State{
+            __RUNITY_SCRIPT.save_state(instance)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn runity_load_state(data: &mut ::runity::DataStruct, state: ::runity::State) -> i32{
+            __RUNITY_SCRIPT.load_state(data, state)
+        }
+    };
+
+    expanded.into()
+}
@@ -0,0 +1,324 @@
+//! The ABI handshake between a runity library and its host.
+//!
+//! A host built against a different version of runity than the library it loads would
+//! otherwise pass structs with the wrong layout, silently corrupting memory. Every library
+//! reports the version of the boundary it was built with (`runity_abi_version`), and the
+//! size, alignment and field offsets of every shared struct (`runity_layout_table`). The host
+//! compares these against its own before calling into the library.
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::ptr::null;
+
+use lazy_static::lazy_static;
+use libc::c_char;
+
+use crate::interop::{self, reflect, StructInfo};
+use crate::panic;
+
+/// The version of the boundary between runity and its host. This is bumped whenever
+/// the meaning of a shared struct or export changes, even if its layout doesn't.
+pub const ABI_VERSION: u32 = 19;
+
+/// # Layout Field
+///
+/// The layout of a single field of a shared struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutField{
+    pub name: *const c_char,
+    pub offset: u32,
+    pub size: u32,
+}
+
+reflect!(LayoutField{ name, offset, size });
+
+/// # Layout Entry
+///
+/// The layout of a shared struct. `fields` points to `field_count` fields, in order.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutEntry{
+    pub name: *const c_char,
+    pub size: u32,
+    pub align: u32,
+    pub fields: *const LayoutField,
+    pub field_count: u32,
+}
+
+reflect!(LayoutEntry{ name, size, align, fields, field_count });
+
+/// # Layout Table
+///
+/// The layout of every shared struct, as returned by `runity_layout_table`. `entries` points
+/// to `len` entries.
+///
+/// The table is owned by the library, and stays valid for as long as the library is loaded.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutTable{
+    pub entries: *const LayoutEntry,
+    pub len: u32,
+}
+
+reflect!(LayoutTable{ entries, len });
+
+impl LayoutTable{
+    /// An empty table, returned if the table couldn't be built.
+    pub const EMPTY: LayoutTable = LayoutTable{ entries: null(), len: 0 };
+}
+
+// The table built from this library's own layouts, along with everything it points to
+struct OwnedTable{
+    _names: Vec<CString>,
+    _fields: Vec<Vec<LayoutField>>,
+    _entries: Vec<LayoutEntry>,
+    table: LayoutTable,
+}
+
+// The table only points into memory it owns, and is never changed once built
+unsafe impl Send for OwnedTable{}
+unsafe impl Sync for OwnedTable{}
+
+impl OwnedTable{
+    fn build(structs: &[StructInfo]) -> Self{
+        // Interior NULs can't appear in rust identifiers, so these never fail
+        let name = |name: &str| CString::new(name).unwrap_or_default();
+
+        let mut names = Vec::new();
+        let mut fields = Vec::new();
+        let mut entries = Vec::new();
+
+        for info in structs.iter(){
+            let struct_fields = info.fields.iter().map(|field| {
+                let field_name = name(field.name);
+                let layout = LayoutField{ name: field_name.as_ptr(), offset: field.offset as u32, size: field.size as u32 };
+                names.push(field_name);
+                layout
+            }).collect::<Vec<_>>();
+
+            let struct_name = name(info.name);
+            entries.push(LayoutEntry{
+                name: struct_name.as_ptr(),
+                size: info.size as u32,
+                align: info.align as u32,
+                fields: struct_fields.as_ptr(),
+                field_count: struct_fields.len() as u32,
+            });
+            names.push(struct_name);
+            fields.push(struct_fields);
+        }
+
+        // Moving the vectors doesn't move their contents, so the pointers above stay valid
+        let table = LayoutTable{ entries: entries.as_ptr(), len: entries.len() as u32 };
+
+        Self{
+            _names: names,
+            _fields: fields,
+            _entries: entries,
+            table,
+        }
+    }
+}
+
+lazy_static!{
+    static ref LAYOUT_TABLE: OwnedTable = OwnedTable::build(&interop::structs());
+}
+
+/// # ABI Mismatch
+///
+/// Why a library isn't compatible with the host.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiMismatch{
+    /// The library was built against a different version of the boundary.
+    Version{ library: u32, host: u32 },
+    /// The library doesn't describe a struct the host shares with it.
+    MissingStruct(std::string::String),
+    /// A struct has a different size or alignment in the library.
+    Layout{ name: std::string::String, library: (u32, u32), host: (u32, u32) },
+    /// A struct's fields are different in the library.
+    Field{ name: std::string::String, field: std::string::String, library: Option<(u32, u32)>, host: Option<(u32, u32)> },
+}
+
+impl fmt::Display for AbiMismatch{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            AbiMismatch::Version{ library, host } => write!(f, "library was built against runity ABI version {}, but the host expects version {}", library, host),
+            AbiMismatch::MissingStruct(name) => write!(f, "library does not describe `{}`", name),
+            AbiMismatch::Layout{ name, library, host } => write!(f, "`{}` is {} bytes (aligned to {}) in the library, but {} bytes (aligned to {}) in the host", name, library.0, library.1, host.0, host.1),
+            AbiMismatch::Field{ name, field, library, host } => match (library, host){
+                (Some(library), Some(host)) => write!(f, "`{}::{}` is {} bytes at offset {} in the library, but {} bytes at offset {} in the host", name, field, library.1, library.0, host.1, host.0),
+                (None, _) => write!(f, "`{}::{}` is missing from the library", name, field),
+                (_, None) => write!(f, "`{}::{}` is in the library, but not the host", name, field),
+            },
+        }
+    }
+}
+
+impl std::error::Error for AbiMismatch{}
+
+/// # Check ABI
+///
+/// Checks a library's ABI version and layout table (from `runity_abi_version` and
+/// `runity_layout_table`) against this build of runity. Hosts should call this after loading
+/// a library, and refuse to run it if it fails.
+///
+/// # Safety
+///
+/// `table` must be a table returned by `runity_layout_table`, from a library that is still loaded.
+pub unsafe fn check_abi(version: u32, table: LayoutTable) -> Result<(), AbiMismatch>{
+    if version != ABI_VERSION{
+        return Err(AbiMismatch::Version{ library: version, host: ABI_VERSION });
+    }
+
+    let entries = slice(table.entries, table.len);
+
+    for info in interop::structs().iter(){
+        let entry = entries.iter().find(|entry| string(entry.name) == info.name)
+            .ok_or_else(|| AbiMismatch::MissingStruct(info.name.to_string()))?;
+
+        let host = (info.size as u32, info.align as u32);
+        if (entry.size, entry.align) != host{
+            return Err(AbiMismatch::Layout{ name: info.name.to_string(), library: (entry.size, entry.align), host });
+        }
+
+        let fields = slice(entry.fields, entry.field_count);
+        let field_count = fields.len().max(info.fields.len());
+        for index in 0..field_count{
+            let library = fields.get(index);
+            let host = info.fields.get(index);
+
+            let name = match (library, host){
+                (Some(library), Some(host)) if string(library.name) == host.name && library.offset == host.offset as u32 && library.size == host.size as u32 => continue,
+                (_, Some(host)) => host.name.to_string(),
+                (Some(library), None) => string(library.name),
+                (None, None) => continue,
+            };
+
+            return Err(AbiMismatch::Field{
+                name: info.name.to_string(),
+                library: fields.iter().find(|field| string(field.name) == name).map(|field| (field.offset, field.size)),
+                host: info.fields.iter().find(|field| field.name == name).map(|field| (field.offset as u32, field.size as u32)),
+                field: name,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+unsafe fn slice<'a, T>(ptr: *const T, len: u32) -> &'a [T]{
+    if ptr.is_null(){
+        &[]
+    }
+    else{
+        std::slice::from_raw_parts(ptr, len as usize)
+    }
+}
+
+unsafe fn string(ptr: *const c_char) -> std::string::String{
+    if ptr.is_null(){
+        std::string::String::new()
+    }
+    else{
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+// Lets the host check it was built against the same version of runity as this library
+#[no_mangle]
+pub extern "C" fn runity_abi_version() -> u32{
+    ABI_VERSION
+}
+
+// Lets the host check the layout of every shared struct matches its own
+#[no_mangle]
+pub extern "C" fn runity_layout_table() -> LayoutTable{
+    panic::catch_panic("runity_layout_table", None, || LAYOUT_TABLE.table).unwrap_or(LayoutTable::EMPTY)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::interop::{FieldInfo, Kind};
+
+    // Checks a library that describes `structs` rather than this build's own
+    fn check(version: u32, structs: &[StructInfo]) -> Result<(), AbiMismatch>{
+        let table = OwnedTable::build(structs);
+        unsafe{ check_abi(version, table.table) }
+    }
+
+    fn describing(name: &str, f: impl FnOnce(&mut StructInfo)) -> Vec<StructInfo>{
+        let mut structs = interop::structs();
+        f(structs.iter_mut().find(|info| info.name == name).expect("the struct is shared"));
+        structs
+    }
+
+    #[test]
+    fn matching_library_passes(){
+        assert_eq!(check(ABI_VERSION, &interop::structs()), Ok(()));
+        assert_eq!(unsafe{ check_abi(runity_abi_version(), runity_layout_table()) }, Ok(()));
+    }
+
+    #[test]
+    fn version_mismatch(){
+        assert_eq!(check(ABI_VERSION - 1, &interop::structs()), Err(AbiMismatch::Version{ library: ABI_VERSION - 1, host: ABI_VERSION }));
+    }
+
+    #[test]
+    fn missing_struct(){
+        let mut structs = interop::structs();
+        structs.retain(|info| info.name != "Transform");
+
+        assert_eq!(check(ABI_VERSION, &structs), Err(AbiMismatch::MissingStruct("Transform".to_string())));
+        assert_eq!(check(ABI_VERSION, &[]), Err(AbiMismatch::MissingStruct("String".to_string())));
+    }
+
+    #[test]
+    fn size_and_alignment_changes(){
+        let host = interop::structs();
+        let host = host.iter().find(|info| info.name == "Vector3").map(|info| (info.size as u32, info.align as u32)).unwrap();
+
+        let structs = describing("Vector3", |info| info.size += 4);
+        assert_eq!(check(ABI_VERSION, &structs), Err(AbiMismatch::Layout{ name: "Vector3".to_string(), library: (host.0 + 4, host.1), host }));
+
+        let structs = describing("Vector3", |info| info.align *= 2);
+        assert_eq!(check(ABI_VERSION, &structs), Err(AbiMismatch::Layout{ name: "Vector3".to_string(), library: (host.0, host.1 * 2), host }));
+    }
+
+    #[test]
+    fn field_changes(){
+        // A field that moved
+        let host = interop::structs();
+        let rotation = host.iter().find(|info| info.name == "Transform").unwrap().fields.iter().find(|field| field.name == "rotation").unwrap();
+        let (offset, size) = (rotation.offset as u32, rotation.size as u32);
+
+        let structs = describing("Transform", |info| info.fields.iter_mut().find(|field| field.name == "rotation").unwrap().offset += 4);
+        assert_eq!(check(ABI_VERSION, &structs), Err(AbiMismatch::Field{
+            name: "Transform".to_string(),
+            field: "rotation".to_string(),
+            library: Some((offset + 4, size)),
+            host: Some((offset, size)),
+        }));
+
+        // A field the library doesn't have
+        let structs = describing("Vector3", |info| { info.fields.pop(); });
+        assert!(matches!(check(ABI_VERSION, &structs), Err(AbiMismatch::Field{ ref field, library: None, host: Some(_), .. }) if field == "z"));
+
+        // A field only the library has
+        let structs = describing("Vector3", |info| info.fields.push(FieldInfo{
+            name: "w",
+            kind: Kind::F32,
+            offset: 12,
+            size: 4,
+            align: 4,
+            function_name: None,
+            argument_names: Vec::new(),
+        }));
+        assert!(matches!(check(ABI_VERSION, &structs), Err(AbiMismatch::Field{ ref field, library: Some((12, 4)), host: None, .. }) if field == "w"));
+
+        // A renamed field
+        let structs = describing("Vector3", |info| info.fields[0].name = "left");
+        assert!(matches!(check(ABI_VERSION, &structs), Err(AbiMismatch::Field{ ref field, library: None, host: Some(_), .. }) if field == "x"));
+    }
+}
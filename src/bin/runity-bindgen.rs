@@ -0,0 +1,57 @@
+//! Generates the host side of the runity boundary from the rust definitions.
+//!
+//! Usage: `runity-bindgen <csharp|c> [--namespace <namespace>] [--output <path>]`
+//!
+//! `--namespace` only applies to C#.
+//!
+//! Without `--output`, the generated code is printed to stdout.
+
+use std::process;
+
+use runity::interop::{c, csharp};
+
+fn usage() -> !{
+    eprintln!("usage: runity-bindgen <csharp|c> [--namespace <namespace>] [--output <path>]");
+    process::exit(2);
+}
+
+fn main(){
+    let mut args = std::env::args().skip(1);
+
+    let language = args.next().unwrap_or_else(|| usage());
+
+    let mut namespace = csharp::DEFAULT_NAMESPACE.to_string();
+    let mut output = None;
+
+    while let Some(arg) = args.next(){
+        match arg.as_str(){
+            "--namespace" => namespace = args.next().unwrap_or_else(|| usage()),
+            "--output" | "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => usage(),
+        }
+    }
+
+    let generated = match language.as_str(){
+        "csharp" | "cs" => csharp::generate(&namespace),
+        "c" | "h" => c::generate(),
+        _ => usage(),
+    };
+
+    let generated = match generated{
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("layout error: {}", error);
+            process::exit(1);
+        }
+    };
+
+    match output{
+        Some(path) => {
+            if let Err(error) = std::fs::write(&path, generated){
+                eprintln!("failed to write {}: {}", path, error);
+                process::exit(1);
+            }
+        }
+        None => print!("{}", generated),
+    }
+}
@@ -0,0 +1,151 @@
+//! Collision and trigger events, sent by the host when the gameobject a script is attached
+//! to touches another.
+//!
+//! Like unity's `OnCollisionEnter/Stay/Exit` and `OnTriggerEnter/Stay/Exit`, each event is
+//! passed to its own (optional) export. Events are only lent for the length of that call -
+//! the host owns the contact points and the tag, and frees them once the call returns. Copy
+//! anything that is needed later.
+
+use crate::interop::reflect;
+use crate::{Str, Vector3};
+
+/// # Contact Point
+///
+/// A single point where two colliders touch.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint{
+    /// Where the colliders touch, in world space.
+    pub point: Vector3,
+    /// The normal of the contact, pointing away from the other collider.
+    pub normal: Vector3,
+    /// How far apart the colliders are at this point. Negative if they overlap.
+    pub separation: f32,
+}
+
+reflect!(ContactPoint{ point, normal, separation });
+
+/// # Collision
+///
+/// A collision between the gameobject the script is attached to and another, passed to
+/// `Script::on_collision_enter`, `on_collision_stay` and `on_collision_exit`.
+///
+/// The collision is lent by the host for the length of the call, and can't be kept.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Collision<'a>{
+    other: u64,
+    tag: Str<'a>,
+    contacts: *const ContactPoint,
+    contact_count: u32,
+    relative_velocity: Vector3,
+    impulse: Vector3,
+}
+
+reflect!(Collision<'_>{ other, tag, contacts, contact_count, relative_velocity, impulse });
+
+impl<'a> Collision<'a>{
+    /// # New
+    ///
+    /// Creates a new `Collision`, borrowing the tag and contact points. This is used by hosts
+    /// to send collisions to rust.
+    pub fn new(other: u64, tag: Str<'a>, contacts: &'a [ContactPoint], relative_velocity: Vector3, impulse: Vector3) -> Self{
+        Self{
+            other,
+            tag,
+            contacts: contacts.as_ptr(),
+            contact_count: contacts.len() as u32,
+            relative_velocity,
+            impulse,
+        }
+    }
+
+    /// # Other
+    ///
+    /// Returns the hash of the other gameobject, the same as `GameObject::hash`.
+    pub fn other(&self) -> u64{
+        self.other
+    }
+
+    /// # Tag
+    ///
+    /// Returns the tag of the other gameobject.
+    pub fn tag(&self) -> Str<'a>{
+        self.tag
+    }
+
+    /// # Contacts
+    ///
+    /// Returns every point where the colliders touch.
+    pub fn contacts(&self) -> &'a [ContactPoint]{
+        if self.contacts.is_null(){
+            return &[];
+        }
+
+        unsafe{ std::slice::from_raw_parts(self.contacts, self.contact_count as usize) }
+    }
+
+    /// # Normal
+    ///
+    /// Returns the normal of the first contact, or zero if there are no contacts (such as in
+    /// `on_collision_exit`).
+    pub fn normal(&self) -> Vector3{
+        self.contacts().first().map(|contact| contact.normal).unwrap_or_else(Vector3::zero)
+    }
+
+    /// # Relative Velocity
+    ///
+    /// Returns the relative velocity of the two gameobjects.
+    pub fn relative_velocity(&self) -> Vector3{
+        self.relative_velocity
+    }
+
+    /// # Impulse
+    ///
+    /// Returns the total impulse applied to resolve the collision.
+    pub fn impulse(&self) -> Vector3{
+        self.impulse
+    }
+}
+
+/// # Trigger
+///
+/// Another gameobject entering, staying in or leaving the trigger of the gameobject the
+/// script is attached to (or the other way around), passed to `Script::on_trigger_enter`,
+/// `on_trigger_stay` and `on_trigger_exit`.
+///
+/// The trigger is lent by the host for the length of the call, and can't be kept.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Trigger<'a>{
+    other: u64,
+    tag: Str<'a>,
+}
+
+reflect!(Trigger<'_>{ other, tag });
+
+impl<'a> Trigger<'a>{
+    /// # New
+    ///
+    /// Creates a new `Trigger`, borrowing the tag. This is used by hosts to send triggers to rust.
+    pub fn new(other: u64, tag: Str<'a>) -> Self{
+        Self{
+            other,
+            tag,
+        }
+    }
+
+    /// # Other
+    ///
+    /// Returns the hash of the other gameobject, the same as `GameObject::hash`.
+    pub fn other(&self) -> u64{
+        self.other
+    }
+
+    /// # Tag
+    ///
+    /// Returns the tag of the other gameobject.
+    pub fn tag(&self) -> Str<'a>{
+        self.tag
+    }
+}
@@ -0,0 +1,78 @@
+//! # Color
+//!
+//! This module provides the colour type used by components such as
+//! `Renderer` and `Light`.
+
+use crate::interop::reflect;
+
+/// # Color
+///
+/// This struct reimplements the `Color` in unity - a colour with red, green,
+/// blue and alpha (transparency) components, each from 0 to 1.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Color{
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+reflect!(Color{ r, g, b, a });
+
+impl Color{
+    /// # New
+    ///
+    /// Creates a new colour from `r`, `g`, `b` and `a`.
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self{
+        Self{
+            r,
+            g,
+            b,
+            a
+        }
+    }
+
+    /// # RGB
+    ///
+    /// Creates a new, fully opaque colour from `r`, `g` and `b`.
+    pub fn rgb(r: f32, g: f32, b: f32) -> Self{
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// # Lerp
+    ///
+    /// Linearly interpolates between colours `a` and `b` by `t`, which is clamped to 0 to 1.
+    pub fn lerp(a: Self, b: Self, t: f32) -> Self{
+        let t = t.clamp(0.0, 1.0);
+
+        Self::new(
+            a.r + (b.r - a.r) * t,
+            a.g + (b.g - a.g) * t,
+            a.b + (b.b - a.b) * t,
+            a.a + (b.a - a.a) * t
+        )
+    }
+}
+
+/* Shorthands for common colours */
+impl Color{
+    pub fn white() -> Self{
+        Self::rgb(1.0, 1.0, 1.0)
+    }
+    pub fn black() -> Self{
+        Self::rgb(0.0, 0.0, 0.0)
+    }
+    pub fn red() -> Self{
+        Self::rgb(1.0, 0.0, 0.0)
+    }
+    pub fn green() -> Self{
+        Self::rgb(0.0, 1.0, 0.0)
+    }
+    pub fn blue() -> Self{
+        Self::rgb(0.0, 0.0, 1.0)
+    }
+    pub fn clear() -> Self{
+        Self::new(0.0, 0.0, 0.0, 0.0)
+    }
+}
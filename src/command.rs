@@ -0,0 +1,306 @@
+//! Commands that create and remove gameobjects, or change their hierarchy.
+//!
+//! Scripts can't call into unity while they are running, so like `GameObjectChanges`, commands
+//! are queued and lent to the host in the next `ChangeBuffer`. The host carries them out in
+//! order, and writes back whether each one worked before releasing the buffer.
+//!
+//! Spawning a gameobject returns a `Handle` straight away. Until the host has spawned it, the
+//! handle is pending - it can still be passed to later commands (even in the same frame), and
+//! resolves to the gameobject's real hash once the buffer is released.
+
+use std::fmt;
+
+use crate::interop::reflect;
+use crate::{Quaternion, String, Transform, Vector3};
+
+/// # Handle
+///
+/// A gameobject commands can be sent to - either one that already exists (from
+/// `GameObject::handle`), or one a script has asked to spawn.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle(u64);
+
+impl Handle{
+    // Handles of gameobjects that haven't been spawned yet have the top bit set, which a
+    // gameobject's hash never has
+    const PENDING: u64 = 1 << 63;
+
+    /// # Of
+    ///
+    /// Returns the handle of an existing gameobject, from its hash.
+    pub fn of(hash: u64) -> Self{
+        Self(hash)
+    }
+
+    // A handle for a gameobject that hasn't been spawned yet
+    pub(crate) fn pending(id: u64) -> Self{
+        Self(id | Self::PENDING)
+    }
+
+    /// # Is Pending
+    ///
+    /// Returns true if this is a gameobject waiting to be spawned.
+    pub fn is_pending(&self) -> bool{
+        self.0 & Self::PENDING != 0
+    }
+
+    /// # ID
+    ///
+    /// Returns the raw id of this handle - the hash of the gameobject, or a pending id.
+    pub fn id(&self) -> u64{
+        self.0
+    }
+}
+
+/// # Prefab
+///
+/// A prefab to spawn, either by name or by the id the host gives it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefab<'a>{
+    Name(&'a str),
+    Id(u64),
+}
+
+/// # Command Kind
+///
+/// What a `Command` does.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind{
+    /// Spawns a prefab, like `Object.Instantiate`.
+    Spawn = 0,
+    /// Destroys a gameobject (and its children), like `Object.Destroy`.
+    Destroy = 1,
+    /// Activates or deactivates a gameobject, like `GameObject.SetActive`.
+    SetActive = 2,
+    /// Changes the parent of a gameobject, like `Transform.SetParent`.
+    SetParent = 3,
+}
+
+/// # Command Status
+///
+/// Whether the host carried out a `Command`, written back into the command before the
+/// buffer is released.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandStatus{
+    /// The host hasn't got to the command yet (or skipped it).
+    Pending = 0,
+    /// The command was carried out.
+    Done = 1,
+    /// There is no prefab with the name or id the command asked for.
+    UnknownPrefab = 2,
+    /// The gameobject the command is for doesn't exist, or has already been destroyed.
+    UnknownObject = 3,
+    /// The parent doesn't exist, or is the gameobject itself (or one of its children).
+    InvalidParent = 4,
+}
+
+impl CommandStatus{
+    /// # From Raw
+    ///
+    /// Reads a status written by the host. Anything unrecognised is treated as `Pending`.
+    pub fn from_raw(status: u32) -> Self{
+        match status{
+            1 => CommandStatus::Done,
+            2 => CommandStatus::UnknownPrefab,
+            3 => CommandStatus::UnknownObject,
+            4 => CommandStatus::InvalidParent,
+            _ => CommandStatus::Pending,
+        }
+    }
+}
+
+impl fmt::Display for CommandStatus{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            CommandStatus::Pending => write!(f, "the command wasn't carried out"),
+            CommandStatus::Done => write!(f, "done"),
+            CommandStatus::UnknownPrefab => write!(f, "no prefab with that name or id"),
+            CommandStatus::UnknownObject => write!(f, "the gameobject doesn't exist, or has already been destroyed"),
+            CommandStatus::InvalidParent => write!(f, "the parent doesn't exist, or is the gameobject itself or one of its children"),
+        }
+    }
+}
+
+/// # Command
+///
+/// A single queued command, lent to the host in a `ChangeBuffer`.
+///
+/// `target` is the gameobject the command is for - the handle being spawned for `Spawn`. It
+/// (and `parent`) may be a pending handle spawned earlier in the same buffer, which the host
+/// must map to the gameobject it spawned. Handles spawned in earlier buffers have already been
+/// replaced with their real hashes.
+///
+/// The host reports back by writing `status` (a `CommandStatus`), and for `Spawn`, the hash of
+/// the new gameobject in `object`.
+#[repr(C)]
+pub struct Command{
+    kind: u32,
+    target: u64,
+    /// The prefab's name, or empty to spawn by `prefab_id`.
+    prefab: String,
+    prefab_id: u64,
+    transform: Transform,
+    /// The new parent for `SetParent`, or `0` for the root of the scene.
+    parent: u64,
+    active: bool,
+    world_position_stays: bool,
+    status: u32,
+    object: u64,
+}
+
+reflect!(Command{ kind, target, prefab, prefab_id, transform, parent, active, world_position_stays, status, object });
+
+impl Command{
+    pub(crate) fn spawn(target: Handle, prefab: Prefab, transform: Transform) -> Self{
+        let (prefab, prefab_id) = match prefab{
+            Prefab::Name(name) => (String::from(name), 0),
+            Prefab::Id(id) => (String::new(), id),
+        };
+
+        Self{
+            prefab,
+            prefab_id,
+            transform,
+            ..Self::new(CommandKind::Spawn, target)
+        }
+    }
+
+    pub(crate) fn destroy(target: Handle) -> Self{
+        Self::new(CommandKind::Destroy, target)
+    }
+
+    pub(crate) fn set_active(target: Handle, active: bool) -> Self{
+        Self{
+            active,
+            ..Self::new(CommandKind::SetActive, target)
+        }
+    }
+
+    pub(crate) fn set_parent(target: Handle, parent: Option<Handle>, world_position_stays: bool) -> Self{
+        Self{
+            parent: parent.map(|parent| parent.id()).unwrap_or(0),
+            world_position_stays,
+            ..Self::new(CommandKind::SetParent, target)
+        }
+    }
+
+    fn new(kind: CommandKind, target: Handle) -> Self{
+        Self{
+            kind: kind as u32,
+            target: target.id(),
+            prefab: String::new(),
+            prefab_id: 0,
+            transform: Transform::new(Vector3::zero(), Quaternion::identity()),
+            parent: 0,
+            active: false,
+            world_position_stays: false,
+            status: CommandStatus::Pending as u32,
+            object: 0,
+        }
+    }
+
+    /// # Kind
+    ///
+    /// Returns what this command does.
+    pub fn kind(&self) -> CommandKind{
+        match self.kind{
+            0 => CommandKind::Spawn,
+            1 => CommandKind::Destroy,
+            2 => CommandKind::SetActive,
+            _ => CommandKind::SetParent,
+        }
+    }
+
+    /// # Target
+    ///
+    /// Returns the gameobject this command is for.
+    pub fn target(&self) -> Handle{
+        Handle(self.target)
+    }
+
+    /// # Prefab
+    ///
+    /// Returns the prefab a `Spawn` command spawns.
+    pub fn prefab(&self) -> Prefab<'_>{
+        if self.prefab.is_empty(){
+            Prefab::Id(self.prefab_id)
+        }else{
+            Prefab::Name(self.prefab.as_str())
+        }
+    }
+
+    /// # Transform
+    ///
+    /// Returns the transform (in world space) a `Spawn` command spawns the prefab with.
+    pub fn transform(&self) -> &Transform{
+        &self.transform
+    }
+
+    /// # Parent
+    ///
+    /// Returns the new parent for a `SetParent` command, or `None` to move to the root of the scene.
+    pub fn parent(&self) -> Option<Handle>{
+        if self.parent == 0{
+            None
+        }else{
+            Some(Handle(self.parent))
+        }
+    }
+
+    /// # Active
+    ///
+    /// Returns whether a `SetActive` command activates or deactivates the gameobject.
+    pub fn active(&self) -> bool{
+        self.active
+    }
+
+    /// # World Position Stays
+    ///
+    /// Returns whether a `SetParent` command keeps the gameobject where it is in the world.
+    pub fn world_position_stays(&self) -> bool{
+        self.world_position_stays
+    }
+
+    /// # Status
+    ///
+    /// Returns whether the host has carried out this command.
+    pub fn status(&self) -> CommandStatus{
+        CommandStatus::from_raw(self.status)
+    }
+
+    /// # Object
+    ///
+    /// Returns the hash of the gameobject a `Spawn` command spawned, once it is `Done`.
+    pub fn object(&self) -> u64{
+        self.object
+    }
+
+    /// # Complete
+    ///
+    /// Reports back whether the command was carried out. `object` is the hash of the spawned
+    /// gameobject for `Spawn`, and ignored otherwise. This is used by hosts.
+    pub fn complete(&mut self, status: CommandStatus, object: u64){
+        self.status = status as u32;
+        self.object = object;
+    }
+}
+
+/// # Command Failure
+///
+/// A command the host couldn't carry out, returned by `DataStruct::take_command_failures`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandFailure{
+    pub kind: CommandKind,
+    /// The gameobject the command was for.
+    pub target: Handle,
+    pub status: CommandStatus,
+}
+
+impl fmt::Display for CommandFailure{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        write!(f, "{:?} for {:?} failed: {}", self.kind, self.target, self.status)
+    }
+}
@@ -0,0 +1,508 @@
+//! Components attached to gameobjects, such as `Rigidbody` and `Light`.
+//!
+//! Components live in unity, so every read and write goes through the host. Properties are
+//! named the same as in unity's C# API (`velocity`, `isKinematic`, `material.color`), and are
+//! read and written in batches - `get_many` and `set_many` cross to the host once for any number
+//! of properties. Components runity doesn't model can still be reached by name through a
+//! `ComponentRef`, as a bag of properties.
+
+use crate::interop::reflect;
+use crate::{Color, Str, Vector3};
+
+/// # Value
+///
+/// The value of a component property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value{
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Vector3(Vector3),
+    Color(Color),
+}
+
+impl Value{
+    /// # As Bool
+    ///
+    /// Returns the value if it is a `Bool`.
+    pub fn as_bool(self) -> Option<bool>{
+        match self{
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// # As Int
+    ///
+    /// Returns the value if it is an `Int`.
+    pub fn as_int(self) -> Option<i32>{
+        match self{
+            Value::Int(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// # As Float
+    ///
+    /// Returns the value if it is a `Float` (or an `Int`, converted).
+    pub fn as_float(self) -> Option<f32>{
+        match self{
+            Value::Float(value) => Some(value),
+            Value::Int(value) => Some(value as f32),
+            _ => None,
+        }
+    }
+
+    /// # As Vector3
+    ///
+    /// Returns the value if it is a `Vector3`.
+    pub fn as_vector3(self) -> Option<Vector3>{
+        match self{
+            Value::Vector3(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// # As Color
+    ///
+    /// Returns the value if it is a `Color`.
+    pub fn as_color(self) -> Option<Color>{
+        match self{
+            Value::Color(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// # Property Value
+///
+/// A `Value` as it crosses to the host. `kind` says which fields are used:
+///
+/// - `0`: nothing (the property couldn't be read)
+/// - `1`: a bool, in `integer` (`0` or `1`)
+/// - `2`: an int, in `integer`
+/// - `3`: a float, in `x`
+/// - `4`: a vector, in `x`, `y` and `z`
+/// - `5`: a colour, in `x`, `y`, `z` and `w` (red, green, blue and alpha)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropertyValue{
+    pub kind: u32,
+    pub integer: i32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+reflect!(PropertyValue{ kind, integer, x, y, z, w });
+
+impl PropertyValue{
+    /// No value.
+    pub const NONE: PropertyValue = PropertyValue{ kind: 0, integer: 0, x: 0.0, y: 0.0, z: 0.0, w: 0.0 };
+
+    /// # Value
+    ///
+    /// Returns the value, or `None` if there isn't one (or `kind` isn't recognised).
+    pub fn value(&self) -> Option<Value>{
+        match self.kind{
+            1 => Some(Value::Bool(self.integer != 0)),
+            2 => Some(Value::Int(self.integer)),
+            3 => Some(Value::Float(self.x)),
+            4 => Some(Value::Vector3(Vector3::new(self.x, self.y, self.z))),
+            5 => Some(Value::Color(Color::new(self.x, self.y, self.z, self.w))),
+            _ => None,
+        }
+    }
+}
+
+impl From<Value> for PropertyValue{
+    fn from(value: Value) -> Self{
+        match value{
+            Value::Bool(value) => PropertyValue{ kind: 1, integer: value as i32, ..PropertyValue::NONE },
+            Value::Int(value) => PropertyValue{ kind: 2, integer: value, ..PropertyValue::NONE },
+            Value::Float(value) => PropertyValue{ kind: 3, x: value, ..PropertyValue::NONE },
+            Value::Vector3(value) => PropertyValue{ kind: 4, x: value.x, y: value.y, z: value.z, ..PropertyValue::NONE },
+            Value::Color(value) => PropertyValue{ kind: 5, integer: 0, x: value.r, y: value.g, z: value.b, w: value.a },
+        }
+    }
+}
+
+/// # Property
+///
+/// A single property in a batch read or written by the host. The name is lent for the call.
+///
+/// For reads, the host fills in `value`. For writes, it reads `value`. Either way, it sets
+/// `found` if the component has the property (and for writes, if the value could be set).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Property<'a>{
+    pub name: Str<'a>,
+    pub value: PropertyValue,
+    pub found: bool,
+}
+
+reflect!(Property<'_>{ name, value, found });
+
+/// # Component Access
+///
+/// The host functions used to reach the components on a gameobject. Each one is passed the
+/// hash of the gameobject and the name of the component's type, such as `Rigidbody`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ComponentAccess{
+    pub has_component: extern "C" fn(u64, Str) -> bool,
+    /// Reads `count` properties.
+    pub get_properties: extern "C" fn(u64, Str, *mut Property, u32),
+    /// Writes `count` properties.
+    pub set_properties: extern "C" fn(u64, Str, *mut Property, u32),
+    /// Calls a method with `count` arguments, returning false if there is no such method.
+    pub invoke: extern "C" fn(u64, Str, Str, *const PropertyValue, u32) -> bool,
+}
+
+reflect!(ComponentAccess{
+    has_component: fn HasComponent(object, component),
+    get_properties: fn GetProperties(object, component, properties, count),
+    set_properties: fn SetProperties(object, component, properties, count),
+    invoke: fn InvokeComponent(object, component, method, arguments, count),
+});
+
+/// # Component Ref
+///
+/// A component on a gameobject, reached by the name of its type. This works for any
+/// component, including ones runity doesn't model - the typed components are built on top of it.
+#[derive(Clone)]
+pub struct ComponentRef{
+    object: u64,
+    name: std::string::String,
+    access: ComponentAccess,
+}
+
+impl ComponentRef{
+    pub(crate) fn new(object: u64, name: &str, access: ComponentAccess) -> Self{
+        Self{
+            object,
+            name: name.to_string(),
+            access,
+        }
+    }
+
+    /// # Object
+    ///
+    /// Returns the hash of the gameobject the component is on.
+    pub fn object(&self) -> u64{
+        self.object
+    }
+
+    /// # Name
+    ///
+    /// Returns the name of the component's type.
+    pub fn name(&self) -> &str{
+        &self.name
+    }
+
+    /// # Get
+    ///
+    /// Reads a single property, or returns `None` if the component doesn't have it.
+    pub fn get(&self, property: &str) -> Option<Value>{
+        self.get_many(&[property]).pop().flatten()
+    }
+
+    /// # Set
+    ///
+    /// Writes a single property, returning false if the component doesn't have it (or it
+    /// couldn't be set to `value`).
+    pub fn set(&self, property: &str, value: Value) -> bool{
+        self.set_many(&[(property, value)]).pop().unwrap_or(false)
+    }
+
+    /// # Get Many
+    ///
+    /// Reads every property in `properties` in one call to the host, returning their values in
+    /// the same order.
+    pub fn get_many(&self, properties: &[&str]) -> Vec<Option<Value>>{
+        let mut batch: Vec<Property> = properties.iter()
+            .map(|name| Property{ name: Str::from(*name), value: PropertyValue::NONE, found: false })
+            .collect();
+
+        (self.access.get_properties)(self.object, Str::from(self.name.as_str()), batch.as_mut_ptr(), batch.len() as u32);
+
+        batch.iter().map(|property| if property.found { property.value.value() } else { None }).collect()
+    }
+
+    /// # Set Many
+    ///
+    /// Writes every property in `properties` in one call to the host, returning whether each
+    /// one was set, in the same order.
+    pub fn set_many(&self, properties: &[(&str, Value)]) -> Vec<bool>{
+        let mut batch: Vec<Property> = properties.iter()
+            .map(|(name, value)| Property{ name: Str::from(*name), value: PropertyValue::from(*value), found: false })
+            .collect();
+
+        (self.access.set_properties)(self.object, Str::from(self.name.as_str()), batch.as_mut_ptr(), batch.len() as u32);
+
+        batch.iter().map(|property| property.found).collect()
+    }
+
+    /// # Invoke
+    ///
+    /// Calls a method on the component, such as `AddForce`. Returns false if there is no
+    /// method with this name that takes these arguments.
+    pub fn invoke(&self, method: &str, arguments: &[Value]) -> bool{
+        let arguments: Vec<PropertyValue> = arguments.iter().map(|argument| PropertyValue::from(*argument)).collect();
+
+        (self.access.invoke)(self.object, Str::from(self.name.as_str()), Str::from(method), arguments.as_ptr(), arguments.len() as u32)
+    }
+}
+
+/// # Component
+///
+/// A component type runity models, returned by `GameObject::get_component`.
+pub trait Component: Sized{
+    /// The name of the component's type in unity.
+    const NAME: &'static str;
+
+    /// Wraps a component of this type.
+    fn from_ref(component: ComponentRef) -> Self;
+}
+
+// Implements `Component` for a typed wrapper around a `ComponentRef`, and lets it be used as one
+macro_rules! component{
+    ($name:ident) => {
+        impl Component for $name{
+            const NAME: &'static str = stringify!($name);
+
+            fn from_ref(component: ComponentRef) -> Self{
+                Self(component)
+            }
+        }
+
+        impl std::ops::Deref for $name{
+            type Target = ComponentRef;
+
+            fn deref(&self) -> &ComponentRef{
+                &self.0
+            }
+        }
+    };
+}
+
+/// # Force Mode
+///
+/// How `Rigidbody::add_force` applies a force, like unity's `ForceMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForceMode{
+    /// A continuous force, using the mass.
+    Force = 0,
+    /// An instant push, using the mass.
+    Impulse = 1,
+    /// An instant change in velocity, ignoring the mass.
+    VelocityChange = 2,
+    /// A continuous acceleration, ignoring the mass.
+    Acceleration = 5,
+}
+
+/// # Rigidbody
+///
+/// A gameobject's `Rigidbody`. Reads return zero (or false) if the host couldn't read the property,
+/// and writes return false if it couldn't be written.
+#[derive(Clone)]
+pub struct Rigidbody(ComponentRef);
+
+component!(Rigidbody);
+
+impl Rigidbody{
+    /// # Velocity
+    ///
+    /// Returns the velocity, in world space.
+    pub fn velocity(&self) -> Vector3{
+        self.get("velocity").and_then(Value::as_vector3).unwrap_or_else(Vector3::zero)
+    }
+
+    /// # Set Velocity
+    ///
+    /// Sets the velocity, in world space.
+    pub fn set_velocity(&self, velocity: Vector3) -> bool{
+        self.set("velocity", Value::Vector3(velocity))
+    }
+
+    /// # Angular Velocity
+    ///
+    /// Returns the angular velocity, in radians per second.
+    pub fn angular_velocity(&self) -> Vector3{
+        self.get("angularVelocity").and_then(Value::as_vector3).unwrap_or_else(Vector3::zero)
+    }
+
+    /// # Set Angular Velocity
+    ///
+    /// Sets the angular velocity, in radians per second.
+    pub fn set_angular_velocity(&self, angular_velocity: Vector3) -> bool{
+        self.set("angularVelocity", Value::Vector3(angular_velocity))
+    }
+
+    /// # Mass
+    ///
+    /// Returns the mass.
+    pub fn mass(&self) -> f32{
+        self.get("mass").and_then(Value::as_float).unwrap_or(0.0)
+    }
+
+    /// # Set Mass
+    ///
+    /// Sets the mass.
+    pub fn set_mass(&self, mass: f32) -> bool{
+        self.set("mass", Value::Float(mass))
+    }
+
+    /// # Is Kinematic
+    ///
+    /// Returns whether physics leaves the rigidbody alone.
+    pub fn is_kinematic(&self) -> bool{
+        self.get("isKinematic").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    /// # Set Is Kinematic
+    ///
+    /// Sets whether physics leaves the rigidbody alone.
+    pub fn set_is_kinematic(&self, is_kinematic: bool) -> bool{
+        self.set("isKinematic", Value::Bool(is_kinematic))
+    }
+
+    /// # Add Force
+    ///
+    /// Adds a force to the rigidbody, in world space.
+    pub fn add_force(&self, force: Vector3, mode: ForceMode){
+        self.invoke("AddForce", &[Value::Vector3(force), Value::Int(mode as i32)]);
+    }
+}
+
+/// # Renderer
+///
+/// A gameobject's `Renderer`. Reads return false (or black) if the host couldn't read the property,
+/// and writes return false if it couldn't be written.
+#[derive(Clone)]
+pub struct Renderer(ComponentRef);
+
+component!(Renderer);
+
+impl Renderer{
+    /// # Enabled
+    ///
+    /// Returns whether the renderer is drawn.
+    pub fn enabled(&self) -> bool{
+        self.get("enabled").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    /// # Set Enabled
+    ///
+    /// Sets whether the renderer is drawn.
+    pub fn set_enabled(&self, enabled: bool) -> bool{
+        self.set("enabled", Value::Bool(enabled))
+    }
+
+    /// # Color
+    ///
+    /// Returns the colour of the renderer's material.
+    pub fn color(&self) -> Color{
+        self.get("material.color").and_then(Value::as_color).unwrap_or_else(Color::black)
+    }
+
+    /// # Set Color
+    ///
+    /// Sets the colour of the renderer's material.
+    pub fn set_color(&self, color: Color) -> bool{
+        self.set("material.color", Value::Color(color))
+    }
+}
+
+/// # Light
+///
+/// A gameobject's `Light`. Reads return zero (or black) if the host couldn't read the property,
+/// and writes return false if it couldn't be written.
+#[derive(Clone)]
+pub struct Light(ComponentRef);
+
+component!(Light);
+
+impl Light{
+    /// # Color
+    ///
+    /// Returns the colour of the light.
+    pub fn color(&self) -> Color{
+        self.get("color").and_then(Value::as_color).unwrap_or_else(Color::black)
+    }
+
+    /// # Set Color
+    ///
+    /// Sets the colour of the light.
+    pub fn set_color(&self, color: Color) -> bool{
+        self.set("color", Value::Color(color))
+    }
+
+    /// # Intensity
+    ///
+    /// Returns the brightness of the light.
+    pub fn intensity(&self) -> f32{
+        self.get("intensity").and_then(Value::as_float).unwrap_or(0.0)
+    }
+
+    /// # Set Intensity
+    ///
+    /// Sets the brightness of the light.
+    pub fn set_intensity(&self, intensity: f32) -> bool{
+        self.set("intensity", Value::Float(intensity))
+    }
+
+    /// # Range
+    ///
+    /// Returns how far the light reaches.
+    pub fn range(&self) -> f32{
+        self.get("range").and_then(Value::as_float).unwrap_or(0.0)
+    }
+
+    /// # Set Range
+    ///
+    /// Sets how far the light reaches.
+    pub fn set_range(&self, range: f32) -> bool{
+        self.set("range", Value::Float(range))
+    }
+}
+
+/// # Collider
+///
+/// A gameobject's `Collider`. Reads return false if the host couldn't read the property,
+/// and writes return false if it couldn't be written.
+#[derive(Clone)]
+pub struct Collider(ComponentRef);
+
+component!(Collider);
+
+impl Collider{
+    /// # Enabled
+    ///
+    /// Returns whether the collider collides.
+    pub fn enabled(&self) -> bool{
+        self.get("enabled").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    /// # Set Enabled
+    ///
+    /// Sets whether the collider collides.
+    pub fn set_enabled(&self, enabled: bool) -> bool{
+        self.set("enabled", Value::Bool(enabled))
+    }
+
+    /// # Is Trigger
+    ///
+    /// Returns whether the collider is a trigger, which sends trigger events instead of colliding.
+    pub fn is_trigger(&self) -> bool{
+        self.get("isTrigger").and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    /// # Set Is Trigger
+    ///
+    /// Sets whether the collider is a trigger.
+    pub fn set_is_trigger(&self, is_trigger: bool) -> bool{
+        self.set("isTrigger", Value::Bool(is_trigger))
+    }
+}
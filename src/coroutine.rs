@@ -0,0 +1,383 @@
+//! Coroutines - async blocks run alongside a script, like unity's `StartCoroutine`.
+//!
+//! A coroutine is started with `DataStruct::start_coroutine`, and polled once at the end of
+//! every `update` of its script (and once at the end of every `fixed_update`, for
+//! `wait_fixed_update`). Between polls it waits on the awaitables in this module, which only
+//! ever look at the script's `Time` - so under a simulated clock (such as `runity_host`'s),
+//! coroutines resume on exactly the same frames every run.
+//!
+//! Coroutines only run while their script does. They are stopped when the script is destroyed,
+//! and aren't carried over a hot reload.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use crate::{DataStruct, Instance, LocalData};
+
+/// # Coroutine Handle
+///
+/// Returned by `DataStruct::start_coroutine`, to cancel the coroutine or check whether it
+/// has finished. Handles can be cloned, and kept anywhere (including in other coroutines).
+#[derive(Clone)]
+pub struct CoroutineHandle{
+    state: Arc<CoroutineState>,
+}
+
+struct CoroutineState{
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl CoroutineHandle{
+    fn new() -> Self{
+        Self{
+            state: Arc::new(CoroutineState{
+                cancelled: AtomicBool::new(false),
+                finished: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub(crate) fn cancelled() -> Self{
+        let handle = Self::new();
+        handle.cancel();
+        handle
+    }
+
+    /// # Cancel
+    ///
+    /// Stops the coroutine. It is never polled again, and is dropped at the next poll.
+    pub fn cancel(&self){
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// # Is Cancelled
+    ///
+    /// Returns whether the coroutine was cancelled (or stopped with its script) before it finished.
+    pub fn is_cancelled(&self) -> bool{
+        self.state.cancelled.load(Ordering::Relaxed) && !self.is_finished()
+    }
+
+    /// # Is Finished
+    ///
+    /// Returns whether the coroutine ran to completion.
+    pub fn is_finished(&self) -> bool{
+        self.state.finished.load(Ordering::Relaxed)
+    }
+
+    /// # Is Running
+    ///
+    /// Returns whether the coroutine is still waiting to be resumed.
+    pub fn is_running(&self) -> bool{
+        !self.state.cancelled.load(Ordering::Relaxed) && !self.is_finished()
+    }
+}
+
+// A started coroutine, and the state its handles share
+struct Task{
+    future: Pin<Box<dyn Future<Output = ()> + Send>>,
+    handle: CoroutineHandle,
+}
+
+// When coroutines are being polled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase{
+    Update,
+    FixedUpdate,
+}
+
+// What the awaitables are checked against, while coroutines are polled
+#[derive(Clone, Copy)]
+struct Tick{
+    phase: Phase,
+    frame: u64,
+    fixed_step: u64,
+    time: f32,
+    real_time: f32,
+}
+
+thread_local!{
+    static TICK: Cell<Option<Tick>> = const{ Cell::new(None) };
+    // The data of the script whose coroutines are being polled, lent through `with_data`
+    static DATA: Cell<*mut DataStruct> = const{ Cell::new(std::ptr::null_mut()) };
+}
+
+// The coroutines of a single instance
+#[derive(Default)]
+pub(crate) struct Coroutines{
+    tasks: Vec<Task>,
+    frame: u64,
+    fixed_step: u64,
+    // Set by `stop_all`, so coroutines taken out to be polled are stopped too
+    stopped: bool,
+}
+
+impl Coroutines{
+    pub(crate) fn start(&mut self, future: Pin<Box<dyn Future<Output = ()> + Send>>) -> CoroutineHandle{
+        let handle = CoroutineHandle::new();
+        self.tasks.push(Task{ future, handle: handle.clone() });
+        handle
+    }
+
+    pub(crate) fn stop_all(&mut self){
+        for task in self.tasks.drain(..){
+            task.handle.cancel();
+        }
+        self.stopped = true;
+    }
+
+    /// Polls every running coroutine of the instance once, for `phase`.
+    ///
+    /// The coroutines are taken out of the instance while they are polled, as they may start
+    /// (or stop) others.
+    pub(crate) fn poll(instance: Instance, phase: Phase, data: &mut DataStruct){
+        let mut coroutines = LocalData::take_coroutines(instance);
+        if coroutines.tasks.is_empty(){
+            LocalData::restore_coroutines(instance, coroutines);
+            return;
+        }
+
+        match phase{
+            Phase::Update => coroutines.frame += 1,
+            Phase::FixedUpdate => coroutines.fixed_step += 1,
+        }
+
+        let tick = Tick{
+            phase,
+            frame: coroutines.frame,
+            fixed_step: coroutines.fixed_step,
+            time: data.time.time,
+            real_time: data.time.real_time_since_startup,
+        };
+
+        TICK.with(|current| current.set(Some(tick)));
+        DATA.with(|current| current.set(data as *mut DataStruct));
+
+        // Cleared even if a coroutine panics, so nothing is lent past the poll
+        struct Lent;
+        impl Drop for Lent{
+            fn drop(&mut self){
+                TICK.with(|current| current.set(None));
+                DATA.with(|current| current.set(std::ptr::null_mut()));
+            }
+        }
+        let lent = Lent;
+
+        let waker = noop_waker();
+        let mut context = Context::from_waker(&waker);
+        coroutines.tasks.retain_mut(|task| {
+            if task.handle.state.cancelled.load(Ordering::Relaxed){
+                return false;
+            }
+
+            match task.future.as_mut().poll(&mut context){
+                Poll::Ready(()) => {
+                    task.handle.state.finished.store(true, Ordering::Relaxed);
+                    false
+                }
+                Poll::Pending => true,
+            }
+        });
+
+        drop(lent);
+        LocalData::restore_coroutines(instance, coroutines);
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool{
+        self.stopped
+    }
+
+    // Puts polled coroutines back, alongside any started while they were being polled
+    pub(crate) fn merge(&mut self, mut polled: Coroutines){
+        polled.tasks.append(&mut self.tasks);
+        polled.stopped = false;
+        *self = polled;
+    }
+}
+
+// Coroutines are polled every update whether or not they were woken, so nothing needs waking
+fn noop_waker() -> Waker{
+    fn clone(_: *const ()) -> RawWaker{
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()){}
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    unsafe{ Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
+
+fn tick() -> Tick{
+    TICK.with(|current| current.get()).expect("runity awaitables can only be awaited inside a coroutine")
+}
+
+/// # With Data
+///
+/// Runs `f` with the `DataStruct` of the script the coroutine belongs to, to move its
+/// gameobject, log, publish messages and so on.
+///
+/// # Panics
+///
+/// Panics if called outside a coroutine, or from inside another `with_data`.
+pub fn with_data<R>(f: impl FnOnce(&mut DataStruct) -> R) -> R{
+    let data = DATA.with(|current| current.replace(std::ptr::null_mut()));
+    assert!(!data.is_null(), "with_data can only be called inside a coroutine, and not from inside another with_data");
+
+    // Lent back even if `f` panics
+    struct Restore(*mut DataStruct);
+    impl Drop for Restore{
+        fn drop(&mut self){
+            DATA.with(|current| current.set(self.0));
+        }
+    }
+    let _restore = Restore(data);
+
+    // The data is borrowed for the whole poll, and only handed to one `f` at a time
+    f(unsafe{ &mut *data })
+}
+
+/// # Wait Seconds
+///
+/// Waits for `seconds` of scaled time (`Time.time`), like unity's `WaitForSeconds`. Slowed
+/// down (or paused) by `time_scale`.
+pub fn wait_seconds(seconds: f32) -> WaitSeconds{
+    WaitSeconds{ seconds, realtime: false, until: None }
+}
+
+/// # Wait Seconds Realtime
+///
+/// Waits for `seconds` of unscaled time (`Time.real_time_since_startup`), like unity's
+/// `WaitForSecondsRealtime`. Ignores `time_scale`.
+pub fn wait_seconds_realtime(seconds: f32) -> WaitSeconds{
+    WaitSeconds{ seconds, realtime: true, until: None }
+}
+
+/// # Wait Frames
+///
+/// Waits for `frames` updates. `wait_frames(1)` resumes in the next frame, like yielding
+/// `null` in unity.
+pub fn wait_frames(frames: u64) -> WaitFrames{
+    WaitFrames{ frames, until: None }
+}
+
+/// # Wait Until
+///
+/// Waits until `predicate` returns true, like unity's `WaitUntil`. It is checked once every
+/// update, starting with the one the coroutine reaches it in.
+pub fn wait_until<F: FnMut() -> bool>(predicate: F) -> WaitUntil<F>{
+    WaitUntil{ predicate }
+}
+
+/// # Wait Fixed Update
+///
+/// Waits until after the next `fixed_update`, like unity's `WaitForFixedUpdate`. Only scripts
+/// with `#[runity::script(fixed_update)]` have fixed updates to wait for.
+pub fn wait_fixed_update() -> WaitFixedUpdate{
+    WaitFixedUpdate{ after: None }
+}
+
+/// # Wait Seconds
+///
+/// Returned by `wait_seconds` and `wait_seconds_realtime`.
+pub struct WaitSeconds{
+    seconds: f32,
+    realtime: bool,
+    until: Option<f32>,
+}
+
+impl Future for WaitSeconds{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _context: &mut Context<'_>) -> Poll<()>{
+        let tick = tick();
+        if tick.phase != Phase::Update{
+            return Poll::Pending;
+        }
+
+        let now = if self.realtime{ tick.real_time }else{ tick.time };
+        let seconds = self.seconds;
+        let until = *self.until.get_or_insert(now + seconds);
+        if now >= until{
+            Poll::Ready(())
+        }else{
+            Poll::Pending
+        }
+    }
+}
+
+/// # Wait Frames
+///
+/// Returned by `wait_frames`.
+pub struct WaitFrames{
+    frames: u64,
+    until: Option<u64>,
+}
+
+impl Future for WaitFrames{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _context: &mut Context<'_>) -> Poll<()>{
+        let tick = tick();
+        if tick.phase != Phase::Update{
+            return Poll::Pending;
+        }
+
+        let frames = self.frames;
+        let until = *self.until.get_or_insert(tick.frame + frames);
+        if tick.frame >= until{
+            Poll::Ready(())
+        }else{
+            Poll::Pending
+        }
+    }
+}
+
+/// # Wait Until
+///
+/// Returned by `wait_until`.
+pub struct WaitUntil<F>{
+    predicate: F,
+}
+
+// The predicate is never pinned
+impl<F> Unpin for WaitUntil<F>{}
+
+impl<F: FnMut() -> bool> Future for WaitUntil<F>{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _context: &mut Context<'_>) -> Poll<()>{
+        if tick().phase != Phase::Update{
+            return Poll::Pending;
+        }
+
+        if (self.predicate)(){
+            Poll::Ready(())
+        }else{
+            Poll::Pending
+        }
+    }
+}
+
+/// # Wait Fixed Update
+///
+/// Returned by `wait_fixed_update`.
+pub struct WaitFixedUpdate{
+    after: Option<u64>,
+}
+
+impl Future for WaitFixedUpdate{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _context: &mut Context<'_>) -> Poll<()>{
+        let tick = tick();
+        let after = *self.after.get_or_insert(tick.fixed_step);
+        if tick.phase == Phase::FixedUpdate && tick.fixed_step > after{
+            Poll::Ready(())
+        }else{
+            Poll::Pending
+        }
+    }
+}
@@ -1,53 +1,739 @@
-use crate::{gameobject::GameObjectChanges, Debug, GameObject, Time};
+use crate::interop::{reflect, Kind, Reflect};
+use crate::coroutine::Coroutines;
+use crate::message::Inbox;
+use crate::CoroutineHandle;
+use crate::{gameobject::GameObjectChanges, panic, Command, CommandFailure, CommandKind, CommandStatus, Debug, Draw, Drawing, Event, GameObject, Handle, Input, Message, MessageKind, Physics, Prefab, ReceivedMessage, Recipients, Time, Transform};
 
-use std::sync::Mutex;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use lazy_static::lazy_static;
 
 /// # DataStruct
 ///
 /// This struct stores all the data we need to run our game.
-/// 
-/// It contains structs that point to timing and debugging information.
+///
+/// It contains the transform and gameobject the script is attached to, structs that
+/// point to timing, input and debugging information, as well as the instance of the script
+/// this data belongs to.
+///
+/// It owns nothing, so unity is free to keep copies of it between calls.
 #[repr(C)]
+#[derive(Clone)]
 pub struct DataStruct{
+    pub transform: Transform,
+    pub game_object: GameObject,
     pub time: Time,
+    pub input: Input,
     pub debug: Debug,
+    pub physics: Physics,
+    /// The instance this data belongs to. This is allocated in `awake`, and must be
+    /// passed back unchanged by unity on every later call.
+    pub instance: Instance,
+}
+
+reflect!(DataStruct{ transform, game_object, time, input, debug, physics, instance });
+
+impl DataStruct{
+    /// # Queue Change
+    ///
+    /// Queues a change to a gameobject, to be sent to unity in the next `ChangeBuffer`
+    /// acquired for this instance.
+    pub fn queue_change(&self, change: GameObjectChanges){
+        LocalData::with(self.instance, |local_data| local_data.changes.push(change));
+    }
+
+    /// # Spawn
+    ///
+    /// Queues `prefab` to be spawned at `transform` (in world space), like `Object.Instantiate`.
+    ///
+    /// The returned handle can be used in other commands straight away, and resolves to the new
+    /// gameobject once the host has spawned it (see `resolve`).
+    pub fn spawn(&self, prefab: Prefab, transform: Transform) -> Handle{
+        LocalData::with(self.instance, |local_data| {
+            local_data.next_handle += 1;
+            let handle = Handle::pending(local_data.next_handle);
+            local_data.commands.push(Command::spawn(handle, prefab, transform));
+            handle
+        }).unwrap_or(Handle::pending(0))
+    }
+
+    /// # Destroy
+    ///
+    /// Queues a gameobject (and its children) to be destroyed, like `Object.Destroy`.
+    pub fn destroy(&self, handle: Handle){
+        self.queue_command(|local_data| Command::destroy(local_data.resolved(handle)));
+    }
+
+    /// # Set Active
+    ///
+    /// Queues a gameobject to be activated or deactivated, like `GameObject.SetActive`.
+    pub fn set_active(&self, handle: Handle, active: bool){
+        self.queue_command(|local_data| Command::set_active(local_data.resolved(handle), active));
+    }
+
+    /// # Set Parent
+    ///
+    /// Queues a gameobject to be moved under `parent`, or to the root of the scene with `None`,
+    /// like `Transform.SetParent`.
+    pub fn set_parent(&self, handle: Handle, parent: Option<Handle>, world_position_stays: bool){
+        self.queue_command(|local_data| {
+            let parent = parent.map(|parent| local_data.resolved(parent));
+            Command::set_parent(local_data.resolved(handle), parent, world_position_stays)
+        });
+    }
+
+    /// # Resolve
+    ///
+    /// Returns the hash of the gameobject behind `handle`, or `None` if it is still waiting to
+    /// be spawned (or couldn't be).
+    ///
+    /// Only the last 1024 gameobjects spawned by the instance are remembered, so older handles
+    /// stop resolving - keep the hash (or `Handle::of` it) instead.
+    pub fn resolve(&self, handle: Handle) -> Option<u64>{
+        LocalData::with(self.instance, |local_data| local_data.resolved(handle))
+            .filter(|handle| !handle.is_pending())
+            .map(|handle| handle.id())
+    }
+
+    /// # Take Command Failures
+    ///
+    /// Returns every command the host couldn't carry out since this was last called.
+    pub fn take_command_failures(&self) -> Vec<CommandFailure>{
+        LocalData::with(self.instance, |local_data| std::mem::take(&mut local_data.failures)).unwrap_or_default()
+    }
+
+    /// # Subscribe
+    ///
+    /// Subscribes to messages named `name`, which are passed to `Script::on_message` from the
+    /// next frame on. Subscriptions are kept by the host, so they last through a hot reload.
+    pub fn subscribe(&self, name: &str){
+        self.queue_message(|_| Message::subscription(MessageKind::Subscribe, name));
+    }
+
+    /// # Subscribe To
+    ///
+    /// Subscribes to the event `E`, like `subscribe(E::NAME)`.
+    pub fn subscribe_to<E: Event>(&self){
+        self.subscribe(E::NAME);
+    }
+
+    /// # Unsubscribe
+    ///
+    /// Stops receiving messages named `name`.
+    pub fn unsubscribe(&self, name: &str){
+        self.queue_message(|_| Message::subscription(MessageKind::Unsubscribe, name));
+    }
+
+    /// # Publish
+    ///
+    /// Queues a message named `name` to be sent to every subscribed script in `to`. It arrives
+    /// in their `Script::on_message` at the start of their next `update` - in the next frame, at
+    /// the earliest.
+    pub fn publish(&self, to: Recipients, name: &str, payload: &[u8]){
+        self.queue_message(|local_data| {
+            let to = match to{
+                Recipients::Object(handle) => Recipients::Object(local_data.resolved(handle)),
+                to => to,
+            };
+            Message::publish(to, name, payload)
+        });
+    }
+
+    /// # Send
+    ///
+    /// Publishes the event `E`, like `publish(to, E::NAME, &event.encode())`.
+    pub fn send<E: Event>(&self, to: Recipients, event: &E){
+        self.publish(to, E::NAME, &event.encode());
+    }
+
+    /// # Start Coroutine
+    ///
+    /// Starts a coroutine, which is polled at the end of every `update` until it finishes (see
+    /// `wait_seconds`, `wait_frames` and the other awaitables). The first poll is at the end of
+    /// the current `update`, or the next one if started from anywhere else.
+    ///
+    /// The coroutine reaches the script's data through `with_data`. It is stopped when the
+    /// script is destroyed, or when the returned handle is cancelled.
+    ///
+    /// ```ignore
+    /// data.start_coroutine(async{
+    ///     wait_seconds(2.0).await;
+    ///     with_data(|data| data.debug.log("Two seconds later"));
+    /// });
+    /// ```
+    pub fn start_coroutine<F: Future<Output = ()> + Send + 'static>(&self, coroutine: F) -> CoroutineHandle{
+        let mut coroutine = Some(Box::pin(coroutine) as Pin<Box<dyn Future<Output = ()> + Send>>);
+        LocalData::with(self.instance, |local_data| {
+            local_data.coroutines.start(coroutine.take().unwrap())
+        }).unwrap_or_else(|| {
+            // There is no instance to run it on
+            let handle = CoroutineHandle::cancelled();
+            drop(coroutine);
+            handle
+        })
+    }
+
+    /// # Stop All Coroutines
+    ///
+    /// Stops every coroutine the script has started, like unity's `StopAllCoroutines`.
+    pub fn stop_all_coroutines(&self){
+        let stopped = LocalData::with(self.instance, |local_data| {
+            let mut stopped = Coroutines::default();
+            std::mem::swap(&mut stopped, &mut local_data.coroutines);
+            local_data.coroutines.stop_all();
+            stopped
+        });
+
+        // Dropped outside the lock, as coroutines may hold anything
+        if let Some(mut stopped) = stopped{
+            stopped.stop_all();
+        }
+    }
+
+    /// # Draw
+    ///
+    /// Returns a `Draw`, to queue debug drawings (lines, wire shapes and labels) for the host
+    /// to show, like `Debug.DrawLine` and gizmos.
+    pub fn draw(&self) -> Draw<'_>{
+        Draw::new(self)
+    }
+
+    fn queue_message(&self, message: impl FnOnce(&LocalData) -> Message){
+        LocalData::with(self.instance, |local_data| {
+            let message = message(local_data);
+            local_data.messages.push(message);
+        });
+    }
+
+    fn queue_command(&self, command: impl FnOnce(&LocalData) -> Command){
+        LocalData::with(self.instance, |local_data| {
+            let command = command(local_data);
+            local_data.commands.push(command);
+        });
+    }
+}
+
+/// # Instance
+///
+/// A handle to a single running instance of a script. Every gameobject running
+/// the same library gets its own instance, so their data doesn't overlap.
+///
+/// An instance of `0` is not a valid instance (`Instance::NONE`).
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Instance(u64);
+
+impl Instance{
+    /// An invalid instance, used before `awake` has allocated one.
+    pub const NONE: Instance = Instance(0);
+
+    /// # Is None
+    ///
+    /// Returns true if this isn't a valid instance.
+    pub fn is_none(&self) -> bool{
+        *self == Self::NONE
+    }
+
+    /// # ID
+    ///
+    /// Returns the raw id of this instance.
+    pub fn id(&self) -> u64{
+        self.0
+    }
+}
+
+impl Reflect for Instance{
+    fn kind() -> Kind{
+        Kind::U64
+    }
 }
 
 // A local store of all data present in unity (that we can interact with)
-// 
+//
 // We then get, and send any state changes to the unity engine. We prefer
 // rust changes over unity changes, as rust is more likely to be the source
 // of truth.
+//
+// There is one `LocalData` per instance.
 pub struct LocalData{
+    #[allow(dead_code)]
     gameobjects: Vec<GameObject>, // All gameobjects in the scene
     changes: Vec<GameObjectChanges>, // All changes to gameobjects - to be sent to unity
-    data: Option<DataStruct>, // A reference to all data in the game like time and debug information
+    sent: Vec<GameObjectChanges>, // The last batch of changes, lent to unity until it is released
+    commands: Vec<Command>, // Commands queued alongside the changes
+    sent_commands: Vec<Command>, // The commands in the last batch, which unity writes the results into
+    next_handle: u64, // The id of the last handle given to a spawned gameobject
+    spawned: BTreeMap<Handle, Handle>, // The gameobjects spawned so far, by the handle they were spawned with
+    failures: Vec<CommandFailure>, // Commands unity couldn't carry out, until the script takes them
+    messages: Vec<Message>, // Messages and subscriptions queued alongside the changes
+    sent_messages: Vec<Message>, // The messages in the last batch
+    drawings: Vec<Drawing>, // Debug drawings queued alongside the changes
+    sent_drawings: Vec<Drawing>, // The drawings in the last batch
+    inbox: Vec<Inbox>, // Messages posted by unity, until the next `update`
+    coroutines: Coroutines, // Polled at the end of every update
+    lent: bool, // Whether `sent` is currently lent to unity
+    debug: Option<Debug>, // The debug functions of the last entry point run on the instance
+    faulted: bool, // Set once the instance has panicked - it won't be run again
+}
+
+// How many spawned gameobjects each instance remembers, so a script spawning every frame
+// doesn't grow `spawned` forever. The oldest are forgotten first.
+const REMEMBERED_SPAWNS: usize = 1024;
+
+// Every live instance, and the id the next allocated instance will use
+struct Instances{
+    next: u64,
+    local_data: BTreeMap<Instance, LocalData>,
 }
 
 lazy_static!{
-    pub static ref LOCAL_DATA: Mutex<LocalData> = Mutex::new(LocalData{
-        gameobjects: Vec::new(),
-        changes: Vec::new(),
-        data: None,
+    static ref INSTANCES: Mutex<Instances> = Mutex::new(Instances{
+        next: 1,
+        local_data: BTreeMap::new(),
     });
 }
 
-// Safe extern wrappers to set the data
+// A panic while the lock is held is contained at the entry point, so a poisoned
+// lock is still safe to use.
+fn instances() -> MutexGuard<'static, Instances>{
+    INSTANCES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl LocalData{
+    /// # Allocate
+    ///
+    /// Allocates a new instance, with its own empty `LocalData`.
+    pub fn allocate() -> Instance{
+        let mut instances = instances();
+        let instance = Instance(instances.next);
+        instances.next += 1;
+
+        instances.local_data.insert(instance, LocalData{
+            gameobjects: Vec::new(),
+            changes: Vec::new(),
+            sent: Vec::new(),
+            commands: Vec::new(),
+            sent_commands: Vec::new(),
+            next_handle: 0,
+            spawned: BTreeMap::new(),
+            failures: Vec::new(),
+            messages: Vec::new(),
+            sent_messages: Vec::new(),
+            drawings: Vec::new(),
+            sent_drawings: Vec::new(),
+            inbox: Vec::new(),
+            coroutines: Coroutines::default(),
+            lent: false,
+            debug: None,
+            faulted: false,
+        });
+
+        instance
+    }
+
+    /// # Release
+    ///
+    /// Releases an instance, dropping all of its data. Any later calls using this
+    /// instance will be ignored.
+    pub fn release(instance: Instance){
+        instances().local_data.remove(&instance);
+    }
+
+    /// # Fault
+    ///
+    /// Marks an instance as faulted, after it has panicked. A faulted instance is never run again.
+    pub fn fault(instance: Instance){
+        LocalData::with(instance, |local_data| local_data.faulted = true);
+    }
+
+    /// # Is Faulted
+    ///
+    /// Returns true if the instance has been marked as faulted.
+    pub fn is_faulted(instance: Instance) -> bool{
+        LocalData::with(instance, |local_data| local_data.faulted).unwrap_or(false)
+    }
+
+    // The debug functions of the last entry point run on an instance, used to report panics in
+    // entry points that aren't passed a `DataStruct`
+    pub(crate) fn debug(instance: Instance) -> Option<Debug>{
+        LocalData::with(instance, |local_data| local_data.debug).flatten()
+    }
+
+    pub(crate) fn set_debug(instance: Instance, debug: Debug){
+        LocalData::with(instance, |local_data| local_data.debug = Some(debug));
+    }
+
+    // The handle of a spawned gameobject, once it has been spawned. Other handles are returned as-is.
+    fn resolved(&self, handle: Handle) -> Handle{
+        self.spawned.get(&handle).copied().unwrap_or(handle)
+    }
+
+    pub(crate) fn queue_drawing(&mut self, drawing: Drawing){
+        self.drawings.push(drawing);
+    }
+
+    // The messages posted to an instance since its last `update`
+    pub(crate) fn take_inbox(instance: Instance) -> Vec<Inbox>{
+        LocalData::with(instance, |local_data| std::mem::take(&mut local_data.inbox)).unwrap_or_default()
+    }
+
+    // The coroutines of an instance, taken out to be polled (or stopped)
+    pub(crate) fn take_coroutines(instance: Instance) -> Coroutines{
+        LocalData::with(instance, |local_data| std::mem::take(&mut local_data.coroutines)).unwrap_or_default()
+    }
+
+    pub(crate) fn restore_coroutines(instance: Instance, mut coroutines: Coroutines){
+        // Coroutines stopped while they were taken out (or whose instance is gone) are dropped
+        // outside the lock, as they may hold anything
+        let stopped = LocalData::with(instance, |local_data| local_data.coroutines.is_stopped()).unwrap_or(true);
+        if stopped{
+            coroutines.stop_all();
+        }
+
+        LocalData::with(instance, |local_data| local_data.coroutines.merge(coroutines));
+    }
+
+    // Reads back the results unity wrote into the last batch of commands
+    fn complete_commands(&mut self){
+        for command in self.sent_commands.drain(..){
+            match command.status(){
+                CommandStatus::Done if command.kind() == CommandKind::Spawn => {
+                    self.spawned.insert(command.target(), Handle::of(command.object()));
+
+                    // Pending handles count up, so the first is the oldest
+                    if self.spawned.len() > REMEMBERED_SPAWNS{
+                        self.spawned.pop_first();
+                    }
+                }
+                CommandStatus::Done => (),
+                status => self.failures.push(CommandFailure{ kind: command.kind(), target: command.target(), status }),
+            }
+        }
+    }
+
+    /// # With
+    ///
+    /// Runs `f` with the `LocalData` of `instance`. Returns `None` if the instance doesn't exist.
+    pub(crate) fn with<R>(instance: Instance, f: impl FnOnce(&mut LocalData) -> R) -> Option<R>{
+        instances().local_data.get_mut(&instance).map(f)
+    }
+}
+
+// Safe extern wrappers to set the data. Only the debug functions are kept (by instance), to
+// report panics in entry points that aren't passed a `DataStruct`
 #[no_mangle]
 pub extern "C" fn set_data(data: DataStruct){
-    let mut local_data = LOCAL_DATA.lock().unwrap();
-    local_data.data = Some(data);
+    let instance = data.instance;
+    let debug = data.debug;
+
+    let stored = panic::catch_panic("set_data", Some(&debug), || LocalData::set_debug(instance, debug));
+
+    if stored.is_none(){
+        LocalData::fault(instance);
+    }
 }
 
 // Data is a one-way street - we only get data from unity
 // However, for changes, we need to send them back to unity
 
-// Send changes to unity - return a reference to the changes
+/// # Change Buffer
+///
+/// A batch of changes lent to the host by `runity_changes_acquire`. `changes` points to
+/// `len` changes, `commands` to `command_count` commands, `messages` to `message_count`
+/// messages and `drawings` to `drawing_count` debug drawings.
+///
+/// The changes (and their tags) are owned by rust. They stay valid until the host calls
+/// `runity_changes_release` for the instance (or destroys it), and must not be freed by the host.
+/// The host carries out the commands in order, writing back the result of each one before
+/// releasing the buffer. Messages are routed after the commands, so they can be published to
+/// gameobjects spawned in the same buffer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ChangeBuffer{
+    pub changes: *const GameObjectChanges,
+    pub len: u32,
+    pub commands: *mut Command,
+    pub command_count: u32,
+    pub messages: *const Message,
+    pub message_count: u32,
+    pub drawings: *const Drawing,
+    pub drawing_count: u32,
+}
+
+reflect!(ChangeBuffer{ changes, len, commands, command_count, messages, message_count, drawings, drawing_count });
+
+impl ChangeBuffer{
+    /// An empty buffer.
+    pub const EMPTY: ChangeBuffer = ChangeBuffer{ changes: std::ptr::null(), len: 0, commands: std::ptr::null_mut(), command_count: 0, messages: std::ptr::null(), message_count: 0, drawings: std::ptr::null(), drawing_count: 0 };
+
+    /// # As Slice
+    ///
+    /// Returns the changes in the buffer.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must have come from `runity_changes_acquire`, and not have been released yet.
+    pub unsafe fn as_slice<'a>(&self) -> &'a [GameObjectChanges]{
+        if self.changes.is_null(){
+            return &[];
+        }
+
+        std::slice::from_raw_parts(self.changes, self.len as usize)
+    }
+
+    /// # Commands
+    ///
+    /// Returns the commands in the buffer, to be carried out and completed.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must have come from `runity_changes_acquire`, and not have been released yet.
+    /// The commands must not be borrowed more than once at a time.
+    pub unsafe fn commands<'a>(&self) -> &'a mut [Command]{
+        if self.commands.is_null(){
+            return &mut [];
+        }
+
+        std::slice::from_raw_parts_mut(self.commands, self.command_count as usize)
+    }
+
+    /// # Messages
+    ///
+    /// Returns the messages (and subscriptions) in the buffer, in the order they were queued.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must have come from `runity_changes_acquire`, and not have been released yet.
+    pub unsafe fn messages<'a>(&self) -> &'a [Message]{
+        if self.messages.is_null(){
+            return &[];
+        }
+
+        std::slice::from_raw_parts(self.messages, self.message_count as usize)
+    }
+
+    /// # Drawings
+    ///
+    /// Returns the debug drawings in the buffer, in the order they were queued.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must have come from `runity_changes_acquire`, and not have been released yet.
+    pub unsafe fn drawings<'a>(&self) -> &'a [Drawing]{
+        if self.drawings.is_null(){
+            return &[];
+        }
+
+        std::slice::from_raw_parts(self.drawings, self.drawing_count as usize)
+    }
+}
+
+// Lends the changes queued since the last batch to unity. Changes are double buffered - the
+// script keeps queueing into one buffer while unity reads the other, so neither has to wait.
+// Acquiring again before releasing returns the same batch.
+#[no_mangle]
+pub extern "C" fn runity_changes_acquire(instance: Instance) -> ChangeBuffer{
+    let debug = LocalData::debug(instance);
+
+    panic::catch_panic("runity_changes_acquire", debug.as_ref(), || {
+        LocalData::with(instance, |local_data| {
+            if !local_data.lent{
+                // `sent` was cleared on release, so this leaves an empty buffer (that keeps
+                // its capacity) to queue into
+                std::mem::swap(&mut local_data.changes, &mut local_data.sent);
+                std::mem::swap(&mut local_data.commands, &mut local_data.sent_commands);
+                std::mem::swap(&mut local_data.messages, &mut local_data.sent_messages);
+                std::mem::swap(&mut local_data.drawings, &mut local_data.sent_drawings);
+                local_data.lent = true;
+            }
+
+            ChangeBuffer{
+                changes: local_data.sent.as_ptr(),
+                len: local_data.sent.len() as u32,
+                commands: local_data.sent_commands.as_mut_ptr(),
+                command_count: local_data.sent_commands.len() as u32,
+                messages: local_data.sent_messages.as_ptr(),
+                message_count: local_data.sent_messages.len() as u32,
+                drawings: local_data.sent_drawings.as_ptr(),
+                drawing_count: local_data.sent_drawings.len() as u32,
+            }
+        }).unwrap_or(ChangeBuffer::EMPTY)
+    }).unwrap_or_else(|| {
+        LocalData::fault(instance);
+        ChangeBuffer::EMPTY
+    })
+}
+
+// Frees the batch lent by `runity_changes_acquire` once unity has applied it, reading back the
+// results of its commands
+#[no_mangle]
+pub extern "C" fn runity_changes_release(instance: Instance){
+    let debug = LocalData::debug(instance);
+
+    let released = panic::catch_panic("runity_changes_release", debug.as_ref(), || {
+        LocalData::with(instance, |local_data| {
+            local_data.sent.clear();
+            local_data.complete_commands();
+            local_data.sent_messages.clear();
+            local_data.sent_drawings.clear();
+            local_data.lent = false;
+        });
+    });
+
+    if released.is_none(){
+        LocalData::fault(instance);
+    }
+}
+
+// Posts messages to an instance, copying them to be passed to `Script::on_message` at the
+// start of its next `update`. Unity calls this for messages routed from other scripts, and for
+// messages published by its own code (UI, for example).
+#[no_mangle]
+pub extern "C" fn runity_post_messages(instance: Instance, messages: *const ReceivedMessage, count: u32){
+    if messages.is_null() || count == 0{
+        return;
+    }
+
+    let debug = LocalData::debug(instance);
+
+    let posted = panic::catch_panic("runity_post_messages", debug.as_ref(), || {
+        let messages = unsafe{ std::slice::from_raw_parts(messages, count as usize) };
+        LocalData::with(instance, |local_data| {
+            local_data.inbox.extend(messages.iter().map(Inbox::copy));
+        });
+    });
+
+    if posted.is_none(){
+        LocalData::fault(instance);
+    }
+}
+
+// Lets unity check whether an instance has panicked, and stopped running
 #[no_mangle]
-pub extern "C" fn send_changes() -> Vec<GameObjectChanges>{
-    let mut local_data = LOCAL_DATA.lock().unwrap();
-    let changes = local_data.changes.clone();
-    local_data.changes.clear();
-    changes
-}
\ No newline at end of file
+pub extern "C" fn runity_is_faulted(instance: Instance) -> bool{
+    LocalData::is_faulted(instance)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::{Quaternion, Vector3};
+
+    // Queues a command and a message naming `target`, the way a script's calls would
+    fn queue(instance: Instance, target: u64){
+        LocalData::with(instance, |local_data| {
+            local_data.commands.push(Command::destroy(Handle::of(target)));
+            local_data.messages.push(Message::publish(Recipients::All, &target.to_string(), &[]));
+        });
+    }
+
+    // The targets of the commands and the names of the messages in a batch
+    fn batch(buffer: &ChangeBuffer) -> (Vec<u64>, Vec<std::string::String>){
+        let commands = unsafe{ buffer.commands() }.iter().map(|command| command.target().id()).collect();
+        let messages = unsafe{ buffer.messages() }.iter().map(|message| message.name().to_string()).collect();
+        (commands, messages)
+    }
+
+    #[test]
+    fn changes_queued_while_lent_go_in_the_next_batch(){
+        let instance = LocalData::allocate();
+
+        queue(instance, 1);
+        let first = runity_changes_acquire(instance);
+        queue(instance, 2);
+        assert_eq!(batch(&first), (vec![1], vec!["1".to_string()]));
+        runity_changes_release(instance);
+
+        let second = runity_changes_acquire(instance);
+        assert_eq!(batch(&second), (vec![2], vec!["2".to_string()]));
+        runity_changes_release(instance);
+
+        let third = runity_changes_acquire(instance);
+        assert_eq!(batch(&third), (vec![], vec![]));
+        runity_changes_release(instance);
+
+        LocalData::release(instance);
+    }
+
+    #[test]
+    fn acquiring_again_returns_the_same_batch(){
+        let instance = LocalData::allocate();
+
+        queue(instance, 1);
+        let first = runity_changes_acquire(instance);
+        queue(instance, 2);
+        let again = runity_changes_acquire(instance);
+
+        assert_eq!(again.commands, first.commands);
+        assert_eq!(again.messages, first.messages);
+        assert_eq!(batch(&again), (vec![1], vec!["1".to_string()]));
+        runity_changes_release(instance);
+
+        // What was queued in between wasn't swapped in, or lost
+        let next = runity_changes_acquire(instance);
+        assert_eq!(batch(&next), (vec![2], vec!["2".to_string()]));
+        runity_changes_release(instance);
+
+        LocalData::release(instance);
+    }
+
+    #[test]
+    fn release_clears_the_lent_batch(){
+        let instance = LocalData::allocate();
+
+        queue(instance, 1);
+        queue(instance, 2);
+        runity_changes_acquire(instance);
+        runity_changes_release(instance);
+
+        let lent = LocalData::with(instance, |local_data| {
+            (local_data.lent, local_data.sent.len(), local_data.sent_commands.len(), local_data.sent_messages.len(), local_data.sent_drawings.len())
+        });
+        assert_eq!(lent, Some((false, 0, 0, 0, 0)));
+
+        // Releasing without a batch lent does nothing
+        runity_changes_release(instance);
+        assert!(!LocalData::is_faulted(instance));
+
+        LocalData::release(instance);
+    }
+
+    #[test]
+    fn only_the_latest_spawns_are_remembered(){
+        let instance = LocalData::allocate();
+        let spawns = REMEMBERED_SPAWNS as u64 + 1;
+
+        LocalData::with(instance, |local_data| {
+            for id in 1..=spawns{
+                local_data.commands.push(Command::spawn(Handle::pending(id), Prefab::Id(0), Transform::new(Vector3::zero(), Quaternion::identity())));
+            }
+        });
+        let buffer = runity_changes_acquire(instance);
+        // Pending handle `n` spawns gameobject `n + 100`
+        for (index, command) in unsafe{ buffer.commands() }.iter_mut().enumerate(){
+            command.complete(CommandStatus::Done, index as u64 + 101);
+        }
+        runity_changes_release(instance);
+
+        let resolved = LocalData::with(instance, |local_data| {
+            (local_data.spawned.len(), local_data.resolved(Handle::pending(1)), local_data.resolved(Handle::pending(2)), local_data.resolved(Handle::pending(spawns)))
+        });
+        assert_eq!(resolved, Some((REMEMBERED_SPAWNS, Handle::pending(1), Handle::of(102), Handle::of(spawns + 100))));
+
+        LocalData::release(instance);
+    }
+
+    #[test]
+    fn released_instances_lend_nothing(){
+        let instance = LocalData::allocate();
+        queue(instance, 1);
+        LocalData::release(instance);
+
+        let buffer = runity_changes_acquire(instance);
+        assert!(buffer.commands.is_null() && buffer.messages.is_null() && buffer.changes.is_null());
+        assert_eq!(batch(&buffer), (vec![], vec![]));
+        runity_changes_release(instance);
+    }
+}
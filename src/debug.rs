@@ -3,56 +3,203 @@
 //! It contains static functions that can be used to print to the console in unity.
 //! 
 //! This is useful for debugging purposes.
+//!
+//! Besides the plain `log` functions, hosts are handed `LogRecord`s - a message along with its
+//! level, the module it was logged from and where in the rust source it was logged - by the
+//! `log!`, `warn!` and `error!` macros, and by anything logged through the `log` or `tracing` crates.
 
-use crate::String;
+use crate::interop::reflect;
+use crate::Str;
 
 /// # Debug
 /// 
 /// Debug is a representation of Debug in unity. This contains
 /// functions that are used to print to the console in unity.
+///
+/// Messages are lent to unity as a `Str`, which is only valid for the length of the call.
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Debug{
     /// # Log
     /// 
     /// Logs a string to the console in unity.
-    log: extern "C" fn(&String),
+    log: extern "C" fn(Str),
 
     /// # Log Warning
     ///
     /// Logs a string to the console in unity as a warning.
-    log_warning: extern "C" fn(&String),
+    log_warning: extern "C" fn(Str),
 
     /// # Log Error
     /// 
     /// Logs a string to the console in unity as an error.
-    log_error: extern "C" fn(&String),
+    log_error: extern "C" fn(Str),
+
+    /// # Log Record
+    ///
+    /// Logs a record to the console in unity, with its level and source location.
+    log_record: extern "C" fn(&LogRecord),
 }
 
+reflect!(Debug{
+    log: fn Log(message),
+    log_warning: fn LogWarning(message),
+    log_error: fn LogError(message),
+    log_record: fn LogRecord(record),
+});
+
 impl Debug{
+    /// # New
+    ///
+    /// Creates a new `Debug` from the host's logging functions.
+    pub fn new(log: extern "C" fn(Str), log_warning: extern "C" fn(Str), log_error: extern "C" fn(Str), log_record: extern "C" fn(&LogRecord)) -> Self{
+        Self{
+            log,
+            log_warning,
+            log_error,
+            log_record,
+        }
+    }
+
     /// # Log
     /// 
     /// Logs a string to the console in unity.
-    pub fn log(&self, message: std::string::String){
-        let message = String::from(message);
-        (self.log)(&message);
-        message.free();
+    pub fn log(&self, message: impl AsRef<str>){
+        (self.log)(Str::from(message.as_ref()));
     }
 
     /// # Log Warning
     /// 
     /// Logs a string to the console in unity as a warning.
-    pub fn log_warning(&self, message: std::string::String){
-        let message = String::from(message);
-        (self.log_warning)(&message);
-        message.free();
+    pub fn log_warning(&self, message: impl AsRef<str>){
+        (self.log_warning)(Str::from(message.as_ref()));
     }
 
     /// # Log Error
     /// 
     /// Logs a string to the console in unity as an error.
-    pub fn log_error(&self, message: std::string::String){
-        let message = String::from(message);
-        (self.log_error)(&message);
-        message.free();
+    pub fn log_error(&self, message: impl AsRef<str>){
+        (self.log_error)(Str::from(message.as_ref()));
+    }
+
+    /// # Record
+    ///
+    /// Logs a record to the console in unity. Most code should use the `log!`, `warn!` and
+    /// `error!` macros instead, which fill in the module and source location.
+    pub fn record(&self, record: &LogRecord){
+        (self.log_record)(record);
+    }
+}
+
+/// # Log Level
+///
+/// The level of a `LogRecord`, from most to least severe. Unity only tells errors, warnings
+/// and everything else apart, so hosts usually log `Info`, `Debug` and `Trace` the same way.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel{
+    Error = 1,
+    Warning = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl From<log::Level> for LogLevel{
+    fn from(level: log::Level) -> Self{
+        match level{
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warning,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl From<LogLevel> for log::Level{
+    fn from(level: LogLevel) -> Self{
+        match level{
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warning => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Trace => log::Level::Trace,
+        }
+    }
+}
+
+/// # Log Record
+///
+/// A message logged with its level, the module (`target`) it was logged from, and the file and
+/// line it was logged on. `file` is relative to the crate it was logged from, as `file!()` is, and
+/// is empty (with a `line` of `0`) when the location isn't known.
+///
+/// Like a `Str`, a record is only lent for the length of the call.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct LogRecord<'a>{
+    message: Str<'a>,
+    target: Str<'a>,
+    file: Str<'a>,
+    line: u32,
+    level: u32,
+}
+
+reflect!(LogRecord<'_>{ message, target, file, line, level });
+
+impl<'a> LogRecord<'a>{
+    /// # New
+    ///
+    /// Creates a new `LogRecord`, borrowing the message, target and file.
+    pub fn new(level: LogLevel, message: &'a str, target: &'a str, file: &'a str, line: u32) -> Self{
+        Self{
+            message: Str::from(message),
+            target: Str::from(target),
+            file: Str::from(file),
+            line,
+            level: level as u32,
+        }
+    }
+
+    /// # Level
+    ///
+    /// Returns the level the record was logged at.
+    pub fn level(&self) -> LogLevel{
+        match self.level{
+            1 => LogLevel::Error,
+            2 => LogLevel::Warning,
+            3 => LogLevel::Info,
+            4 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+
+    /// # Message
+    ///
+    /// Returns the message.
+    pub fn message(&self) -> Str<'a>{
+        self.message
+    }
+
+    /// # Target
+    ///
+    /// Returns the module the record was logged from, such as `my_game::player`.
+    pub fn target(&self) -> Str<'a>{
+        self.target
+    }
+
+    /// # File
+    ///
+    /// Returns the file the record was logged from, or an empty string if it isn't known.
+    pub fn file(&self) -> Str<'a>{
+        self.file
+    }
+
+    /// # Line
+    ///
+    /// Returns the line the record was logged on, or `0` if it isn't known.
+    pub fn line(&self) -> u32{
+        self.line
     }
 }
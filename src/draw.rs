@@ -0,0 +1,343 @@
+//! Debug drawing - lines, rays, arrows, wire shapes and labels, like unity's `Debug.DrawLine`
+//! and gizmos.
+//!
+//! Drawings are queued with `data.draw()`, and lent to the host in the next `ChangeBuffer`
+//! alongside commands and messages. The host shows each one for its duration in seconds, or
+//! for a single frame if the duration is `0` (the default) - so something that should stay on
+//! screen is drawn again every `update`, like it would be with `Debug.DrawLine`.
+//!
+//! Hosts without a way to draw wire shapes (or that only draw lines) can use
+//! `Drawing::segments`, which breaks every shape but labels down into lines.
+
+use std::f32::consts::PI;
+
+use crate::interop::reflect;
+use crate::{Color, DataStruct, LocalData, Quaternion, String, Vector3};
+
+// How many lines a full circle is drawn with
+const CIRCLE_SEGMENTS: usize = 24;
+
+/// # Drawing Kind
+///
+/// What a `Drawing` draws.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawingKind{
+    /// A line from `from` to `to`. Rays are drawn as lines.
+    Line = 0,
+    /// A line from `from` to `to`, with an arrowhead at `to`.
+    Arrow = 1,
+    /// A wire sphere around `from`, with a radius of `radius`.
+    WireSphere = 2,
+    /// A wire box around `from`, `to` in size, rotated by `rotation`.
+    WireBox = 3,
+    /// A wire capsule, made of two spheres of `radius` around `from` and `to` and the lines between them.
+    WireCapsule = 4,
+    /// `text`, written at `from`.
+    Label = 5,
+}
+
+/// # Drawing
+///
+/// A debug drawing queued by a script, lent to the host in a `ChangeBuffer`. Which fields are
+/// used depends on its `kind`.
+///
+/// `duration` is how long it is shown for, in seconds. A drawing with a duration of `0` is
+/// shown for a single frame.
+#[repr(C)]
+pub struct Drawing{
+    kind: u32,
+    from: Vector3,
+    to: Vector3,
+    rotation: Quaternion,
+    radius: f32,
+    color: Color,
+    duration: f32,
+    text: String,
+}
+
+reflect!(Drawing{ kind, from, to, rotation, radius, color, duration, text });
+
+impl Drawing{
+    fn new(kind: DrawingKind, from: Vector3, to: Vector3, style: &Draw) -> Self{
+        Self{
+            kind: kind as u32,
+            from,
+            to,
+            rotation: Quaternion::identity(),
+            radius: 0.0,
+            color: style.color,
+            duration: style.duration,
+            text: String::new(),
+        }
+    }
+
+    /// # Kind
+    ///
+    /// Returns what this drawing draws.
+    pub fn kind(&self) -> DrawingKind{
+        match self.kind{
+            0 => DrawingKind::Line,
+            1 => DrawingKind::Arrow,
+            2 => DrawingKind::WireSphere,
+            3 => DrawingKind::WireBox,
+            4 => DrawingKind::WireCapsule,
+            _ => DrawingKind::Label,
+        }
+    }
+
+    /// # From
+    ///
+    /// Returns where a line starts, or the center (or position) of anything else.
+    pub fn from(&self) -> Vector3{
+        self.from
+    }
+
+    /// # To
+    ///
+    /// Returns where a line ends, the size of a box, or the center of a capsule's second sphere.
+    pub fn to(&self) -> Vector3{
+        self.to
+    }
+
+    /// # Rotation
+    ///
+    /// Returns the rotation of a box.
+    pub fn rotation(&self) -> Quaternion{
+        self.rotation
+    }
+
+    /// # Radius
+    ///
+    /// Returns the radius of a sphere or capsule.
+    pub fn radius(&self) -> f32{
+        self.radius
+    }
+
+    /// # Color
+    ///
+    /// Returns the colour the drawing is drawn in.
+    pub fn color(&self) -> Color{
+        self.color
+    }
+
+    /// # Duration
+    ///
+    /// Returns how long the drawing is shown for, in seconds, or `0` for a single frame.
+    pub fn duration(&self) -> f32{
+        self.duration
+    }
+
+    /// # Text
+    ///
+    /// Returns the text of a label.
+    pub fn text(&self) -> &str{
+        self.text.as_str()
+    }
+
+    /// # Segments
+    ///
+    /// Breaks the drawing down into the lines it is made of, as pairs of points. Circles are
+    /// drawn with 24 lines. Labels have no lines.
+    pub fn segments(&self) -> Vec<(Vector3, Vector3)>{
+        let mut segments = Vec::new();
+
+        match self.kind(){
+            DrawingKind::Line => segments.push((self.from, self.to)),
+            DrawingKind::Arrow => {
+                segments.push((self.from, self.to));
+
+                let direction = self.to - self.from;
+                let length = Vector3::magnitude(direction);
+                if length > Vector3::K_EPSILON{
+                    let forward = scaled(direction, 1.0 / length);
+                    let (right, up) = perpendiculars(forward);
+                    let head = length * 0.2;
+                    let back = self.to - scaled(forward, head);
+
+                    for side in [right, up, scaled(right, -1.0), scaled(up, -1.0)]{
+                        segments.push((self.to, back + scaled(side, head * 0.5)));
+                    }
+                }
+            }
+            DrawingKind::WireSphere => {
+                let (x, y, z) = (Vector3::right(), Vector3::up(), Vector3::forward());
+                arc(&mut segments, self.from, x, y, self.radius, 0.0, 2.0 * PI);
+                arc(&mut segments, self.from, x, z, self.radius, 0.0, 2.0 * PI);
+                arc(&mut segments, self.from, y, z, self.radius, 0.0, 2.0 * PI);
+            }
+            DrawingKind::WireBox => {
+                let half = scaled(self.to, 0.5);
+                let corner = |x: f32, y: f32, z: f32| self.from + self.rotation * Vector3::new(half.x * x, half.y * y, half.z * z);
+
+                for (a, b) in [
+                    ((-1.0, -1.0, -1.0), (1.0, -1.0, -1.0)), ((-1.0, 1.0, -1.0), (1.0, 1.0, -1.0)),
+                    ((-1.0, -1.0, 1.0), (1.0, -1.0, 1.0)), ((-1.0, 1.0, 1.0), (1.0, 1.0, 1.0)),
+                    ((-1.0, -1.0, -1.0), (-1.0, 1.0, -1.0)), ((1.0, -1.0, -1.0), (1.0, 1.0, -1.0)),
+                    ((-1.0, -1.0, 1.0), (-1.0, 1.0, 1.0)), ((1.0, -1.0, 1.0), (1.0, 1.0, 1.0)),
+                    ((-1.0, -1.0, -1.0), (-1.0, -1.0, 1.0)), ((1.0, -1.0, -1.0), (1.0, -1.0, 1.0)),
+                    ((-1.0, 1.0, -1.0), (-1.0, 1.0, 1.0)), ((1.0, 1.0, -1.0), (1.0, 1.0, 1.0)),
+                ]{
+                    segments.push((corner(a.0, a.1, a.2), corner(b.0, b.1, b.2)));
+                }
+            }
+            DrawingKind::WireCapsule => {
+                let axis = self.to - self.from;
+                let length = Vector3::magnitude(axis);
+                let forward = if length > Vector3::K_EPSILON{ scaled(axis, 1.0 / length) }else{ Vector3::up() };
+                let (right, up) = perpendiculars(forward);
+                let radius = self.radius;
+
+                // A ring around each end, joined along the sides
+                arc(&mut segments, self.from, right, up, radius, 0.0, 2.0 * PI);
+                arc(&mut segments, self.to, right, up, radius, 0.0, 2.0 * PI);
+                for side in [right, up, scaled(right, -1.0), scaled(up, -1.0)]{
+                    segments.push((self.from + scaled(side, radius), self.to + scaled(side, radius)));
+                }
+
+                // And capped with a half circle each way
+                for side in [right, up]{
+                    arc(&mut segments, self.to, side, forward, radius, 0.0, PI);
+                    arc(&mut segments, self.from, side, forward, radius, PI, 2.0 * PI);
+                }
+            }
+            DrawingKind::Label => (),
+        }
+
+        segments
+    }
+}
+
+fn scaled(vector: Vector3, scale: f32) -> Vector3{
+    Vector3::new(vector.x * scale, vector.y * scale, vector.z * scale)
+}
+
+// Two directions at right angles to `forward`, and to each other
+fn perpendiculars(forward: Vector3) -> (Vector3, Vector3){
+    let reference = if forward.y.abs() < 0.99{ Vector3::up() }else{ Vector3::right() };
+    let right = Vector3::normalize(Vector3::cross(reference, forward));
+    let up = Vector3::cross(forward, right);
+    (right, up)
+}
+
+// Adds the lines of an arc around `center` from `start` to `end` radians, in the plane of `x` and `y`
+fn arc(segments: &mut Vec<(Vector3, Vector3)>, center: Vector3, x: Vector3, y: Vector3, radius: f32, start: f32, end: f32){
+    let count = ((CIRCLE_SEGMENTS as f32 * (end - start) / (2.0 * PI)).ceil() as usize).max(1);
+    let point = |angle: f32| center + scaled(x, angle.cos() * radius) + scaled(y, angle.sin() * radius);
+
+    for i in 0..count{
+        let a = start + (end - start) * i as f32 / count as f32;
+        let b = start + (end - start) * (i + 1) as f32 / count as f32;
+        segments.push((point(a), point(b)));
+    }
+}
+
+/// # Draw
+///
+/// Queues debug drawings, returned by `DataStruct::draw`. The colour and duration apply to
+/// everything drawn with it, and each shape returns the `Draw` so they can be chained.
+///
+/// ```ignore
+/// data.draw()
+///     .color(Color::red())
+///     .ray(data.transform.position, forward)
+///     .wire_sphere(target, 0.5)
+///     .label(target, "target");
+///
+/// data.draw().color(Color::green()).seconds(2.0).line(from, to);
+/// ```
+pub struct Draw<'a>{
+    data: &'a DataStruct,
+    color: Color,
+    duration: f32,
+}
+
+impl<'a> Draw<'a>{
+    pub(crate) fn new(data: &'a DataStruct) -> Self{
+        Self{
+            data,
+            color: Color::white(),
+            duration: 0.0,
+        }
+    }
+
+    /// # Color
+    ///
+    /// Sets the colour to draw in. Defaults to white.
+    pub fn color(mut self, color: Color) -> Self{
+        self.color = color;
+        self
+    }
+
+    /// # Seconds
+    ///
+    /// Shows the drawings for `duration` seconds, rather than a single frame.
+    pub fn seconds(mut self, duration: f32) -> Self{
+        self.duration = duration.max(0.0);
+        self
+    }
+
+    fn queue(&self, drawing: Drawing) -> &Self{
+        LocalData::with(self.data.instance, |local_data| local_data.queue_drawing(drawing));
+        self
+    }
+
+    /// # Line
+    ///
+    /// Draws a line from `from` to `to`, like `Debug.DrawLine`.
+    pub fn line(&self, from: Vector3, to: Vector3) -> &Self{
+        self.queue(Drawing::new(DrawingKind::Line, from, to, self))
+    }
+
+    /// # Ray
+    ///
+    /// Draws a line from `origin` to `origin + direction`, like `Debug.DrawRay`.
+    pub fn ray(&self, origin: Vector3, direction: Vector3) -> &Self{
+        self.queue(Drawing::new(DrawingKind::Line, origin, origin + direction, self))
+    }
+
+    /// # Arrow
+    ///
+    /// Draws a line from `from` to `to`, with an arrowhead at `to`.
+    pub fn arrow(&self, from: Vector3, to: Vector3) -> &Self{
+        self.queue(Drawing::new(DrawingKind::Arrow, from, to, self))
+    }
+
+    /// # Wire Sphere
+    ///
+    /// Draws a wire sphere around `center`, like `Gizmos.DrawWireSphere`.
+    pub fn wire_sphere(&self, center: Vector3, radius: f32) -> &Self{
+        let mut drawing = Drawing::new(DrawingKind::WireSphere, center, center, self);
+        drawing.radius = radius;
+        self.queue(drawing)
+    }
+
+    /// # Wire Box
+    ///
+    /// Draws a wire box around `center`, `size` in size and rotated by `rotation`, like
+    /// `Gizmos.DrawWireCube`.
+    pub fn wire_box(&self, center: Vector3, size: Vector3, rotation: Quaternion) -> &Self{
+        let mut drawing = Drawing::new(DrawingKind::WireBox, center, size, self);
+        drawing.rotation = rotation;
+        self.queue(drawing)
+    }
+
+    /// # Wire Capsule
+    ///
+    /// Draws a wire capsule between the centers of its two ends, `start` and `end`, like the
+    /// capsules `Physics.CapsuleCast` takes.
+    pub fn wire_capsule(&self, start: Vector3, end: Vector3, radius: f32) -> &Self{
+        let mut drawing = Drawing::new(DrawingKind::WireCapsule, start, end, self);
+        drawing.radius = radius;
+        self.queue(drawing)
+    }
+
+    /// # Label
+    ///
+    /// Writes `text` at `position`, like `Handles.Label`.
+    pub fn label(&self, position: Vector3, text: impl AsRef<str>) -> &Self{
+        let mut drawing = Drawing::new(DrawingKind::Label, position, position, self);
+        drawing.text = String::from(text.as_ref());
+        self.queue(drawing)
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{String, Transform, Vector3, Quaternion};
+use std::convert::TryFrom;
+
+use crate::interop::reflect;
+use crate::{Component, ComponentAccess, ComponentRef, Handle, Str, String, Transform, Vector3, Quaternion};
 
 /// # GameObject
 ///
@@ -6,45 +9,256 @@ use crate::{String, Transform, Vector3, Quaternion};
 /// It aims to implement many of the gameobject functions through function pointers
 /// (as many functions rely on unityengine functions, which is cheaper to just run across
 /// rather than pass through every value possible). It is currently incomplete, but
-/// stores a tag, transform and function(s).
+/// stores a transform and function(s), and reaches its tag and components through the host.
+///
+/// It owns nothing, so copies of it (like the ones unity keeps) never free anything.
 ///
 /// The aim is to get as much compatibility as possible between unity and rust.
 #[repr(C)]
+#[derive(Clone)]
 pub struct GameObject{
     /* gameobject info */
     hash: u64, // This is a hash of the gameobject, used to identify the gameobject
-    pub tag: String,
     pub transform: Transform,
 
     /* function pointers */
 
-    // Get gameobject by tag - this takes in a string and a pointer to a gameobject
-    get_gameobject_from_tag_callback: extern "C" fn(&String, *mut GameObject),
+    // Finds other gameobjects. Every gameobject found is handed the same lookups
+    lookup: GameObjectLookup,
+    // Reads and writes components, on this gameobject or any other
+    components: ComponentAccess,
 }
 
 impl GameObject{
+    /// # New
+    ///
+    /// Creates a new `GameObject`. This is used by hosts to hand gameobjects to rust -
+    /// scripts should get gameobjects through functions such as `find_with_tag`.
+    pub fn new(hash: u64, transform: Transform, lookup: GameObjectLookup, components: ComponentAccess) -> Self{
+        Self{
+            hash,
+            transform,
+            lookup,
+            components,
+        }
+    }
+
+    /// # Hash
+    ///
+    /// Returns the hash of the gameobject, used to identify the gameobject.
+    pub fn hash(&self) -> u64{
+        self.hash
+    }
+
+    /// # Tag
+    ///
+    /// Returns the gameobject's tag, like `GameObject.tag`. The tag is owned by the host, so this
+    /// asks it for a copy, which is empty if the gameobject no longer exists.
+    pub fn tag(&self) -> std::string::String{
+        let mut found = FoundGameObject::new(0, Str::EMPTY, Transform::new(Vector3::zero(), Quaternion::identity()));
+        if (self.lookup.find_by_id)(self.hash, &mut found){
+            found.tag.to_string_lossy().into_owned()
+        }else{
+            std::string::String::new()
+        }
+    }
+
+    /// # Handle
+    ///
+    /// Returns the handle used to send commands to this gameobject, such as `DataStruct::destroy`.
+    pub fn handle(&self) -> Handle{
+        Handle::of(self.hash)
+    }
+
+    /// # Get Component
+    ///
+    /// Returns the component of type `T` attached to this gameobject, like `GetComponent<T>`, or
+    /// `None` if there isn't one.
+    ///
+    /// ```ignore
+    /// if let Some(rigidbody) = data.game_object.get_component::<Rigidbody>(){
+    ///     rigidbody.add_force(Vector3::new(0.0, 10.0, 0.0), ForceMode::Impulse);
+    /// }
+    /// ```
+    pub fn get_component<T: Component>(&self) -> Option<T>{
+        self.get_component_by_name(T::NAME).map(T::from_ref)
+    }
+
+    /// # Get Component By Name
+    ///
+    /// Returns the component with the given type name attached to this gameobject, or `None` if
+    /// there isn't one. This reaches components runity doesn't model, through their properties.
+    pub fn get_component_by_name(&self, name: &str) -> Option<ComponentRef>{
+        if (self.components.has_component)(self.hash, Str::from(name)){
+            Some(ComponentRef::new(self.hash, name, self.components))
+        }else{
+            None
+        }
+    }
+
+    /// # Parent
+    ///
+    /// Returns the parent gameobject, like `Transform.parent`, or `None` if this gameobject is at
+    /// the root of the scene.
+    pub fn parent(&self) -> Option<Self>{
+        self.transform.parent().and_then(|hash| self.find_by_id(hash))
+    }
+
+    /// # Child Count
+    ///
+    /// Returns how many children this gameobject has, like `Transform.childCount`.
+    pub fn child_count(&self) -> usize{
+        (self.lookup.child_count)(self.hash) as usize
+    }
+
+    /// # Child
+    ///
+    /// Returns the child at `index`, like `Transform.GetChild`, or `None` if there are only
+    /// `index` children or fewer. Children are in the order unity lists them in.
+    pub fn child(&self, index: usize) -> Option<Self>{
+        let index = u32::try_from(index).ok()?;
+
+        let mut found = FoundGameObject::new(0, Str::EMPTY, Transform::new(Vector3::zero(), Quaternion::identity()));
+        if (self.lookup.child)(self.hash, index, &mut found){
+            Some(self.adopt(&found))
+        }else{
+            None
+        }
+    }
+
+    /// # Find
+    ///
+    /// Returns the gameobject with the given name, like `GameObject.Find`, or `None` if there isn't one.
+    pub fn find(&self, name: &str) -> Option<Self>{
+        let mut found = FoundGameObject::new(0, Str::EMPTY, Transform::new(Vector3::zero(), Quaternion::identity()));
+        if (self.lookup.find)(Str::from(name), &mut found){
+            Some(self.adopt(&found))
+        }else{
+            None
+        }
+    }
+
+    /// # Find With Tag
+    ///
+    /// Returns a gameobject with the given tag, like `GameObject.FindWithTag`, or `None` if there isn't one.
+    pub fn find_with_tag(&self, tag: &str) -> Option<Self>{
+        let mut found = FoundGameObject::new(0, Str::EMPTY, Transform::new(Vector3::zero(), Quaternion::identity()));
+        if (self.lookup.find_with_tag)(Str::from(tag), &mut found){
+            Some(self.adopt(&found))
+        }else{
+            None
+        }
+    }
+
+    /// # Find All With Tag
+    ///
+    /// Returns every gameobject with the given tag, like `GameObject.FindGameObjectsWithTag`.
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<Self>{
+        let mut found: Vec<FoundGameObject> = Vec::new();
+
+        // The host writes up to `capacity` gameobjects and returns how many there are in total, so
+        // if there wasn't enough room, it is asked again with enough
+        loop{
+            let capacity = found.capacity();
+            let count = (self.lookup.find_all_with_tag)(Str::from(tag), found.as_mut_ptr(), capacity as u32) as usize;
+
+            if count <= capacity{
+                unsafe{ found.set_len(count) };
+                break;
+            }
+
+            found.reserve_exact(count);
+        }
+
+        found.iter().map(|found| self.adopt(found)).collect()
+    }
+
+    /// # Find By ID
+    ///
+    /// Returns the gameobject with the given hash (the same as `GameObject::hash`), or `None` if
+    /// there isn't one.
+    pub fn find_by_id(&self, hash: u64) -> Option<Self>{
+        let mut found = FoundGameObject::new(0, Str::EMPTY, Transform::new(Vector3::zero(), Quaternion::identity()));
+        if (self.lookup.find_by_id)(hash, &mut found){
+            Some(self.adopt(&found))
+        }else{
+            None
+        }
+    }
+
     /// # Get GameObject from tag
     ///
-    /// Takes a string, returns the gameobject attached to the associated tag.
-    /// 
-    /// The returned gameobject is a read-only gameobject, meaning that it cannot be modified.
-    /// Any modifications to the gameobject is undefined behaviour.
-    pub fn get_gameobject_from_tag(&self, tag: &String) -> Self{
-        // Create a new gameobject using this gameobject's function pointer
-        let mut gameobject = GameObject{
-            hash: 0,
-            tag: tag.clone(),
-            transform: Transform::new(Vector3::new(0.0, 0.0, 0.0), Quaternion::new(0.0, 0.0, 0.0, 0.0)),
-            get_gameobject_from_tag_callback: self.get_gameobject_from_tag_callback,
-        };
+    /// Takes a tag, returns the gameobject attached to the associated tag.
+    ///
+    /// If there isn't one, this returns a gameobject with a hash of `0` at the origin. Prefer
+    /// `find_with_tag`, which returns `None` instead.
+    pub fn get_gameobject_from_tag(&self, tag: &str) -> Self{
+        self.find_with_tag(tag).unwrap_or_else(|| {
+            GameObject::new(0, Transform::new(Vector3::zero(), Quaternion::identity()), self.lookup, self.components)
+        })
+    }
+
+    // Copies a gameobject found by the host, handing it the same lookups
+    fn adopt(&self, found: &FoundGameObject) -> Self{
+        GameObject::new(found.hash, found.transform.clone(), self.lookup, self.components)
+    }
+}
 
-        // Convert the gameobject to a mutable gameobject pointer
-        let gameobject_ptr = &mut gameobject as *mut GameObject;
+reflect!(GameObject{ hash, transform, lookup, components });
 
-        (self.get_gameobject_from_tag_callback)(tag, gameobject_ptr);
+/// # GameObject Lookup
+///
+/// The host functions used to find gameobjects. Each one fills in a `FoundGameObject`, and
+/// returns whether it found anything.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct GameObjectLookup{
+    pub find: extern "C" fn(Str, *mut FoundGameObject) -> bool,
+    pub find_with_tag: extern "C" fn(Str, *mut FoundGameObject) -> bool,
+    /// Writes up to `capacity` gameobjects, returning how many there are in total.
+    pub find_all_with_tag: extern "C" fn(Str, *mut FoundGameObject, u32) -> u32,
+    pub find_by_id: extern "C" fn(u64, *mut FoundGameObject) -> bool,
+    /// Returns how many children the gameobject with the given hash has.
+    pub child_count: extern "C" fn(u64) -> u32,
+    /// Finds the child at the given index of the gameobject with the given hash.
+    pub child: extern "C" fn(u64, u32, *mut FoundGameObject) -> bool,
+}
+
+reflect!(GameObjectLookup{
+    find: fn FindGameObject(name, found),
+    find_with_tag: fn FindGameObjectWithTag(tag, found),
+    find_all_with_tag: fn FindGameObjectsWithTag(tag, found, capacity),
+    find_by_id: fn FindGameObjectById(hash, found),
+    child_count: fn GetChildCount(hash),
+    child: fn GetChild(hash, index, found),
+});
+
+/// # Found GameObject
+///
+/// A gameobject found by the host, written by the functions in `GameObjectLookup`.
+///
+/// The tag is lent by the host - it only has to stay valid until the next lookup, as rust
+/// never keeps it.
+#[repr(C)]
+#[derive(Clone)]
+pub struct FoundGameObject<'a>{
+    hash: u64,
+    tag: Str<'a>,
+    transform: Transform,
+}
 
-        // Return the gameobject
-        gameobject
+reflect!(FoundGameObject<'_>{ hash, tag, transform });
+
+impl<'a> FoundGameObject<'a>{
+    /// # New
+    ///
+    /// Creates a new `FoundGameObject`, borrowing the tag. This is used by hosts to answer lookups.
+    pub fn new(hash: u64, tag: Str<'a>, transform: Transform) -> Self{
+        Self{
+            hash,
+            tag,
+            transform,
+        }
     }
 }
 
@@ -53,21 +267,32 @@ impl GameObject{
 /// This struct stores all the changes that have been made to a gameobject.
 /// 
 /// This allows for efficient changes to be made to a gameobject, and then sent to the unity engine.
+/// Changes are queued with `DataStruct::queue_change`, and lent to unity in a `ChangeBuffer`.
 #[repr(C)]
-#[derive(Clone)]
 pub struct GameObjectChanges{
     hash: u64,
     pub tag: String,
     pub transform: Transform,
 }
 
+reflect!(GameObjectChanges{ hash, tag, transform });
+
+impl GameObjectChanges{
+    /// # Hash
+    ///
+    /// Returns the hash of the gameobject these changes are for.
+    pub fn hash(&self) -> u64{
+        self.hash
+    }
+}
+
 impl From<GameObject> for GameObjectChanges{
     /// Convert a gameobject to a gameobject changes
     fn from(gameobject: GameObject) -> Self{
         Self{
             hash: gameobject.hash,
-            tag: gameobject.tag.clone(),
-            transform: gameobject.transform.clone(),
+            tag: String::from(gameobject.tag()),
+            transform: gameobject.transform,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,455 @@
+//! # Input
+//!
+//! The keyboard, mouse and axes, as sampled by the host at the start of each frame.
+//!
+//! The host passes both this frame's snapshot and last frame's in `DataStruct::input`, so
+//! whether a key went down (or up) this frame is worked out by comparing the two - rust
+//! doesn't need to remember anything between calls, and every call in a frame sees the same
+//! input.
+
+use crate::interop::reflect;
+use crate::{Str, Vector3};
+
+/// The number of keys an `InputSnapshot` can hold. Every unity `KeyCode` is below this.
+pub const KEY_COUNT: usize = 512;
+
+/// # Key Code
+///
+/// A key, numbered the same as unity's `KeyCode`. The most common keys are named here, and
+/// any other key can be made from its unity value (`KeyCode(330)` is `JoystickButton0`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyCode(pub u16);
+
+impl KeyCode{
+    pub const BACKSPACE: KeyCode = KeyCode(8);
+    pub const TAB: KeyCode = KeyCode(9);
+    pub const RETURN: KeyCode = KeyCode(13);
+    pub const ESCAPE: KeyCode = KeyCode(27);
+    pub const SPACE: KeyCode = KeyCode(32);
+    pub const ALPHA0: KeyCode = KeyCode(48);
+    pub const ALPHA1: KeyCode = KeyCode(49);
+    pub const ALPHA2: KeyCode = KeyCode(50);
+    pub const ALPHA3: KeyCode = KeyCode(51);
+    pub const ALPHA4: KeyCode = KeyCode(52);
+    pub const ALPHA5: KeyCode = KeyCode(53);
+    pub const ALPHA6: KeyCode = KeyCode(54);
+    pub const ALPHA7: KeyCode = KeyCode(55);
+    pub const ALPHA8: KeyCode = KeyCode(56);
+    pub const ALPHA9: KeyCode = KeyCode(57);
+    pub const A: KeyCode = KeyCode(97);
+    pub const B: KeyCode = KeyCode(98);
+    pub const C: KeyCode = KeyCode(99);
+    pub const D: KeyCode = KeyCode(100);
+    pub const E: KeyCode = KeyCode(101);
+    pub const F: KeyCode = KeyCode(102);
+    pub const G: KeyCode = KeyCode(103);
+    pub const H: KeyCode = KeyCode(104);
+    pub const I: KeyCode = KeyCode(105);
+    pub const J: KeyCode = KeyCode(106);
+    pub const K: KeyCode = KeyCode(107);
+    pub const L: KeyCode = KeyCode(108);
+    pub const M: KeyCode = KeyCode(109);
+    pub const N: KeyCode = KeyCode(110);
+    pub const O: KeyCode = KeyCode(111);
+    pub const P: KeyCode = KeyCode(112);
+    pub const Q: KeyCode = KeyCode(113);
+    pub const R: KeyCode = KeyCode(114);
+    pub const S: KeyCode = KeyCode(115);
+    pub const T: KeyCode = KeyCode(116);
+    pub const U: KeyCode = KeyCode(117);
+    pub const V: KeyCode = KeyCode(118);
+    pub const W: KeyCode = KeyCode(119);
+    pub const X: KeyCode = KeyCode(120);
+    pub const Y: KeyCode = KeyCode(121);
+    pub const Z: KeyCode = KeyCode(122);
+    pub const DELETE: KeyCode = KeyCode(127);
+    pub const UP_ARROW: KeyCode = KeyCode(273);
+    pub const DOWN_ARROW: KeyCode = KeyCode(274);
+    pub const RIGHT_ARROW: KeyCode = KeyCode(275);
+    pub const LEFT_ARROW: KeyCode = KeyCode(276);
+    pub const INSERT: KeyCode = KeyCode(277);
+    pub const HOME: KeyCode = KeyCode(278);
+    pub const END: KeyCode = KeyCode(279);
+    pub const PAGE_UP: KeyCode = KeyCode(280);
+    pub const PAGE_DOWN: KeyCode = KeyCode(281);
+    pub const F1: KeyCode = KeyCode(282);
+    pub const F2: KeyCode = KeyCode(283);
+    pub const F3: KeyCode = KeyCode(284);
+    pub const F4: KeyCode = KeyCode(285);
+    pub const F5: KeyCode = KeyCode(286);
+    pub const F6: KeyCode = KeyCode(287);
+    pub const F7: KeyCode = KeyCode(288);
+    pub const F8: KeyCode = KeyCode(289);
+    pub const F9: KeyCode = KeyCode(290);
+    pub const F10: KeyCode = KeyCode(291);
+    pub const F11: KeyCode = KeyCode(292);
+    pub const F12: KeyCode = KeyCode(293);
+    pub const RIGHT_SHIFT: KeyCode = KeyCode(303);
+    pub const LEFT_SHIFT: KeyCode = KeyCode(304);
+    pub const RIGHT_CONTROL: KeyCode = KeyCode(305);
+    pub const LEFT_CONTROL: KeyCode = KeyCode(306);
+    pub const RIGHT_ALT: KeyCode = KeyCode(307);
+    pub const LEFT_ALT: KeyCode = KeyCode(308);
+    pub const MOUSE0: KeyCode = KeyCode(323);
+    pub const MOUSE1: KeyCode = KeyCode(324);
+    pub const MOUSE2: KeyCode = KeyCode(325);
+}
+
+/// # Axis
+///
+/// The value of a named axis (such as `"Horizontal"`), usually between -1 and 1.
+///
+/// The name is lent by the host along with the snapshot it belongs to.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Axis<'a>{
+    pub name: Str<'a>,
+    pub value: f32,
+}
+
+reflect!(Axis<'_>{ name, value });
+
+impl<'a> Axis<'a>{
+    /// # New
+    ///
+    /// Creates an axis, borrowing its name.
+    pub fn new(name: &'a str, value: f32) -> Self{
+        Self{
+            name: Str::from(name),
+            value,
+        }
+    }
+}
+
+/// # Input Snapshot
+///
+/// The state of the input during a single frame.
+///
+/// Keys are a bitset indexed by `KeyCode`, and mouse buttons a bitset indexed like unity's
+/// `Input.GetMouseButton` (0 is the left button, 1 the right and 2 the middle). The mouse
+/// position is in pixels, from the bottom left of the screen.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct InputSnapshot{
+    pub keys: [u64; KEY_COUNT / 64],
+    pub mouse_buttons: u32,
+    pub mouse_position: Vector3,
+    pub mouse_delta: Vector3,
+    pub mouse_scroll: Vector3,
+    axes: *const Axis<'static>,
+    axis_count: u32,
+}
+
+reflect!(InputSnapshot{ keys, mouse_buttons, mouse_position, mouse_delta, mouse_scroll, axes, axis_count });
+
+impl InputSnapshot{
+    /// Nothing pressed, and the mouse at the origin.
+    pub const EMPTY: InputSnapshot = InputSnapshot{
+        keys: [0; KEY_COUNT / 64],
+        mouse_buttons: 0,
+        mouse_position: Vector3{ x: 0.0, y: 0.0, z: 0.0 },
+        mouse_delta: Vector3{ x: 0.0, y: 0.0, z: 0.0 },
+        mouse_scroll: Vector3{ x: 0.0, y: 0.0, z: 0.0 },
+        axes: std::ptr::null(),
+        axis_count: 0,
+    };
+
+    /// # Key
+    ///
+    /// Returns true if `key` is held down. Keys outside of the snapshot are never held.
+    pub fn key(&self, key: KeyCode) -> bool{
+        let index = key.0 as usize;
+        index < KEY_COUNT && self.keys[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// # Set Key
+    ///
+    /// Sets whether `key` is held down. Keys outside of the snapshot are ignored.
+    pub fn set_key(&mut self, key: KeyCode, down: bool){
+        let index = key.0 as usize;
+        if index >= KEY_COUNT{
+            return;
+        }
+
+        if down{
+            self.keys[index / 64] |= 1 << (index % 64);
+        } else {
+            self.keys[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    /// # Mouse Button
+    ///
+    /// Returns true if mouse button `button` is held down.
+    pub fn mouse_button(&self, button: u32) -> bool{
+        button < 32 && self.mouse_buttons & (1 << button) != 0
+    }
+
+    /// # Set Mouse Button
+    ///
+    /// Sets whether mouse button `button` is held down. Buttons past 31 are ignored.
+    pub fn set_mouse_button(&mut self, button: u32, down: bool){
+        if button >= 32{
+            return;
+        }
+
+        if down{
+            self.mouse_buttons |= 1 << button;
+        } else {
+            self.mouse_buttons &= !(1 << button);
+        }
+    }
+
+    /// # Axes
+    ///
+    /// Returns every named axis.
+    pub fn axes(&self) -> &[Axis<'_>]{
+        if self.axes.is_null(){
+            return &[];
+        }
+
+        unsafe{ std::slice::from_raw_parts(self.axes, self.axis_count as usize) }
+    }
+
+    /// # Set Axes
+    ///
+    /// Lends the snapshot its axes.
+    ///
+    /// # Safety
+    ///
+    /// `axes` (and the name of every axis) must stay valid for as long as the snapshot is used.
+    pub unsafe fn set_axes(&mut self, axes: &[Axis<'_>]){
+        self.axes = axes.as_ptr().cast();
+        self.axis_count = axes.len() as u32;
+    }
+}
+
+// The axes are only ever read, and stay valid for as long as the snapshot is used
+unsafe impl Send for InputSnapshot{}
+
+impl Default for InputSnapshot{
+    fn default() -> Self{
+        Self::EMPTY
+    }
+}
+
+/// # Input
+///
+/// The input for the current frame, along with the previous frame's so edges can be found.
+/// This mirrors unity's `Input` class.
+///
+/// ```ignore
+/// fn update(&mut self, data: &mut DataStruct){
+///     if data.input.get_key_down(KeyCode::SPACE){
+///         self.jump();
+///     }
+///     self.speed = data.input.get_axis("Horizontal") * 5.0;
+/// }
+/// ```
+///
+/// Only the current snapshot's axes are read, so the previous snapshot never has any.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Input{
+    pub current: InputSnapshot,
+    pub previous: InputSnapshot,
+}
+
+reflect!(Input{ current, previous });
+
+impl Input{
+    /// # Advance
+    ///
+    /// Moves on to the next frame's snapshot, keeping the current one as the previous. This
+    /// is how hosts (and tests) feed input in, one frame at a time.
+    ///
+    /// ```
+    /// # use runity::{Input, InputSnapshot, KeyCode};
+    /// let mut input = Input::default();
+    /// let mut snapshot = InputSnapshot::EMPTY;
+    ///
+    /// snapshot.set_key(KeyCode::SPACE, true);
+    /// input.advance(snapshot);
+    /// assert!(input.get_key_down(KeyCode::SPACE));
+    ///
+    /// input.advance(snapshot);
+    /// assert!(input.get_key(KeyCode::SPACE) && !input.get_key_down(KeyCode::SPACE));
+    /// ```
+    pub fn advance(&mut self, next: InputSnapshot){
+        self.previous = self.current;
+        // The previous axes may no longer be valid, and are never read
+        unsafe{ self.previous.set_axes(&[]) };
+        self.current = next;
+    }
+
+    /// # Get Key
+    ///
+    /// Returns true while `key` is held down.
+    pub fn get_key(&self, key: KeyCode) -> bool{
+        self.current.key(key)
+    }
+
+    /// # Get Key Down
+    ///
+    /// Returns true during the frame `key` was pressed.
+    pub fn get_key_down(&self, key: KeyCode) -> bool{
+        self.current.key(key) && !self.previous.key(key)
+    }
+
+    /// # Get Key Up
+    ///
+    /// Returns true during the frame `key` was released.
+    pub fn get_key_up(&self, key: KeyCode) -> bool{
+        !self.current.key(key) && self.previous.key(key)
+    }
+
+    /// # Get Mouse Button
+    ///
+    /// Returns true while mouse button `button` is held down.
+    pub fn get_mouse_button(&self, button: u32) -> bool{
+        self.current.mouse_button(button)
+    }
+
+    /// # Get Mouse Button Down
+    ///
+    /// Returns true during the frame mouse button `button` was pressed.
+    pub fn get_mouse_button_down(&self, button: u32) -> bool{
+        self.current.mouse_button(button) && !self.previous.mouse_button(button)
+    }
+
+    /// # Get Mouse Button Up
+    ///
+    /// Returns true during the frame mouse button `button` was released.
+    pub fn get_mouse_button_up(&self, button: u32) -> bool{
+        !self.current.mouse_button(button) && self.previous.mouse_button(button)
+    }
+
+    /// # Get Axis
+    ///
+    /// Returns the value of the named axis, or 0 if the host didn't send it.
+    pub fn get_axis(&self, name: &str) -> f32{
+        self.current.axes().iter()
+            .find(|axis| axis.name.as_bytes() == name.as_bytes())
+            .map(|axis| axis.value)
+            .unwrap_or(0.0)
+    }
+
+    /// # Mouse Position
+    ///
+    /// Returns the position of the mouse, in pixels from the bottom left of the screen.
+    pub fn mouse_position(&self) -> Vector3{
+        self.current.mouse_position
+    }
+
+    /// # Mouse Delta
+    ///
+    /// Returns how far the mouse moved this frame, in pixels.
+    pub fn mouse_delta(&self) -> Vector3{
+        self.current.mouse_delta
+    }
+
+    /// # Mouse Scroll
+    ///
+    /// Returns how far the mouse wheel scrolled this frame.
+    pub fn mouse_scroll(&self) -> Vector3{
+        self.current.mouse_scroll
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // Feeds `frames` into a fresh `Input`, one snapshot per frame, returning the input after each
+    fn frames(frames: &[&[KeyCode]]) -> Vec<Input>{
+        let mut input = Input::default();
+        frames.iter().map(|keys| {
+            let mut snapshot = InputSnapshot::EMPTY;
+            for key in keys.iter(){
+                snapshot.set_key(*key, true);
+            }
+            input.advance(snapshot);
+            input
+        }).collect()
+    }
+
+    #[test]
+    fn key_edges_last_a_single_frame(){
+        let frames = frames(&[&[], &[KeyCode::SPACE], &[KeyCode::SPACE], &[KeyCode::SPACE], &[]]);
+
+        let held: Vec<_> = frames.iter().map(|input| input.get_key(KeyCode::SPACE)).collect();
+        let down: Vec<_> = frames.iter().map(|input| input.get_key_down(KeyCode::SPACE)).collect();
+        let up: Vec<_> = frames.iter().map(|input| input.get_key_up(KeyCode::SPACE)).collect();
+
+        assert_eq!(held, [false, true, true, true, false]);
+        assert_eq!(down, [false, true, false, false, false]);
+        assert_eq!(up, [false, false, false, false, true]);
+    }
+
+    #[test]
+    fn tapping_every_other_frame(){
+        let frames = frames(&[&[KeyCode::A], &[], &[KeyCode::A], &[]]);
+
+        let down: Vec<_> = frames.iter().map(|input| input.get_key_down(KeyCode::A)).collect();
+        let up: Vec<_> = frames.iter().map(|input| input.get_key_up(KeyCode::A)).collect();
+
+        assert_eq!(down, [true, false, true, false]);
+        assert_eq!(up, [false, true, false, true]);
+    }
+
+    #[test]
+    fn keys_are_independent(){
+        let frames = frames(&[&[KeyCode::A], &[KeyCode::A, KeyCode::D], &[KeyCode::D]]);
+
+        assert!(frames[0].get_key_down(KeyCode::A) && !frames[0].get_key(KeyCode::D));
+        assert!(frames[1].get_key_down(KeyCode::D) && !frames[1].get_key_down(KeyCode::A));
+        assert!(frames[2].get_key_up(KeyCode::A) && !frames[2].get_key_up(KeyCode::D));
+    }
+
+    #[test]
+    fn keys_past_the_snapshot_are_never_held(){
+        let mut snapshot = InputSnapshot::EMPTY;
+        snapshot.set_key(KeyCode(KEY_COUNT as u16), true);
+
+        assert_eq!(snapshot.keys, InputSnapshot::EMPTY.keys);
+        assert!(!snapshot.key(KeyCode(KEY_COUNT as u16)));
+        assert!(!snapshot.key(KeyCode(u16::MAX)));
+    }
+
+    #[test]
+    fn mouse_button_edges(){
+        let mut input = Input::default();
+        let mut snapshot = InputSnapshot::EMPTY;
+
+        snapshot.set_mouse_button(1, true);
+        input.advance(snapshot);
+        assert!(input.get_mouse_button_down(1) && !input.get_mouse_button_down(0));
+
+        input.advance(snapshot);
+        assert!(input.get_mouse_button(1) && !input.get_mouse_button_down(1));
+
+        snapshot.set_mouse_button(1, false);
+        input.advance(snapshot);
+        assert!(input.get_mouse_button_up(1) && !input.get_mouse_button(1));
+
+        // Buttons past 31 are ignored
+        snapshot.set_mouse_button(32, true);
+        assert_eq!(snapshot.mouse_buttons, 0);
+    }
+
+    #[test]
+    fn axes_are_only_read_from_the_current_frame(){
+        let axes = [Axis::new("Horizontal", 0.5)];
+        let mut input = Input::default();
+        let mut snapshot = InputSnapshot::EMPTY;
+        unsafe{ snapshot.set_axes(&axes) };
+
+        input.advance(snapshot);
+        assert_eq!(input.get_axis("Horizontal"), 0.5);
+        assert_eq!(input.get_axis("Vertical"), 0.0);
+
+        input.advance(InputSnapshot::EMPTY);
+        assert_eq!(input.get_axis("Horizontal"), 0.0);
+        assert!(input.previous.axes().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! Generates a C header (`runity.h`) for the boundary.
+//!
+//! This lets C and C++ hosts load runity libraries the same way unity does. Every shared
+//! struct, callback and export is emitted with a `Runity` prefix, so nothing clashes with
+//! the host's own types. The header also checks the size and field offsets of every struct
+//! at compile time, for hosts built with the same pointer width as the generator.
+
+use std::fmt::Write;
+
+use super::{ExportInfo, Kind, StructInfo};
+
+/// # Generate
+///
+/// Generates `runity.h`.
+///
+/// Returns an error if any struct description doesn't match the struct's real layout.
+pub fn generate() -> Result<std::string::String, std::string::String>{
+    generate_from(&super::structs(), &super::exports())
+}
+
+/// # Generate From
+///
+/// Generates `runity.h` for the given structs and exports.
+pub fn generate_from(structs: &[StructInfo], exports: &[ExportInfo]) -> Result<std::string::String, std::string::String>{
+    for info in structs.iter(){
+        info.validate()?;
+    }
+
+    let mut out = std::string::String::new();
+
+    out.push_str("/*\n");
+    out.push_str(" * runity.h\n");
+    out.push_str(" *\n");
+    out.push_str(" * Generated by runity-bindgen from the rust definitions in runity. Do not edit this\n");
+    out.push_str(" * file by hand - regenerate it with `cargo run --bin runity-bindgen -- c`.\n");
+    out.push_str(" */\n\n");
+    out.push_str("#ifndef RUNITY_H\n#define RUNITY_H\n\n");
+    out.push_str("#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n");
+    out.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n");
+    out.push_str("/* The ABI version this header describes. Check it against `runity_abi_version()` before calling into a library. */\n");
+    let _ = writeln!(out, "#define RUNITY_ABI_VERSION {}\n", crate::ABI_VERSION);
+
+    // Every struct is declared up front, so callbacks can refer to them
+    out.push_str("/* Structs shared with rust */\n\n");
+    for info in structs.iter(){
+        let _ = writeln!(out, "typedef struct {0} {0};", type_name(info.name));
+    }
+
+    // Callbacks
+    out.push_str("\n/* Callbacks rust can call */\n\n");
+    let mut seen = Vec::new();
+    for info in structs.iter(){
+        for field in info.fields.iter(){
+            if let (Kind::Function(arguments, returns), Some(function)) = (&field.kind, field.function_name){
+                if seen.contains(&function){
+                    continue;
+                }
+
+                let arguments = arguments.iter().enumerate()
+                    .map(|(index, kind)| (field.argument_names.get(index).copied().unwrap_or("arg"), kind.clone()))
+                    .collect::<Vec<_>>();
+                let _ = writeln!(out, "typedef {} (*{})({});", c_type(returns), callback_name(function), arguments_list(&arguments));
+                seen.push(function);
+            }
+        }
+    }
+
+    // Struct definitions
+    for info in structs.iter(){
+        let _ = writeln!(out, "\n/* {} bytes, aligned to {} */", info.size, info.align);
+        let _ = writeln!(out, "struct {}{{", type_name(info.name));
+        for field in info.fields.iter(){
+            let declaration = match (&field.kind, field.function_name){
+                (Kind::Function(..), Some(function)) => declare(&callback_name(function), field.name),
+                (Kind::Array(inner, len), _) => declare(&c_type(inner), &format!("{}[{}]", field.name, len)),
+                (kind, _) => declare(&c_type(kind), field.name),
+            };
+            let _ = writeln!(out, "    {};", declaration);
+        }
+        out.push_str("};\n");
+    }
+
+    // Layout checks
+    let pointer_width = std::mem::size_of::<usize>() * 8;
+    let _ = writeln!(out, "\n/* Layout checks - these only hold for {}-bit hosts */", pointer_width);
+    let _ = writeln!(out, "#if UINTPTR_MAX == {}", if pointer_width == 64 { "0xffffffffffffffffu" } else { "0xffffffffu" });
+    out.push_str("#if defined(__cplusplus)\n#define RUNITY_STATIC_ASSERT(condition, message) static_assert(condition, message)\n");
+    out.push_str("#else\n#define RUNITY_STATIC_ASSERT(condition, message) _Static_assert(condition, message)\n#endif\n");
+    for info in structs.iter(){
+        let name = type_name(info.name);
+        let _ = writeln!(out, "RUNITY_STATIC_ASSERT(sizeof({0}) == {1}, \"{0} must be {1} bytes\");", name, info.size);
+        for field in info.fields.iter(){
+            let _ = writeln!(out, "RUNITY_STATIC_ASSERT(offsetof({0}, {1}) == {2}, \"{0}.{1} must be at offset {2}\");", name, field.name, field.offset);
+        }
+    }
+    out.push_str("#undef RUNITY_STATIC_ASSERT\n#endif\n");
+
+    // Exports - as both function pointer types (for hosts loading the library at runtime)
+    // and declarations (for hosts linking against it)
+    out.push_str("\n/* Functions exported by a runity library */\n\n");
+    for export in exports.iter(){
+        let _ = writeln!(out, "typedef {} (*{})({});{}", c_type(&export.returns), export_name(export), arguments_list(&export.arguments), if export.required { "" } else { " /* optional */" });
+    }
+    out.push('\n');
+    for export in exports.iter(){
+        let _ = writeln!(out, "{} {}({});", c_type(&export.returns), export.name, arguments_list(&export.arguments));
+    }
+
+    out.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n#endif /* RUNITY_H */\n");
+
+    Ok(out)
+}
+
+fn arguments_list(arguments: &[(&str, Kind)]) -> std::string::String{
+    if arguments.is_empty(){
+        return "void".to_string();
+    }
+
+    arguments.iter()
+        .map(|(name, kind)| declare(&c_type(kind), name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Declares `name` as `ty`, keeping pointers next to the name (`char *ptr`)
+fn declare(ty: &str, name: &str) -> std::string::String{
+    if ty.ends_with('*'){
+        format!("{}{}", ty, name)
+    } else {
+        format!("{} {}", ty, name)
+    }
+}
+
+fn type_name(name: &str) -> std::string::String{
+    format!("Runity{}", name)
+}
+
+fn callback_name(function: &str) -> std::string::String{
+    format!("Runity{}Fn", function)
+}
+
+fn export_name(export: &ExportInfo) -> std::string::String{
+    let pascal = export.name.split('_')
+        .filter(|part| !part.is_empty() && *part != "runity")
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next(){
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => std::string::String::new(),
+            }
+        })
+        .collect::<std::string::String>();
+
+    format!("Runity{}Fn", pascal)
+}
+
+fn c_type(kind: &Kind) -> std::string::String{
+    match kind{
+        Kind::Void => "void".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::U8 => "uint8_t".to_string(),
+        Kind::I32 => "int32_t".to_string(),
+        Kind::U32 => "uint32_t".to_string(),
+        Kind::U64 => "uint64_t".to_string(),
+        Kind::F32 => "float".to_string(),
+        Kind::CString => "char *".to_string(),
+        Kind::Struct(name) => type_name(name),
+        Kind::Ref(inner) => format!("const {} *", c_type(inner)),
+        Kind::RefMut(inner) => format!("{} *", c_type(inner)),
+        Kind::Pointer(inner) => format!("{} *", c_type(inner)),
+        // Function pointers are only named through struct fields
+        Kind::Function(..) => "void *".to_string(),
+        // Arrays are only declared as struct fields, which add the length
+        Kind::Array(inner, _) => c_type(inner),
+    }
+}
@@ -0,0 +1,257 @@
+//! Generates the C# side of the boundary.
+//!
+//! This emits every shared struct, the delegates for every callback and export, and a
+//! `RunityExports` class which loads the exports from a library through `DLLPool` (see
+//! `cs_examples`). The output replaces the hand-written definitions that used to live in
+//! `runity.cs`.
+
+use std::fmt::Write;
+
+use super::{ExportInfo, Kind, StructInfo};
+
+/// The namespace the generated code is placed in, matching `cs_examples`.
+pub const DEFAULT_NAMESPACE: &str = "runity_test";
+
+/// # Generate
+///
+/// Generates the C# interop layer, in `namespace`.
+///
+/// Returns an error if any struct description doesn't match the struct's real layout.
+pub fn generate(namespace: &str) -> Result<std::string::String, std::string::String>{
+    generate_from(namespace, &super::structs(), &super::exports())
+}
+
+/// # Generate From
+///
+/// Generates the C# interop layer for the given structs and exports, in `namespace`.
+pub fn generate_from(namespace: &str, structs: &[StructInfo], exports: &[ExportInfo]) -> Result<std::string::String, std::string::String>{
+    for info in structs.iter(){
+        info.validate()?;
+    }
+
+    let mut out = std::string::String::new();
+
+    out.push_str("// <auto-generated>\n");
+    out.push_str("// Generated by runity-bindgen from the rust definitions in runity. Do not edit this\n");
+    out.push_str("// file by hand - regenerate it with `cargo run --bin runity-bindgen -- csharp`.\n");
+    out.push_str("// </auto-generated>\n\n");
+    out.push_str("using System;\n");
+    out.push_str("using System.Collections.Generic;\n");
+    out.push_str("using System.Runtime.InteropServices;\n\n");
+    let _ = writeln!(out, "namespace {}\n{{", namespace);
+
+    // Structs
+    out.push_str("    /* Structs shared with rust. These must match the rust layout exactly. */\n");
+    for info in structs.iter(){
+        let _ = writeln!(out, "\n    // {} bytes, aligned to {}", info.size, info.align);
+        out.push_str("    [StructLayout(LayoutKind.Sequential)]\n");
+        let _ = writeln!(out, "    public struct {}\n    {{", info.name);
+        for field in info.fields.iter(){
+            let ty = match (&field.kind, field.function_name){
+                (Kind::Function(..), Some(function)) => format!("{}Delegate", function),
+                (kind, _) => field_type(kind),
+            };
+            match &field.kind{
+                Kind::Bool => out.push_str("        [MarshalAs(UnmanagedType.U1)]\n"),
+                Kind::Array(_, len) => { let _ = writeln!(out, "        [MarshalAs(UnmanagedType.ByValArray, SizeConst = {})]", len); }
+                _ => {}
+            }
+            let _ = writeln!(out, "        public {} {}; // offset {}", ty, identifier(&camel_case(field.name)), field.offset);
+        }
+        out.push_str("    }\n");
+    }
+
+    // Callbacks - every function pointer field is a delegate
+    out.push_str("\n    /* Callbacks rust can call. */\n");
+    let mut seen = Vec::new();
+    for info in structs.iter(){
+        for field in info.fields.iter(){
+            if let (Kind::Function(arguments, returns), Some(function)) = (&field.kind, field.function_name){
+                let name = format!("{}Delegate", function);
+                if seen.contains(&name){
+                    continue;
+                }
+
+                let arguments = arguments.iter().enumerate()
+                    .map(|(index, kind)| (field.argument_names.get(index).copied().unwrap_or("arg"), kind.clone()))
+                    .collect::<Vec<_>>();
+                write_delegate(&mut out, &name, &arguments, returns);
+                seen.push(name);
+            }
+        }
+    }
+
+    // Exports
+    out.push_str("\n    /* Functions exported by a runity library. */\n");
+    for export in exports.iter(){
+        write_delegate(&mut out, &export_delegate(export), &export.arguments, &export.returns);
+    }
+
+    out.push_str("\n    /// <summary>\n");
+    out.push_str("    /// Every function exported by a runity library. Optional exports are null if the library\n");
+    out.push_str("    /// doesn't export them.\n");
+    out.push_str("    /// </summary>\n");
+    out.push_str("    public class RunityExports\n    {\n");
+    out.push_str("        // The ABI version this file describes. Libraries reporting a different version from\n");
+    out.push_str("        // `runityAbiVersion` must not be run.\n");
+    let _ = writeln!(out, "        public const uint AbiVersion = {};\n", crate::ABI_VERSION);
+    for export in exports.iter(){
+        let _ = writeln!(out, "        public {} {};{}", export_delegate(export), camel_case(export.name), if export.required { "" } else { " // Optional" });
+    }
+    out.push_str("\n        // The name of every required export the library is missing\n");
+    out.push_str("        public List<string> missing = new List<string>();\n\n");
+    out.push_str("        public static RunityExports Load(string dllName)\n        {\n");
+    out.push_str("            RunityExports exports = new RunityExports();\n");
+    out.push_str("            Delegate function;\n");
+    out.push_str("            IntPtr pointer;\n");
+    for export in exports.iter(){
+        let delegate = export_delegate(export);
+        let _ = writeln!(out, "\n            (function, pointer) = DLLPool.LoadFunctionFromDLL(dllName, \"{}\", typeof({}));", export.name, delegate);
+        let _ = writeln!(out, "            if (pointer != IntPtr.Zero) exports.{} = ({})function;", camel_case(export.name), delegate);
+        if export.required{
+            let _ = writeln!(out, "            else exports.missing.Add(\"{}\");", export.name);
+        }
+    }
+    out.push_str("\n            return exports;\n        }\n");
+
+    if exports.iter().any(|export| export.name == "runity_abi_version") && exports.iter().any(|export| export.name == "runity_layout_table"){
+        write_check_abi(&mut out, structs);
+    }
+
+    out.push_str("    }\n}\n");
+
+    Ok(out)
+}
+
+// Emits `RunityExports.CheckAbi`, which compares the library's layout table against the
+// layout the C# structs marshal to
+fn write_check_abi(out: &mut std::string::String, structs: &[StructInfo]){
+    out.push_str("\n        // The C# type and field names of every struct shared with rust, by their rust names\n");
+    out.push_str("        static readonly Dictionary<string, Type> sharedStructs = new Dictionary<string, Type>\n        {\n");
+    for info in structs.iter(){
+        let _ = writeln!(out, "            {{ \"{0}\", typeof({0}) }},", info.name);
+    }
+    out.push_str("        };\n\n");
+    out.push_str("        static readonly Dictionary<string, string> sharedFields = new Dictionary<string, string>\n        {\n");
+    for info in structs.iter(){
+        for field in info.fields.iter(){
+            let _ = writeln!(out, "            {{ \"{}.{}\", \"{}\" }},", info.name, field.name, camel_case(field.name));
+        }
+    }
+    out.push_str("        };\n\n");
+
+    out.push_str("        /// <summary>\n");
+    out.push_str("        /// Checks the library was built against the same ABI as this file, and that every shared\n");
+    out.push_str("        /// struct has the same layout on both sides. Returns null if it is compatible, or why it\n");
+    out.push_str("        /// isn't. This must be checked before calling into the library.\n");
+    out.push_str("        /// </summary>\n");
+    out.push_str("        public string CheckAbi()\n        {\n");
+    out.push_str("            if (runityAbiVersion == null || runityLayoutTable == null)\n");
+    out.push_str("                return \"library does not report its ABI version\";\n\n");
+    out.push_str("            uint version = runityAbiVersion();\n");
+    out.push_str("            if (version != AbiVersion)\n");
+    out.push_str("                return \"library was built against runity ABI version \" + version + \", but this host expects version \" + AbiVersion;\n\n");
+    out.push_str("            LayoutTable table = runityLayoutTable();\n");
+    out.push_str("            HashSet<string> described = new HashSet<string>();\n");
+    out.push_str("            for (int i = 0; i < table.len; i++)\n            {\n");
+    out.push_str("                LayoutEntry entry = Marshal.PtrToStructure<LayoutEntry>(table.entries + i * Marshal.SizeOf<LayoutEntry>());\n");
+    out.push_str("                string name = Marshal.PtrToStringAnsi(entry.name);\n");
+    out.push_str("                if (!sharedStructs.TryGetValue(name, out Type type))\n                    continue;\n\n");
+    out.push_str("                described.Add(name);\n");
+    out.push_str("                if (Marshal.SizeOf(type) != entry.size)\n");
+    out.push_str("                    return name + \" is \" + entry.size + \" bytes in the library, but \" + Marshal.SizeOf(type) + \" bytes in C#\";\n\n");
+    out.push_str("                for (int j = 0; j < entry.fieldCount; j++)\n                {\n");
+    out.push_str("                    LayoutField field = Marshal.PtrToStructure<LayoutField>(entry.fields + j * Marshal.SizeOf<LayoutField>());\n");
+    out.push_str("                    string fieldName = name + \".\" + Marshal.PtrToStringAnsi(field.name);\n");
+    out.push_str("                    if (!sharedFields.TryGetValue(fieldName, out string csharpName))\n");
+    out.push_str("                        return fieldName + \" is in the library, but not C#\";\n");
+    out.push_str("                    long offset = Marshal.OffsetOf(type, csharpName).ToInt64();\n");
+    out.push_str("                    if (offset != field.offset)\n");
+    out.push_str("                        return fieldName + \" is at offset \" + field.offset + \" in the library, but \" + offset + \" in C#\";\n");
+    out.push_str("                }\n            }\n\n");
+    out.push_str("            foreach (string name in sharedStructs.Keys)\n            {\n");
+    out.push_str("                if (!described.Contains(name))\n");
+    out.push_str("                    return \"library does not describe \" + name;\n");
+    out.push_str("            }\n\n");
+    out.push_str("            return null;\n        }\n");
+}
+
+fn write_delegate(out: &mut std::string::String, name: &str, arguments: &[(&str, Kind)], returns: &Kind){
+    out.push_str("\n    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]\n");
+    if *returns == Kind::Bool{
+        out.push_str("    [return: MarshalAs(UnmanagedType.U1)]\n");
+    }
+
+    let arguments = arguments.iter()
+        .map(|(name, kind)| format!("{}{} {}", if *kind == Kind::Bool { "[MarshalAs(UnmanagedType.U1)] " } else { "" }, argument_type(kind), identifier(&camel_case(name))))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let _ = writeln!(out, "    public delegate {} {}({});", field_type(returns), name, arguments);
+}
+
+fn export_delegate(export: &ExportInfo) -> std::string::String{
+    format!("{}Delegate", pascal_case(export.name))
+}
+
+// The C# type of a value stored in a struct, or returned
+fn field_type(kind: &Kind) -> std::string::String{
+    match kind{
+        Kind::Void => "void".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::U8 => "byte".to_string(),
+        Kind::I32 => "int".to_string(),
+        Kind::U32 => "uint".to_string(),
+        Kind::U64 => "ulong".to_string(),
+        Kind::F32 => "float".to_string(),
+        Kind::Struct(name) => name.to_string(),
+        Kind::Array(inner, _) => format!("{}[]", field_type(inner)),
+        Kind::CString | Kind::Ref(_) | Kind::RefMut(_) | Kind::Pointer(_) | Kind::Function(..) => "IntPtr".to_string(),
+    }
+}
+
+// The C# type of an argument - references to values are passed with `ref`
+fn argument_type(kind: &Kind) -> std::string::String{
+    match kind{
+        Kind::Ref(inner) | Kind::RefMut(inner) if !matches!(**inner, Kind::Ref(_) | Kind::RefMut(_) | Kind::Pointer(_) | Kind::Function(..)) => format!("ref {}", field_type(inner)),
+        kind => field_type(kind),
+    }
+}
+
+// Escapes names which are C# keywords (`string` becomes `@string`)
+fn identifier(name: &str) -> std::string::String{
+    const KEYWORDS: &[&str] = &[
+        "base", "bool", "byte", "char", "checked", "class", "const", "decimal", "default", "delegate", "double",
+        "event", "fixed", "float", "in", "int", "internal", "is", "lock", "long", "object", "operator", "out",
+        "params", "ref", "sbyte", "short", "sizeof", "string", "this", "uint", "ulong", "ushort", "value",
+    ];
+
+    if KEYWORDS.contains(&name){
+        format!("@{}", name)
+    }
+    else{
+        name.to_string()
+    }
+}
+
+fn camel_case(name: &str) -> std::string::String{
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next(){
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => pascal,
+    }
+}
+
+fn pascal_case(name: &str) -> std::string::String{
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next(){
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => std::string::String::new(),
+            }
+        })
+        .collect()
+}
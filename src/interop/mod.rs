@@ -0,0 +1,390 @@
+//! Interop describes the C ABI runity shares with its host.
+//!
+//! Every `#[repr(C)]` type passed across the boundary describes itself here - its size,
+//! alignment and the offset and type of each field - along with every function the
+//! library exports. The descriptions are built from the rust definitions themselves
+//! (through `reflect!`), so they can't drift from them.
+//!
+//! The generators (`csharp` and `c`) then emit the host side of the boundary from these
+//! descriptions, rather than it being written by hand.
+
+use std::mem::{align_of, size_of};
+
+use libc::c_char;
+
+use crate::{Axis, ChangeBuffer, Collision, Color, Command, ComponentAccess, ContactPoint, DataStruct, Debug, Drawing, FoundGameObject, FoundHit, GameObject, GameObjectChanges, GameObjectLookup, Input, InputSnapshot, Instance, LayoutEntry, LayoutField, LayoutTable, LogRecord, Message, Physics, PhysicsQuery, Profiler, Property, PropertyValue, Quaternion, ReceivedMessage, SpanSample, State, Str, String, Time, Transform, Trigger, Vector3};
+
+pub mod c;
+pub mod csharp;
+
+/// # Kind
+///
+/// The kind of a value passed across the boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind{
+    /// No value, only used as a return type.
+    Void,
+    Bool,
+    U8,
+    I32,
+    U32,
+    U64,
+    F32,
+    /// A pointer to a NUL terminated C string.
+    CString,
+    /// A `#[repr(C)]` struct passed by value, by name.
+    Struct(&'static str),
+    /// A reference to a value, that is never null.
+    Ref(Box<Kind>),
+    /// A mutable reference to a value, that is never null.
+    RefMut(Box<Kind>),
+    /// A raw pointer to a value.
+    Pointer(Box<Kind>),
+    /// A fixed number of values, stored inline. Only used for struct fields.
+    Array(Box<Kind>, usize),
+    /// A function pointer, with its argument and return kinds.
+    Function(Vec<Kind>, Box<Kind>),
+}
+
+/// # Reflect
+///
+/// Implemented by every type that can be passed across the boundary.
+pub trait Reflect{
+    /// # Kind
+    ///
+    /// Returns the kind of this type.
+    fn kind() -> Kind;
+
+    /// # Describe
+    ///
+    /// Returns the layout of this type, if it is a `#[repr(C)]` struct.
+    fn describe() -> Option<StructInfo>{
+        None
+    }
+}
+
+/// # Field Info
+///
+/// A single field of a `#[repr(C)]` struct.
+#[derive(Debug, Clone)]
+pub struct FieldInfo{
+    pub name: &'static str,
+    pub kind: Kind,
+    pub offset: usize,
+    pub size: usize,
+    pub align: usize,
+    /// For function pointer fields, the name of the function type (`Log` becomes `LogDelegate` in C#).
+    pub function_name: Option<&'static str>,
+    /// For function pointer fields, the name of each argument.
+    pub argument_names: Vec<&'static str>,
+}
+
+/// # Struct Info
+///
+/// The layout of a `#[repr(C)]` struct.
+#[derive(Debug, Clone)]
+pub struct StructInfo{
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    pub fields: Vec<FieldInfo>,
+}
+
+impl StructInfo{
+    /// # Validate
+    ///
+    /// Checks the described fields lay out to exactly the size of the struct, in order.
+    /// This catches a field missing from (or out of order in) a description.
+    pub fn validate(&self) -> Result<(), std::string::String>{
+        let align_up = |offset: usize, align: usize| offset.div_ceil(align) * align;
+
+        let mut offset = 0;
+        for field in self.fields.iter(){
+            offset = align_up(offset, field.align);
+            if offset != field.offset{
+                return Err(format!("`{}::{}` is at offset {}, but the description lays it out at {}", self.name, field.name, field.offset, offset));
+            }
+            offset += field.size;
+        }
+
+        let size = align_up(offset, self.align);
+        if size != self.size{
+            return Err(format!("`{}` is {} bytes, but its fields only describe {} - is a field missing?", self.name, self.size, size));
+        }
+
+        Ok(())
+    }
+}
+
+// Builds a field description from an accessor, so the field's kind and size come from its real type.
+#[doc(hidden)]
+pub fn field_info<S, T: Reflect>(name: &'static str, offset: usize, _field: fn(&S) -> &T) -> FieldInfo{
+    FieldInfo{
+        name,
+        kind: T::kind(),
+        offset,
+        size: size_of::<T>(),
+        align: align_of::<T>(),
+        function_name: None,
+        argument_names: Vec::new(),
+    }
+}
+
+/// # Reflect
+///
+/// Implements `Reflect` for a `#[repr(C)]` struct, from a list of its fields. Function
+/// pointer fields also name their function type and arguments:
+///
+/// ```ignore
+/// reflect!(Debug{
+///     log: fn Log(message),
+/// });
+/// ```
+///
+/// Structs that borrow are reflected with an anonymous lifetime (`reflect!(Str<'_>{ ptr, len })`).
+///
+/// This has to be used in the module the struct is defined in, so private fields can be reached.
+macro_rules! reflect{
+    ($name:ident $(<$lifetime:lifetime>)? { $( $field:ident $( : fn $function:ident ( $( $argument:ident ),* ) )? ),* $(,)? }) => {
+        impl $crate::interop::Reflect for $name $(<$lifetime>)?{
+            fn kind() -> $crate::interop::Kind{
+                $crate::interop::Kind::Struct(stringify!($name))
+            }
+
+            fn describe() -> Option<$crate::interop::StructInfo>{
+                Some($crate::interop::StructInfo{
+                    name: stringify!($name),
+                    size: std::mem::size_of::<$name>(),
+                    align: std::mem::align_of::<$name>(),
+                    fields: vec![
+                        $({
+                            #[allow(unused_mut)]
+                            let mut field = $crate::interop::field_info(stringify!($field), std::mem::offset_of!($name, $field), |value: &$name| &value.$field);
+                            $(
+                                field.function_name = Some(stringify!($function));
+                                field.argument_names = vec![$( stringify!($argument) ),*];
+                            )?
+                            field
+                        }),*
+                    ],
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use reflect;
+
+macro_rules! reflect_primitive{
+    ($($ty:ty => $kind:ident),* $(,)?) => {
+        $(
+            impl Reflect for $ty{
+                fn kind() -> Kind{
+                    Kind::$kind
+                }
+            }
+        )*
+    };
+}
+
+reflect_primitive!{
+    () => Void,
+    bool => Bool,
+    u8 => U8,
+    i32 => I32,
+    u32 => U32,
+    u64 => U64,
+    f32 => F32,
+    *mut c_char => CString,
+    *const c_char => CString,
+}
+
+impl<T: Reflect> Reflect for &T{
+    fn kind() -> Kind{
+        Kind::Ref(Box::new(T::kind()))
+    }
+}
+
+impl<T: Reflect> Reflect for &mut T{
+    fn kind() -> Kind{
+        Kind::RefMut(Box::new(T::kind()))
+    }
+}
+
+impl<T: Reflect> Reflect for *mut T{
+    fn kind() -> Kind{
+        Kind::Pointer(Box::new(T::kind()))
+    }
+}
+
+impl<T: Reflect> Reflect for *const T{
+    fn kind() -> Kind{
+        Kind::Pointer(Box::new(T::kind()))
+    }
+}
+
+impl<T: Reflect, const N: usize> Reflect for [T; N]{
+    fn kind() -> Kind{
+        Kind::Array(Box::new(T::kind()), N)
+    }
+}
+
+// Function pointers are described one signature at a time. A blanket implementation over
+// every argument type can't also cover callbacks that borrow (`for<'a> extern "C" fn(&'a String)`),
+// as the two overlap.
+macro_rules! reflect_callback{
+    ($( fn ( $( $argument:ty ),* ) $( -> $returns:ty )? ;)*) => {
+        $(
+            impl Reflect for extern "C" fn($($argument),*) $( -> $returns )?{
+                fn kind() -> Kind{
+                    Kind::Function(vec![$( <$argument as Reflect>::kind() ),*], Box::new(reflect_callback!(@returns $($returns)?)))
+                }
+            }
+        )*
+    };
+    (@returns) => { Kind::Void };
+    (@returns $returns:ty) => { <$returns as Reflect>::kind() };
+}
+
+reflect_callback!{
+    fn(Str);
+    fn(Str, *mut FoundGameObject) -> bool;
+    fn(Str, *mut FoundGameObject, u32) -> u32;
+    fn(u64, *mut FoundGameObject) -> bool;
+    fn(u64) -> u32;
+    fn(u64, u32, *mut FoundGameObject) -> bool;
+    fn(u64, Str) -> bool;
+    fn(u64, Str, *mut Property, u32);
+    fn(u64, Str, Str, *const PropertyValue, u32) -> bool;
+    fn(&PhysicsQuery, *mut FoundHit, u32) -> u32;
+    fn(&LogRecord);
+    fn();
+}
+
+/// # Export Info
+///
+/// A function exported by a runity library.
+#[derive(Debug, Clone)]
+pub struct ExportInfo{
+    pub name: &'static str,
+    pub arguments: Vec<(&'static str, Kind)>,
+    pub returns: Kind,
+    /// Whether every runity library exports this function. Optional exports may be missing.
+    pub required: bool,
+}
+
+macro_rules! export{
+    ($required:expr, fn $name:ident ( $( $argument:ident : $ty:ty ),* ) $( -> $returns:ty )?) => {
+        ExportInfo{
+            name: stringify!($name),
+            arguments: vec![$( (stringify!($argument), <$ty as Reflect>::kind()) ),*],
+            returns: export!(@returns $($returns)?),
+            required: $required,
+        }
+    };
+    (@returns) => { Kind::Void };
+    (@returns $returns:ty) => { <$returns as Reflect>::kind() };
+}
+
+/// # Structs
+///
+/// Returns the layout of every `#[repr(C)]` struct shared with the host, dependencies first.
+pub fn structs() -> Vec<StructInfo>{
+    vec![
+        String::describe(),
+        Str::describe(),
+        Vector3::describe(),
+        Quaternion::describe(),
+        Transform::describe(),
+        FoundGameObject::describe(),
+        GameObjectLookup::describe(),
+        PropertyValue::describe(),
+        Property::describe(),
+        ComponentAccess::describe(),
+        GameObject::describe(),
+        GameObjectChanges::describe(),
+        Command::describe(),
+        Message::describe(),
+        Color::describe(),
+        Drawing::describe(),
+        ChangeBuffer::describe(),
+        Time::describe(),
+        Axis::describe(),
+        InputSnapshot::describe(),
+        Input::describe(),
+        LogRecord::describe(),
+        Debug::describe(),
+        PhysicsQuery::describe(),
+        FoundHit::describe(),
+        Physics::describe(),
+        Profiler::describe(),
+        DataStruct::describe(),
+        State::describe(),
+        ContactPoint::describe(),
+        Collision::describe(),
+        Trigger::describe(),
+        ReceivedMessage::describe(),
+        SpanSample::describe(),
+        LayoutField::describe(),
+        LayoutEntry::describe(),
+        LayoutTable::describe(),
+    ].into_iter().flatten().collect()
+}
+
+/// # Exports
+///
+/// Returns every function a runity library exports.
+///
+/// The lifecycle functions (`awake` through `destroy`), `runity_save_state` and `runity_load_state`
+/// are generated by `#[runity::script]` (or written by hand), the rest are exported by runity itself.
+pub fn exports() -> Vec<ExportInfo>{
+    // Make sure the descriptions below match the functions runity exports
+    let _: extern "C" fn(DataStruct) = crate::data::set_data;
+    let _: extern "C" fn(Instance) -> bool = crate::data::runity_is_faulted;
+    let _: extern "C" fn(Instance) -> ChangeBuffer = crate::data::runity_changes_acquire;
+    let _: extern "C" fn(Instance) = crate::data::runity_changes_release;
+    let _: extern "C" fn(Instance, *const ReceivedMessage, u32) = crate::data::runity_post_messages;
+    let _: extern "C" fn(*mut SpanSample<'static>, u32) -> u32 = crate::span_timer::runity_span_samples;
+    let _: extern "C" fn(Profiler) = crate::profiler::runity_set_profiler;
+    let _: extern "C" fn() = crate::profiler::runity_clear_profiler;
+    let _: extern "C" fn(Str) -> bool = crate::profiler::runity_write_chrome_trace;
+    let _: extern "C" fn() -> u32 = crate::abi::runity_abi_version;
+    let _: extern "C" fn() -> LayoutTable = crate::abi::runity_layout_table;
+    let _: extern "C" fn(String) = crate::string::runity_string_free;
+    let _: extern "C" fn(State) = crate::state::runity_state_free;
+
+    vec![
+        export!(false, fn awake(data: DataStruct) -> DataStruct),
+        export!(false, fn start(data: DataStruct) -> DataStruct),
+        export!(false, fn update(data: DataStruct) -> DataStruct),
+        export!(false, fn fixed_update(data: DataStruct) -> DataStruct),
+        export!(false, fn late_update(data: DataStruct) -> DataStruct),
+        export!(false, fn on_enable(data: DataStruct) -> DataStruct),
+        export!(false, fn on_disable(data: DataStruct) -> DataStruct),
+        export!(false, fn on_application_focus(data: DataStruct, focused: bool) -> DataStruct),
+        export!(false, fn on_application_pause(data: DataStruct, paused: bool) -> DataStruct),
+        export!(false, fn on_application_quit(data: DataStruct) -> DataStruct),
+        export!(false, fn on_collision_enter(data: DataStruct, collision: &Collision) -> DataStruct),
+        export!(false, fn on_collision_stay(data: DataStruct, collision: &Collision) -> DataStruct),
+        export!(false, fn on_collision_exit(data: DataStruct, collision: &Collision) -> DataStruct),
+        export!(false, fn on_trigger_enter(data: DataStruct, other: &Trigger) -> DataStruct),
+        export!(false, fn on_trigger_stay(data: DataStruct, other: &Trigger) -> DataStruct),
+        export!(false, fn on_trigger_exit(data: DataStruct, other: &Trigger) -> DataStruct),
+        export!(true, fn destroy(data: DataStruct) -> i32),
+        export!(false, fn runity_save_state(instance: Instance) -> State),
+        export!(false, fn runity_load_state(data: &mut DataStruct, state: State) -> i32),
+        export!(true, fn set_data(data: DataStruct)),
+        export!(true, fn runity_is_faulted(instance: Instance) -> bool),
+        export!(true, fn runity_changes_acquire(instance: Instance) -> ChangeBuffer),
+        export!(true, fn runity_changes_release(instance: Instance)),
+        export!(true, fn runity_post_messages(instance: Instance, messages: *const ReceivedMessage, count: u32)),
+        export!(true, fn runity_span_samples(samples: *mut SpanSample, capacity: u32) -> u32),
+        export!(true, fn runity_set_profiler(profiler: Profiler)),
+        export!(true, fn runity_clear_profiler()),
+        export!(true, fn runity_write_chrome_trace(path: Str) -> bool),
+        export!(true, fn runity_abi_version() -> u32),
+        export!(true, fn runity_layout_table() -> LayoutTable),
+        export!(true, fn runity_string_free(string: String)),
+        export!(true, fn runity_state_free(state: State)),
+    ]
+}
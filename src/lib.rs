@@ -7,21 +7,49 @@ mod gameobject;
 mod string;
 mod time;
 mod debug;
-
-/// Define a NULL ptr for our string. This will help us
-/// prevent allocating an invalid string
-const NULL: *mut c_char = null::<c_char>() as *mut c_char;
-
-use libc::c_char;
-use std::ptr::null;
+mod script;
+mod panic;
+mod abi;
+mod state;
+mod collision;
+mod input;
+mod command;
+mod color;
+mod component;
+mod physics;
+mod message;
+mod coroutine;
+mod draw;
+mod logger;
+mod span_timer;
+mod profiler;
+pub mod interop;
 
 pub use vector3::Vector3;
-pub use transform::Transform;
-pub use gameobject::GameObject;
-use gameobject::GameObjectChanges;
+pub use transform::{Space, Transform};
+pub use gameobject::{FoundGameObject, GameObject, GameObjectChanges, GameObjectLookup};
 pub use math::Math;
-pub use data::DataStruct;
-pub use string::String;
+pub use data::{ChangeBuffer, DataStruct, Instance, LocalData};
+pub use string::{Str, String};
 pub use quaternion::Quaternion;
 pub use time::Time;
-use debug::Debug;
+pub use debug::{Debug, LogLevel, LogRecord};
+pub use script::{Script, ScriptStore};
+pub use state::State;
+pub use collision::{Collision, ContactPoint, Trigger};
+pub use color::Color;
+pub use component::{Collider, Component, ComponentAccess, ComponentRef, ForceMode, Light, Property, PropertyValue, Renderer, Rigidbody, Value};
+pub use physics::{FoundHit, Physics, PhysicsQuery, QueryShape, RaycastHit};
+pub use message::{Event, Message, MessageKind, ReceivedMessage, Recipients};
+pub use draw::{Draw, Drawing, DrawingKind};
+pub use coroutine::{wait_fixed_update, wait_frames, wait_seconds, wait_seconds_realtime, wait_until, with_data, CoroutineHandle, WaitFixedUpdate, WaitFrames, WaitSeconds, WaitUntil};
+pub use command::{Command, CommandFailure, CommandKind, CommandStatus, Handle, Prefab};
+pub use input::{Axis, Input, InputSnapshot, KeyCode, KEY_COUNT};
+pub use panic::{catch_panic, Status};
+pub use logger::Logger;
+#[doc(hidden)]
+pub use logger::log as __log;
+pub use span_timer::{SpanSample, SpanTimer};
+pub use profiler::{FrameProfiler, ProfileSample, ProfileScope, Profiler};
+pub use abi::{check_abi, AbiMismatch, LayoutEntry, LayoutField, LayoutTable, ABI_VERSION};
+pub use runity_macros::script;
@@ -0,0 +1,466 @@
+//! A `log` backend, so `log::info!` and friends (including from other crates) reach unity's console.
+//!
+//! The logger is installed the first time unity calls into the library. Records are written
+//! through the `Debug` of the entry point being run - errors through `log_error`, warnings
+//! through `log_warning` and everything else through `log`. Records logged anywhere else (on a
+//! worker thread, or between entry points) are queued, and written at the start of the next
+//! entry point on the thread unity calls from.
+//!
+//! Levels can be changed at runtime, for everything with `Logger::set_level` or for a single
+//! module (and its children) with `Logger::set_module_level`.
+//!
+//! runity's own `log!`, `warn!` and `error!` macros go through the same filters and queue, but
+//! don't need the `log` crate. Like `log`'s records, they are written to unity as a `LogRecord`
+//! with the module, file and line they were logged from. Messages are formatted into a buffer
+//! kept by each thread, and lent to unity from there, so logging doesn't allocate once the buffer
+//! has grown to fit.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock};
+
+use lazy_static::lazy_static;
+use log::{Level, LevelFilter, Metadata, Record};
+
+use crate::{Debug, LogLevel, LogRecord};
+
+// Queued records past this are dropped (and counted), so a busy worker can't grow the queue forever
+const QUEUE_CAPACITY: usize = 1024;
+// A thread's message buffer is shrunk back to this after a longer message, so one huge message isn't kept around
+const BUFFER_CAPACITY: usize = 4096;
+
+/// # Logger
+///
+/// The `log::Log` implementation runity installs. It is only ever used through its
+/// associated functions.
+pub struct Logger{
+    _private: (),
+}
+
+static LOGGER: Logger = Logger{ _private: () };
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+// The levels records are filtered with
+struct Filters{
+    default: LevelFilter,
+    modules: BTreeMap<std::string::String, LevelFilter>,
+}
+
+// A record waiting for an entry point
+struct Queued{
+    level: LogLevel,
+    message: std::string::String,
+    target: std::string::String,
+    file: std::string::String,
+    line: u32,
+}
+
+// Records waiting for an entry point
+struct Queue{
+    records: VecDeque<Queued>,
+    dropped: usize,
+}
+
+lazy_static!{
+    static ref FILTERS: RwLock<Filters> = RwLock::new(Filters{
+        default: LevelFilter::Info,
+        modules: BTreeMap::new(),
+    });
+    static ref QUEUE: Mutex<Queue> = Mutex::new(Queue{
+        records: VecDeque::new(),
+        dropped: 0,
+    });
+}
+
+thread_local!{
+    // The debug of the entry point running on this thread, if any
+    static CURRENT: Cell<Option<Debug>> = const{ Cell::new(None) };
+    // Messages are formatted into this, and lent to unity from it
+    static BUFFER: RefCell<std::string::String> = const{ RefCell::new(std::string::String::new()) };
+}
+
+impl Filters{
+    // The level for `target`, from the most specific module that contains it
+    fn level(&self, target: &str) -> LevelFilter{
+        self.modules.iter()
+            .filter(|(module, _)| target == module.as_str() || (target.starts_with(module.as_str()) && target[module.len()..].starts_with("::")))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter{
+        self.modules.values().copied().fold(self.default, Ord::max)
+    }
+}
+
+impl Logger{
+    /// # Install
+    ///
+    /// Installs runity's logger as the `log` backend. This is done the first time unity calls
+    /// into the library, so it only needs calling to log before then.
+    ///
+    /// Returns false if a different logger was installed first, in which case it is left alone.
+    pub fn install() -> bool{
+        if INSTALLED.load(Ordering::Acquire){
+            return true;
+        }
+
+        match log::set_logger(&LOGGER){
+            Ok(()) => {
+                INSTALLED.store(true, Ordering::Release);
+                log::set_max_level(filters().max_level());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// # Set Level
+    ///
+    /// Sets the level records are logged at, for every module without its own level.
+    /// Defaults to `LevelFilter::Info`.
+    pub fn set_level(level: LevelFilter){
+        Self::configure(|filters| filters.default = level);
+    }
+
+    /// # Set Module Level
+    ///
+    /// Sets the level records from `module` (a path such as `my_game::ai`, or a crate name)
+    /// and its children are logged at. The most specific module wins.
+    pub fn set_module_level(module: &str, level: LevelFilter){
+        Self::configure(|filters| {
+            filters.modules.insert(module.to_string(), level);
+        });
+    }
+
+    /// # Clear Module Level
+    ///
+    /// Removes the level set for `module`, so it uses its parent's again.
+    pub fn clear_module_level(module: &str){
+        Self::configure(|filters| {
+            filters.modules.remove(module);
+        });
+    }
+
+    fn configure(f: impl FnOnce(&mut Filters)){
+        let mut filters = FILTERS.write().unwrap_or_else(PoisonError::into_inner);
+        f(&mut filters);
+
+        // `log` skips anything above the max level before it reaches the logger
+        if INSTALLED.load(Ordering::Acquire){
+            log::set_max_level(filters.max_level());
+        }
+    }
+}
+
+fn filters() -> std::sync::RwLockReadGuard<'static, Filters>{
+    FILTERS.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl log::Log for Logger{
+    fn enabled(&self, metadata: &Metadata) -> bool{
+        enabled(metadata.level(), metadata.target())
+    }
+
+    fn log(&self, record: &Record){
+        if self.enabled(record.metadata()){
+            submit_args(record.level().into(), record.target(), record.file().unwrap_or(""), record.line().unwrap_or(0), *record.args());
+        }
+    }
+
+    fn flush(&self){
+        if let Some(debug) = CURRENT.with(|current| current.get()){
+            flush(&debug);
+        }
+    }
+}
+
+/// Logs a record that has already been filtered, such as a `tracing` event. It is written
+/// straight away inside an entry point, and queued anywhere else.
+pub(crate) fn submit(level: LogLevel, target: &str, file: &str, line: u32, message: &str){
+    match CURRENT.with(|current| current.get()){
+        Some(debug) => {
+            // Anything queued was logged first
+            flush(&debug);
+            debug.record(&LogRecord::new(level, message, target, file, line));
+        }
+        None => {
+            let mut queue = QUEUE.lock().unwrap_or_else(PoisonError::into_inner);
+            if queue.records.len() < QUEUE_CAPACITY{
+                queue.records.push_back(Queued{
+                    level,
+                    message: message.to_string(),
+                    target: target.to_string(),
+                    file: file.to_string(),
+                    line,
+                });
+            }else{
+                queue.dropped += 1;
+            }
+        }
+    }
+}
+
+/// Formats a record that has already been filtered into this thread's buffer, and logs it
+/// like `submit`.
+pub(crate) fn submit_args(level: LogLevel, target: &str, file: &str, line: u32, args: fmt::Arguments){
+    // A message that is a single literal needs no formatting
+    if let Some(message) = args.as_str(){
+        submit(level, target, file, line, message);
+        return;
+    }
+
+    BUFFER.with(|buffer| match buffer.try_borrow_mut(){
+        Ok(mut buffer) => {
+            buffer.clear();
+            let _ = buffer.write_fmt(args);
+            submit(level, target, file, line, &buffer);
+
+            if buffer.capacity() > BUFFER_CAPACITY{
+                buffer.clear();
+                buffer.shrink_to(BUFFER_CAPACITY);
+            }
+        }
+        // Logged while another message was being formatted (from a `Display` implementation, say)
+        Err(_) => submit(level, target, file, line, &args.to_string()),
+    });
+}
+
+/// Logs a message from the `log!`, `warn!` and `error!` macros.
+#[doc(hidden)]
+pub fn log(level: LogLevel, target: &'static str, file: &'static str, line: u32, args: fmt::Arguments){
+    if enabled(level.into(), target){
+        submit_args(level, target, file, line, args);
+    }
+}
+
+/// Returns whether a record at `level` from `target` would be logged.
+pub(crate) fn enabled(level: Level, target: &str) -> bool{
+    level <= filters().level(target)
+}
+
+// Writes every queued record through `debug`
+fn flush(debug: &Debug){
+    let (records, dropped) = {
+        let mut queue = QUEUE.lock().unwrap_or_else(PoisonError::into_inner);
+        if queue.records.is_empty() && queue.dropped == 0{
+            return;
+        }
+        (std::mem::take(&mut queue.records), std::mem::take(&mut queue.dropped))
+    };
+
+    for record in records{
+        debug.record(&LogRecord::new(record.level, &record.message, &record.target, &record.file, record.line));
+    }
+    if dropped > 0{
+        debug.log_warning(format!("runity: {} log records were dropped while waiting to be logged", dropped));
+    }
+}
+
+// Lends an entry point's debug to the logger until dropped
+pub(crate) struct Entered{
+    previous: Option<Debug>,
+}
+
+impl Drop for Entered{
+    fn drop(&mut self){
+        CURRENT.with(|current| current.set(self.previous));
+    }
+}
+
+/// Called at the start of every entry point with a `Debug`, installing the logger (the first
+/// time) and writing anything queued since the last one.
+pub(crate) fn enter(debug: &Debug) -> Entered{
+    Logger::install();
+    flush(debug);
+
+    Entered{
+        previous: CURRENT.with(|current| current.replace(Some(*debug))),
+    }
+}
+
+/// # Log
+///
+/// Logs a formatted message to the console in unity, like `println!`. The record carries the
+/// module, file and line it was logged from, and is filtered by the levels set on `Logger`.
+///
+/// Inside an entry point, the message is formatted into a buffer kept by the thread and lent
+/// straight to unity. Anywhere else it is queued, and logged at the start of the next entry point.
+///
+/// ```
+/// # let count = 3;
+/// runity::log!("spawned {} enemies", count);
+/// ```
+#[macro_export]
+macro_rules! log{
+    ($($argument:tt)+) => {
+        $crate::__log($crate::LogLevel::Info, ::core::module_path!(), ::core::file!(), ::core::line!(), ::core::format_args!($($argument)+))
+    };
+}
+
+/// # Warn
+///
+/// Logs a formatted message to the console in unity as a warning, like `log!`.
+#[macro_export]
+macro_rules! warn{
+    ($($argument:tt)+) => {
+        $crate::__log($crate::LogLevel::Warning, ::core::module_path!(), ::core::file!(), ::core::line!(), ::core::format_args!($($argument)+))
+    };
+}
+
+/// # Error
+///
+/// Logs a formatted message to the console in unity as an error, like `log!`.
+#[macro_export]
+macro_rules! error{
+    ($($argument:tt)+) => {
+        $crate::__log($crate::LogLevel::Error, ::core::module_path!(), ::core::file!(), ::core::line!(), ::core::format_args!($($argument)+))
+    };
+}
+
+#[cfg(test)]
+pub(crate) mod tests{
+    use super::*;
+    use std::sync::MutexGuard;
+
+    use crate::Str;
+
+    // A record written through the capturing `Debug`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct Captured{
+        pub level: LogLevel,
+        pub target: std::string::String,
+        pub message: std::string::String,
+    }
+
+    lazy_static!{
+        // The filters and queue are shared by every test, so tests using them take turns
+        static ref SERIAL: Mutex<()> = Mutex::new(());
+    }
+
+    thread_local!{
+        static CAPTURED: RefCell<Vec<Captured>> = const{ RefCell::new(Vec::new()) };
+    }
+
+    fn capture(level: LogLevel, target: &str, message: Str){
+        CAPTURED.with(|captured| captured.borrow_mut().push(Captured{ level, target: target.to_string(), message: message.to_string() }));
+    }
+
+    extern "C" fn log(message: Str){
+        capture(LogLevel::Info, "", message);
+    }
+
+    extern "C" fn log_warning(message: Str){
+        capture(LogLevel::Warning, "", message);
+    }
+
+    extern "C" fn log_error(message: Str){
+        capture(LogLevel::Error, "", message);
+    }
+
+    extern "C" fn log_record(record: &LogRecord){
+        capture(record.level(), &record.target().to_string(), record.message());
+    }
+
+    /// Takes the logger for a test, with anything left queued by an earlier test thrown away.
+    /// Records written through the returned `Debug` are read back with `captured`.
+    pub(crate) fn serial() -> (MutexGuard<'static, ()>, Debug){
+        let guard = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+        let debug = Debug::new(log, log_warning, log_error, log_record);
+
+        flush(&debug);
+        captured();
+        (guard, debug)
+    }
+
+    /// Every record written on this thread since the last call.
+    pub(crate) fn captured() -> Vec<Captured>{
+        CAPTURED.with(|captured| std::mem::take(&mut *captured.borrow_mut()))
+    }
+
+    fn filters(levels: &[(&str, LevelFilter)]) -> Filters{
+        Filters{
+            default: LevelFilter::Info,
+            modules: levels.iter().map(|(module, level)| (module.to_string(), *level)).collect(),
+        }
+    }
+
+    #[test]
+    fn most_specific_module_wins(){
+        let filters = filters(&[("game", LevelFilter::Warn), ("game::ai", LevelFilter::Trace), ("game::ai::path", LevelFilter::Off)]);
+
+        assert_eq!(filters.level("game"), LevelFilter::Warn);
+        assert_eq!(filters.level("game::ui"), LevelFilter::Warn);
+        assert_eq!(filters.level("game::ai"), LevelFilter::Trace);
+        assert_eq!(filters.level("game::ai::brain"), LevelFilter::Trace);
+        assert_eq!(filters.level("game::ai::path::astar"), LevelFilter::Off);
+        assert_eq!(filters.level("other"), LevelFilter::Info);
+        assert_eq!(filters.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn modules_only_match_whole_path_segments(){
+        let filters = filters(&[("game", LevelFilter::Error), ("game::ai", LevelFilter::Debug)]);
+
+        assert_eq!(filters.level("gameplay"), LevelFilter::Info);
+        assert_eq!(filters.level("gameplay::ai"), LevelFilter::Info);
+        assert_eq!(filters.level("game::aim"), LevelFilter::Error);
+        assert_eq!(filters.level("game:ai"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn records_queued_on_other_threads_are_written_in_order_on_enter(){
+        let (_serial, debug) = serial();
+
+        std::thread::spawn(|| {
+            for index in 0..3{
+                submit(LogLevel::Info, "worker", "worker.rs", index, &format!("record {}", index));
+            }
+            submit(LogLevel::Warning, "worker::io", "io.rs", 7, "slow disk");
+        }).join().unwrap();
+        assert_eq!(captured(), []);
+
+        let _entered = enter(&debug);
+        let written: Vec<_> = captured().into_iter().map(|record| (record.level, record.target, record.message)).collect();
+        assert_eq!(written, [
+            (LogLevel::Info, "worker".to_string(), "record 0".to_string()),
+            (LogLevel::Info, "worker".to_string(), "record 1".to_string()),
+            (LogLevel::Info, "worker".to_string(), "record 2".to_string()),
+            (LogLevel::Warning, "worker::io".to_string(), "slow disk".to_string()),
+        ]);
+
+        // Inside the entry point, records are written straight away
+        submit(LogLevel::Error, "main", "main.rs", 1, "now");
+        assert_eq!(captured(), [Captured{ level: LogLevel::Error, target: "main".to_string(), message: "now".to_string() }]);
+    }
+
+    #[test]
+    fn records_past_the_queue_capacity_are_dropped_and_counted(){
+        let (_serial, debug) = serial();
+
+        std::thread::spawn(|| {
+            for index in 0..QUEUE_CAPACITY + 5{
+                submit(LogLevel::Info, "worker", "worker.rs", 1, &index.to_string());
+            }
+        }).join().unwrap();
+
+        let _entered = enter(&debug);
+        let written = captured();
+        assert_eq!(written.len(), QUEUE_CAPACITY + 1);
+        // The oldest records are kept
+        assert_eq!(written[0].message, "0");
+        assert_eq!(written[QUEUE_CAPACITY - 1].message, (QUEUE_CAPACITY - 1).to_string());
+        assert_eq!(written[QUEUE_CAPACITY], Captured{
+            level: LogLevel::Warning,
+            target: std::string::String::new(),
+            message: "runity: 5 log records were dropped while waiting to be logged".to_string(),
+        });
+
+        // The count starts again once it has been reported
+        flush(&debug);
+        assert_eq!(captured(), []);
+    }
+}
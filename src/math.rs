@@ -1,7 +1,6 @@
-use std::{f32::INFINITY, ops::BitAnd};
+use std::ops::BitAnd;
 
 use std::f32::consts::PI;
-use std::f32::EPSILON;
 
 use num::{Float, Integer};
 
@@ -23,13 +22,13 @@ impl Math{
     /// Radians-to-degrees conversion constant (Read Only).
     pub const RAD2DEG: f32 = 360.0 / (PI * 2.0);
     /// A tiny floating point value (Read Only).
-    pub const EPSILON: f32 = EPSILON;
+    pub const EPSILON: f32 = f32::EPSILON;
     /// The well-known 3.14159265358979... value (Read Only).
     pub const PI: f32 = PI;
     /// A representation of positive INFINITY (Read Only).
-    pub const INFINITY: f32 = INFINITY;
+    pub const INFINITY: f32 = f32::INFINITY;
     /// A representation of negative INFINITY (Read Only).
-    pub const NEGATIVE_INFINITY: f32 = -INFINITY;
+    pub const NEGATIVE_INFINITY: f32 = -f32::INFINITY;
 
 
     /// # Deg To Rad
@@ -121,7 +120,7 @@ impl Math{
             return max;
         }
 
-        return val;
+        val
     }
 
     /// # Clamp01
@@ -135,7 +134,7 @@ impl Math{
             return 1.0.into();
         }
 
-        return f;
+        f
     }
 
     /// # ClosestPowerOfTwo
@@ -324,7 +323,7 @@ impl Math{
             return target;
         }
         target = current + delta_angle;
-        return Math::move_towards(current, target, max_delta);
+        Math::move_towards(current, target, max_delta)
     }   
 
     /// # Next Power of Two
@@ -420,7 +419,7 @@ impl Math{
             *current_velocity = (output - original_to) / delta_time;
         }
 
-        return output;
+        output
     }
 
     /// # Smooth Damp Angle
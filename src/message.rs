@@ -0,0 +1,282 @@
+//! Messages sent between scripts, like unity's `SendMessage` and `BroadcastMessage`.
+//!
+//! A message is a name and a payload of bytes. Scripts subscribe to the names they want to
+//! receive, and publish messages to a single gameobject, to every gameobject with a tag, or to
+//! every script. Typed messages implement `Event`, which names them and turns them into bytes.
+//!
+//! Like commands, messages (and subscriptions) are queued and lent to the host in the next
+//! `ChangeBuffer`. The host keeps track of who is subscribed to what, and routes each message
+//! to every subscribed script. It hands them to each script through `runity_post_messages`
+//! before the script's next `update`, at the start of which `Script::on_message` is called
+//! for each one. Hosts can also post their own messages (from UI code, for example) the same way.
+
+use crate::interop::reflect;
+use crate::{Handle, Str, String};
+
+/// # Event
+///
+/// A typed message. `NAME` is the name it is published (and subscribed to) under, and the
+/// payload is whatever `encode` returns.
+///
+/// ```
+/// # use std::convert::TryInto;
+/// # use runity::Event;
+/// struct Damage{ amount: f32 }
+///
+/// impl Event for Damage{
+///     const NAME: &'static str = "Damage";
+///
+///     fn encode(&self) -> Vec<u8>{
+///         self.amount.to_le_bytes().to_vec()
+///     }
+///
+///     fn decode(payload: &[u8]) -> Option<Self>{
+///         Some(Damage{ amount: f32::from_le_bytes(payload.try_into().ok()?) })
+///     }
+/// }
+/// # assert_eq!(Damage::decode(&Damage{ amount: 2.5 }.encode()).map(|damage| damage.amount), Some(2.5));
+/// ```
+pub trait Event: Sized{
+    /// The name of the message.
+    const NAME: &'static str;
+
+    /// Turns the event into the message's payload.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Reads the event back from a payload, or returns `None` if it isn't valid.
+    fn decode(payload: &[u8]) -> Option<Self>;
+}
+
+/// # Recipients
+///
+/// Who a message is published to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recipients<'a>{
+    /// Every script attached to a single gameobject, like `SendMessage`.
+    Object(Handle),
+    /// Every script attached to a gameobject with this tag.
+    Tag(&'a str),
+    /// Every script.
+    All,
+}
+
+/// # Message Kind
+///
+/// What a queued `Message` does.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind{
+    /// Publishes a message to its recipients.
+    Publish = 0,
+    /// Subscribes the script to messages with this name.
+    Subscribe = 1,
+    /// Unsubscribes the script from messages with this name.
+    Unsubscribe = 2,
+}
+
+/// # Message
+///
+/// A message (or subscription) queued by a script, lent to the host in a `ChangeBuffer`.
+///
+/// For `Publish`, `recipients` is `0` for a single gameobject (`object`, which may be a pending
+/// handle spawned earlier in the same buffer, like a command's target), `1` for every gameobject
+/// tagged `tag`, or `2` for every script. Subscriptions only use `name`.
+///
+/// The payload is owned by rust, and freed with the message.
+#[repr(C)]
+pub struct Message{
+    kind: u32,
+    name: String,
+    payload: *mut u8,
+    payload_len: u32,
+    recipients: u32,
+    object: u64,
+    tag: String,
+}
+
+reflect!(Message{ kind, name, payload, payload_len, recipients, object, tag });
+
+impl Message{
+    pub(crate) fn publish(to: Recipients, name: &str, payload: &[u8]) -> Self{
+        let (recipients, object, tag) = match to{
+            Recipients::Object(handle) => (0, handle.id(), String::new()),
+            Recipients::Tag(tag) => (1, 0, String::from(tag)),
+            Recipients::All => (2, 0, String::new()),
+        };
+
+        let payload = Box::into_raw(payload.to_vec().into_boxed_slice());
+
+        Self{
+            kind: MessageKind::Publish as u32,
+            name: String::from(name),
+            payload: payload as *mut u8,
+            payload_len: payload.len() as u32,
+            recipients,
+            object,
+            tag,
+        }
+    }
+
+    pub(crate) fn subscription(kind: MessageKind, name: &str) -> Self{
+        Self{
+            kind: kind as u32,
+            name: String::from(name),
+            payload: std::ptr::null_mut(),
+            payload_len: 0,
+            recipients: 0,
+            object: 0,
+            tag: String::new(),
+        }
+    }
+
+    /// # Kind
+    ///
+    /// Returns what this message does.
+    pub fn kind(&self) -> MessageKind{
+        match self.kind{
+            0 => MessageKind::Publish,
+            1 => MessageKind::Subscribe,
+            _ => MessageKind::Unsubscribe,
+        }
+    }
+
+    /// # Name
+    ///
+    /// Returns the name of the message, or of the messages being subscribed to.
+    pub fn name(&self) -> &str{
+        self.name.as_str()
+    }
+
+    /// # Payload
+    ///
+    /// Returns the payload of a published message.
+    pub fn payload(&self) -> &[u8]{
+        if self.payload.is_null(){
+            return &[];
+        }
+
+        unsafe{ std::slice::from_raw_parts(self.payload, self.payload_len as usize) }
+    }
+
+    /// # Recipients
+    ///
+    /// Returns who a published message is for.
+    pub fn recipients(&self) -> Recipients<'_>{
+        match self.recipients{
+            0 => Recipients::Object(Handle::of(self.object)),
+            1 => Recipients::Tag(self.tag.as_str()),
+            _ => Recipients::All,
+        }
+    }
+}
+
+impl Drop for Message{
+    fn drop(&mut self){
+        if !self.payload.is_null(){
+            unsafe{ drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.payload, self.payload_len as usize))) };
+        }
+    }
+}
+
+// The payload is owned by the message, like a `String`'s bytes
+unsafe impl Send for Message{}
+
+/// # Received Message
+///
+/// A message posted to a script, passed to `Script::on_message`.
+///
+/// Like a `Collision`, it is only lent for the length of the call.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ReceivedMessage<'a>{
+    name: Str<'a>,
+    payload: *const u8,
+    payload_len: u32,
+    sender: u64,
+}
+
+reflect!(ReceivedMessage<'_>{ name, payload, payload_len, sender });
+
+impl<'a> ReceivedMessage<'a>{
+    /// # New
+    ///
+    /// Creates a new `ReceivedMessage`, borrowing the name and payload. `sender` is the hash
+    /// of the gameobject that published it, or `0` if it came from the host. This is used by
+    /// hosts to post messages to rust.
+    pub fn new(name: Str<'a>, payload: &'a [u8], sender: u64) -> Self{
+        Self{
+            name,
+            payload: payload.as_ptr(),
+            payload_len: payload.len() as u32,
+            sender,
+        }
+    }
+
+    /// # Name
+    ///
+    /// Returns the name of the message.
+    pub fn name(&self) -> Str<'a>{
+        self.name
+    }
+
+    /// # Payload
+    ///
+    /// Returns the payload of the message.
+    pub fn payload(&self) -> &'a [u8]{
+        if self.payload.is_null(){
+            return &[];
+        }
+
+        unsafe{ std::slice::from_raw_parts(self.payload, self.payload_len as usize) }
+    }
+
+    /// # Sender
+    ///
+    /// Returns the gameobject that published the message, or `None` if the host did.
+    pub fn sender(&self) -> Option<Handle>{
+        if self.sender == 0{
+            None
+        }else{
+            Some(Handle::of(self.sender))
+        }
+    }
+
+    /// # Is
+    ///
+    /// Returns whether this is an `E`.
+    pub fn is<E: Event>(&self) -> bool{
+        self.name.to_string_lossy() == E::NAME
+    }
+
+    /// # Decode
+    ///
+    /// Returns the message as an `E`, or `None` if it isn't one (or its payload isn't valid).
+    pub fn decode<E: Event>(&self) -> Option<E>{
+        if self.is::<E>(){
+            E::decode(self.payload())
+        }else{
+            None
+        }
+    }
+}
+
+// A message posted to an instance, held until its next `update`
+pub(crate) struct Inbox{
+    name: std::string::String,
+    payload: Vec<u8>,
+    sender: u64,
+}
+
+impl Inbox{
+    pub(crate) fn copy(message: &ReceivedMessage) -> Self{
+        Self{
+            name: message.name.to_string_lossy().into_owned(),
+            payload: message.payload().to_vec(),
+            sender: message.sender,
+        }
+    }
+
+    pub(crate) fn lend(&self) -> ReceivedMessage<'_>{
+        ReceivedMessage::new(Str::from(self.name.as_str()), &self.payload, self.sender)
+    }
+}
@@ -0,0 +1,97 @@
+//! Panic containment for every entry point unity calls.
+//!
+//! A panic unwinding out of an `extern "C"` function takes the whole unity editor down
+//! with it. Every exported function runs its body through `catch_panic`, which stops the panic
+//! at the boundary and reports it through `Debug::log_error` instead.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::{logger, Debug};
+
+/// # Status
+///
+/// The status codes returned by entry points that return an `i32`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status{
+    /// The call completed.
+    Ok = 0,
+    /// The call panicked. The panic was reported through `Debug::log_error`, and the
+    /// instance has been marked as faulted.
+    Panicked = -1,
+    /// The saved state passed to `runity_load_state` was written with a different
+    /// `Script::STATE_VERSION`, so it wasn't restored.
+    StateVersionMismatch = -2,
+    /// The script's `load_state` couldn't read the saved state. The reason was reported
+    /// through `Debug::log_error`.
+    StateRejected = -3,
+}
+
+thread_local!{
+    // The message and location of the last panic on this thread, recorded by the panic hook
+    static LAST_PANIC: RefCell<Option<std::string::String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+// The location of a panic is only available to the panic hook, so we record it there
+// (on top of whatever the previous hook does) to report it once the panic is caught.
+fn install_hook(){
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = payload_message(info.payload());
+            let report = match info.location(){
+                Some(location) => format!("{} (at {}:{}:{})", message, location.file(), location.line(), location.column()),
+                None => message,
+            };
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
+
+            previous(info);
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> std::string::String{
+    if let Some(message) = payload.downcast_ref::<&str>(){
+        message.to_string()
+    }
+    else if let Some(message) = payload.downcast_ref::<std::string::String>(){
+        message.clone()
+    }
+    else{
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// # Catch Panic
+///
+/// Runs `f`, catching any panic. If `f` panics, the panic message and location are
+/// logged through `debug` (when there is one) and `None` is returned.
+///
+/// `entry` is the name of the entry point, and is included in the report. While `f` runs,
+/// anything logged through the `log` crate on this thread is written through `debug`.
+pub fn catch_panic<R>(entry: &str, debug: Option<&Debug>, f: impl FnOnce() -> R) -> Option<R>{
+    install_hook();
+
+    // Records logged through `log` during the call are written through its debug
+    let _logging = debug.map(logger::enter);
+
+    match panic::catch_unwind(AssertUnwindSafe(f)){
+        Ok(value) => Some(value),
+        Err(payload) => {
+            let message = LAST_PANIC.with(|last| last.borrow_mut().take())
+                .unwrap_or_else(|| payload_message(payload.as_ref()));
+
+            if let Some(debug) = debug{
+                let report = format!("runity: `{}` panicked: {}", entry, message);
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| debug.log_error(report)));
+            }
+
+            None
+        }
+    }
+}
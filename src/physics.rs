@@ -0,0 +1,330 @@
+//! Physics scene queries - raycasts, sphere casts and overlaps, like unity's `Physics`.
+//!
+//! Queries are answered by the host straight away, against the colliders in its scene. Each
+//! hit is written into a `FoundHit`, which lends the collider's tag until the next query, and
+//! is copied into a `RaycastHit` before it is handed to the script.
+
+use crate::interop::reflect;
+use crate::{Handle, Quaternion, Str, Vector3};
+
+/// # Physics
+///
+/// The host functions used to query the physics scene, like unity's `Physics`. Reached
+/// through `data.physics`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Physics{
+    /// Casts a ray or sphere, writing up to `capacity` hits and returning how many there are
+    /// in total.
+    cast: extern "C" fn(&PhysicsQuery, *mut FoundHit, u32) -> u32,
+    /// Finds every collider touching a sphere or box, writing up to `capacity` hits and
+    /// returning how many there are in total.
+    overlap: extern "C" fn(&PhysicsQuery, *mut FoundHit, u32) -> u32,
+}
+
+reflect!(Physics{
+    cast: fn PhysicsCast(query, hits, capacity),
+    overlap: fn PhysicsOverlap(query, hits, capacity),
+});
+
+impl Physics{
+    /// Every layer.
+    pub const ALL_LAYERS: i32 = -1;
+    /// Every layer but `Ignore Raycast`, like unity's `Physics.DefaultRaycastLayers`.
+    pub const DEFAULT_RAYCAST_LAYERS: i32 = !(1 << 2);
+
+    /// # New
+    ///
+    /// Creates a new `Physics` from the host's query functions.
+    pub fn new(cast: extern "C" fn(&PhysicsQuery, *mut FoundHit, u32) -> u32, overlap: extern "C" fn(&PhysicsQuery, *mut FoundHit, u32) -> u32) -> Self{
+        Self{
+            cast,
+            overlap,
+        }
+    }
+
+    /// # Raycast
+    ///
+    /// Casts a ray from `origin` along `direction`, returning the closest collider it hits
+    /// within `max_distance` (which may be `f32::INFINITY`) on the layers in `layer_mask`.
+    /// Like unity, colliders the ray starts inside aren't hit.
+    pub fn raycast(&self, origin: Vector3, direction: Vector3, max_distance: f32, layer_mask: i32) -> Option<RaycastHit>{
+        let query = PhysicsQuery::cast(QueryShape::Ray, origin, 0.0, direction, max_distance, layer_mask, false);
+        self.query(self.cast, &query).into_iter().next()
+    }
+
+    /// # Raycast All
+    ///
+    /// Like `raycast`, but returns every collider the ray hits, closest first.
+    pub fn raycast_all(&self, origin: Vector3, direction: Vector3, max_distance: f32, layer_mask: i32) -> Vec<RaycastHit>{
+        let query = PhysicsQuery::cast(QueryShape::Ray, origin, 0.0, direction, max_distance, layer_mask, true);
+        Self::closest_first(self.query(self.cast, &query))
+    }
+
+    /// # Sphere Cast
+    ///
+    /// Sweeps a sphere of `radius` from `origin` along `direction`, returning the closest
+    /// collider it hits. The hit's `distance` is how far the sphere moved before touching it.
+    pub fn sphere_cast(&self, origin: Vector3, radius: f32, direction: Vector3, max_distance: f32, layer_mask: i32) -> Option<RaycastHit>{
+        let query = PhysicsQuery::cast(QueryShape::Sphere, origin, radius, direction, max_distance, layer_mask, false);
+        self.query(self.cast, &query).into_iter().next()
+    }
+
+    /// # Sphere Cast All
+    ///
+    /// Like `sphere_cast`, but returns every collider the sphere hits, closest first.
+    pub fn sphere_cast_all(&self, origin: Vector3, radius: f32, direction: Vector3, max_distance: f32, layer_mask: i32) -> Vec<RaycastHit>{
+        let query = PhysicsQuery::cast(QueryShape::Sphere, origin, radius, direction, max_distance, layer_mask, true);
+        Self::closest_first(self.query(self.cast, &query))
+    }
+
+    /// # Overlap Sphere
+    ///
+    /// Returns every collider touching or inside the sphere at `center`. Each hit's `point` is
+    /// the closest point on the collider to `center`, and `distance` is how far away it is
+    /// (`0` if `center` is inside the collider).
+    pub fn overlap_sphere(&self, center: Vector3, radius: f32, layer_mask: i32) -> Vec<RaycastHit>{
+        let query = PhysicsQuery{
+            radius,
+            ..PhysicsQuery::overlap(QueryShape::Sphere, center, layer_mask)
+        };
+        self.query(self.overlap, &query)
+    }
+
+    /// # Overlap Box
+    ///
+    /// Returns every collider touching or inside the box at `center`, with half its size in
+    /// `half_extents`, rotated by `rotation`. Hits are like those from `overlap_sphere`.
+    pub fn overlap_box(&self, center: Vector3, half_extents: Vector3, rotation: Quaternion, layer_mask: i32) -> Vec<RaycastHit>{
+        let query = PhysicsQuery{
+            half_extents,
+            rotation,
+            ..PhysicsQuery::overlap(QueryShape::Box, center, layer_mask)
+        };
+        self.query(self.overlap, &query)
+    }
+
+    // Runs a query, copying every hit before whatever the host lent is freed
+    fn query(&self, function: extern "C" fn(&PhysicsQuery, *mut FoundHit, u32) -> u32, query: &PhysicsQuery) -> Vec<RaycastHit>{
+        let mut found: Vec<FoundHit> = Vec::with_capacity(if query.all{ 0 }else{ 1 });
+
+        // Like `GameObject::find_all_with_tag`, the host is asked again if there wasn't enough room
+        loop{
+            let capacity = found.capacity();
+            let count = function(query, found.as_mut_ptr(), capacity as u32) as usize;
+
+            if count <= capacity || !query.all{
+                unsafe{ found.set_len(count.min(capacity)) };
+                break;
+            }
+
+            found.reserve_exact(count);
+        }
+
+        found.iter().map(RaycastHit::from).collect()
+    }
+
+    fn closest_first(mut hits: Vec<RaycastHit>) -> Vec<RaycastHit>{
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
+
+/// # Query Shape
+///
+/// The shape a `PhysicsQuery` casts, or checks for overlaps with.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryShape{
+    Ray = 0,
+    Sphere = 1,
+    Box = 2,
+}
+
+/// # Physics Query
+///
+/// A query lent to the host by `Physics`.
+///
+/// Casts move the shape from `origin` along `direction` (which is normalised) for up to
+/// `max_distance`. Overlaps check the shape at `origin`. Spheres use `radius`, and boxes use
+/// `half_extents` and `rotation`. Only colliders on a layer in `layer_mask` are hit.
+///
+/// If `all` is false, only the closest hit is wanted, and there is only room for one.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsQuery{
+    shape: u32,
+    origin: Vector3,
+    direction: Vector3,
+    max_distance: f32,
+    radius: f32,
+    half_extents: Vector3,
+    rotation: Quaternion,
+    layer_mask: i32,
+    all: bool,
+}
+
+reflect!(PhysicsQuery{ shape, origin, direction, max_distance, radius, half_extents, rotation, layer_mask, all });
+
+impl PhysicsQuery{
+    fn cast(shape: QueryShape, origin: Vector3, radius: f32, direction: Vector3, max_distance: f32, layer_mask: i32, all: bool) -> Self{
+        Self{
+            direction: Vector3::normalize(direction),
+            max_distance,
+            radius,
+            all,
+            ..Self::overlap(shape, origin, layer_mask)
+        }
+    }
+
+    fn overlap(shape: QueryShape, origin: Vector3, layer_mask: i32) -> Self{
+        Self{
+            shape: shape as u32,
+            origin,
+            direction: Vector3::zero(),
+            max_distance: 0.0,
+            radius: 0.0,
+            half_extents: Vector3::zero(),
+            rotation: Quaternion::identity(),
+            layer_mask,
+            all: true,
+        }
+    }
+
+    /// # Shape
+    ///
+    /// Returns the shape being cast or checked.
+    pub fn shape(&self) -> QueryShape{
+        match self.shape{
+            0 => QueryShape::Ray,
+            1 => QueryShape::Sphere,
+            _ => QueryShape::Box,
+        }
+    }
+
+    /// # Origin
+    ///
+    /// Returns where a cast starts, or the center of an overlap.
+    pub fn origin(&self) -> Vector3{
+        self.origin
+    }
+
+    /// # Direction
+    ///
+    /// Returns the (normalised) direction of a cast.
+    pub fn direction(&self) -> Vector3{
+        self.direction
+    }
+
+    /// # Max Distance
+    ///
+    /// Returns how far a cast goes.
+    pub fn max_distance(&self) -> f32{
+        self.max_distance
+    }
+
+    /// # Radius
+    ///
+    /// Returns the radius of a sphere.
+    pub fn radius(&self) -> f32{
+        self.radius
+    }
+
+    /// # Half Extents
+    ///
+    /// Returns half the size of a box.
+    pub fn half_extents(&self) -> Vector3{
+        self.half_extents
+    }
+
+    /// # Rotation
+    ///
+    /// Returns the rotation of a box.
+    pub fn rotation(&self) -> Quaternion{
+        self.rotation
+    }
+
+    /// # Layer Mask
+    ///
+    /// Returns the layers colliders must be on to be hit.
+    pub fn layer_mask(&self) -> i32{
+        self.layer_mask
+    }
+
+    /// # Hits Layer
+    ///
+    /// Returns whether colliders on `layer` (from 0 to 31) can be hit.
+    pub fn hits_layer(&self, layer: u32) -> bool{
+        layer < 32 && (self.layer_mask as u32) & (1 << layer) != 0
+    }
+
+    /// # All
+    ///
+    /// Returns whether every hit is wanted, rather than just the closest.
+    pub fn all(&self) -> bool{
+        self.all
+    }
+}
+
+/// # Found Hit
+///
+/// A hit found by the host, written by the functions in `Physics`.
+///
+/// Like `FoundGameObject`, the tag is lent by the host - it only has to stay valid until the
+/// next query, as rust copies it straight away.
+#[repr(C)]
+#[derive(Clone)]
+pub struct FoundHit<'a>{
+    point: Vector3,
+    normal: Vector3,
+    distance: f32,
+    object: u64,
+    tag: Str<'a>,
+}
+
+reflect!(FoundHit<'_>{ point, normal, distance, object, tag });
+
+impl<'a> FoundHit<'a>{
+    /// # New
+    ///
+    /// Creates a new `FoundHit`, borrowing the tag of the collider's gameobject. This is used
+    /// by hosts to answer queries.
+    pub fn new(point: Vector3, normal: Vector3, distance: f32, object: u64, tag: Str<'a>) -> Self{
+        Self{
+            point,
+            normal,
+            distance,
+            object,
+            tag,
+        }
+    }
+}
+
+/// # Raycast Hit
+///
+/// A collider hit by a query, like unity's `RaycastHit`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaycastHit{
+    /// Where the collider was hit, in world space.
+    pub point: Vector3,
+    /// The normal of the collider's surface where it was hit.
+    pub normal: Vector3,
+    /// How far the ray (or shape) travelled before it hit the collider.
+    pub distance: f32,
+    /// The gameobject the collider is on.
+    pub object: Handle,
+    /// The tag of the gameobject the collider is on.
+    pub tag: std::string::String,
+}
+
+impl From<&FoundHit<'_>> for RaycastHit{
+    fn from(found: &FoundHit) -> Self{
+        Self{
+            point: found.point,
+            normal: found.normal,
+            distance: found.distance,
+            object: Handle::of(found.object),
+            tag: found.tag.to_string_lossy().into_owned(),
+        }
+    }
+}
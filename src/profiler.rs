@@ -0,0 +1,621 @@
+//! Profiler scopes, like unity's `Profiler.BeginSample` and `Profiler.EndSample`.
+//!
+//! A `ProfileScope` (or the `profile!` macro) marks a section of code until it is dropped. On
+//! the thread the host handed its profiler to (with `runity_set_profiler`), scopes are passed
+//! straight to the host - in unity, they show up in the Profiler window under the script's
+//! update. Anywhere else, or if the host has no profiler, they are recorded by `FrameProfiler`,
+//! which keeps the last few hundred frames of scopes from every thread and writes them out as a
+//! Chrome trace (for `chrome://tracing` or Perfetto).
+//!
+//! Every script entry point is a scope of its own, named after the entry point, so recorded
+//! scopes nest under the `update` (or `fixed_update`, and so on) they ran in.
+//!
+//! A recorded scope costs two clock reads and an uncontended lock, so scopes can be left in
+//! development builds. Recording is off by default in release builds
+//! (`FrameProfiler::set_enabled` turns it on or off), after which a scope the host doesn't take
+//! only costs a thread local read and an atomic load.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+
+use crate::interop::reflect;
+use crate::{panic, Str};
+
+// Past this many scopes, a thread's oldest are dropped, so a thread recording while frames
+// don't move on can't grow forever
+const THREAD_CAPACITY: usize = 1 << 16;
+
+/// # Profiler
+///
+/// The host functions used to mark samples in its profiler, like unity's `Profiler`. Handed to
+/// rust through `runity_set_profiler`, and only used on the thread it was handed over on.
+///
+/// Names are lent for the length of `begin_sample`, but live as long as the library, so hosts
+/// may cache anything they create for a name by its pointer.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Profiler{
+    /// Starts a sample with the given name, nested in the sample already started.
+    begin_sample: extern "C" fn(Str),
+    /// Ends the sample started last.
+    end_sample: extern "C" fn(),
+}
+
+reflect!(Profiler{
+    begin_sample: fn ProfilerBeginSample(name),
+    end_sample: fn ProfilerEndSample(),
+});
+
+impl Profiler{
+    /// # New
+    ///
+    /// Creates a new `Profiler` from the host's profiler functions. This is used by hosts.
+    pub fn new(begin_sample: extern "C" fn(Str), end_sample: extern "C" fn()) -> Self{
+        Self{
+            begin_sample,
+            end_sample,
+        }
+    }
+}
+
+/// # Frame Profiler
+///
+/// The in-process profiler that records scopes the host doesn't take. It is only ever used
+/// through its associated functions.
+pub struct FrameProfiler{
+    _private: (),
+}
+
+/// # Profile Sample
+///
+/// A single scope recorded by `FrameProfiler`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileSample{
+    /// The name of the scope.
+    pub name: &'static str,
+    /// The thread the scope ran on, numbered from 1 in the order threads first recorded a scope.
+    pub thread: u64,
+    /// The frame (`Time.frame_count`) the scope started in, or `0` if it started before the first frame.
+    pub frame: u64,
+    /// How many scopes the scope was nested in.
+    pub depth: u32,
+    /// When the scope started, from when the profiler was first used.
+    pub start: Duration,
+    /// How long the scope took.
+    pub duration: Duration,
+}
+
+// A recorded scope, with times in nanoseconds from `EPOCH`
+#[derive(Clone, Copy)]
+struct Sample{
+    name: &'static str,
+    start: u64,
+    duration: u64,
+    depth: u32,
+}
+
+// The scopes recorded on a single thread
+struct ThreadSamples{
+    thread: u64,
+    name: std::string::String,
+    samples: VecDeque<Sample>,
+}
+
+// When each frame kept started
+struct Frames{
+    starts: VecDeque<(u64, u64)>,
+    history: usize,
+}
+
+lazy_static!{
+    static ref EPOCH: Instant = Instant::now();
+    static ref THREADS: Mutex<Vec<Arc<Mutex<ThreadSamples>>>> = Mutex::new(Vec::new());
+    static ref FRAMES: Mutex<Frames> = Mutex::new(Frames{
+        starts: VecDeque::new(),
+        history: 300,
+    });
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+static NEXT_THREAD: AtomicUsize = AtomicUsize::new(1);
+
+thread_local!{
+    // The host's profiler, if it was handed over on this thread
+    static HOST: Cell<Option<Profiler>> = const{ Cell::new(None) };
+    // How many recorded scopes are open on this thread
+    static DEPTH: Cell<u32> = const{ Cell::new(0) };
+    static SAMPLES: RefCell<Option<Arc<Mutex<ThreadSamples>>>> = const{ RefCell::new(None) };
+}
+
+// Nothing is left inconsistent by a panic while these are held
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T>{
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn now() -> u64{
+    EPOCH.elapsed().as_nanos() as u64
+}
+
+/// # Profile Scope
+///
+/// Marks a section of code for the profiler, from when it is created until it is dropped -
+/// like a `Profiler.BeginSample` with the `Profiler.EndSample` done for you. Scopes nest, and
+/// must be dropped on the thread they were created on.
+///
+/// ```ignore
+/// fn update(&mut self, data: &mut DataStruct){
+///     let _scope = ProfileScope::new("pathfinding");
+///     self.find_path(data);
+/// }
+/// ```
+pub struct ProfileScope{
+    name: &'static str,
+    mode: Mode,
+    // Ended on the thread it was started on
+    _thread: PhantomData<*const ()>,
+}
+
+enum Mode{
+    Host(Profiler),
+    Recorded{ start: u64, depth: u32 },
+    Off,
+}
+
+impl ProfileScope{
+    /// # New
+    ///
+    /// Starts a scope named `name`.
+    pub fn new(name: &'static str) -> Self{
+        let mode = match HOST.with(Cell::get){
+            Some(host) => {
+                (host.begin_sample)(Str::from(name));
+                Mode::Host(host)
+            }
+            None if ENABLED.load(Ordering::Relaxed) => {
+                let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+                Mode::Recorded{ start: now(), depth }
+            }
+            None => Mode::Off,
+        };
+
+        Self{
+            name,
+            mode,
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Drop for ProfileScope{
+    fn drop(&mut self){
+        match self.mode{
+            Mode::Host(host) => (host.end_sample)(),
+            Mode::Recorded{ start, depth } => {
+                let duration = now().saturating_sub(start);
+                DEPTH.with(|open| open.set(depth));
+                record(Sample{ name: self.name, start, duration, depth });
+            }
+            Mode::Off => (),
+        }
+    }
+}
+
+// Adds a scope to this thread's samples, registering the thread the first time
+fn record(sample: Sample){
+    // Scopes dropped while the thread is being torn down are lost
+    let _ = SAMPLES.try_with(|samples| {
+        let mut samples = samples.borrow_mut();
+        let samples = samples.get_or_insert_with(|| {
+            let current = std::thread::current();
+            let thread = Arc::new(Mutex::new(ThreadSamples{
+                thread: NEXT_THREAD.fetch_add(1, Ordering::Relaxed) as u64,
+                name: current.name().map(str::to_string).unwrap_or_else(|| format!("{:?}", current.id())),
+                samples: VecDeque::new(),
+            }));
+            lock(&THREADS).push(thread.clone());
+            thread
+        });
+
+        let mut samples = lock(samples);
+        if samples.samples.len() >= THREAD_CAPACITY{
+            samples.samples.pop_front();
+        }
+        samples.samples.push_back(sample);
+    });
+}
+
+/// Starts `frame` once `frame` moves on, dropping the scopes of frames no longer kept.
+/// Called at the start of every script entry point.
+pub(crate) fn frame(frame: u64){
+    if !ENABLED.load(Ordering::Relaxed){
+        return;
+    }
+
+    let mut frames = lock(&FRAMES);
+    if frames.starts.back().map(|(last, _)| *last) == Some(frame){
+        return;
+    }
+    frames.starts.push_back((frame, now()));
+    trim(&mut frames);
+}
+
+// Drops the frames past the history kept (always keeping the frame running), and their scopes
+fn trim(frames: &mut Frames){
+    if frames.starts.len() <= frames.history.max(1){
+        return;
+    }
+
+    while frames.starts.len() > frames.history.max(1){
+        frames.starts.pop_front();
+    }
+    let oldest = frames.starts.front().map(|(_, start)| *start).unwrap_or(0);
+    drop_before(oldest);
+}
+
+// Drops every recorded scope that started before `start`, and threads that are gone with nothing left
+fn drop_before(start: u64){
+    lock(&THREADS).retain(|thread| {
+        let mut samples = lock(thread);
+        while samples.samples.front().is_some_and(|sample| sample.start < start){
+            samples.samples.pop_front();
+        }
+        !samples.samples.is_empty() || Arc::strong_count(thread) > 1
+    });
+}
+
+impl FrameProfiler{
+    /// # Set Enabled
+    ///
+    /// Turns recording scopes on or off. On by default in debug builds, and off in release builds.
+    /// Scopes the host takes are passed to it either way.
+    pub fn set_enabled(enabled: bool){
+        ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    /// # Is Enabled
+    ///
+    /// Returns whether scopes are being recorded.
+    pub fn is_enabled() -> bool{
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// # Set History
+    ///
+    /// Sets how many frames of scopes are kept. Defaults to 300.
+    pub fn set_history(frames: usize){
+        let mut frames_lock = lock(&FRAMES);
+        frames_lock.history = frames;
+        trim(&mut frames_lock);
+    }
+
+    /// # Clear
+    ///
+    /// Drops every scope recorded so far.
+    pub fn clear(){
+        let mut frames = lock(&FRAMES);
+        frames.starts.clear();
+        drop_before(u64::MAX);
+    }
+
+    /// # Samples
+    ///
+    /// Returns every scope kept, thread by thread, in the order they started. Scopes still open
+    /// aren't included.
+    pub fn samples() -> Vec<ProfileSample>{
+        let frames = lock(&FRAMES);
+        let threads: Vec<Arc<Mutex<ThreadSamples>>> = lock(&THREADS).clone();
+
+        let mut kept = Vec::new();
+        for thread in threads.iter(){
+            let samples = lock(thread);
+            let first = kept.len();
+            kept.extend(samples.samples.iter().map(|sample| ProfileSample{
+                name: sample.name,
+                thread: samples.thread,
+                frame: frame_of(&frames, sample.start),
+                depth: sample.depth,
+                start: Duration::from_nanos(sample.start),
+                duration: Duration::from_nanos(sample.duration),
+            }));
+
+            // Scopes are recorded as they end, so inner scopes come before the scopes around them
+            kept[first..].sort_by_key(|sample| (sample.start, sample.depth));
+        }
+        kept
+    }
+
+    /// # Chrome Trace
+    ///
+    /// Returns every scope kept as Chrome trace-event JSON, which `chrome://tracing` and
+    /// Perfetto open. Each thread is a track, and the start of every frame is marked.
+    pub fn chrome_trace() -> std::string::String{
+        let samples = Self::samples();
+        let threads: Vec<(u64, std::string::String)> = lock(&THREADS).iter()
+            .map(|thread| {
+                let thread = lock(thread);
+                (thread.thread, thread.name.clone())
+            })
+            .collect();
+        let frames: Vec<(u64, u64)> = lock(&FRAMES).starts.iter().copied().collect();
+
+        let mut events = Vec::with_capacity(threads.len() + frames.len() + samples.len());
+        for (thread, name) in threads.iter(){
+            events.push(format!(r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{},"args":{{"name":"{}"}}}}"#, thread, escape(name)));
+        }
+        for (frame, start) in frames.iter(){
+            events.push(format!(r#"{{"name":"frame {}","ph":"i","s":"g","pid":1,"tid":0,"ts":{}}}"#, frame, micros(*start)));
+        }
+        for sample in samples.iter(){
+            events.push(format!(r#"{{"name":"{}","cat":"runity","ph":"X","pid":1,"tid":{},"ts":{},"dur":{},"args":{{"frame":{}}}}}"#,
+                escape(sample.name),
+                sample.thread,
+                micros(sample.start.as_nanos() as u64),
+                micros(sample.duration.as_nanos() as u64),
+                sample.frame));
+        }
+
+        let mut trace = std::string::String::from("{\"traceEvents\":[\n");
+        trace.push_str(&events.join(",\n"));
+        trace.push_str("\n],\"displayTimeUnit\":\"ms\"}\n");
+        trace
+    }
+
+    /// # Write Chrome Trace
+    ///
+    /// Writes `chrome_trace` to a file.
+    pub fn write_chrome_trace(path: impl AsRef<Path>) -> std::io::Result<()>{
+        std::fs::write(path, Self::chrome_trace())
+    }
+}
+
+// The frame that was running at `start`
+fn frame_of(frames: &Frames, start: u64) -> u64{
+    let index = frames.starts.partition_point(|(_, frame_start)| *frame_start <= start);
+    if index == 0{
+        0
+    }else{
+        frames.starts[index - 1].0
+    }
+}
+
+// Trace-event times are in microseconds
+fn micros(nanos: u64) -> std::string::String{
+    format!("{}.{:03}", nanos / 1000, nanos % 1000)
+}
+
+fn escape(text: &str) -> std::string::String{
+    let mut escaped = std::string::String::with_capacity(text.len());
+    for character in text.chars(){
+        match character{
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            character if (character as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", character as u32);
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// # Profile
+///
+/// Starts a `ProfileScope` that lasts until the end of the enclosing block, or (given an
+/// expression too) profiles just that expression, returning its value.
+///
+/// ```
+/// # fn find_path() -> Vec<u32>{ vec![1, 2, 3] }
+/// runity::profile!("update enemies");
+/// let path = runity::profile!("pathfinding", find_path());
+/// # assert_eq!(path.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! profile{
+    ($name:expr) => {
+        let _profile_scope = $crate::ProfileScope::new($name);
+    };
+    ($name:expr, $expression:expr) => {{
+        let _profile_scope = $crate::ProfileScope::new($name);
+        $expression
+    }};
+}
+
+// Hands the host's profiler to rust, for scopes started on the calling thread (which should be
+// the thread the host calls the script from)
+#[no_mangle]
+pub extern "C" fn runity_set_profiler(profiler: Profiler){
+    panic::catch_panic("runity_set_profiler", None, || HOST.with(|host| host.set(Some(profiler))));
+}
+
+// Stops passing scopes started on the calling thread to the host, so they are recorded instead
+#[no_mangle]
+pub extern "C" fn runity_clear_profiler(){
+    panic::catch_panic("runity_clear_profiler", None, || HOST.with(|host| host.set(None)));
+}
+
+// Writes the recorded scopes to `path` as a Chrome trace, returning whether it was written
+#[no_mangle]
+pub extern "C" fn runity_write_chrome_trace(path: Str) -> bool{
+    let written = panic::catch_panic("runity_write_chrome_trace", None, || {
+        FrameProfiler::write_chrome_trace(&*path.to_string_lossy()).is_ok()
+    });
+
+    written.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // The profiler's state is shared by every test
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn serial() -> MutexGuard<'static, ()>{
+        let guard = lock(&SERIAL);
+        FrameProfiler::set_enabled(true);
+        FrameProfiler::set_history(300);
+        FrameProfiler::clear();
+        guard
+    }
+
+    fn frames(starts: &[(u64, u64)], history: usize) -> Frames{
+        Frames{ starts: starts.iter().copied().collect(), history }
+    }
+
+    #[test]
+    fn scopes_nest(){
+        let _serial = serial();
+
+        {
+            profile!("outer");
+            profile!("inner", profile!("innermost", ()));
+            profile!("sibling", ());
+        }
+        profile!("after", ());
+
+        let samples = FrameProfiler::samples();
+        let names: Vec<_> = samples.iter().map(|sample| (sample.name, sample.depth)).collect();
+        assert_eq!(names, [("outer", 0), ("inner", 1), ("innermost", 2), ("sibling", 1), ("after", 0)]);
+
+        // Nested scopes lie within the scopes around them
+        let end = |sample: &ProfileSample| sample.start + sample.duration;
+        assert!(samples[0].start <= samples[1].start && end(&samples[1]) <= end(&samples[0]));
+        assert!(samples[1].start <= samples[2].start && end(&samples[2]) <= end(&samples[1]));
+        assert!(end(&samples[1]) <= samples[3].start);
+        assert!(samples.iter().all(|sample| sample.thread == samples[0].thread && sample.frame == 0));
+    }
+
+    #[test]
+    fn scopes_are_only_recorded_while_enabled(){
+        let _serial = serial();
+
+        FrameProfiler::set_enabled(false);
+        profile!("off", ());
+        frame(1);
+        FrameProfiler::set_enabled(true);
+        profile!("on", ());
+
+        let names: Vec<_> = FrameProfiler::samples().iter().map(|sample| (sample.name, sample.frame)).collect();
+        assert_eq!(names, [("on", 0)]);
+    }
+
+    #[test]
+    fn scopes_are_handed_to_the_host_profiler(){
+        thread_local!{
+            static HANDED: RefCell<Vec<std::string::String>> = const{ RefCell::new(Vec::new()) };
+        }
+        extern "C" fn begin_sample(name: Str){
+            HANDED.with(|handed| handed.borrow_mut().push(name.to_string_lossy().into_owned()));
+        }
+        extern "C" fn end_sample(){
+            HANDED.with(|handed| handed.borrow_mut().push("end".to_string()));
+        }
+
+        let _serial = serial();
+
+        runity_set_profiler(Profiler::new(begin_sample, end_sample));
+        profile!("outer", profile!("inner", ()));
+        runity_clear_profiler();
+        profile!("recorded", ());
+
+        assert_eq!(HANDED.with(|handed| handed.take()), ["outer", "inner", "end", "end"]);
+        let names: Vec<_> = FrameProfiler::samples().iter().map(|sample| (sample.name, sample.depth)).collect();
+        assert_eq!(names, [("recorded", 0)]);
+    }
+
+    #[test]
+    fn frame_of_finds_the_running_frame(){
+        let kept = frames(&[(4, 100), (5, 200), (7, 300)], 300);
+
+        assert_eq!(frame_of(&kept, 0), 0);
+        assert_eq!(frame_of(&kept, 99), 0);
+        assert_eq!(frame_of(&kept, 100), 4);
+        assert_eq!(frame_of(&kept, 199), 4);
+        assert_eq!(frame_of(&kept, 200), 5);
+        assert_eq!(frame_of(&kept, 300), 7);
+        assert_eq!(frame_of(&kept, u64::MAX), 7);
+        assert_eq!(frame_of(&frames(&[], 300), 100), 0);
+    }
+
+    #[test]
+    fn trim_keeps_the_history_and_the_running_frame(){
+        let _serial = serial();
+
+        let mut kept = frames(&[(1, 1), (2, 2), (3, 3)], 2);
+        trim(&mut kept);
+        assert_eq!(kept.starts, [(2, 2), (3, 3)]);
+
+        kept.history = 0;
+        trim(&mut kept);
+        assert_eq!(kept.starts, [(3, 3)]);
+    }
+
+    #[test]
+    fn set_history_drops_old_frames_and_their_scopes(){
+        let _serial = serial();
+
+        for (number, name) in ["frame 1", "frame 2", "frame 3", "frame 4"].iter().enumerate(){
+            frame(number as u64 + 1);
+            profile!(name, ());
+        }
+        // Scopes in the frame already running don't start it again
+        frame(4);
+        profile!("frame 4 again", ());
+
+        FrameProfiler::set_history(2);
+        let names: Vec<_> = FrameProfiler::samples().iter().map(|sample| (sample.name, sample.frame)).collect();
+        assert_eq!(names, [("frame 3", 3), ("frame 4", 4), ("frame 4 again", 4)]);
+
+        FrameProfiler::set_history(0);
+        let names: Vec<_> = FrameProfiler::samples().iter().map(|sample| (sample.name, sample.frame)).collect();
+        assert_eq!(names, [("frame 4", 4), ("frame 4 again", 4)]);
+
+        // Later frames push the oldest out as they start
+        frame(5);
+        profile!("frame 5", ());
+        let names: Vec<_> = FrameProfiler::samples().iter().map(|sample| (sample.name, sample.frame)).collect();
+        assert_eq!(names, [("frame 5", 5)]);
+        FrameProfiler::set_history(300);
+    }
+
+    #[test]
+    fn chrome_trace_lists_threads_frames_and_scopes(){
+        let _serial = serial();
+
+        frame(1);
+        profile!("say \"hi\"\\\n", ());
+        let sample = FrameProfiler::samples()[0];
+        let trace = FrameProfiler::chrome_trace();
+
+        // Threads of other tests may not have been torn down yet
+        let thread = std::thread::current();
+        let own = format!(r#""tid":{},"#, sample.thread);
+        let events: Vec<_> = trace.lines().filter(|event| !event.contains("thread_name") || event.contains(&own)).collect();
+        assert_eq!(events, [
+            r#"{"traceEvents":["#.to_string(),
+            format!(r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{},"args":{{"name":"{}"}}}},"#, sample.thread, thread.name().unwrap()),
+            format!(r#"{{"name":"frame 1","ph":"i","s":"g","pid":1,"tid":0,"ts":{}}},"#, micros(lock(&FRAMES).starts[0].1)),
+            format!(r#"{{"name":"say \"hi\"\\\u000a","cat":"runity","ph":"X","pid":1,"tid":{},"ts":{},"dur":{},"args":{{"frame":1}}}}"#,
+                sample.thread,
+                micros(sample.start.as_nanos() as u64),
+                micros(sample.duration.as_nanos() as u64)),
+            r#"],"displayTimeUnit":"ms"}"#.to_string(),
+        ]);
+        assert!(trace.ends_with('\n'));
+    }
+
+    #[test]
+    fn times_are_in_microseconds(){
+        assert_eq!(micros(0), "0.000");
+        assert_eq!(micros(1), "0.001");
+        assert_eq!(micros(1_234_567), "1234.567");
+    }
+}
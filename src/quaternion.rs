@@ -1,10 +1,11 @@
-/// # Quaternion
-///
-/// This module provides access to various functions and
-/// methods used for quaternions in unity.
+//! # Quaternion
+//!
+//! This module provides access to various functions and
+//! methods used for quaternions in unity.
 
 use std::ops::{Mul, MulAssign};
 
+use crate::interop::reflect;
 use crate::{Math, Vector3};
 
 /// # Quaternion
@@ -90,28 +91,123 @@ impl Quaternion{
         Vector3::new(x, y, z)
     }
 
+    /// # Identity
+    ///
+    /// The quaternion that doesn't rotate at all.
+    pub fn identity() -> Self{
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// # Angle Axis
+    ///
+    /// Creates a rotation of `angle` degrees around `axis`.
+    pub fn angle_axis(angle: f32, axis: Vector3) -> Self{
+        let length = Vector3::magnitude(axis);
+
+        if length < Vector3::K_EPSILON{
+            return Self::identity();
+        }
+
+        let half = angle * Math::DEG2RAD / 2.0;
+        let scale = half.sin() / length;
+
+        Self::new(axis.x * scale, axis.y * scale, axis.z * scale, half.cos())
+    }
+
+    /// # Look Rotation
+    ///
+    /// Creates a rotation whose forward (`z`) axis points along `forward`, with its up (`y`)
+    /// axis as close to `up` as possible. Returns the identity if `forward` is zero.
+    pub fn look_rotation(forward: Vector3, up: Vector3) -> Self{
+        let forward = Vector3::normalize(forward);
+
+        if forward == Vector3::zero(){
+            return Self::identity();
+        }
+
+        let mut right = Vector3::normalize(Vector3::cross(up, forward));
+
+        if right == Vector3::zero(){
+            // `up` is parallel to `forward`, so any perpendicular axis will do
+            right = Vector3::normalize(Vector3::cross(Vector3::forward(), forward));
+
+            if right == Vector3::zero(){
+                right = Vector3::normalize(Vector3::cross(Vector3::right(), forward));
+            }
+        }
+
+        let up = Vector3::cross(forward, right);
+
+        // Build the quaternion from the rotation matrix whose columns are `right`, `up`, `forward`
+        let trace = right.x + up.y + forward.z;
+
+        if trace > 0.0{
+            let s = Math::sqrt(trace + 1.0) * 2.0;
+            Self::new((up.z - forward.y) / s, (forward.x - right.z) / s, (right.y - up.x) / s, 0.25 * s)
+        }else if right.x > up.y && right.x > forward.z{
+            let s = Math::sqrt(1.0 + right.x - up.y - forward.z) * 2.0;
+            Self::new(0.25 * s, (up.x + right.y) / s, (forward.x + right.z) / s, (up.z - forward.y) / s)
+        }else if up.y > forward.z{
+            let s = Math::sqrt(1.0 + up.y - right.x - forward.z) * 2.0;
+            Self::new((up.x + right.y) / s, 0.25 * s, (forward.y + up.z) / s, (forward.x - right.z) / s)
+        }else{
+            let s = Math::sqrt(1.0 + forward.z - right.x - up.y) * 2.0;
+            Self::new((forward.x + right.z) / s, (forward.y + up.z) / s, 0.25 * s, (right.y - up.x) / s)
+        }
+    }
+
+    /// # Inverse
+    ///
+    /// Returns the inverse of `rotation`, which undoes it.
+    pub fn inverse(rotation: Self) -> Self{
+        let sqr_length = Self::dot(rotation, rotation);
+
+        if sqr_length < Vector3::K_EPSILON{
+            return Self::identity();
+        }
+
+        Self::new(-rotation.x / sqr_length, -rotation.y / sqr_length, -rotation.z / sqr_length, rotation.w / sqr_length)
+    }
+
+    /// # Normalize
+    ///
+    /// Returns `rotation` scaled to a length of 1. Returns the identity if it has no length.
+    pub fn normalize(rotation: Self) -> Self{
+        let length = Math::sqrt(Self::dot(rotation, rotation));
+
+        if length < Vector3::K_EPSILON{
+            return Self::identity();
+        }
+
+        Self::new(rotation.x / length, rotation.y / length, rotation.z / length, rotation.w / length)
+    }
+
+    /// # Dot
+    ///
+    /// The dot product of two rotations.
+    pub fn dot(a: Self, b: Self) -> f32{
+        a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w
+    }
+
     /// # Rotate
     ///
-    /// Rotate Quaternion `self` by Quaternion `rhs`.
+    /// Rotate Quaternion `self` by Quaternion `rhs` (in world space, so `rhs` is applied after `self`).
     pub fn rotate(&mut self, rhs: Self){
-        
-        self.w = -rhs.x * self.x - rhs.y * self.y - rhs.z * self.z + rhs.w * self.w;
-        self.x = rhs.x * self.w + rhs.y * self.z - rhs.z * self.y + rhs.w * self.x;
-        self.y = -rhs.x * self.z + rhs.y * self.w + rhs.z * self.x + rhs.w * self.y;
-        self.z = rhs.x * self.y - rhs.y * self.x + rhs.z * self.w + rhs.w * self.z;
-        
+        *self = rhs * *self;
     }
 }
 
+reflect!(Quaternion{ x, y, z, w });
 
 impl Mul for Quaternion{
     type Output = Self;
 
+    /// Combines two rotations, like unity - `a * b` rotates by `b`, then by `a`.
     fn mul(self, rhs: Self) -> Self::Output {
-        let w = -rhs.x * self.x - rhs.y * self.y - rhs.z * self.z + rhs.w * self.w;
-        let x = rhs.x * self.w + rhs.y * self.z - rhs.z * self.y + rhs.w * self.x;
-        let y = -rhs.x * self.z + rhs.y * self.w + rhs.z * self.x + rhs.w * self.y;
-        let z = rhs.x * self.y - rhs.y * self.x + rhs.z * self.w + rhs.w * self.z;
+        let w = self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z;
+        let x = self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y;
+        let y = self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x;
+        let z = self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w;
 
         Self{
             x, 
@@ -124,9 +220,108 @@ impl Mul for Quaternion{
 
 impl MulAssign for Quaternion{
     fn mul_assign(&mut self, rhs: Self) {
-        self.w = -rhs.x * self.x - rhs.y * self.y - rhs.z * self.z + rhs.w * self.w;
-        self.x = rhs.x * self.w + rhs.y * self.z - rhs.z * self.y + rhs.w * self.x;
-        self.y = -rhs.x * self.z + rhs.y * self.w + rhs.z * self.x + rhs.w * self.y;
-        self.z = rhs.x * self.y - rhs.y * self.x + rhs.z * self.w + rhs.w * self.z;
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<Vector3> for Quaternion{
+    type Output = Vector3;
+
+    /// Rotates a point by this rotation.
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        // v' = v + 2w(q x v) + 2(q x (q x v))
+        let q = Vector3::new(self.x, self.y, self.z);
+        let t = Vector3::cross(q, rhs);
+        let t = Vector3::new(t.x * 2.0, t.y * 2.0, t.z * 2.0);
+        let u = Vector3::cross(q, t);
+
+        Vector3::new(
+            rhs.x + self.w * t.x + u.x,
+            rhs.y + self.w * t.y + u.y,
+            rhs.z + self.w * t.z + u.z
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn mul_applies_rhs_first(){
+        // i * j = k, while j * i = -k
+        let i = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        let j = Quaternion::new(0.0, 1.0, 0.0, 0.0);
+
+        assert_eq!(i * j, Quaternion::new(0.0, 0.0, 1.0, 0.0));
+        assert_eq!(j * i, Quaternion::new(0.0, 0.0, -1.0, 0.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mul_assign_and_rotate_match_mul(){
+        let a = Quaternion::from_euler(10.0, 20.0, 30.0);
+        let b = Quaternion::from_euler(-40.0, 50.0, 60.0);
+
+        let mut assigned = a;
+        assigned *= b;
+        assert_eq!(assigned, a * b);
+
+        let mut rotated = a;
+        rotated.rotate(b);
+        assert_eq!(rotated, b * a);
+    }
+
+    fn assert_close(a: Vector3, b: Vector3){
+        assert!(Vector3::distance(a, b) < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn mul_vector_rotates_like_unity(){
+        // A quarter turn around up takes forward to right, and right to back
+        let turn = Quaternion::angle_axis(90.0, Vector3::up());
+        assert_close(turn * Vector3::forward(), Vector3::right());
+        assert_close(turn * Vector3::right(), Vector3::back());
+        assert_close(turn * Vector3::up(), Vector3::up());
+        assert_close(Quaternion::identity() * Vector3::new(1.0, 2.0, 3.0), Vector3::new(1.0, 2.0, 3.0));
+
+        // Rotating by `a * b` is rotating by `b`, then by `a`
+        let a = Quaternion::angle_axis(30.0, Vector3::new(1.0, 2.0, 0.5));
+        let b = Quaternion::angle_axis(-75.0, Vector3::new(-0.3, 0.1, 1.0));
+        let v = Vector3::new(0.4, -2.0, 1.5);
+        assert_close((a * b) * v, a * (b * v));
+        assert_close(Quaternion::inverse(a) * (a * v), v);
+    }
+
+    #[test]
+    fn look_rotation_points_forward_along_forward(){
+        // Looking every way, both ways up, takes every branch building the quaternion
+        let directions = [
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.5, -1.0),
+            Vector3::new(3.0, -1.0, 0.5),
+            Vector3::new(-1.0, 0.2, 0.3),
+        ];
+
+        for up in [Vector3::up(), Vector3::new(0.0, -1.0, 0.0)].iter(){
+            for direction in directions.iter(){
+                let rotation = Quaternion::look_rotation(*direction, *up);
+                assert_close(rotation * Vector3::forward(), Vector3::normalize(*direction));
+                // The right axis stays level, so up is as close to `up` as it can be
+                assert!((rotation * Vector3::right()).y.abs() < 1e-5, "{:?}", direction);
+                assert!(Vector3::dot(rotation * Vector3::up(), *up) > 0.0, "{:?}", direction);
+            }
+        }
+    }
+
+    #[test]
+    fn look_rotation_edge_cases(){
+        assert_eq!(Quaternion::look_rotation(Vector3::zero(), Vector3::up()), Quaternion::identity());
+
+        // Looking straight along up still points forward along forward
+        let rotation = Quaternion::look_rotation(Vector3::up(), Vector3::up());
+        assert_close(rotation * Vector3::forward(), Vector3::up());
+        assert!((Quaternion::dot(rotation, rotation) - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,514 @@
+//! Script is the declarative way of writing runity scripts.
+//!
+//! Rather than hand-writing every `extern "C"` lifecycle function, a script
+//! is a plain rust struct implementing the `Script` trait. The `#[runity::script]`
+//! attribute then generates the exports unity expects, and keeps one struct per
+//! instance alive between calls so state doesn't need to live in statics.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::coroutine::{Coroutines, Phase};
+use crate::{profiler, span_timer};
+use crate::{panic, Collision, DataStruct, Instance, LocalData, ProfileScope, ReceivedMessage, State, Status, Trigger};
+
+/// # Script
+///
+/// A script attached to a gameobject in unity. Each lifecycle function mirrors
+/// its `MonoBehaviour` counterpart, and receives the `DataStruct` for the current
+/// call as its context. Any changes made to the `DataStruct` are sent back to unity.
+///
+/// All functions are optional - only implement the ones you need. Apart from `awake`, `start`,
+/// `update`, `on_message` and `destroy`, hooks are only called if they are named in
+/// `#[runity::script(...)]`, such as `#[runity::script(fixed_update, collisions)]`.
+///
+/// ```
+/// use runity::{DataStruct, Script};
+///
+/// #[runity::script]
+/// #[derive(Default)]
+/// pub struct Spinner{
+///     speed: f32,
+/// }
+///
+/// impl Script for Spinner{
+///     fn start(&mut self, _data: &mut DataStruct){
+///         self.speed = 5.0;
+///     }
+/// }
+/// ```
+pub trait Script: Default + Send + 'static{
+    /// # Awake
+    ///
+    /// Called when the script is first loaded, before `start`.
+    fn awake(&mut self, _data: &mut DataStruct){}
+
+    /// # Start
+    ///
+    /// Called after `awake`, use it to initialize values and setup the rest of the script.
+    fn start(&mut self, _data: &mut DataStruct){}
+
+    /// # Update
+    ///
+    /// Called once every frame.
+    fn update(&mut self, _data: &mut DataStruct){}
+
+    /// # Fixed Update
+    ///
+    /// Called once every fixed (physics) step, which may be any number of times per frame.
+    /// `Time::fixed_delta_time` is the length of a step - use this for physics-rate logic.
+    /// Needs `#[runity::script(fixed_update)]`.
+    fn fixed_update(&mut self, _data: &mut DataStruct){}
+
+    /// # Late Update
+    ///
+    /// Called once every frame, after every script's `update`. Use this for anything that
+    /// follows movement made during `update`, like a following camera. Needs
+    /// `#[runity::script(late_update)]`.
+    fn late_update(&mut self, _data: &mut DataStruct){}
+
+    /// # On Enable
+    ///
+    /// Called when the script is enabled, including straight after `awake`. Needs
+    /// `#[runity::script(enable)]`, as does `on_disable`.
+    fn on_enable(&mut self, _data: &mut DataStruct){}
+
+    /// # On Disable
+    ///
+    /// Called when the script is disabled, including before `destroy`.
+    fn on_disable(&mut self, _data: &mut DataStruct){}
+
+    /// # On Application Focus
+    ///
+    /// Called when the application gains (`focused` is true) or loses focus. Needs
+    /// `#[runity::script(application)]`, as do `on_application_pause` and `on_application_quit`.
+    fn on_application_focus(&mut self, _data: &mut DataStruct, _focused: bool){}
+
+    /// # On Application Pause
+    ///
+    /// Called when the application is paused (`paused` is true) or resumed.
+    fn on_application_pause(&mut self, _data: &mut DataStruct, _paused: bool){}
+
+    /// # On Application Quit
+    ///
+    /// Called before the application quits, before `on_disable` and `destroy`.
+    fn on_application_quit(&mut self, _data: &mut DataStruct){}
+
+    /// # On Collision Enter
+    ///
+    /// Called when the gameobject starts touching another. The collision is only lent for
+    /// this call - copy anything needed later. Needs `#[runity::script(collisions)]`, as do
+    /// `on_collision_stay` and `on_collision_exit`.
+    fn on_collision_enter(&mut self, _data: &mut DataStruct, _collision: &Collision){}
+
+    /// # On Collision Stay
+    ///
+    /// Called once every fixed step while the gameobject is touching another.
+    fn on_collision_stay(&mut self, _data: &mut DataStruct, _collision: &Collision){}
+
+    /// # On Collision Exit
+    ///
+    /// Called when the gameobject stops touching another.
+    fn on_collision_exit(&mut self, _data: &mut DataStruct, _collision: &Collision){}
+
+    /// # On Trigger Enter
+    ///
+    /// Called when another gameobject enters a trigger (either this gameobject's, or the other's).
+    /// Needs `#[runity::script(triggers)]`, as do `on_trigger_stay` and `on_trigger_exit`.
+    fn on_trigger_enter(&mut self, _data: &mut DataStruct, _other: &Trigger){}
+
+    /// # On Trigger Stay
+    ///
+    /// Called once every fixed step while another gameobject is inside a trigger.
+    fn on_trigger_stay(&mut self, _data: &mut DataStruct, _other: &Trigger){}
+
+    /// # On Trigger Exit
+    ///
+    /// Called when another gameobject leaves a trigger.
+    fn on_trigger_exit(&mut self, _data: &mut DataStruct, _other: &Trigger){}
+
+    /// # On Message
+    ///
+    /// Called at the start of `update` for each message posted to the script since its last
+    /// update, in the order they were published. Only messages the script has subscribed to
+    /// are posted. The message is only lent for this call.
+    fn on_message(&mut self, _data: &mut DataStruct, _message: &ReceivedMessage){}
+
+    /// # Destroy
+    ///
+    /// Called when the script is destroyed. This is the place to release anything
+    /// allocated during the lifetime of the script.
+    fn destroy(&mut self, _data: &mut DataStruct){}
+
+    /// # State Version
+    ///
+    /// The version of the state written by `save_state`. Bump this whenever the saved state
+    /// changes shape - state saved with a different version is never passed to `load_state`,
+    /// and the hot reload is refused instead.
+    const STATE_VERSION: u32 = 0;
+
+    /// # Save State
+    ///
+    /// Saves the script's state before the library is hot reloaded, in any format `load_state`
+    /// can read back. Returning `None` (the default) starts the reloaded script from `Default`.
+    fn save_state(&self) -> Option<Vec<u8>>{
+        None
+    }
+
+    /// # Load State
+    ///
+    /// Restores the state written by `save_state` into a new script, after the library has
+    /// been hot reloaded. Neither `awake` nor `start` are run again.
+    ///
+    /// Returning an error refuses the hot reload, and the error is reported to the host.
+    fn load_state(&mut self, _state: &[u8]) -> Result<(), std::string::String>{
+        Ok(())
+    }
+}
+
+/// # Script Store
+///
+/// Holds the state of every instance of a `Script` between calls from unity. This is
+/// what the `#[runity::script]` attribute stores the script in, and forwards every exported
+/// lifecycle function to.
+///
+/// Each instance gets its own script, created (through `Default`) in `awake` and dropped in
+/// `destroy`. The instance is taken from the `DataStruct` passed in by unity.
+///
+/// Every call is run through `catch_panic`, so a panicking script is reported through
+/// `Debug::log_error` and marked as faulted, rather than unwinding into unity.
+///
+/// A script is taken out of the store while it runs, so instances run independently of each
+/// other. A call that reaches the same instance again while it is running (from inside one of
+/// its own functions) finds no script, and does nothing.
+pub struct ScriptStore<T: Script>{
+    scripts: Mutex<BTreeMap<Instance, T>>,
+}
+
+impl<T: Script> ScriptStore<T>{
+    /// # New
+    ///
+    /// Creates a new, empty `ScriptStore`. This is `const` so it can be used in a `static`.
+    pub const fn new() -> Self{
+        Self{
+            scripts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    // A script that panicked while the lock was held has already been marked as faulted
+    // (and won't be run again), so a poisoned lock is still safe to use.
+    fn scripts(&self) -> MutexGuard<'_, BTreeMap<Instance, T>>{
+        self.scripts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Takes the instance's script out of the store while `f` runs, so the store isn't locked
+    // while user code runs. It is put back afterwards, even if `f` panics.
+    fn with_script<R>(&self, instance: Instance, f: impl FnOnce(&mut T) -> R) -> Option<R>{
+        let script = self.scripts().remove(&instance)?;
+        let mut taken = Taken{
+            store: self,
+            instance,
+            script: Some(script),
+        };
+
+        taken.script.as_mut().map(f)
+    }
+
+    // Runs `f` on the instance's script, containing any panic.
+    //
+    // If the script panics, the instance is marked as faulted and the `DataStruct` is
+    // handed back to unity exactly as it was passed in. Faulted instances aren't run at all.
+    fn run(&self, entry: &'static str, mut data: DataStruct, f: impl FnOnce(&mut T, &mut DataStruct)) -> DataStruct{
+        if LocalData::is_faulted(data.instance){
+            return data;
+        }
+        // Kept for the entry points that aren't passed a `DataStruct`, to report their panics
+        LocalData::set_debug(data.instance, data.debug);
+
+        // What unity passed in, returned in place of `data` if the script panics
+        let original = data.clone();
+        let debug = data.debug;
+
+        let completed = panic::catch_panic(entry, Some(&debug), || {
+            span_timer::frame(data.time.frame_count as u64);
+            profiler::frame(data.time.frame_count as u64);
+            let _scope = ProfileScope::new(entry);
+
+            let instance = data.instance;
+            self.with_script(instance, |script| f(script, &mut data));
+        });
+
+        match completed{
+            Some(()) => data,
+            None => {
+                LocalData::fault(original.instance);
+                original
+            }
+        }
+    }
+
+    /// # Awake
+    ///
+    /// Allocates a new instance, creates its script and runs its `awake` function.
+    ///
+    /// The returned `DataStruct` carries the new instance, which unity must pass back on every later call.
+    pub fn awake(&self, mut data: DataStruct) -> DataStruct{
+        let debug = data.debug;
+        let allocated = panic::catch_panic("awake", Some(&debug), || {
+            let instance = LocalData::allocate();
+            self.scripts().insert(instance, T::default());
+            instance
+        });
+
+        match allocated{
+            Some(instance) => data.instance = instance,
+            None => return data,
+        }
+
+        self.run("awake", data, |script, data| script.awake(data))
+    }
+
+    /// # Start
+    ///
+    /// Runs the `start` function of the instance's script.
+    pub fn start(&self, data: DataStruct) -> DataStruct{
+        self.run("start", data, |script, data| script.start(data))
+    }
+
+    /// # Update
+    ///
+    /// Runs the `update` function of the instance's script, after passing it the messages
+    /// posted since its last update, then polls its coroutines.
+    pub fn update(&self, data: DataStruct) -> DataStruct{
+        let inbox = LocalData::take_inbox(data.instance);
+
+        self.run("update", data, |script, data| {
+            for message in inbox.iter(){
+                script.on_message(data, &message.lend());
+            }
+            script.update(data);
+            Coroutines::poll(data.instance, Phase::Update, data);
+        })
+    }
+
+    /// # Fixed Update
+    ///
+    /// Runs the `fixed_update` function of the instance's script.
+    pub fn fixed_update(&self, data: DataStruct) -> DataStruct{
+        self.run("fixed_update", data, |script, data| {
+            script.fixed_update(data);
+            Coroutines::poll(data.instance, Phase::FixedUpdate, data);
+        })
+    }
+
+    /// # Late Update
+    ///
+    /// Runs the `late_update` function of the instance's script.
+    pub fn late_update(&self, data: DataStruct) -> DataStruct{
+        self.run("late_update", data, |script, data| script.late_update(data))
+    }
+
+    /// # On Enable
+    ///
+    /// Runs the `on_enable` function of the instance's script.
+    pub fn on_enable(&self, data: DataStruct) -> DataStruct{
+        self.run("on_enable", data, |script, data| script.on_enable(data))
+    }
+
+    /// # On Disable
+    ///
+    /// Runs the `on_disable` function of the instance's script.
+    pub fn on_disable(&self, data: DataStruct) -> DataStruct{
+        self.run("on_disable", data, |script, data| script.on_disable(data))
+    }
+
+    /// # On Application Focus
+    ///
+    /// Runs the `on_application_focus` function of the instance's script.
+    pub fn on_application_focus(&self, data: DataStruct, focused: bool) -> DataStruct{
+        self.run("on_application_focus", data, |script, data| script.on_application_focus(data, focused))
+    }
+
+    /// # On Application Pause
+    ///
+    /// Runs the `on_application_pause` function of the instance's script.
+    pub fn on_application_pause(&self, data: DataStruct, paused: bool) -> DataStruct{
+        self.run("on_application_pause", data, |script, data| script.on_application_pause(data, paused))
+    }
+
+    /// # On Application Quit
+    ///
+    /// Runs the `on_application_quit` function of the instance's script.
+    pub fn on_application_quit(&self, data: DataStruct) -> DataStruct{
+        self.run("on_application_quit", data, |script, data| script.on_application_quit(data))
+    }
+
+    /// # On Collision Enter
+    ///
+    /// Runs the `on_collision_enter` function of the instance's script.
+    pub fn on_collision_enter(&self, data: DataStruct, collision: &Collision) -> DataStruct{
+        self.run("on_collision_enter", data, |script, data| script.on_collision_enter(data, collision))
+    }
+
+    /// # On Collision Stay
+    ///
+    /// Runs the `on_collision_stay` function of the instance's script.
+    pub fn on_collision_stay(&self, data: DataStruct, collision: &Collision) -> DataStruct{
+        self.run("on_collision_stay", data, |script, data| script.on_collision_stay(data, collision))
+    }
+
+    /// # On Collision Exit
+    ///
+    /// Runs the `on_collision_exit` function of the instance's script.
+    pub fn on_collision_exit(&self, data: DataStruct, collision: &Collision) -> DataStruct{
+        self.run("on_collision_exit", data, |script, data| script.on_collision_exit(data, collision))
+    }
+
+    /// # On Trigger Enter
+    ///
+    /// Runs the `on_trigger_enter` function of the instance's script.
+    pub fn on_trigger_enter(&self, data: DataStruct, other: &Trigger) -> DataStruct{
+        self.run("on_trigger_enter", data, |script, data| script.on_trigger_enter(data, other))
+    }
+
+    /// # On Trigger Stay
+    ///
+    /// Runs the `on_trigger_stay` function of the instance's script.
+    pub fn on_trigger_stay(&self, data: DataStruct, other: &Trigger) -> DataStruct{
+        self.run("on_trigger_stay", data, |script, data| script.on_trigger_stay(data, other))
+    }
+
+    /// # On Trigger Exit
+    ///
+    /// Runs the `on_trigger_exit` function of the instance's script.
+    pub fn on_trigger_exit(&self, data: DataStruct, other: &Trigger) -> DataStruct{
+        self.run("on_trigger_exit", data, |script, data| script.on_trigger_exit(data, other))
+    }
+
+    /// # Destroy
+    ///
+    /// Runs the `destroy` function of the instance's script, then drops the script
+    /// and releases the instance. This happens even if the instance is faulted.
+    ///
+    /// Returns `Status::Ok`, or `Status::Panicked` if the script panicked.
+    pub fn destroy(&self, mut data: DataStruct) -> i32{
+        let instance = data.instance;
+        let faulted = LocalData::is_faulted(instance);
+        let debug = data.debug;
+
+        let completed = panic::catch_panic("destroy", Some(&debug), || {
+            // Coroutines stop before the script is destroyed
+            LocalData::take_coroutines(instance).stop_all();
+
+            let script = self.scripts().remove(&instance);
+            if let (Some(mut script), false) = (script, faulted){
+                script.destroy(&mut data);
+            }
+        });
+
+        LocalData::release(instance);
+
+        match completed{
+            Some(()) => Status::Ok as i32,
+            None => Status::Panicked as i32,
+        }
+    }
+
+    /// # Save State
+    ///
+    /// Saves the state of the instance's script, to be restored by `load_state` after a hot
+    /// reload. Faulted instances (and scripts without state) save `State::EMPTY`, so they
+    /// start again from `Default`.
+    pub fn save_state(&self, instance: Instance) -> State{
+        if LocalData::is_faulted(instance){
+            return State::EMPTY;
+        }
+
+        let debug = LocalData::debug(instance);
+        let saved = panic::catch_panic("runity_save_state", debug.as_ref(), || {
+            self.with_script(instance, |script| script.save_state()).flatten()
+        });
+
+        match saved{
+            Some(Some(data)) => {
+                let len = data.len();
+                State::from_vec(data, T::STATE_VERSION).unwrap_or_else(|| {
+                    // The script starts again from `Default`, rather than the host freeing the wrong length
+                    if let Some(debug) = debug.as_ref(){
+                        debug.log_error(format!("runity: the saved state is {} bytes, but a `State` holds at most {}", len, u32::MAX));
+                    }
+                    State::EMPTY
+                })
+            }
+            Some(None) => State::EMPTY,
+            None => {
+                LocalData::fault(instance);
+                State::EMPTY
+            }
+        }
+    }
+
+    /// # Load State
+    ///
+    /// Allocates a new instance, and restores its script from `state` (saved by `save_state`
+    /// in the library this one replaces). The new instance is written to `data`.
+    ///
+    /// Returns `Status::Ok`, or why the state couldn't be restored. No instance is allocated
+    /// unless the state was restored.
+    pub fn load_state(&self, data: &mut DataStruct, state: State) -> i32{
+        let debug = data.debug;
+
+        if !state.is_empty() && state.version != T::STATE_VERSION{
+            debug.log_error(format!("runity: the saved state is version {}, but the script expects version {}", state.version, T::STATE_VERSION));
+            return Status::StateVersionMismatch as i32;
+        }
+
+        let loaded = panic::catch_panic("runity_load_state", Some(&debug), || {
+            let mut script = T::default();
+            if !state.is_empty(){
+                script.load_state(unsafe{ state.as_bytes() })?;
+            }
+
+            let instance = LocalData::allocate();
+            LocalData::set_debug(instance, debug);
+            self.scripts().insert(instance, script);
+            Ok::<_, std::string::String>(instance)
+        });
+
+        match loaded{
+            Some(Ok(instance)) => {
+                data.instance = instance;
+                Status::Ok as i32
+            }
+            Some(Err(reason)) => {
+                debug.log_error(format!("runity: the saved state couldn't be restored: {}", reason));
+                Status::StateRejected as i32
+            }
+            None => Status::Panicked as i32,
+        }
+    }
+}
+
+// A script taken out of its store to be run, put back when dropped
+struct Taken<'a, T: Script>{
+    store: &'a ScriptStore<T>,
+    instance: Instance,
+    script: Option<T>,
+}
+
+impl<T: Script> Drop for Taken<'_, T>{
+    fn drop(&mut self){
+        // The instance may have been destroyed while its script was running, in which case
+        // the script is dropped here rather than put back
+        let released = LocalData::with(self.instance, |_| ()).is_none();
+        if let (Some(script), false) = (self.script.take(), released){
+            self.store.scripts().insert(self.instance, script);
+        }
+    }
+}
+
+impl<T: Script> Default for ScriptStore<T>{
+    fn default() -> Self{
+        Self::new()
+    }
+}
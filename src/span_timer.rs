@@ -0,0 +1,504 @@
+//! A `tracing` subscriber that times spans, frame by frame.
+//!
+//! Once `SpanTimer::install` has been called, every span entered (on any thread) is timed,
+//! and the time spent in it is added up for the frame it ran in. Frames are told apart by
+//! `Time.frame_count`. Every `report_interval` frames, a summary of the spans that took the
+//! most time is logged at `Info` from this module (so `Logger::set_module_level` can quiet it),
+//! and the last `history` frames are kept for hosts to read through `runity_span_samples` (to
+//! chart them in an overlay, for example).
+//!
+//! Span times are inclusive - a span's time includes the time spent in the spans inside it.
+//! A span is counted in the frame it is exited in, so one still open when a frame starts (on a
+//! worker thread, say) is counted whole in the later frame.
+//! Events (`tracing::info!` and friends) are passed on to the `log` backend, so they reach
+//! unity's console with the same filtering as `log`'s records.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+use crate::interop::reflect;
+use crate::{logger, LogLevel, Str};
+
+/// # Span Timer
+///
+/// The `tracing` subscriber runity provides. It is only ever used through its associated
+/// functions.
+pub struct SpanTimer{
+    _private: (),
+}
+
+/// # Span Sample
+///
+/// The time spent in every span with the same name during a single frame. Hosts read these
+/// through `runity_span_samples`, and scripts through `SpanTimer::samples`.
+///
+/// The name is the span's name from its callsite, which lives as long as the library.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SpanSample<'a>{
+    frame: u64,
+    name: Str<'a>,
+    calls: u32,
+    total_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+reflect!(SpanSample<'_>{ frame, name, calls, total_ns, min_ns, max_ns });
+
+impl<'a> SpanSample<'a>{
+    /// # Frame
+    ///
+    /// Returns the frame (`Time.frame_count`) the spans ran in.
+    pub fn frame(&self) -> u64{
+        self.frame
+    }
+
+    /// # Name
+    ///
+    /// Returns the name of the spans.
+    pub fn name(&self) -> Str<'a>{
+        self.name
+    }
+
+    /// # Calls
+    ///
+    /// Returns how many times the spans were entered.
+    pub fn calls(&self) -> u32{
+        self.calls
+    }
+
+    /// # Total
+    ///
+    /// Returns the time spent in the spans altogether.
+    pub fn total(&self) -> Duration{
+        Duration::from_nanos(self.total_ns)
+    }
+
+    /// # Min
+    ///
+    /// Returns the shortest time spent in the spans in a single call.
+    pub fn min(&self) -> Duration{
+        Duration::from_nanos(self.min_ns)
+    }
+
+    /// # Max
+    ///
+    /// Returns the longest time spent in the spans in a single call.
+    pub fn max(&self) -> Duration{
+        Duration::from_nanos(self.max_ns)
+    }
+}
+
+// A frame's sample, before it is lent
+#[derive(Clone, Copy)]
+struct Sample{
+    frame: u64,
+    name: &'static str,
+    calls: u32,
+    total_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+impl Sample{
+    fn add(&mut self, elapsed: u64){
+        self.calls += 1;
+        self.total_ns += elapsed;
+        self.min_ns = if self.calls == 1{ elapsed }else{ self.min_ns.min(elapsed) };
+        self.max_ns = self.max_ns.max(elapsed);
+    }
+
+    fn lend(&self) -> SpanSample<'static>{
+        SpanSample{
+            frame: self.frame,
+            name: Str::from(self.name),
+            calls: self.calls,
+            total_ns: self.total_ns,
+            min_ns: self.min_ns,
+            max_ns: self.max_ns,
+        }
+    }
+}
+
+// The time spent in spans with the same name, across the frames since the last report
+struct Summary{
+    frames: u32,
+    calls: u64,
+    total_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+struct Settings{
+    report_interval: u32,
+    top: usize,
+    history: usize,
+}
+
+// Every frame's samples, from the frame being timed back
+struct Frames{
+    frame: Option<u64>,
+    current: BTreeMap<&'static str, Sample>,
+    history: VecDeque<Vec<Sample>>,
+    summaries: BTreeMap<&'static str, Summary>,
+    reported_frames: u32,
+}
+
+lazy_static!{
+    static ref SETTINGS: Mutex<Settings> = Mutex::new(Settings{
+        report_interval: 300,
+        top: 10,
+        history: 120,
+    });
+    static ref FRAMES: Mutex<Frames> = Mutex::new(Frames{
+        frame: None,
+        current: BTreeMap::new(),
+        history: VecDeque::new(),
+        summaries: BTreeMap::new(),
+        reported_frames: 0,
+    });
+    // The name of every open span, and how many handles it has
+    static ref SPANS: Mutex<HashMap<u64, (&'static str, usize)>> = Mutex::new(HashMap::new());
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static NEXT_SPAN: AtomicU64 = AtomicU64::new(1);
+
+thread_local!{
+    // The spans entered on this thread, and when
+    static ENTERED: RefCell<Vec<(u64, Instant)>> = const{ RefCell::new(Vec::new()) };
+}
+
+// Nothing is left inconsistent by a panic while these are held
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T>{
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl SpanTimer{
+    /// # Install
+    ///
+    /// Installs the span timer as the global `tracing` subscriber.
+    ///
+    /// Returns false if a different subscriber was installed first, in which case it is left alone.
+    pub fn install() -> bool{
+        if INSTALLED.load(Ordering::Acquire){
+            return true;
+        }
+
+        let installed = tracing::subscriber::set_global_default(SpanTimer{ _private: () }).is_ok();
+        if installed{
+            INSTALLED.store(true, Ordering::Release);
+        }
+        installed
+    }
+
+    /// # Set Report Interval
+    ///
+    /// Sets how many frames each summary covers. Defaults to 300; `0` stops logging summaries.
+    pub fn set_report_interval(frames: u32){
+        lock(&SETTINGS).report_interval = frames;
+    }
+
+    /// # Set Top
+    ///
+    /// Sets how many spans each summary lists, those that took the most time first. Defaults to 10.
+    pub fn set_top(spans: usize){
+        lock(&SETTINGS).top = spans;
+    }
+
+    /// # Set History
+    ///
+    /// Sets how many frames of samples are kept for `samples`. Defaults to 120.
+    pub fn set_history(frames: usize){
+        lock(&SETTINGS).history = frames;
+        let mut frames_lock = lock(&FRAMES);
+        while frames_lock.history.len() > frames{
+            frames_lock.history.pop_front();
+        }
+    }
+
+    /// # Samples
+    ///
+    /// Returns the samples of the frames kept, oldest first. The frame being timed isn't
+    /// included until it ends.
+    pub fn samples() -> Vec<SpanSample<'static>>{
+        lock(&FRAMES).history.iter().flatten().map(Sample::lend).collect()
+    }
+}
+
+/// Ends the frame being timed once `frame` moves on, logging a summary if one is due. Called
+/// at the start of every script entry point, once its debug has been lent to the logger.
+pub(crate) fn frame(frame: u64){
+    if !INSTALLED.load(Ordering::Acquire){
+        return;
+    }
+
+    let report = {
+        let settings = lock(&SETTINGS);
+        let mut frames = lock(&FRAMES);
+        if frames.frame == Some(frame){
+            return;
+        }
+
+        let report = if frames.frame.is_some(){ frames.end(&settings) }else{ None };
+        frames.frame = Some(frame);
+        report
+    };
+
+    // Through the logger, so the summary is filtered like any other record from this module
+    if let (Some(report), true) = (report, logger::enabled(log::Level::Info, module_path!())){
+        logger::submit(LogLevel::Info, module_path!(), file!(), line!(), &report);
+    }
+}
+
+impl Frames{
+    // Moves the frame being timed into the history, returning the summary if one is due
+    fn end(&mut self, settings: &Settings) -> Option<std::string::String>{
+        let samples: Vec<Sample> = std::mem::take(&mut self.current).into_values().collect();
+
+        for sample in samples.iter(){
+            let summary = self.summaries.entry(sample.name).or_insert(Summary{ frames: 0, calls: 0, total_ns: 0, min_ns: u64::MAX, max_ns: 0 });
+            summary.frames += 1;
+            summary.calls += sample.calls as u64;
+            summary.total_ns += sample.total_ns;
+            summary.min_ns = summary.min_ns.min(sample.total_ns);
+            summary.max_ns = summary.max_ns.max(sample.total_ns);
+        }
+        self.reported_frames += 1;
+
+        if settings.history > 0{
+            self.history.push_back(samples);
+            while self.history.len() > settings.history{
+                self.history.pop_front();
+            }
+        }
+
+        if settings.report_interval == 0 || self.reported_frames < settings.report_interval{
+            return None;
+        }
+
+        let frames = std::mem::take(&mut self.reported_frames);
+        let mut summaries: Vec<(&'static str, Summary)> = std::mem::take(&mut self.summaries).into_iter().collect();
+        if summaries.is_empty(){
+            return None;
+        }
+        summaries.sort_by_key(|(_, summary)| std::cmp::Reverse(summary.total_ns));
+
+        // Per frame the span ran in
+        let mut report = format!("runity: span timings over the last {} frames (time per frame)", frames);
+        for (name, summary) in summaries.iter().take(settings.top){
+            let _ = write!(report, "\n  {}: avg {}, min {}, max {}, {:.1} calls/frame",
+                name,
+                millis(summary.total_ns / summary.frames as u64),
+                millis(summary.min_ns),
+                millis(summary.max_ns),
+                summary.calls as f64 / summary.frames as f64);
+        }
+        Some(report)
+    }
+}
+
+fn millis(nanos: u64) -> std::string::String{
+    format!("{:.3}ms", nanos as f64 / 1_000_000.0)
+}
+
+// Lends the span samples kept to the host, writing up to `capacity` of them into `samples`
+// (oldest first) and returning how many there are. The names live as long as the library.
+#[no_mangle]
+pub extern "C" fn runity_span_samples(samples: *mut SpanSample<'static>, capacity: u32) -> u32{
+    let written = crate::panic::catch_panic("runity_span_samples", None, || {
+        let kept = SpanTimer::samples();
+        if !samples.is_null(){
+            let count = kept.len().min(capacity as usize);
+            unsafe{ std::ptr::copy_nonoverlapping(kept.as_ptr(), samples, count) };
+        }
+        kept.len() as u32
+    });
+
+    written.unwrap_or(0)
+}
+
+// Collects an event's fields into a single line
+struct Message(std::string::String);
+
+impl Visit for Message{
+    fn record_str(&mut self, field: &Field, value: &str){
+        if field.name() == "message"{
+            self.0.insert_str(0, value);
+        }else{
+            let _ = write!(self.0, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug){
+        if field.name() == "message"{
+            self.0.insert_str(0, &format!("{:?}", value));
+        }else{
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+fn log_level(level: &Level) -> log::Level{
+    match *level{
+        Level::ERROR => log::Level::Error,
+        Level::WARN => log::Level::Warn,
+        Level::INFO => log::Level::Info,
+        Level::DEBUG => log::Level::Debug,
+        _ => log::Level::Trace,
+    }
+}
+
+impl Subscriber for SpanTimer{
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest{
+        // Log levels can change at runtime, so events are checked every time
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool{
+        metadata.is_span() || logger::enabled(log_level(metadata.level()), metadata.target())
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id{
+        let id = NEXT_SPAN.fetch_add(1, Ordering::Relaxed);
+        lock(&SPANS).insert(id, (attributes.metadata().name(), 1));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>){}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id){}
+
+    fn event(&self, event: &Event<'_>){
+        let metadata = event.metadata();
+        let mut message = Message(std::string::String::new());
+        event.record(&mut message);
+
+        logger::submit(log_level(metadata.level()).into(), metadata.target(), metadata.file().unwrap_or(""), metadata.line().unwrap_or(0), &message.0);
+    }
+
+    fn enter(&self, span: &Id){
+        ENTERED.with(|entered| entered.borrow_mut().push((span.into_u64(), Instant::now())));
+    }
+
+    // The whole time is added to the frame being timed when the span is exited, even if it
+    // was entered during an earlier one
+    fn exit(&self, span: &Id){
+        let id = span.into_u64();
+        let entered = ENTERED.with(|entered| {
+            let mut entered = entered.borrow_mut();
+            let index = entered.iter().rposition(|(entered, _)| *entered == id)?;
+            Some(entered.remove(index).1)
+        });
+        let elapsed = match entered{
+            Some(entered) => entered.elapsed().as_nanos() as u64,
+            None => return,
+        };
+
+        let name = match lock(&SPANS).get(&id){
+            Some((name, _)) => *name,
+            None => return,
+        };
+
+        let mut frames = lock(&FRAMES);
+        let frame = frames.frame.unwrap_or(0);
+        frames.current
+            .entry(name)
+            .or_insert(Sample{ frame, name, calls: 0, total_ns: 0, min_ns: 0, max_ns: 0 })
+            .add(elapsed);
+    }
+
+    fn clone_span(&self, span: &Id) -> Id{
+        if let Some((_, handles)) = lock(&SPANS).get_mut(&span.into_u64()){
+            *handles += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool{
+        let mut spans = lock(&SPANS);
+        let id = span.into_u64();
+        let closed = match spans.get_mut(&id){
+            Some((_, handles)) => {
+                *handles -= 1;
+                *handles == 0
+            }
+            None => false,
+        };
+        if closed{
+            spans.remove(&id);
+        }
+        closed
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::logger::tests::{captured, serial};
+    use crate::Logger;
+    use log::LevelFilter;
+
+    // The names of the spans sampled in `frame`
+    fn sampled(frame: u64) -> Vec<std::string::String>{
+        SpanTimer::samples().iter().filter(|sample| sample.frame() == frame).map(|sample| sample.name().to_string()).collect()
+    }
+
+    #[test]
+    fn summaries_go_through_the_module_filter(){
+        let (_serial, debug) = serial();
+        let _entered = logger::enter(&debug);
+        assert!(SpanTimer::install());
+        SpanTimer::set_report_interval(1);
+
+        // Quieted like any other module (which also throws away anything left by other tests)
+        Logger::set_module_level("runity::span_timer", LevelFilter::Warn);
+        frame(100);
+        tracing::info_span!("quiet").in_scope(|| ());
+        frame(101);
+        assert_eq!(captured(), []);
+
+        Logger::clear_module_level("runity::span_timer");
+        tracing::info_span!("reported").in_scope(|| ());
+        frame(102);
+        let written = captured();
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].level, LogLevel::Info);
+        assert_eq!(written[0].target, "runity::span_timer");
+        assert!(written[0].message.starts_with("runity: span timings over the last 1 frames"), "{}", written[0].message);
+        assert!(written[0].message.contains("\n  reported: avg "), "{}", written[0].message);
+
+        SpanTimer::set_report_interval(0);
+    }
+
+    #[test]
+    fn spans_crossing_a_frame_count_in_the_later_frame(){
+        let (_serial, debug) = serial();
+        let _entered = logger::enter(&debug);
+        assert!(SpanTimer::install());
+        SpanTimer::set_report_interval(0);
+
+        frame(200);
+        let span = tracing::info_span!("crossing");
+        let entered = span.enter();
+        tracing::info_span!("inside").in_scope(|| ());
+        frame(201);
+        drop(entered);
+        frame(202);
+
+        assert_eq!(sampled(200), ["inside"]);
+        assert_eq!(sampled(201), ["crossing"]);
+        assert_eq!(captured(), []);
+    }
+}
@@ -0,0 +1,104 @@
+//! Saved script state, used to hot reload a library.
+//!
+//! Before a rebuilt library replaces the running one, the host saves the state of every
+//! instance through `runity_save_state`, and restores it into the new library through
+//! `runity_load_state`. The state itself is whatever bytes the script's `Script::save_state`
+//! returns, tagged with its `Script::STATE_VERSION` so a script whose state has changed shape
+//! is never handed state it can't read.
+
+use std::convert::TryFrom;
+
+use crate::interop::reflect;
+
+/// # State
+///
+/// The saved state of a single instance. `data` points to `len` bytes, written by a script
+/// whose `STATE_VERSION` was `version`.
+///
+/// State returned by `runity_save_state` is owned by the library, and must be freed with
+/// `runity_state_free` (by the same library, before it is unloaded). State passed to
+/// `runity_load_state` is only borrowed for the call.
+///
+/// A null `data` means there is no saved state, and the script starts from `Default`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct State{
+    pub data: *const u8,
+    pub len: u32,
+    pub version: u32,
+}
+
+reflect!(State{ data, len, version });
+
+impl State{
+    /// No saved state.
+    pub const EMPTY: State = State{ data: std::ptr::null(), len: 0, version: 0 };
+
+    /// # Is Empty
+    ///
+    /// Returns true if there is no saved state.
+    pub fn is_empty(&self) -> bool{
+        self.data.is_null()
+    }
+
+    /// # As Bytes
+    ///
+    /// Returns the saved bytes.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be null, or point to `len` bytes that stay valid for `'a`.
+    pub unsafe fn as_bytes<'a>(&self) -> &'a [u8]{
+        if self.data.is_null(){
+            return &[];
+        }
+
+        std::slice::from_raw_parts(self.data, self.len as usize)
+    }
+
+    // Gives the bytes to the host, to be freed with `runity_state_free`. Returns `None` if there
+    // are more than `len` can describe, as the host would free the wrong length.
+    pub(crate) fn from_vec(data: Vec<u8>, version: u32) -> Option<Self>{
+        let len = u32::try_from(data.len()).ok()?;
+        let data = Box::into_raw(data.into_boxed_slice());
+
+        Some(Self{
+            data: data as *const u8,
+            len,
+            version,
+        })
+    }
+}
+
+// Frees state returned by `runity_save_state`
+#[no_mangle]
+pub extern "C" fn runity_state_free(state: State){
+    if !state.data.is_null(){
+        unsafe{ drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(state.data as *mut u8, state.len as usize))) };
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn saved_state_is_lent_to_the_host(){
+        let state = State::from_vec(vec![1, 2, 3], 7).unwrap();
+        assert_eq!((state.len, state.version), (3, 7));
+        assert_eq!(unsafe{ state.as_bytes() }, [1, 2, 3]);
+        runity_state_free(state);
+
+        assert!(State::EMPTY.is_empty());
+        assert_eq!(unsafe{ State::EMPTY.as_bytes() }, [0u8; 0]);
+        runity_state_free(State::EMPTY);
+    }
+
+    // Zeroed memory is only mapped when it is touched, so this doesn't use 4 GiB
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn state_too_long_to_describe_is_refused(){
+        let data = vec![0u8; u32::MAX as usize + 1];
+        assert!(State::from_vec(data, 1).is_none());
+    }
+}
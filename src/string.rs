@@ -1,38 +1,124 @@
+//! Strings passed across the boundary.
+//!
+//! There are two kinds of string, depending on who owns the memory:
+//!
+//! - `String` is allocated by rust, and owned by whoever holds it. It can only be moved,
+//!   never copied, and is freed when it is dropped. A host holding a `String` rust gave it
+//!   frees it with `runity_string_free`.
+//! - `Str` is borrowed. It is used for strings the host lends to rust (or rust lends to the
+//!   host) for the length of a single call, and is never freed by the receiver.
+//!
+//! Both are a pointer to UTF-8 bytes and a length in bytes. A `String` is also NUL terminated,
+//! but a `Str` may not be.
+
 use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::null_mut;
 
 use libc::c_char;
 
-
-use crate::NULL;
+use crate::interop::reflect;
 
 /// # String
 ///
-/// This struct defines a string that can be used to safely allocate a c-compatiable string
-/// and a respective pointer. Will return an error upon allocation if the pointer is `NULL`
+/// An owned, NUL terminated string allocated by rust.
+///
+/// Dropping a `String` frees it, so it can't be cloned - a copy of the pointer would be
+/// freed twice. An empty `String` doesn't allocate, and has a null pointer.
 #[repr(C)]
-#[derive(Clone)]
 pub struct String{
-    /// This is an immutable pointer.
-    pub ptr: *mut c_char,
-    pub len: u32,
+    ptr: *mut c_char,
+    len: u32,
 }
 
+reflect!(String{ ptr, len });
+
 impl String{
-    // Free the string. *very* important to call this!
-    pub fn free(&self){
-        unsafe{
-            if self.ptr.is_null() {
-                return;
-            }
-            CString::from_raw(self.ptr)
-        };
+    /// # New
+    ///
+    /// Creates an empty `String`, without allocating.
+    pub const fn new() -> Self{
+        Self{
+            ptr: null_mut(),
+            len: 0,
+        }
+    }
+
+    /// # Len
+    ///
+    /// Returns the length of the string in bytes, without the NUL terminator.
+    pub fn len(&self) -> usize{
+        self.len as usize
+    }
+
+    /// # Is Empty
+    ///
+    /// Returns true if the string is empty.
+    pub fn is_empty(&self) -> bool{
+        self.ptr.is_null() || self.len == 0
+    }
+
+    /// # As Str
+    ///
+    /// Borrows the string as a `str`.
+    pub fn as_str(&self) -> &str{
+        if self.ptr.is_null(){
+            return "";
+        }
+
+        // The string was built from a `str`, so it is valid UTF-8
+        unsafe{ std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.ptr as *const u8, self.len as usize)) }
+    }
+
+    /// # As Borrowed
+    ///
+    /// Borrows the string as a `Str`, to lend it across the boundary.
+    pub fn as_borrowed(&self) -> Str<'_>{
+        Str::from(self.as_str())
+    }
+
+    /// # Into Raw
+    ///
+    /// Gives up ownership of the string, returning its pointer. The pointer must be passed
+    /// back to `from_raw` to be freed.
+    pub fn into_raw(self) -> *mut c_char{
+        let ptr = self.ptr;
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// # From Raw
+    ///
+    /// Takes ownership of a pointer returned by `into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null, or have come from `into_raw`, and must not be used again.
+    pub unsafe fn from_raw(ptr: *mut c_char) -> Self{
+        if ptr.is_null(){
+            return Self::new();
+        }
+
+        Self{
+            ptr,
+            len: CStr::from_ptr(ptr).to_bytes().len() as u32,
+        }
+    }
+}
+
+impl Default for String{
+    fn default() -> Self{
+        Self::new()
     }
 }
 
 impl Drop for String{
-    /// Drop the string
+    /// Frees the string
     fn drop(&mut self){
-        self.free();
+        if !self.ptr.is_null(){
+            unsafe{ drop(CString::from_raw(self.ptr)) };
+        }
     }
 }
 
@@ -41,37 +127,74 @@ impl Drop for String{
 unsafe impl Send for String{}
 unsafe impl Sync for String{}
 
-/* Conversion */
+/* Formatting */
 
+impl fmt::Debug for String{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
 
-impl From<CString> for String{
-    /// Create a new `String` from a `CString`
-    fn from(value: CString) -> Self{
-        let len = value.to_str().unwrap().len() as u32;
-        let ptr = value.into_raw();
+impl fmt::Display for String{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        f.write_str(self.as_str())
+    }
+}
 
-        if ptr == NULL{
-            panic!("Error - pointer is null");
-        }
+impl PartialEq for String{
+    fn eq(&self, other: &Self) -> bool{
+        self.as_str() == other.as_str()
+    }
+}
 
-        Self{
-            ptr,
-            len
+impl PartialEq<str> for String{
+    fn eq(&self, other: &str) -> bool{
+        self.as_str() == other
+    }
+}
+
+impl Eq for String{}
+
+/* Conversion */
+
+impl From<CString> for String{
+    /// Create a new `String` from a `CString`.
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    fn from(value: CString) -> Self{
+        match value.into_string(){
+            Ok(value) => String::from(value),
+            Err(error) => String::from(error.into_cstring().to_string_lossy().into_owned()),
         }
     }
 }
 
-impl From<&'static str> for String{
-    /// Create a new `String` from a `str`
+impl From<&str> for String{
+    /// Create a new `String` from a `str`.
+    ///
+    /// A C string can't contain a NUL, so the string is cut off at the first NUL.
     fn from(value: &str) -> Self{
-        let len = value.len() as u32;
-        let string = CString::new(value).unwrap();
-        let ptr = string.into_raw() as *mut c_char;
+        String::from(value.to_string())
+    }
+}
 
-        if ptr == NULL{
-            panic!("Error - pointer is null");
+impl From<std::string::String> for String{
+    /// Create a new `String` from an `std::string::String`.
+    ///
+    /// A C string can't contain a NUL, so the string is cut off at the first NUL.
+    fn from(mut value: std::string::String) -> Self{
+        if let Some(nul) = value.find('\0'){
+            value.truncate(nul);
         }
 
+        if value.is_empty(){
+            return Self::new();
+        }
+
+        let len = value.len() as u32;
+        // The NULs were removed above, so this can't fail
+        let ptr = CString::new(value).map(CString::into_raw).unwrap_or(null_mut());
+
         Self{
             ptr,
             len
@@ -79,38 +202,276 @@ impl From<&'static str> for String{
     }
 }
 
-impl From<std::string::String> for String{
-    /// Create a new `String` from an `std::string::String`
-    fn from(value: std::string::String) -> Self{
-        let len = value.len() as u32;
-        let string = CString::new(value).unwrap();
-        let ptr = string.into_raw() as *mut c_char;
+impl From<&CStr> for String{
+    /// Create a new `String` from a `CStr`.
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    fn from(value: &CStr) -> Self{
+        String::from(value.to_string_lossy().into_owned())
+    }
+}
+
+impl From<Str<'_>> for String{
+    /// Copies a borrowed `Str` into a new `String`.
+    fn from(value: Str<'_>) -> Self{
+        String::from(value.to_string_lossy().into_owned())
+    }
+}
+
+/// # Str
+///
+/// A borrowed string, like a `&str` that can be passed across the boundary.
+///
+/// This is used for strings lent for the length of a call, such as messages passed to
+/// `Debug` and tags passed to `GameObject::find_with_tag`. It is never freed by whoever
+/// receives it, and must not be kept after the call returns - copy it into a `String`
+/// (or an `std::string::String`) instead.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Str<'a>{
+    ptr: *const c_char,
+    len: u32,
+    _borrow: PhantomData<&'a str>,
+}
 
-        if ptr == NULL{
-            panic!("Error - pointer is null");
+reflect!(Str<'_>{ ptr, len });
+
+impl<'a> Str<'a>{
+    /// # Empty
+    ///
+    /// An empty `Str`, with a null pointer.
+    pub const EMPTY: Str<'static> = Str{ ptr: std::ptr::null(), len: 0, _borrow: PhantomData };
+
+    /// # Len
+    ///
+    /// Returns the length of the string in bytes.
+    pub fn len(&self) -> usize{
+        self.len as usize
+    }
+
+    /// # Is Empty
+    ///
+    /// Returns true if the string is empty.
+    pub fn is_empty(&self) -> bool{
+        self.ptr.is_null() || self.len == 0
+    }
+
+    /// # As Bytes
+    ///
+    /// Returns the bytes of the string.
+    pub fn as_bytes(&self) -> &'a [u8]{
+        if self.ptr.is_null(){
+            return &[];
         }
 
+        unsafe{ std::slice::from_raw_parts(self.ptr as *const u8, self.len as usize) }
+    }
+
+    /// # To Str
+    ///
+    /// Returns the string as a `str`, if it is valid UTF-8. Strings lent by rust always are,
+    /// but strings lent by the host may not be.
+    pub fn to_str(&self) -> Result<&'a str, std::str::Utf8Error>{
+        std::str::from_utf8(self.as_bytes())
+    }
+
+    /// # To String Lossy
+    ///
+    /// Returns the string as a `str`, replacing invalid UTF-8 with `U+FFFD`.
+    pub fn to_string_lossy(&self) -> std::borrow::Cow<'a, str>{
+        std::string::String::from_utf8_lossy(self.as_bytes())
+    }
+
+    /// # From Raw Parts
+    ///
+    /// Creates a `Str` from a pointer and a length in bytes.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null, or point to `len` bytes that stay valid and unchanged for `'a`.
+    pub unsafe fn from_raw_parts(ptr: *const c_char, len: usize) -> Self{
         Self{
             ptr,
-            len
+            len: len as u32,
+            _borrow: PhantomData,
         }
     }
 }
 
-impl From<&'static CStr> for String{
-    /// Create a new `String` from a `CStr`
-    fn from(value: &CStr) -> Self{
-        let len = value.to_str().unwrap().len() as u32;
-        let string = CString::new(value.to_str().unwrap()).unwrap();
-        let ptr = string.into_raw() as *mut c_char;
+impl<'a> From<&'a str> for Str<'a>{
+    /// Borrows a `str`. Strings longer than `u32::MAX` bytes are cut short.
+    fn from(value: &'a str) -> Self{
+        Self{
+            ptr: value.as_ptr() as *const c_char,
+            len: value.len().min(u32::MAX as usize) as u32,
+            _borrow: PhantomData,
+        }
+    }
+}
 
-        if ptr == NULL{
-            panic!("Error - pointer is null");
+impl<'a> From<&'a String> for Str<'a>{
+    fn from(value: &'a String) -> Self{
+        value.as_borrowed()
+    }
+}
+
+impl fmt::Debug for Str<'_>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
+
+impl fmt::Display for Str<'_>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+// Lets the host free a `String` rust gave it
+#[no_mangle]
+pub extern "C" fn runity_string_free(string: String){
+    drop(string);
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    // Counts the bytes allocated and not yet freed on each thread, so tests can see what a
+    // string frees without seeing other tests' allocations
+    struct Counting;
+
+    thread_local!{
+        static LIVE: Cell<isize> = const{ Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting{
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8{
+            let _ = LIVE.try_with(|live| live.set(live.get() + layout.size() as isize));
+            System.alloc(layout)
         }
 
-        Self{
-            ptr,
-            len
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8{
+            let _ = LIVE.try_with(|live| live.set(live.get() + layout.size() as isize));
+            System.alloc_zeroed(layout)
         }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout){
+            let _ = LIVE.try_with(|live| live.set(live.get() - layout.size() as isize));
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8{
+            let _ = LIVE.try_with(|live| live.set(live.get() + new_size as isize - layout.size() as isize));
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn live() -> isize{
+        LIVE.with(Cell::get)
+    }
+
+    // The bytes behind a string's pointer, up to its NUL terminator
+    fn terminated(string: &String) -> &[u8]{
+        unsafe{ CStr::from_ptr(string.ptr).to_bytes() }
+    }
+
+    #[test]
+    fn strings_are_cut_at_the_first_nul(){
+        let string = String::from("tag\0ged");
+        assert_eq!(string.as_str(), "tag");
+        assert_eq!(string.len(), 3);
+        assert_eq!(terminated(&string), b"tag");
+
+        assert_eq!(String::from(std::string::String::from("a\0b\0c")), *"a");
+        assert_eq!(String::from(CString::new("whole").unwrap()), *"whole");
+    }
+
+    #[test]
+    fn empty_strings_are_null(){
+        for string in [String::new(), String::default(), String::from(""), String::from("\0tag"), String::from(Str::EMPTY)]{
+            assert!(string.ptr.is_null());
+            assert!(string.is_empty());
+            assert_eq!(string.len(), 0);
+            assert_eq!(string.as_str(), "");
+            assert!(string.into_raw().is_null());
+        }
+
+        let string = unsafe{ String::from_raw(null_mut()) };
+        assert!(string.ptr.is_null() && string.is_empty());
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced(){
+        let bytes = [b'a', 0xff, b'b'];
+        let lent = unsafe{ Str::from_raw_parts(bytes.as_ptr() as *const c_char, bytes.len()) };
+        assert!(lent.to_str().is_err());
+        assert_eq!(lent.to_string_lossy(), "a\u{fffd}b");
+        assert_eq!(String::from(lent), *"a\u{fffd}b");
+
+        assert_eq!(String::from(CString::new(bytes.to_vec()).unwrap()), *"a\u{fffd}b");
+    }
+
+    #[test]
+    fn strings_are_freed_once_when_dropped(){
+        let before = live();
+
+        let string = String::from("a tag");
+        let allocated = live() - before;
+        assert!(allocated > 0);
+
+        // Moving the string (or lending it) doesn't free it
+        let moved = string;
+        let lent = moved.as_borrowed();
+        assert_eq!(lent.to_str(), Ok("a tag"));
+        assert_eq!(lent.as_bytes().as_ptr(), moved.ptr as *const u8);
+        assert_eq!(live() - before, allocated);
+
+        drop(moved);
+        assert_eq!(live(), before);
+    }
+
+    #[test]
+    fn raw_strings_are_taken_back(){
+        let before = live();
+
+        let ptr = String::from("given away").into_raw();
+        assert!(live() > before);
+
+        let string = unsafe{ String::from_raw(ptr) };
+        assert_eq!(string.as_str(), "given away");
+        assert_eq!(string.len(), 10);
+        drop(string);
+        assert_eq!(live(), before);
+    }
+
+    #[test]
+    fn hosts_free_strings_with_runity_string_free(){
+        let before = live();
+
+        runity_string_free(String::from("for the host"));
+        assert_eq!(live(), before);
+
+        // Empty strings own nothing, so freeing them does nothing
+        runity_string_free(String::new());
+        assert_eq!(live(), before);
+    }
+
+    #[test]
+    fn borrowed_strings_copy_into_owned_ones(){
+        let text = "borrowed";
+        let lent = Str::from(text);
+        assert_eq!(lent.len(), 8);
+        assert_eq!(lent.as_bytes().as_ptr(), text.as_ptr());
+
+        let owned = String::from(lent);
+        assert_ne!(owned.ptr as *const u8, text.as_ptr());
+        assert_eq!(terminated(&owned), b"borrowed");
+        assert_eq!(format!("{} {:?}", owned, Str::from(&owned)), "borrowed \"borrowed\"");
     }
 }
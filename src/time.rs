@@ -1,6 +1,8 @@
-/// # Time
-///
-/// This contains all time related variables and functions - including things such as deltatime and fixedtime.
+//! # Time
+//!
+//! This contains all time related variables and functions - including things such as deltatime and fixedtime.
+
+use crate::interop::reflect;
 
 /// # Time
 ///
@@ -25,6 +27,24 @@ pub struct Time{
     pub unscaled_time: f32
 }
 
+reflect!(Time{
+    delta_time,
+    fixed_delta_time,
+    fixed_time,
+    fixed_unscaled_delta_time,
+    fixed_unscaled_time,
+    frame_count,
+    maximum_delta_time,
+    maximum_particle_delta_time,
+    real_time_since_startup,
+    smooth_delta_time,
+    time,
+    time_scale,
+    time_since_level_load,
+    unscaled_delta_time,
+    unscaled_time,
+});
+
 impl Default for Time{
     fn default() -> Self{
         Self{
@@ -1,25 +1,292 @@
+use crate::interop::reflect;
 use crate::{Vector3, Quaternion};
 /// # Transform
-/// 
+///
 /// This struct represents a transform in unity. It will aim, like the `GameObject` struct,
 /// to implement as many unity-specific functions as possible. This will likely be done through
 /// function pointers as it is cheaper to run most (such as child searching) through pointers rather than
 /// natively. However, some, such as `translate` will be natively implemented.
+///
+/// `position` and `rotation` are in world space. The host also sends the world space frame of
+/// the parent (if there is one), so the local space values are worked out from them, and
+/// setting either one keeps the other consistent.
 #[repr(C)]
 #[derive(Clone)]
 pub struct Transform{
     pub position: Vector3,
     pub rotation: Quaternion,
+    /// The scale relative to the parent, like unity's `localScale`.
+    pub local_scale: Vector3,
+    parent: u64,
+    parent_position: Vector3,
+    parent_rotation: Quaternion,
+    parent_scale: Vector3,
 }
 
 impl Transform{
     /// # New
     ///
-    /// Creates a new `Transform` struct from a position (`Vector3`)
+    /// Creates a new `Transform` struct from a position (`Vector3`) and rotation (`Quaternion`),
+    /// without a parent and with a scale of one.
     pub fn new(position: Vector3, rotation: Quaternion) -> Self{
         Self{
             position,
-            rotation
+            rotation,
+            local_scale: Vector3::one(),
+            parent: 0,
+            parent_position: Vector3::zero(),
+            parent_rotation: Quaternion::identity(),
+            parent_scale: Vector3::one(),
         }
     }
-}
\ No newline at end of file
+
+    /// # Set Parent Transform
+    ///
+    /// Makes `parent` the parent of this transform, keeping its world position and rotation.
+    /// This is used by hosts to send the parent's frame to rust - it doesn't move the
+    /// gameobject in unity.
+    ///
+    /// `hash` is the hash of the parent gameobject, or `0` for none.
+    pub fn set_parent_transform(&mut self, hash: u64, parent: &Transform){
+        self.parent = hash;
+        self.parent_position = parent.position;
+        self.parent_rotation = parent.rotation;
+        self.parent_scale = parent.lossy_scale();
+    }
+
+    /// # Parent
+    ///
+    /// Returns the hash of the parent gameobject, if there is one. `GameObject::parent` and
+    /// `GameObject::child` reach the gameobjects themselves.
+    pub fn parent(&self) -> Option<u64>{
+        if self.parent == 0{
+            None
+        }else{
+            Some(self.parent)
+        }
+    }
+
+    /// # Local Position
+    ///
+    /// Returns the position relative to the parent.
+    pub fn local_position(&self) -> Vector3{
+        divide(Quaternion::inverse(self.parent_rotation) * (self.position - self.parent_position), self.parent_scale)
+    }
+
+    /// # Set Local Position
+    ///
+    /// Sets the position relative to the parent, moving the world position with it.
+    pub fn set_local_position(&mut self, local_position: Vector3){
+        self.position = self.parent_position + self.parent_rotation * Vector3::scale(self.parent_scale, local_position);
+    }
+
+    /// # Local Rotation
+    ///
+    /// Returns the rotation relative to the parent.
+    pub fn local_rotation(&self) -> Quaternion{
+        Quaternion::inverse(self.parent_rotation) * self.rotation
+    }
+
+    /// # Set Local Rotation
+    ///
+    /// Sets the rotation relative to the parent, rotating the world rotation with it.
+    pub fn set_local_rotation(&mut self, local_rotation: Quaternion){
+        self.rotation = self.parent_rotation * local_rotation;
+    }
+
+    /// # Lossy Scale
+    ///
+    /// Returns the scale in world space. Like unity, this is only exact when no parent
+    /// is both rotated and scaled unevenly.
+    pub fn lossy_scale(&self) -> Vector3{
+        Vector3::scale(self.parent_scale, self.local_scale)
+    }
+
+    /// # Forward
+    ///
+    /// Returns the blue (`z`) axis of the transform, in world space.
+    pub fn forward(&self) -> Vector3{
+        self.rotation * Vector3::forward()
+    }
+
+    /// # Right
+    ///
+    /// Returns the red (`x`) axis of the transform, in world space.
+    pub fn right(&self) -> Vector3{
+        self.rotation * Vector3::right()
+    }
+
+    /// # Up
+    ///
+    /// Returns the green (`y`) axis of the transform, in world space.
+    pub fn up(&self) -> Vector3{
+        self.rotation * Vector3::up()
+    }
+
+    /// # Transform Point
+    ///
+    /// Transforms `point` from local space to world space. This is affected by position,
+    /// rotation and scale.
+    pub fn transform_point(&self, point: Vector3) -> Vector3{
+        self.position + self.transform_vector(point)
+    }
+
+    /// # Inverse Transform Point
+    ///
+    /// Transforms `point` from world space to local space - the opposite of `transform_point`.
+    pub fn inverse_transform_point(&self, point: Vector3) -> Vector3{
+        self.inverse_transform_vector(point - self.position)
+    }
+
+    /// # Transform Direction
+    ///
+    /// Transforms `direction` from local space to world space. This is only affected by rotation,
+    /// so the length stays the same.
+    pub fn transform_direction(&self, direction: Vector3) -> Vector3{
+        self.rotation * direction
+    }
+
+    /// # Inverse Transform Direction
+    ///
+    /// Transforms `direction` from world space to local space - the opposite of `transform_direction`.
+    pub fn inverse_transform_direction(&self, direction: Vector3) -> Vector3{
+        Quaternion::inverse(self.rotation) * direction
+    }
+
+    /// # Transform Vector
+    ///
+    /// Transforms `vector` from local space to world space. This is affected by rotation and scale,
+    /// but not position.
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3{
+        self.rotation * Vector3::scale(self.lossy_scale(), vector)
+    }
+
+    /// # Inverse Transform Vector
+    ///
+    /// Transforms `vector` from world space to local space - the opposite of `transform_vector`.
+    pub fn inverse_transform_vector(&self, vector: Vector3) -> Vector3{
+        divide(Quaternion::inverse(self.rotation) * vector, self.lossy_scale())
+    }
+
+    /// # Look At
+    ///
+    /// Rotates the transform so its forward axis points at `target` (in world space), with its
+    /// up axis as close to `world_up` as possible.
+    pub fn look_at(&mut self, target: Vector3, world_up: Vector3){
+        if target != self.position{
+            self.rotation = Quaternion::look_rotation(target - self.position, world_up);
+        }
+    }
+
+    /// # Rotate
+    ///
+    /// Rotates the transform by `angle` degrees around `axis`. The axis is in world space with
+    /// `Space::World`, or relative to the transform's own axes with `Space::Local`.
+    pub fn rotate(&mut self, axis: Vector3, angle: f32, space: Space){
+        let rotation = Quaternion::angle_axis(angle, axis);
+
+        self.rotation = match space{
+            Space::World => rotation * self.rotation,
+            Space::Local => self.rotation * rotation,
+        };
+    }
+
+    /// # Translate
+    ///
+    /// Moves the transform by `translation`. The translation is in world space with
+    /// `Space::World`, or along the transform's own axes with `Space::Local`.
+    pub fn translate(&mut self, translation: Vector3, space: Space){
+        self.position += match space{
+            Space::World => translation,
+            Space::Local => self.transform_direction(translation),
+        };
+    }
+}
+
+reflect!(Transform{ position, rotation, local_scale, parent, parent_position, parent_rotation, parent_scale });
+
+/// # Space
+///
+/// Which space a transform operation is in, like unity's `Space`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Space{
+    /// Relative to the world.
+    World,
+    /// Relative to the transform itself (unity's `Space.Self`).
+    Local,
+}
+
+// Divides `value` by `scale` component-wise. A zero scale flattens that axis, so it has no
+// inverse - it gives zero rather than dividing by zero
+fn divide(value: Vector3, scale: Vector3) -> Vector3{
+    let divide = |value: f32, scale: f32| if scale.abs() < Vector3::K_EPSILON{ 0.0 }else{ value / scale };
+
+    Vector3::new(divide(value.x, scale.x), divide(value.y, scale.y), divide(value.z, scale.z))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn assert_close(a: Vector3, b: Vector3){
+        assert!(Vector3::distance(a, b) < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    // A transform with a rotated, unevenly scaled parent
+    fn child() -> Transform{
+        let mut parent = Transform::new(Vector3::new(1.0, -2.0, 3.0), Quaternion::angle_axis(40.0, Vector3::new(0.2, 1.0, -0.5)));
+        parent.local_scale = Vector3::new(2.0, 0.5, 1.5);
+
+        let mut child = Transform::new(Vector3::zero(), Quaternion::identity());
+        child.set_parent_transform(1, &parent);
+        child.set_local_position(Vector3::new(0.5, 1.0, -2.0));
+        child.set_local_rotation(Quaternion::angle_axis(-25.0, Vector3::new(1.0, 0.0, 0.3)));
+        child.local_scale = Vector3::new(1.0, 3.0, 0.25);
+        child
+    }
+
+    #[test]
+    fn transform_point_round_trips(){
+        let transform = child();
+        let points = [Vector3::zero(), Vector3::new(1.0, 2.0, 3.0), Vector3::new(-4.0, 0.5, 10.0)];
+
+        for point in points.iter(){
+            assert_close(transform.inverse_transform_point(transform.transform_point(*point)), *point);
+            assert_close(transform.transform_point(transform.inverse_transform_point(*point)), *point);
+            assert_close(transform.inverse_transform_direction(transform.transform_direction(*point)), *point);
+        }
+
+        // The local origin is the transform's position
+        assert_close(transform.transform_point(Vector3::zero()), transform.position);
+    }
+
+    #[test]
+    fn transform_point_applies_scale_then_rotation_then_position(){
+        let mut transform = Transform::new(Vector3::new(0.0, 0.0, 5.0), Quaternion::angle_axis(90.0, Vector3::up()));
+        transform.local_scale = Vector3::new(2.0, 1.0, 1.0);
+
+        // Scaled to (2, 0, 0), turned to (0, 0, -2), then moved
+        assert_close(transform.transform_point(Vector3::right()), Vector3::new(0.0, 0.0, 3.0));
+        assert_close(transform.transform_direction(Vector3::right()), Vector3::back());
+    }
+
+    #[test]
+    fn look_at_points_forward_at_the_target(){
+        let mut transform = child();
+        let target = Vector3::new(-3.0, 4.0, 7.0);
+        transform.look_at(target, Vector3::up());
+
+        assert_close(transform.forward(), Vector3::normalize(target - transform.position));
+        assert!(transform.right().y.abs() < 1e-5);
+        assert!(transform.up().y > 0.0);
+        // The target is straight ahead in local space
+        let local = transform.inverse_transform_direction(target - transform.position);
+        assert_close(Vector3::normalize(local), Vector3::forward());
+
+        // Looking at its own position leaves the rotation alone
+        let rotation = transform.rotation;
+        let position = transform.position;
+        transform.look_at(position, Vector3::up());
+        assert_eq!(transform.rotation, rotation);
+    }
+}
@@ -1,5 +1,6 @@
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
+use crate::interop::reflect;
 use crate::Math;
 
 /// # Vector3
@@ -45,6 +46,8 @@ impl Vector3{
     }
 }
 
+reflect!(Vector3{ x, y, z });
+
 /* Functions to help with Vector3 usage */
 impl Vector3{
     /// # Dot
@@ -125,7 +128,7 @@ impl Vector3{
         let mut change_y = current.y - target.y;
         let mut change_z = current.z - target.z;
 
-        let original_to = target.clone();
+        let original_to = target;
 
         let max_change = max_speed * smooth_time;
 
@@ -250,14 +253,14 @@ impl Vector3{
     ///
     /// Return the length of this vector
     pub fn magnitude(value: Self) -> f32{
-        (value.x * value.x + value.y * value.y).sqrt()
+        (value.x * value.x + value.y * value.y + value.z * value.z).sqrt()
     }
 
     /// # Square Magnitude
     ///
     /// Return the squared length of this vector
     pub fn sqr_magnitude(value: Self) -> f32{
-        value.x * value.x + value.y * value.y
+        value.x * value.x + value.y * value.y + value.z * value.z
     }
 
     /// # Project
@@ -507,4 +510,25 @@ impl DivAssign for Vector3{
         self.y = if self.y == 0.0 || rhs.y == 0.0 { 0.0 } else { self.y / rhs.y };
         self.z = if self.z == 0.0 || rhs.z == 0.0 { 0.0 } else { self.z / rhs.z };
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn magnitude_includes_z(){
+        let value = Vector3::new(1.0, 2.0, 2.0);
+
+        assert_eq!(Vector3::magnitude(value), 3.0);
+        assert_eq!(Vector3::sqr_magnitude(value), 9.0);
+        assert_eq!(Vector3::magnitude(Vector3::new(0.0, 0.0, -4.0)), 4.0);
+    }
+
+    #[test]
+    fn normalize_uses_every_axis(){
+        let normalized = Vector3::normalize(Vector3::new(0.0, 3.0, 4.0));
+
+        assert!((Vector3::magnitude(normalized) - 1.0).abs() < 1.0e-6);
+        assert!((normalized.z - 0.8).abs() < 1.0e-6);
+    }
+}